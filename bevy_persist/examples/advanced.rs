@@ -56,7 +56,8 @@ fn main() {
         .add_plugins(persist_plugin)
         // Resources are auto-registered by the Persist derive macro
         .add_systems(Startup, setup)
-        .add_systems(Update, game_loop);
+        .add_systems(Update, game_loop)
+        .add_systems(Last, report_saves);
 
     println!("\n=== Bevy Persist Advanced Example ===");
     println!("Demonstrates multiple persistence strategies for a real game.\n");
@@ -150,7 +151,7 @@ fn game_loop(
     mut balance: ResMut<GameBalance>,
     mut prefs: ResMut<UserPreferences>,
     mut progress: ResMut<PlayerProgress>,
-    mut manager: ResMut<PersistManager>,
+    mut commands: Commands,
     mut exit: EventWriter<AppExit>,
 ) {
     print!("> ");
@@ -249,33 +250,8 @@ fn game_loop(
             "4" => {
                 println!("\nManually saving player progress...");
 
-                // Force save the PlayerProgress resource
-                let data = progress.to_persist_data();
-
-                #[cfg(feature = "prod")]
-                {
-                    let path = manager.get_resource_path("PlayerProgress", PersistMode::Secure);
-                    let mut file = PersistFile::new();
-                    file.set_type_data("PlayerProgress".to_string(), data);
-
-                    if let Err(e) = file.save_to_file(&path) {
-                        println!("❌ Failed to save progress: {}", e);
-                    } else {
-                        println!("✅ Progress saved to secure location: {:?}", path);
-                    }
-                }
-
-                #[cfg(not(feature = "prod"))]
-                {
-                    manager
-                        .get_persist_file_mut()
-                        .set_type_data("PlayerProgress".to_string(), data);
-                    if let Err(e) = manager.save() {
-                        println!("❌ Failed to save progress: {}", e);
-                    } else {
-                        println!("✅ Progress saved to advancedgame_dev.ron");
-                    }
-                }
+                // Force save the PlayerProgress resource; report_saves says how it went
+                commands.save_persistent::<PlayerProgress>();
             }
             "5" => {
                 println!("\n=== All Current Values ===");
@@ -326,18 +302,8 @@ fn game_loop(
             "q" | "quit" => {
                 println!("\nShutting down...");
 
-                // Save progress before exiting
-                let data = progress.to_persist_data();
-                manager
-                    .get_persist_file_mut()
-                    .set_type_data("PlayerProgress".to_string(), data);
-                let _ = manager.save();
-
-                println!("✓ User preferences saved (auto)");
-                println!("✓ Player progress saved (manual)");
-
-                #[cfg(feature = "dev")]
-                println!("✓ Game balance saved (for embedding later)");
+                // Save progress before exiting; report_saves runs before the app exits
+                commands.save_persistent::<PlayerProgress>();
 
                 exit.write(AppExit::Success);
             }
@@ -349,3 +315,12 @@ fn game_loop(
         }
     }
 }
+
+fn report_saves(mut saved: EventReader<PersistSaved>, mut failed: EventReader<PersistSaveFailed>) {
+    for event in saved.read() {
+        println!("✅ {} saved to {:?}", event.type_name, event.path);
+    }
+    for event in failed.read() {
+        println!("❌ {} failed to save: {}", event.type_name, event.error);
+    }
+}
@@ -0,0 +1,240 @@
+//! Backing up every persisted file as one archive (`archive` feature).
+
+use crate::{
+    create_parent_dir, temp_path, write_error, DataLocation, PersistError, PersistFile,
+    PersistManager, PersistResult,
+};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Layout version of the archive, bumped when it changes incompatibly
+const ARCHIVE_FORMAT: u32 = 1;
+
+/// Name of the manifest entry describing the archive
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// What an archive holds and which game wrote it.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveManifest {
+    format: u32,
+    organization: String,
+    app_name: String,
+    /// Version of bevy_persist that wrote the archive
+    version: String,
+    created: String,
+    files: Vec<ArchivedFile>,
+}
+
+/// One persisted file in the archive.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedFile {
+    entry: String,
+    location: DataLocation,
+}
+
+impl PersistManager {
+    /// Bundles every persisted file into one tar archive at `path`.
+    ///
+    /// Covers the same files as [`delete_all_data`](Self::delete_all_data),
+    /// for "back up my saves" buttons and support tickets. Only what's on
+    /// disk is included, so [flush](Self::flush) first to include unsaved
+    /// changes. Returns the files archived.
+    pub fn export_archive(&self, path: impl AsRef<Path>) -> PersistResult<Vec<PathBuf>> {
+        let now = self.now();
+        let mut manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT,
+            organization: self.organization.clone(),
+            app_name: self.app_name.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            created: now.to_rfc3339(),
+            files: Vec::new(),
+        };
+        let mut sources: Vec<PathBuf> = Vec::new();
+        for location in self.data_locations() {
+            // Grouped types share a file, which only needs archiving once
+            let Some(source) = self.location_path(&location) else {
+                continue;
+            };
+            if !source.is_file() || sources.contains(&source) {
+                continue;
+            }
+            let file_name = source
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            manifest.files.push(ArchivedFile {
+                entry: format!("files/{}-{}", manifest.files.len(), file_name),
+                location,
+            });
+            sources.push(source);
+        }
+
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent).map_err(archive_error)?;
+        }
+        let file = File::create(path.as_ref()).map_err(|e| archive_error(e).at(path.as_ref()))?;
+        let mut builder = tar::Builder::new(BufWriter::new(file));
+        let manifest_json =
+            serde_json::to_vec_pretty(&manifest).map_err(PersistError::serialization)?;
+        let mtime = now.timestamp().max(0) as u64;
+        append(&mut builder, MANIFEST_ENTRY, &manifest_json, mtime)?;
+        for (archived, source) in manifest.files.iter().zip(&sources) {
+            let content = fs::read(source).map_err(|e| archive_error(e).at(source))?;
+            append(&mut builder, &archived.entry, &content, mtime)?;
+        }
+        builder
+            .into_inner()
+            .and_then(|mut writer| writer.flush())
+            .map_err(archive_error)?;
+
+        info!(
+            "Exported {} persisted files to {:?}",
+            sources.len(),
+            path.as_ref()
+        );
+        Ok(sources)
+    }
+
+    /// Restores the files in an archive from [`export_archive`](Self::export_archive).
+    ///
+    /// Files go to wherever they belong on this machine, so a backup moves
+    /// between computers. Archives from another game, or from a newer
+    /// archive format, are rejected before anything is written. Files of
+    /// types that aren't registered are skipped.
+    ///
+    /// Every file is staged next to its target before any is replaced, so
+    /// an import that fails partway leaves the current saves as they were.
+    ///
+    /// Live resources aren't touched; call [`load_all`](Self::load_all)
+    /// afterwards to apply the restored data. Returns the files written.
+    pub fn import_archive(&mut self, path: impl AsRef<Path>) -> PersistResult<Vec<PathBuf>> {
+        if self.read_only {
+            return Err(PersistError::io_other(
+                "Read-only, not importing an archive",
+                std::io::ErrorKind::PermissionDenied,
+            ));
+        }
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| archive_error(e).at(path))?;
+        let mut entries: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut archive = tar::Archive::new(file);
+        for entry in archive.entries().map_err(archive_error)? {
+            let mut entry = entry.map_err(archive_error)?;
+            let name = entry
+                .path()
+                .map_err(archive_error)?
+                .to_string_lossy()
+                .into_owned();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).map_err(archive_error)?;
+            entries.insert(name, content);
+        }
+
+        let manifest: ArchiveManifest = entries
+            .get(MANIFEST_ENTRY)
+            .ok_or_else(|| invalid_archive("it has no manifest"))
+            .and_then(|json| {
+                serde_json::from_slice(json)
+                    .map_err(|e| invalid_archive(format!("its manifest is unreadable: {}", e)))
+            })?;
+        if manifest.format > ARCHIVE_FORMAT {
+            return Err(invalid_archive(format!(
+                "it was written by a newer version ({})",
+                manifest.version
+            )));
+        }
+        if manifest.organization != self.organization || manifest.app_name != self.app_name {
+            return Err(invalid_archive(format!(
+                "it belongs to {}/{}",
+                manifest.organization, manifest.app_name
+            )));
+        }
+
+        let mut files = Vec::new();
+        for archived in &manifest.files {
+            let Some(content) = entries.get(&archived.entry) else {
+                return Err(invalid_archive(format!("{} is missing", archived.entry)));
+            };
+            let Some(target) = self.location_path(&archived.location) else {
+                warn!(
+                    "Skipping {} from archive, its type isn't registered",
+                    archived.entry
+                );
+                continue;
+            };
+            files.push((target, content));
+        }
+
+        let mut staged: Vec<(PathBuf, PathBuf)> = Vec::new();
+        for (target, content) in files {
+            let temp = temp_path(&target);
+            let written = create_parent_dir(&target).and_then(|()| {
+                PersistFile::write_temp(&temp, |writer| {
+                    writer.write_all(content).map_err(write_error)
+                })
+            });
+            if let Err(e) = written {
+                let _ = fs::remove_file(&temp);
+                discard_staged(&staged);
+                return Err(e.at(&target));
+            }
+            staged.push((temp, target));
+        }
+
+        let mut restored = Vec::new();
+        for (index, (temp, target)) in staged.iter().enumerate() {
+            if let Err(e) = fs::rename(temp, target) {
+                discard_staged(&staged[index..]);
+                return Err(write_error(e).at(target));
+            }
+            restored.push(target.clone());
+        }
+
+        self.refresh_from_disk()?;
+        info!(
+            "Imported {} persisted files from {:?}",
+            restored.len(),
+            path
+        );
+        Ok(restored)
+    }
+}
+
+/// Adds one file to the archive.
+fn append<W: Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    content: &[u8],
+    mtime: u64,
+) -> PersistResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, content)
+        .map_err(archive_error)
+}
+
+/// Removes staged files that won't replace their targets after all.
+fn discard_staged(staged: &[(PathBuf, PathBuf)]) {
+    for (temp, _) in staged {
+        let _ = fs::remove_file(temp);
+    }
+}
+
+fn archive_error(e: std::io::Error) -> PersistError {
+    PersistError::io("Archive error", e)
+}
+
+fn invalid_archive(reason: impl std::fmt::Display) -> PersistError {
+    PersistError::io_other(
+        format!("Invalid archive, {}", reason),
+        std::io::ErrorKind::InvalidData,
+    )
+}
@@ -0,0 +1,158 @@
+//! Persisting asset handles by path (`assets` feature).
+
+use bevy::asset::{Asset, AssetPath, AssetServer, Handle};
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An asset handle that persists as its asset path.
+///
+/// Handles mean nothing in the next run, so only the path is saved. After
+/// loading, the handle is resolved again through the [`AssetServer`] by
+/// resources registered with
+/// [`PersistAppExt::resolve_persist_assets`](crate::PersistAppExt::resolve_persist_assets).
+///
+/// ```ignore
+/// #[derive(Resource, Default, Serialize, Deserialize, Persist)]
+/// struct Profile {
+///     portrait: PersistAsset<Image>,
+/// }
+///
+/// impl PersistAssets for Profile {
+///     fn resolve_assets(&mut self, assets: &AssetServer) {
+///         self.portrait.resolve(assets);
+///     }
+/// }
+///
+/// app.resolve_persist_assets::<Profile>();
+/// ```
+pub struct PersistAsset<A: Asset> {
+    path: Option<AssetPath<'static>>,
+    handle: Handle<A>,
+}
+
+impl<A: Asset> PersistAsset<A> {
+    /// Wraps a handle, remembering the path it was loaded from.
+    ///
+    /// Handles to assets made at runtime have no path and persist as `None`.
+    pub fn new(handle: Handle<A>) -> Self {
+        Self {
+            path: handle.path().cloned(),
+            handle,
+        }
+    }
+
+    /// Refers to an asset by path, without a handle until it is resolved.
+    pub fn from_path(path: impl Into<AssetPath<'static>>) -> Self {
+        Self {
+            path: Some(path.into()),
+            handle: Handle::default(),
+        }
+    }
+
+    /// The asset's path, if it has one.
+    pub fn path(&self) -> Option<&AssetPath<'static>> {
+        self.path.as_ref()
+    }
+
+    /// The asset's handle, which is the default handle until resolved.
+    pub fn handle(&self) -> &Handle<A> {
+        &self.handle
+    }
+
+    /// Points at another asset.
+    pub fn set(&mut self, handle: Handle<A>) {
+        *self = Self::new(handle);
+    }
+
+    /// Whether the handle matches the path.
+    pub fn is_resolved(&self) -> bool {
+        self.path.as_ref() == self.handle.path()
+    }
+
+    /// Loads the asset at the path, unless the handle already points to it.
+    pub fn resolve(&mut self, assets: &AssetServer) {
+        if self.is_resolved() {
+            return;
+        }
+        if let Some(path) = &self.path {
+            self.handle = assets.load(path.clone());
+        }
+    }
+}
+
+impl<A: Asset> Default for PersistAsset<A> {
+    fn default() -> Self {
+        Self {
+            path: None,
+            handle: Handle::default(),
+        }
+    }
+}
+
+impl<A: Asset> Clone for PersistAsset<A> {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl<A: Asset> std::fmt::Debug for PersistAsset<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistAsset")
+            .field("path", &self.path)
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+// Only the path is persisted, so it's all that counts
+impl<A: Asset> PartialEq for PersistAsset<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl<A: Asset> From<Handle<A>> for PersistAsset<A> {
+    fn from(handle: Handle<A>) -> Self {
+        Self::new(handle)
+    }
+}
+
+impl<A: Asset> Serialize for PersistAsset<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.path.serialize(serializer)
+    }
+}
+
+impl<'de, A: Asset> Deserialize<'de> for PersistAsset<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = Option::<AssetPath<'static>>::deserialize(deserializer)?;
+        Ok(Self {
+            path,
+            handle: Handle::default(),
+        })
+    }
+}
+
+/// A resource holding [`PersistAsset`] fields that need resolving after a load.
+pub trait PersistAssets {
+    /// Calls [`PersistAsset::resolve`] on every persisted asset.
+    fn resolve_assets(&mut self, assets: &AssetServer);
+}
+
+/// Resolves a resource's asset handles whenever it changes.
+///
+/// Resolving doesn't count as a change, so it doesn't trigger a save.
+pub fn resolve_persist_assets<R: Resource + PersistAssets>(
+    resource: Option<ResMut<R>>,
+    assets: Option<Res<AssetServer>>,
+) {
+    let (Some(mut resource), Some(assets)) = (resource, assets) else {
+        return;
+    };
+    if resource.is_changed() {
+        resource.bypass_change_detection().resolve_assets(&assets);
+    }
+}
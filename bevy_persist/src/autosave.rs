@@ -0,0 +1,211 @@
+//! Rotating autosave files, so a bad autosave never overwrites the only copy.
+
+use crate::{PersistError, PersistFile, PersistFormat, PersistManager, PersistMode, PersistResult};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use log::{error, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Autosave files kept unless configured otherwise
+pub(crate) const DEFAULT_AUTOSAVE_LIMIT: usize = 5;
+
+/// Start of every autosave file name
+const PREFIX: &str = "autosave_";
+
+/// Time in autosave file names, sortable and valid on every platform
+const TIME_FORMAT: &str = "%Y-%m-%dT%H-%M-%S";
+
+/// An autosave file, from [`PersistManager::list_autosaves`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistAutosave {
+    /// The autosave file
+    pub path: PathBuf,
+    /// When it was written, from its file name
+    pub saved_at: DateTime<Utc>,
+}
+
+impl PersistManager {
+    /// Writes every resource to a new timestamped autosave file each
+    /// `interval`, keeping only the newest `keep` files.
+    ///
+    /// Each file, like `autosave_2024-06-01T12-00-00.ron`, holds every
+    /// registered type, so one bad autosave can be undone with
+    /// [`restore_autosave`](Self::restore_autosave) instead of replacing
+    /// the only copy. The usual files are saved as before.
+    pub fn with_autosaves(mut self, interval: Duration, keep: usize) -> Self {
+        self.autosave_interval = Some(interval);
+        self.autosave_limit = keep.max(1);
+        self
+    }
+
+    /// Directory autosave files are written to.
+    pub fn autosave_dir(&self) -> PathBuf {
+        if self.is_production() {
+            self.resolve_path("autosaves", PersistMode::Dynamic, "")
+        } else {
+            self.dev_file.with_file_name("autosaves")
+        }
+    }
+
+    /// Writes every registered resource to a new autosave file now.
+    ///
+    /// Session data is left out. Older files beyond the
+    /// [configured](Self::with_autosaves) number are deleted. Returns the
+    /// new file.
+    pub fn autosave(world: &mut World) -> PersistResult<PathBuf> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let now = manager.now();
+        let mut file = manager.capture_file(world, |_| true)?;
+
+        let path =
+            manager
+                .autosave_dir()
+                .join(format!("{}{}.ron", PREFIX, now.format(TIME_FORMAT)));
+        file.save_to_file_at(&path, PersistFormat::Ron, &manager.style, now)?;
+        info!("Autosaved to {:?}", path);
+
+        let mut manager = world.resource_mut::<PersistManager>();
+        manager.last_autosave = Some(Instant::now());
+        manager.prune_autosaves();
+        Ok(path)
+    }
+
+    /// Every autosave file, newest first.
+    pub fn list_autosaves(&self) -> Vec<PersistAutosave> {
+        let Ok(entries) = fs::read_dir(self.autosave_dir()) else {
+            return Vec::new();
+        };
+        let mut autosaves: Vec<_> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let saved_at = autosave_time(&path)?;
+                Some(PersistAutosave { path, saved_at })
+            })
+            .collect();
+        autosaves.sort_by_key(|autosave| std::cmp::Reverse(autosave.saved_at));
+        autosaves
+    }
+
+    /// Applies the resources in an autosave file to the world.
+    ///
+    /// Restored resources are marked as changed, so auto-save writes them
+    /// to their usual files like any other change. Types that aren't
+    /// registered are skipped. Returns how many resources were restored.
+    pub fn restore_autosave(world: &mut World, path: impl AsRef<Path>) -> PersistResult<usize> {
+        let path = path.as_ref();
+        let file = PersistFile::load_from_file_as(path, PersistFormat::Ron)?;
+        let restored = Self::restore_file(world, file, |_| true)?;
+        info!("Restored {} resources from {:?}", restored, path);
+        Ok(restored)
+    }
+
+    /// Every registered resource passing `include`, sealed for writing
+    /// to one file.
+    ///
+    /// Session data is left out.
+    pub(crate) fn capture_file(
+        &self,
+        world: &World,
+        include: impl Fn(&str) -> bool,
+    ) -> PersistResult<PersistFile> {
+        let now = self.now();
+        let mut file = PersistFile::new();
+        for (type_name, hooks) in &self.hooks {
+            if self.get_type_mode(type_name) == PersistMode::Session || !include(type_name) {
+                continue;
+            }
+            let Some(data) = hooks.extract_from_world(world) else {
+                continue;
+            };
+            let data = self
+                .seal_fields(type_name, data)
+                .map_err(|e| e.for_type(self.get_type_name(type_name)))?;
+            file.set_type_data_at(type_name.clone(), data, now);
+        }
+        file.version = self.file_version();
+        Ok(file)
+    }
+
+    /// Applies the registered resources in a file from
+    /// [`capture_file`](Self::capture_file) that pass `include`, returning
+    /// how many were restored.
+    pub(crate) fn restore_file(
+        world: &mut World,
+        file: PersistFile,
+        include: impl Fn(&str) -> bool,
+    ) -> PersistResult<usize> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let restores: Vec<_> = file
+            .type_data
+            .into_iter()
+            .filter(|(type_name, _)| include(type_name))
+            .filter_map(|(type_name, data)| {
+                let key = manager.get_storage_key(&type_name);
+                let hooks = manager.get_type_hooks(&key)?.clone();
+                Some((hooks, manager.unseal_fields(&key, data)))
+            })
+            .collect();
+
+        let mut restored = 0;
+        for (hooks, data) in restores {
+            if hooks.apply_to_world(world, &data) {
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Deletes the oldest autosave files beyond the limit.
+    fn prune_autosaves(&self) {
+        for autosave in self.list_autosaves().iter().skip(self.autosave_limit) {
+            if let Err(e) = fs::remove_file(&autosave.path) {
+                warn!("Failed to remove old autosave {:?}: {}", autosave.path, e);
+            }
+        }
+    }
+
+    /// Whether the autosave interval has passed since the last autosave.
+    fn autosave_due(&self) -> bool {
+        let Some(interval) = self.autosave_interval else {
+            return false;
+        };
+        self.last_autosave
+            .map_or(true, |last| last.elapsed() >= interval)
+    }
+}
+
+/// When an autosave file was written, or `None` if `path` isn't one.
+fn autosave_time(path: &Path) -> Option<DateTime<Utc>> {
+    if path.extension()? != "ron" {
+        return None;
+    }
+    let stamp = path.file_stem()?.to_str()?.strip_prefix(PREFIX)?;
+    let time = NaiveDateTime::parse_from_str(stamp, TIME_FORMAT).ok()?;
+    Some(time.and_utc())
+}
+
+/// Writes an autosave file once the configured interval has passed.
+pub(crate) fn run_autosaves(world: &mut World) {
+    let Some(manager) = world.get_resource::<PersistManager>() else {
+        return;
+    };
+    if manager.is_read_only() || !manager.autosave_due() {
+        return;
+    }
+    if manager.last_autosave.is_none() {
+        // The first autosave waits a full interval, like every later one
+        world.resource_mut::<PersistManager>().last_autosave = Some(Instant::now());
+        return;
+    }
+    if let Err(e) = PersistManager::autosave(world) {
+        error!("Failed to autosave: {}", e);
+        world.resource_mut::<PersistManager>().last_autosave = Some(Instant::now());
+    }
+}
@@ -0,0 +1,239 @@
+//! Persisting components on entities tagged with a [`PersistId`].
+
+use crate::{
+    PersistData, PersistHooks, PersistLoadState, PersistLoaded, PersistManager, PersistMode,
+    PersistSet,
+};
+use bevy::prelude::*;
+use log::error;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info_span;
+
+/// A stable identifier for an entity whose components are persisted.
+///
+/// Entity ids change between runs, so persisted components are stored
+/// under this id instead. On load, an entity with a matching id gets its
+/// components overwritten; ids with no entity are spawned.
+///
+/// ```ignore
+/// commands.spawn((PersistId::new("player"), Health(100)));
+/// ```
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PersistId(pub String);
+
+impl PersistId {
+    /// Creates an id from any string.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// The id as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for PersistId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<String> for PersistId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl std::fmt::Display for PersistId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A component persisted for every entity that has a [`PersistId`].
+///
+/// Usually derived with `#[derive(PersistComponent)]`, which takes the
+/// same `name`, `file`, `store`, `auto_save`, `dynamic`, `secure`, `obfuscated`
+/// and `session` attributes as `#[derive(Persist)]`. Each component type is one
+/// entry in the persist file, holding a value per entity id.
+pub trait PersistComponent: Component + Serialize + DeserializeOwned {
+    /// Get the name this component is persisted under
+    fn type_name() -> &'static str;
+
+    /// Get the full Rust path of the component
+    fn type_path() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Get the persistence mode for this component
+    fn persist_mode() -> PersistMode {
+        PersistMode::Dev
+    }
+
+    /// Get the file this component is pinned to, if any
+    fn persist_file() -> Option<&'static str> {
+        None
+    }
+
+    /// Whether this component auto-saves when no explicit preference is given
+    fn default_auto_save() -> bool {
+        true
+    }
+}
+
+impl PersistHooks {
+    /// Hooks that persist a component on every entity with a [`PersistId`].
+    ///
+    /// Applying spawns or overwrites entities by id, and resetting removes
+    /// the component from them. Entities missing from the data are left
+    /// alone.
+    pub fn for_component<T: PersistComponent>() -> Self {
+        Self {
+            // There's no single value to extract from or apply to
+            extract: Arc::new(|_| None),
+            apply: Arc::new(|_, _| {}),
+            apply_to_world: Arc::new(|world, data| {
+                apply_components::<T>(world, data);
+                true
+            }),
+            extract_from_world: Arc::new(|world| {
+                let mut query = world.try_query::<(&PersistId, &T)>()?;
+                let mut data = PersistData::new();
+                for (id, component) in query.iter(world) {
+                    data.insert(id.as_str(), component);
+                }
+                Some(data)
+            }),
+            reset: Some(Arc::new(|world| {
+                let entities: Vec<Entity> = world
+                    .query_filtered::<Entity, (With<PersistId>, With<T>)>()
+                    .iter(world)
+                    .collect();
+                for entity in entities {
+                    world.entity_mut(entity).remove::<T>();
+                }
+                true
+            })),
+            validate: None,
+            before_save: None,
+        }
+    }
+}
+
+/// Writes each id's component onto its entity, spawning missing ones.
+fn apply_components<T: PersistComponent>(world: &mut World, data: &PersistData) {
+    let mut entities: HashMap<String, Entity> = world
+        .query::<(Entity, &PersistId)>()
+        .iter(world)
+        .map(|(entity, id)| (id.0.clone(), entity))
+        .collect();
+
+    for id in data.keys() {
+        let Some(component) = data.get::<T>(id) else {
+            error!("Failed to apply persisted {} for {}", T::type_name(), id);
+            continue;
+        };
+        match entities.get(id) {
+            Some(&entity) => {
+                world.entity_mut(entity).insert(component);
+            }
+            None => {
+                let entity = world.spawn((PersistId::new(id), component)).id();
+                entities.insert(id.to_string(), entity);
+            }
+        }
+    }
+}
+
+/// Registers a persisted component with the manager and adds its systems.
+///
+/// Used by `#[derive(PersistComponent)]` and
+/// [`PersistAppExt::register_persist_component`](crate::PersistAppExt::register_persist_component).
+pub fn register_persist_component<T: PersistComponent>(app: &mut App, auto_save: bool) {
+    let type_name = T::type_name();
+    let key = storage_key::<T>(app.world());
+    if let Some(mut manager) = app.world_mut().get_resource_mut::<PersistManager>() {
+        manager.set_type_path(type_name.to_string(), T::type_path());
+        manager.set_type_auto_save(key.clone(), auto_save);
+        manager.set_type_mode(key.clone(), T::persist_mode());
+        if let Some(file) = T::persist_file() {
+            manager.set_type_file(key.clone(), file);
+        }
+        manager.set_type_hooks(key.clone(), PersistHooks::for_component::<T>());
+    }
+    app.world_mut()
+        .get_resource_or_init::<PersistLoadState>()
+        .expect::<T>(&key, type_name);
+
+    // After Startup, so entities spawned there are overwritten rather than duplicated
+    app.add_systems(
+        PostStartup,
+        load_persisted_components::<T>.in_set(PersistSet::Load),
+    )
+    .add_systems(
+        PostUpdate,
+        persist_component_system::<T>.in_set(PersistSet::Save),
+    );
+}
+
+/// Loads a persisted component onto its entities.
+pub fn load_persisted_components<T: PersistComponent>(world: &mut World) {
+    let type_name = T::type_name();
+    let _span = info_span!("persist_load", type_name).entered();
+    let key = storage_key::<T>(world);
+    world
+        .get_resource_or_init::<PersistLoadState>()
+        .finish(&key);
+    let Some(manager) = world.get_resource::<PersistManager>() else {
+        return;
+    };
+    let (Some(hooks), Some(data)) = (
+        manager.get_type_hooks(&key).cloned(),
+        manager.read_type_data(&key, None),
+    ) else {
+        return;
+    };
+
+    hooks.apply_to_world(world, &data);
+    world.resource_mut::<PersistManager>().mark_loaded(&key);
+    world.send_event(PersistLoaded {
+        type_name: type_name.to_string(),
+    });
+}
+
+/// Persisted entities whose component or id changed.
+type ChangedPersisted<T> = (
+    With<PersistId>,
+    With<T>,
+    Or<(Changed<T>, Changed<PersistId>)>,
+);
+
+/// Marks a component for saving when it changes, or leaves an entity.
+pub fn persist_component_system<T: PersistComponent>(
+    mut manager: ResMut<PersistManager>,
+    changed: Query<(), ChangedPersisted<T>>,
+    mut removed: RemovedComponents<T>,
+    mut removed_ids: RemovedComponents<PersistId>,
+) {
+    // Both readers are drained every frame so old removals don't linger
+    let removed = removed.read().count() + removed_ids.read().count() > 0;
+    if !removed && changed.is_empty() {
+        return;
+    }
+    let key = manager.key_for(T::type_name(), T::type_path());
+    if manager.is_auto_save_enabled(&key) {
+        manager.mark_dirty_named(&key);
+    }
+}
+
+/// Storage key of `T`, or its type name before a manager is added.
+fn storage_key<T: PersistComponent>(world: &World) -> String {
+    world.get_resource::<PersistManager>().map_or_else(
+        || T::type_name().to_string(),
+        |manager| manager.key_for(T::type_name(), T::type_path()),
+    )
+}
@@ -0,0 +1,36 @@
+//! Gzip for saves that would otherwise go over their size quota (`compression` feature).
+
+use crate::{PersistError, PersistResult};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// First bytes of every gzip stream
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Gzips `plain`.
+pub(crate) fn compress(plain: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(plain)
+        .expect("writing to a Vec can't fail");
+    encoder.finish().expect("writing to a Vec can't fail")
+}
+
+/// Whether `bytes` were written by [`compress`].
+///
+/// RON and JSON text never starts with these bytes, so uncompressed files
+/// are read as they always were.
+pub(crate) fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Undoes [`compress`].
+pub(crate) fn decompress(bytes: &[u8]) -> PersistResult<Vec<u8>> {
+    let mut plain = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut plain)
+        .map_err(|e| PersistError::serialization(format!("Invalid compressed data: {}", e)))?;
+    Ok(plain)
+}
@@ -0,0 +1,701 @@
+//! Format-preserving storage for the fields of [`PersistData`](crate::PersistData).
+//!
+//! Capturing a value as [`Content`] records exactly which serializer calls it
+//! makes, so replaying it into RON, JSON or TOML produces the same output as
+//! serializing the original value. Unlike `serde_json::Value` this keeps enum
+//! variant and struct names, 128-bit integers and `f32` precision.
+//!
+//! Fields read from a file are kept as they were written ([`FieldValue`]) and
+//! deserialized straight from that text when a resource is loaded.
+
+use ron::value::RawValue;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use serde::Deserializer;
+use std::borrow::Cow;
+use std::fmt;
+
+/// One value captured from a [`Serialize`] implementation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Content {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Content>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(&'static str, u32, &'static str),
+    NewtypeStruct(&'static str, Box<Content>),
+    NewtypeVariant(&'static str, u32, &'static str, Box<Content>),
+    Seq(Vec<Content>),
+    Tuple(Vec<Content>),
+    TupleStruct(&'static str, Vec<Content>),
+    TupleVariant(&'static str, u32, &'static str, Vec<Content>),
+    Map(Vec<(Content, Content)>),
+    Struct(&'static str, Vec<(&'static str, Content)>),
+    StructVariant(
+        &'static str,
+        u32,
+        &'static str,
+        Vec<(&'static str, Content)>,
+    ),
+}
+
+impl Content {
+    /// Captures a value.
+    pub(crate) fn capture<T: Serialize + ?Sized>(value: &T) -> Result<Self, ContentError> {
+        value.serialize(ContentSerializer)
+    }
+}
+
+impl Serialize for Content {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Content::Bool(v) => serializer.serialize_bool(*v),
+            Content::I8(v) => serializer.serialize_i8(*v),
+            Content::I16(v) => serializer.serialize_i16(*v),
+            Content::I32(v) => serializer.serialize_i32(*v),
+            Content::I64(v) => serializer.serialize_i64(*v),
+            Content::I128(v) => serializer.serialize_i128(*v),
+            Content::U8(v) => serializer.serialize_u8(*v),
+            Content::U16(v) => serializer.serialize_u16(*v),
+            Content::U32(v) => serializer.serialize_u32(*v),
+            Content::U64(v) => serializer.serialize_u64(*v),
+            Content::U128(v) => serializer.serialize_u128(*v),
+            Content::F32(v) => serializer.serialize_f32(*v),
+            Content::F64(v) => serializer.serialize_f64(*v),
+            Content::Char(v) => serializer.serialize_char(*v),
+            Content::String(v) => serializer.serialize_str(v),
+            Content::Bytes(v) => serializer.serialize_bytes(v),
+            Content::None => serializer.serialize_none(),
+            Content::Some(v) => serializer.serialize_some(&**v),
+            Content::Unit => serializer.serialize_unit(),
+            Content::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Content::UnitVariant(name, index, variant) => {
+                serializer.serialize_unit_variant(name, *index, variant)
+            }
+            Content::NewtypeStruct(name, v) => serializer.serialize_newtype_struct(name, &**v),
+            Content::NewtypeVariant(name, index, variant, v) => {
+                serializer.serialize_newtype_variant(name, *index, variant, &**v)
+            }
+            Content::Seq(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Content::Tuple(items) => {
+                let mut tuple = serializer.serialize_tuple(items.len())?;
+                for item in items {
+                    tuple.serialize_element(item)?;
+                }
+                tuple.end()
+            }
+            Content::TupleStruct(name, items) => {
+                let mut tuple = serializer.serialize_tuple_struct(name, items.len())?;
+                for item in items {
+                    tuple.serialize_field(item)?;
+                }
+                tuple.end()
+            }
+            Content::TupleVariant(name, index, variant, items) => {
+                let mut tuple =
+                    serializer.serialize_tuple_variant(name, *index, variant, items.len())?;
+                for item in items {
+                    tuple.serialize_field(item)?;
+                }
+                tuple.end()
+            }
+            Content::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Content::Struct(name, fields) => {
+                let mut object = serializer.serialize_struct(name, fields.len())?;
+                for (key, value) in fields {
+                    object.serialize_field(key, value)?;
+                }
+                object.end()
+            }
+            Content::StructVariant(name, index, variant, fields) => {
+                let mut object =
+                    serializer.serialize_struct_variant(name, *index, variant, fields.len())?;
+                for (key, value) in fields {
+                    object.serialize_field(key, value)?;
+                }
+                object.end()
+            }
+        }
+    }
+}
+
+/// A [`Serialize`] implementation failed while being captured.
+#[derive(Debug)]
+pub(crate) struct ContentError(String);
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+impl ser::Error for ContentError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ContentError(msg.to_string())
+    }
+}
+
+impl de::Error for ContentError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ContentError(msg.to_string())
+    }
+}
+
+struct ContentSerializer;
+
+impl Serializer for ContentSerializer {
+    type Ok = Content;
+    type Error = ContentError;
+    type SerializeSeq = SeqCapture;
+    type SerializeTuple = SeqCapture;
+    type SerializeTupleStruct = SeqCapture;
+    type SerializeTupleVariant = SeqCapture;
+    type SerializeMap = MapCapture;
+    type SerializeStruct = StructCapture;
+    type SerializeStructVariant = StructCapture;
+
+    fn serialize_bool(self, v: bool) -> Result<Content, ContentError> {
+        Ok(Content::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Content, ContentError> {
+        Ok(Content::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Content, ContentError> {
+        Ok(Content::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Content, ContentError> {
+        Ok(Content::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Content, ContentError> {
+        Ok(Content::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Content, ContentError> {
+        Ok(Content::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Content, ContentError> {
+        Ok(Content::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Content, ContentError> {
+        Ok(Content::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Content, ContentError> {
+        Ok(Content::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Content, ContentError> {
+        Ok(Content::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Content, ContentError> {
+        Ok(Content::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Content, ContentError> {
+        Ok(Content::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Content, ContentError> {
+        Ok(Content::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Content, ContentError> {
+        Ok(Content::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Content, ContentError> {
+        Ok(Content::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Content, ContentError> {
+        Ok(Content::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Content, ContentError> {
+        Ok(Content::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Content, ContentError> {
+        Ok(Content::Some(Box::new(Content::capture(value)?)))
+    }
+
+    fn serialize_unit(self) -> Result<Content, ContentError> {
+        Ok(Content::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Content, ContentError> {
+        Ok(Content::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<Content, ContentError> {
+        Ok(Content::UnitVariant(name, index, variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Content, ContentError> {
+        Ok(Content::NewtypeStruct(
+            name,
+            Box::new(Content::capture(value)?),
+        ))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Content, ContentError> {
+        Ok(Content::NewtypeVariant(
+            name,
+            index,
+            variant,
+            Box::new(Content::capture(value)?),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqCapture, ContentError> {
+        Ok(SeqCapture::new(SeqKind::Seq, len.unwrap_or_default()))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqCapture, ContentError> {
+        Ok(SeqCapture::new(SeqKind::Tuple, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SeqCapture, ContentError> {
+        Ok(SeqCapture::new(SeqKind::TupleStruct(name), len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqCapture, ContentError> {
+        Ok(SeqCapture::new(
+            SeqKind::TupleVariant(name, index, variant),
+            len,
+        ))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapCapture, ContentError> {
+        Ok(MapCapture {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<StructCapture, ContentError> {
+        Ok(StructCapture {
+            variant: None,
+            name,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<StructCapture, ContentError> {
+        Ok(StructCapture {
+            variant: Some((index, variant)),
+            name,
+            fields: Vec::with_capacity(len),
+        })
+    }
+}
+
+enum SeqKind {
+    Seq,
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant(&'static str, u32, &'static str),
+}
+
+struct SeqCapture {
+    kind: SeqKind,
+    items: Vec<Content>,
+}
+
+impl SeqCapture {
+    fn new(kind: SeqKind, len: usize) -> Self {
+        Self {
+            kind,
+            items: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.items.push(Content::capture(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, ContentError> {
+        Ok(match self.kind {
+            SeqKind::Seq => Content::Seq(self.items),
+            SeqKind::Tuple => Content::Tuple(self.items),
+            SeqKind::TupleStruct(name) => Content::TupleStruct(name, self.items),
+            SeqKind::TupleVariant(name, index, variant) => {
+                Content::TupleVariant(name, index, variant, self.items)
+            }
+        })
+    }
+}
+
+impl SerializeSeq for SeqCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for SeqCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for SeqCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for SeqCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+struct MapCapture {
+    entries: Vec<(Content, Content)>,
+    key: Option<Content>,
+}
+
+impl SerializeMap for MapCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ContentError> {
+        self.key = Some(Content::capture(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ContentError("map value serialized before its key".to_string()))?;
+        self.entries.push((key, Content::capture(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        Ok(Content::Map(self.entries))
+    }
+}
+
+struct StructCapture {
+    variant: Option<(u32, &'static str)>,
+    name: &'static str,
+    fields: Vec<(&'static str, Content)>,
+}
+
+impl SerializeStruct for StructCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ContentError> {
+        self.fields.push((key, Content::capture(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        Ok(Content::Struct(self.name, self.fields))
+    }
+}
+
+impl SerializeStructVariant for StructCapture {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ContentError> {
+        self.fields.push((key, Content::capture(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        let (index, variant) = self.variant.unwrap_or_default();
+        Ok(Content::StructVariant(
+            self.name,
+            index,
+            variant,
+            self.fields,
+        ))
+    }
+}
+
+/// The stored value of one persisted field.
+#[derive(Debug, Clone)]
+pub(crate) enum FieldValue {
+    /// Captured from a live value
+    Captured(Content),
+    /// Read from a RON file, kept exactly as written
+    Ron(Box<RawValue>),
+    /// Read from a JSON or TOML file, or set through the JSON introspection API
+    Json(serde_json::Value),
+}
+
+impl FieldValue {
+    /// Converts to JSON for introspection.
+    ///
+    /// RON values are converted without their type, with enum variants laid
+    /// out as `serde_json` would write them.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        match self {
+            FieldValue::Captured(content) => serde_json::to_value(content).unwrap_or_default(),
+            FieldValue::Ron(raw) => crate::ron_json::to_json(raw.get_ron()).unwrap_or_default(),
+            FieldValue::Json(value) => value.clone(),
+        }
+    }
+
+    /// A compact rendering used to detect changes.
+    pub(crate) fn canonical(&self) -> String {
+        match self {
+            FieldValue::Captured(content) => ron::to_string(content).unwrap_or_default(),
+            FieldValue::Ron(raw) => raw.get_ron().trim().to_string(),
+            FieldValue::Json(value) => value.to_string(),
+        }
+    }
+
+    /// Whether this is RON text that a non-RON file can't hold as-is.
+    pub(crate) fn is_ron(&self) -> bool {
+        matches!(self, FieldValue::Ron(_))
+    }
+
+    /// Text or JSON that a typed deserializer can read directly.
+    pub(crate) fn source(&self) -> Result<FieldSource<'_>, ContentError> {
+        Ok(match self {
+            FieldValue::Captured(content) => FieldSource::Ron(Cow::Owned(
+                ron::to_string(content).map_err(<ContentError as ser::Error>::custom)?,
+            )),
+            FieldValue::Ron(raw) => FieldSource::Ron(Cow::Borrowed(raw.get_ron())),
+            FieldValue::Json(value) => FieldSource::Json(value),
+        })
+    }
+
+    /// Deserializes this field on its own.
+    pub(crate) fn deserialize<T: DeserializeOwned>(&self) -> Result<T, ContentError> {
+        let source = self.source()?;
+        T::deserialize(source.deserializer())
+    }
+}
+
+impl PartialEq for FieldValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FieldValue::Captured(a), FieldValue::Captured(b)) => a == b,
+            (FieldValue::Ron(a), FieldValue::Ron(b)) => a.get_ron().trim() == b.get_ron().trim(),
+            (FieldValue::Json(a), FieldValue::Json(b)) => a == b,
+            _ => self.to_json() == other.to_json(),
+        }
+    }
+}
+
+impl Serialize for FieldValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FieldValue::Captured(content) => content.serialize(serializer),
+            // Written verbatim by the RON serializer
+            FieldValue::Ron(raw) => raw.trim().serialize(serializer),
+            FieldValue::Json(value) => value.serialize(serializer),
+        }
+    }
+}
+
+/// A field ready to be deserialized, see [`FieldValue::source`].
+pub(crate) enum FieldSource<'a> {
+    Ron(Cow<'a, str>),
+    Json(&'a serde_json::Value),
+}
+
+impl FieldSource<'_> {
+    pub(crate) fn deserializer(&self) -> FieldDeserializer<'_> {
+        match self {
+            FieldSource::Ron(text) => FieldDeserializer::Ron(text),
+            FieldSource::Json(value) => FieldDeserializer::Json(value),
+        }
+    }
+}
+
+/// Deserializes a field with the deserializer of the format it was stored in.
+pub(crate) enum FieldDeserializer<'de> {
+    Ron(&'de str),
+    Json(&'de serde_json::Value),
+}
+
+macro_rules! forward_to_format {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V: Visitor<'de>>(
+            self,
+            $($arg: $ty,)*
+            visitor: V,
+        ) -> Result<V::Value, ContentError> {
+            match self {
+                FieldDeserializer::Ron(text) => {
+                    let mut ron = ron::Deserializer::from_str(text)
+                        .map_err(<ContentError as de::Error>::custom)?;
+                    let value = (&mut ron)
+                        .$method($($arg,)* visitor)
+                        .map_err(<ContentError as de::Error>::custom)?;
+                    ron.end().map_err(<ContentError as de::Error>::custom)?;
+                    Ok(value)
+                }
+                FieldDeserializer::Json(value) => value
+                    .$method($($arg,)* visitor)
+                    .map_err(<ContentError as de::Error>::custom),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for FieldDeserializer<'de> {
+    type Error = ContentError;
+
+    forward_to_format! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ContentError> for FieldDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
@@ -0,0 +1,100 @@
+//! Bevy diagnostics for save activity.
+
+use crate::{PersistManager, PersistSet};
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore, RegisterDiagnostic,
+};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+/// Publishes [`PersistStats`](crate::PersistStats) as Bevy diagnostics.
+///
+/// Besides the totals below, each type gets a `persist/saves/<TypeName>`
+/// diagnostic once it has been saved. A count that keeps climbing usually
+/// means a system is touching a resource every frame.
+///
+/// ```ignore
+/// app.add_plugins(PersistDiagnosticsPlugin)
+///     .add_plugins(LogDiagnosticsPlugin::default());
+/// ```
+pub struct PersistDiagnosticsPlugin;
+
+impl PersistDiagnosticsPlugin {
+    /// Number of saves this session.
+    pub const SAVE_COUNT: DiagnosticPath = DiagnosticPath::const_new("persist/save_count");
+    /// Total bytes written this session.
+    pub const BYTES_WRITTEN: DiagnosticPath = DiagnosticPath::const_new("persist/bytes_written");
+    /// Duration of the most recent save, in milliseconds.
+    pub const SAVE_DURATION: DiagnosticPath = DiagnosticPath::const_new("persist/save_duration");
+    /// Number of auto-saves skipped because nothing had changed.
+    pub const SKIPPED_SAVES: DiagnosticPath = DiagnosticPath::const_new("persist/skipped_saves");
+
+    /// Path of the save count diagnostic for one type.
+    pub fn type_save_count(type_name: &str) -> DiagnosticPath {
+        DiagnosticPath::from_components(["persist", "saves", type_name])
+    }
+}
+
+impl Plugin for PersistDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::SAVE_COUNT))
+            .register_diagnostic(Diagnostic::new(Self::BYTES_WRITTEN).with_suffix(" B"))
+            .register_diagnostic(Diagnostic::new(Self::SAVE_DURATION).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::SKIPPED_SAVES))
+            .add_systems(PostUpdate, record_diagnostics.after(PersistSet::Flush));
+    }
+}
+
+fn record_diagnostics(
+    manager: Res<PersistManager>,
+    mut store: ResMut<DiagnosticsStore>,
+    mut last_counts: Local<(u64, u64)>,
+) {
+    let stats = manager.stats();
+    let counts = (stats.saves, stats.skipped_saves);
+    if counts == *last_counts {
+        return;
+    }
+    *last_counts = counts;
+
+    measure(
+        &mut store,
+        &PersistDiagnosticsPlugin::SAVE_COUNT,
+        stats.saves as f64,
+    );
+    measure(
+        &mut store,
+        &PersistDiagnosticsPlugin::BYTES_WRITTEN,
+        stats.bytes_written as f64,
+    );
+    measure(
+        &mut store,
+        &PersistDiagnosticsPlugin::SKIPPED_SAVES,
+        stats.skipped_saves as f64,
+    );
+    if let Some(duration) = stats.last_save_duration {
+        measure(
+            &mut store,
+            &PersistDiagnosticsPlugin::SAVE_DURATION,
+            duration.as_secs_f64() * 1000.0,
+        );
+    }
+
+    for (type_name, &saves) in &stats.saves_per_type {
+        let path = PersistDiagnosticsPlugin::type_save_count(type_name);
+        // Types are only known once they're saved
+        if store.get(&path).is_none() {
+            store.add(Diagnostic::new(path.clone()));
+        }
+        measure(&mut store, &path, saves as f64);
+    }
+}
+
+fn measure(store: &mut DiagnosticsStore, path: &DiagnosticPath, value: f64) {
+    if let Some(diagnostic) = store.get_mut(path).filter(|d| d.is_enabled) {
+        diagnostic.add_measurement(DiagnosticMeasurement {
+            time: Instant::now(),
+            value,
+        });
+    }
+}
@@ -0,0 +1,48 @@
+//! Writes field doc comments into pretty-printed RON files.
+
+use crate::PersistData;
+use std::collections::BTreeMap;
+
+/// Adds each field's doc comment as `//` lines above it.
+///
+/// `content` is a whole file as written by [`PersistFile`](crate::PersistFile),
+/// pretty-printed with `indent`: entries one level deep, their fields three
+/// levels deep inside `values`. Lines that don't fit that shape, like the
+/// insides of nested values, are copied as they are.
+pub(crate) fn write_field_docs(
+    content: &str,
+    indent: &str,
+    entries: &BTreeMap<String, PersistData>,
+) -> String {
+    let field_indent = indent.repeat(3);
+    let mut output = String::with_capacity(content.len());
+    let mut entry: Option<&PersistData> = None;
+
+    for line in content.split_inclusive('\n') {
+        if let Some(key) = quoted_key(line, indent) {
+            entry = entries.get(key);
+        } else if let Some(doc) = quoted_key(line, &field_indent)
+            .zip(entry)
+            .and_then(|(field, data)| data.field_doc(field))
+        {
+            for doc_line in doc.lines() {
+                output.push_str(&field_indent);
+                output.push_str("//");
+                if !doc_line.is_empty() {
+                    output.push(' ');
+                    output.push_str(doc_line);
+                }
+                output.push('\n');
+            }
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+/// The key of a `"key": value` line indented by exactly `indent`.
+fn quoted_key<'a>(line: &'a str, indent: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(indent)?.strip_prefix('"')?;
+    let (key, _) = rest.split_once("\": ")?;
+    Some(key)
+}
@@ -0,0 +1,155 @@
+//! Reapplies persisted files edited outside the game (`hot-reload` feature).
+
+use crate::{validate_loaded, PersistLoaded, PersistManager};
+use bevy::prelude::*;
+use log::{debug, error, info, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+
+/// Watches the files registered types are stored in.
+///
+/// Inserted at startup when [`PersistPlugin::with_hot_reload`](crate::PersistPlugin::with_hot_reload)
+/// is enabled. Only files that exist once startup loading is done are watched.
+#[derive(Resource)]
+pub struct PersistWatcher {
+    _watcher: RecommendedWatcher,
+    events: Mutex<Receiver<notify::Result<Event>>>,
+    files: HashSet<PathBuf>,
+}
+
+impl PersistWatcher {
+    /// Whether a file is being watched for edits.
+    pub fn is_watching(&self, path: impl AsRef<Path>) -> bool {
+        watch_key(path.as_ref()).is_some_and(|key| self.files.contains(&key))
+    }
+
+    /// Takes the watched files that changed since the last call.
+    fn changed_files(&self) -> HashSet<PathBuf> {
+        let Ok(events) = self.events.lock() else {
+            return HashSet::new();
+        };
+        events
+            .try_iter()
+            .filter_map(|event| match event {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    warn!("File watcher error: {}", e);
+                    None
+                }
+            })
+            .filter(|event| event.kind.is_modify() || event.kind.is_create())
+            .flat_map(|event| event.paths)
+            .filter(|path| self.files.contains(path))
+            .collect()
+    }
+}
+
+/// Canonical form of a path, so relative paths match the ones the watcher reports.
+///
+/// The file itself may not exist, so only its directory is canonicalized.
+fn watch_key(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(parent.canonicalize().ok()?.join(file_name))
+}
+
+/// Starts watching the directories of every registered type's file.
+pub(crate) fn start_watching(mut commands: Commands, manager: Res<PersistManager>) {
+    let files: HashSet<PathBuf> = manager
+        .registered_types()
+        .filter_map(|info| info.path)
+        .chain(std::iter::once(manager.dev_file.clone()))
+        .filter_map(|path| watch_key(&path))
+        .collect();
+
+    let (sender, receiver) = channel();
+    let mut watcher = match notify::recommended_watcher(sender) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            error!("Failed to start file watcher: {}", e);
+            return;
+        }
+    };
+
+    let directories: HashSet<&Path> = files.iter().filter_map(|path| path.parent()).collect();
+    for directory in directories {
+        // Watching the directory catches editors that save by replacing the file
+        match watcher.watch(directory, RecursiveMode::NonRecursive) {
+            Ok(()) => debug!("Watching {:?} for persisted file edits", directory),
+            Err(e) => error!("Failed to watch {:?}: {}", directory, e),
+        }
+    }
+
+    commands.insert_resource(PersistWatcher {
+        _watcher: watcher,
+        events: Mutex::new(receiver),
+        files,
+    });
+}
+
+/// Applies edited files to the live resources.
+///
+/// Types whose resource already matches the file are left alone, which also
+/// ignores the events caused by our own saves. Reapplied data is not saved
+/// back.
+pub(crate) fn apply_file_changes(world: &mut World) {
+    let Some(changed) = world
+        .get_resource::<PersistWatcher>()
+        .map(PersistWatcher::changed_files)
+    else {
+        return;
+    };
+    if changed.is_empty() {
+        return;
+    }
+
+    world.resource_scope(|world, mut manager: Mut<PersistManager>| {
+        // A file caught mid-write fails to parse; the next event retries it
+        if let Err(e) = manager.refresh_from_disk() {
+            warn!("Skipping hot reload: {}", e);
+            return;
+        }
+
+        let pending: Vec<_> = manager
+            .registered_types()
+            .filter(|info| {
+                info.path
+                    .as_deref()
+                    .and_then(watch_key)
+                    .is_some_and(|key| changed.contains(&key))
+            })
+            .map(|info| info.storage_key.to_string())
+            .collect();
+
+        for type_name in pending {
+            let Some(hooks) = manager.get_type_hooks(&type_name).cloned() else {
+                continue;
+            };
+            let Some(data) = manager.read_type_data(&type_name, None) else {
+                continue;
+            };
+            if hooks.extract_from_world(world).as_ref() == Some(&data) {
+                continue;
+            }
+            if hooks.apply_to_world(world, &data) {
+                info!("Hot-reloaded {}", type_name);
+                validate_loaded(world, &type_name, &hooks, manager.validation_policy());
+                if let Some(applied) = hooks.extract_from_world(world) {
+                    manager.keep_unknown_fields(&type_name, &data, &applied);
+                    manager.remember_loaded_save(&type_name, &data, &applied);
+                    manager.suppress_echo_save(&type_name, applied);
+                }
+                manager.mark_loaded(&type_name);
+                world.send_event(PersistLoaded {
+                    type_name: manager.get_type_name(&type_name).to_string(),
+                });
+            }
+        }
+    });
+}
@@ -0,0 +1,191 @@
+//! Importing settings from legacy INI files (`ini` feature).
+
+use crate::{
+    display_name, PersistData, PersistError, PersistFile, PersistManager, PersistResult,
+    Persistable,
+};
+use bevy::prelude::*;
+use ini::Ini;
+use log::{debug, info};
+use std::fs;
+use std::path::Path;
+
+impl PersistFile {
+    /// Parses INI text, with each section's keys becoming one type's fields.
+    ///
+    /// Entries are keyed by section name. Values that read as booleans or
+    /// numbers become those, values starting with `[` or `{` are read as
+    /// JSON if they parse, and everything else stays a string. Keys outside
+    /// any section are ignored.
+    pub fn parse_ini(content: &str) -> PersistResult<Self> {
+        let parsed = Ini::load_from_str(content)
+            .map_err(|e| PersistError::serialization(format!("INI parse error: {}", e)))?;
+        let mut file = PersistFile::new();
+        for (section, properties) in parsed.iter() {
+            let Some(section) = section else {
+                continue;
+            };
+            let values = properties
+                .iter()
+                .map(|(key, value)| (key.to_string(), ini_value(value)));
+            file.set_type_data(section.to_string(), PersistData::from_json(values));
+        }
+        Ok(file)
+    }
+
+    /// Writes the entries as INI text, one section per entry.
+    ///
+    /// Strings, booleans and numbers are written as they are; lists, maps
+    /// and structs are written as JSON, which [`parse_ini`](Self::parse_ini)
+    /// reads back.
+    pub fn to_ini(&self) -> String {
+        let mut out = Ini::new();
+        for (section, data) in &self.type_data {
+            for (key, value) in data.to_json() {
+                let value = match value {
+                    serde_json::Value::String(text) => text,
+                    serde_json::Value::Null => String::new(),
+                    value => value.to_string(),
+                };
+                out.with_section(Some(section.as_str())).set(key, value);
+            }
+        }
+        let mut bytes = Vec::new();
+        out.write_to(&mut bytes)
+            .expect("writing to a Vec can't fail");
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl PersistManager {
+    /// Imports a legacy INI file on first run, one section per type.
+    ///
+    /// Each section is applied to the registered type of the same name,
+    /// ignoring case, so `[Settings]` fills `Settings`. Keys that aren't
+    /// fields of the type are ignored, and missing ones keep their current
+    /// values. Types that already have saved data are skipped, so a type is
+    /// imported once if this runs on startup before anything is saved, as
+    /// [`PersistPlugin::with_ini_import`](crate::PersistPlugin::with_ini_import)
+    /// does:
+    ///
+    /// ```ignore
+    /// app.add_plugins(PersistPlugin::new("MyCompany", "MyGame").with_ini_import("settings.ini"));
+    /// ```
+    ///
+    /// Imported resources are marked as changed, so auto-save writes them to
+    /// their usual files. Returns the names of the types imported. A missing
+    /// file imports nothing.
+    pub fn import_ini(world: &mut World, path: impl AsRef<Path>) -> PersistResult<Vec<String>> {
+        let Some(file) = read_ini(path.as_ref())? else {
+            return Ok(Vec::new());
+        };
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let sections: Vec<(String, String)> = file
+            .type_data
+            .keys()
+            .filter_map(|section| {
+                let key = manager
+                    .hooks
+                    .keys()
+                    .find(|key| manager.get_type_name(key).eq_ignore_ascii_case(section));
+                if key.is_none() {
+                    debug!("No registered type for INI section [{}]", section);
+                }
+                Some((section.clone(), key?.clone()))
+            })
+            .collect();
+
+        let mut imported = Vec::new();
+        for (section, key) in sections {
+            if Self::import_ini_data(world, &file, &section, &key)? {
+                imported.push(display_name(world, &key));
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Imports one section of a legacy INI file into `T` on first run.
+    ///
+    /// For sections whose name doesn't match the type, e.g. `[Graphics]`
+    /// into `VideoSettings`. Otherwise works like
+    /// [`import_ini`](Self::import_ini). Returns whether `T` was imported.
+    pub fn import_ini_section<T: Persistable>(
+        world: &mut World,
+        path: impl AsRef<Path>,
+        section: &str,
+    ) -> PersistResult<bool> {
+        let Some(file) = read_ini(path.as_ref())? else {
+            return Ok(false);
+        };
+        Self::import_ini_data(world, &file, section, T::type_path())
+    }
+
+    /// Applies a section to a type that has nothing saved yet.
+    fn import_ini_data(
+        world: &mut World,
+        file: &PersistFile,
+        section: &str,
+        type_name: &str,
+    ) -> PersistResult<bool> {
+        let Some(data) = file.get_type_data(section) else {
+            return Ok(false);
+        };
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let hooks = manager
+            .get_type_hooks(type_name)
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?
+            .clone();
+        let key = manager.get_storage_key(type_name);
+        let type_name = manager.get_type_name(&key).to_string();
+        if manager.read_saved_type_data(&key, None).is_some() {
+            debug!(
+                "{} is already saved, not importing [{}]",
+                type_name, section
+            );
+            return Ok(false);
+        }
+        if !hooks.apply_to_world(world, data) {
+            return Ok(false);
+        }
+        info!("Imported {} from INI section [{}]", type_name, section);
+        Ok(true)
+    }
+}
+
+/// Reads and parses an INI file, or `None` if there isn't one.
+fn read_ini(path: &Path) -> PersistResult<Option<PersistFile>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| PersistError::io("Failed to read file", e).at(path))?;
+    PersistFile::parse_ini(&content)
+        .map(Some)
+        .map_err(|e| e.at(path))
+}
+
+/// An INI value as the JSON value it most likely stands for.
+fn ini_value(text: &str) -> serde_json::Value {
+    let text = text.trim();
+    if let Ok(value) = text.parse::<bool>() {
+        return value.into();
+    }
+    if let Ok(value) = text.parse::<i64>() {
+        return value.into();
+    }
+    if let Ok(value) = text.parse::<f64>() {
+        if value.is_finite() {
+            return value.into();
+        }
+    }
+    if text.starts_with('[') || text.starts_with('{') {
+        if let Ok(value) = serde_json::from_str(text) {
+            return value;
+        }
+    }
+    text.into()
+}
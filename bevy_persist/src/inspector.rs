@@ -0,0 +1,227 @@
+//! An egui tuning panel for persisted resources (`inspector` feature).
+
+use crate::{PersistData, PersistLoaded, PersistManager, PersistSaved};
+use bevy::ecs::event::EventCursor;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContextPass, EguiContexts};
+use log::error;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Adds a "Persist" window listing every registered resource.
+///
+/// Fields can be edited in place, and each resource has save, reload and
+/// reset buttons. A `●` marks resources whose live value differs from the
+/// last one saved or loaded. Edits go through auto-save like any other
+/// change.
+///
+/// Requires bevy_egui's `EguiPlugin`:
+///
+/// ```ignore
+/// app.add_plugins(EguiPlugin { enable_multipass_for_primary_context: true })
+///     .add_plugins(PersistInspectorPlugin);
+/// ```
+pub struct PersistInspectorPlugin;
+
+impl Plugin for PersistInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PanelState>()
+            .add_systems(EguiContextPass, persist_panel);
+    }
+}
+
+/// What each resource looked like when it was last saved or loaded.
+#[derive(Resource, Default)]
+struct PanelState {
+    snapshots: HashMap<String, PersistData>,
+    saved: EventCursor<PersistSaved>,
+    loaded: EventCursor<PersistLoaded>,
+}
+
+enum PanelAction {
+    Patch(PersistData),
+    Save,
+    Reload,
+    Reset,
+}
+
+fn persist_panel(world: &mut World, contexts: &mut SystemState<EguiContexts>) {
+    let Some(ctx) = contexts.get_mut(world).try_ctx_mut().cloned() else {
+        return;
+    };
+
+    world.resource_scope(|world, mut state: Mut<PanelState>| {
+        state.refresh_snapshots(world);
+
+        let types: Vec<_> = world
+            .resource::<PersistManager>()
+            .registered_types()
+            .map(|info| {
+                let key = info.storage_key.to_string();
+                (key, info.type_name.to_string(), info.auto_save)
+            })
+            .collect();
+
+        let mut actions = Vec::new();
+        egui::Window::new("Persist").show(&ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (key, type_name, auto_save) in &types {
+                    let Ok(data) = PersistManager::live_data(world, key) else {
+                        continue;
+                    };
+                    let snapshot = state
+                        .snapshots
+                        .entry(key.clone())
+                        .or_insert_with(|| data.clone());
+                    let title = if *snapshot != data {
+                        format!("● {}", type_name)
+                    } else {
+                        type_name.clone()
+                    };
+
+                    egui::CollapsingHeader::new(title)
+                        .id_salt(key)
+                        .show(ui, |ui| {
+                            let mut values = data.to_json();
+                            if fields_ui(ui, values.iter_mut()) {
+                                let patch = PersistData::from_json(values);
+                                actions.push((key.clone(), PanelAction::Patch(patch)));
+                            }
+                            ui.horizontal(|ui| {
+                                if ui.button("Save").clicked() {
+                                    actions.push((key.clone(), PanelAction::Save));
+                                }
+                                if ui.button("Reload").clicked() {
+                                    actions.push((key.clone(), PanelAction::Reload));
+                                }
+                                if ui.button("Reset").clicked() {
+                                    actions.push((key.clone(), PanelAction::Reset));
+                                }
+                                if !auto_save {
+                                    ui.weak("auto-save off");
+                                }
+                            });
+                        });
+                }
+            });
+        });
+
+        for (key, action) in actions {
+            let result = match action {
+                PanelAction::Patch(data) => {
+                    // Stays marked until auto-save (or the save button) writes it
+                    if let Err(e) = PersistManager::patch_live_data(world, &key, data) {
+                        error!("Persist panel failed to edit {}: {}", key, e);
+                    }
+                    continue;
+                }
+                PanelAction::Save => PersistManager::save_named(world, &key),
+                PanelAction::Reload => PersistManager::reload_named(world, &key).map(|_| ()),
+                PanelAction::Reset => PersistManager::reset_named(world, &key),
+            };
+            match result.and_then(|()| PersistManager::live_data(world, &key)) {
+                Ok(data) => {
+                    state.snapshots.insert(key, data);
+                }
+                Err(e) => error!("Persist panel failed on {}: {}", key, e),
+            }
+        }
+    });
+}
+
+impl PanelState {
+    /// Takes new snapshots of resources saved or loaded since the last frame.
+    fn refresh_snapshots(&mut self, world: &World) {
+        let mut touched: Vec<String> = Vec::new();
+        if let Some(events) = world.get_resource::<Events<PersistSaved>>() {
+            touched.extend(self.saved.read(events).map(|event| event.type_name.clone()));
+        }
+        if let Some(events) = world.get_resource::<Events<PersistLoaded>>() {
+            touched.extend(
+                self.loaded
+                    .read(events)
+                    .map(|event| event.type_name.clone()),
+            );
+        }
+        let Some(manager) = world.get_resource::<PersistManager>() else {
+            return;
+        };
+        for type_name in touched {
+            let key = manager.get_storage_key(&type_name);
+            if let Ok(data) = PersistManager::live_data(world, &key) {
+                self.snapshots.insert(key, data);
+            }
+        }
+    }
+}
+
+/// Draws an editor for each field, in name order. Returns whether any changed.
+fn fields_ui<'a>(
+    ui: &mut egui::Ui,
+    fields: impl IntoIterator<Item = (&'a String, &'a mut Value)>,
+) -> bool {
+    let mut fields: Vec<_> = fields.into_iter().collect();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut changed = false;
+    for (name, value) in fields {
+        match value {
+            Value::Array(_) | Value::Object(_) => {
+                egui::CollapsingHeader::new(name.as_str())
+                    .id_salt(name)
+                    .show(ui, |ui| changed |= value_ui(ui, value));
+            }
+            _ => {
+                ui.horizontal(|ui| {
+                    ui.label(name.as_str());
+                    changed |= value_ui(ui, value);
+                });
+            }
+        }
+    }
+    changed
+}
+
+/// Draws an editor matching a JSON value's type. Returns whether it changed.
+fn value_ui(ui: &mut egui::Ui, value: &mut Value) -> bool {
+    match value {
+        Value::Bool(flag) => ui.checkbox(flag, "").changed(),
+        Value::String(text) => ui.text_edit_singleline(text).changed(),
+        Value::Number(number) => {
+            if let Some(mut integer) = number.as_i64() {
+                let changed = ui.add(egui::DragValue::new(&mut integer)).changed();
+                if changed {
+                    *value = Value::from(integer);
+                }
+                changed
+            } else {
+                let mut float = number.as_f64().unwrap_or_default();
+                let changed = ui
+                    .add(egui::DragValue::new(&mut float).speed(0.01))
+                    .changed();
+                if changed {
+                    *value = Value::from(float);
+                }
+                changed
+            }
+        }
+        Value::Array(items) => {
+            let mut changed = false;
+            for (index, item) in items.iter_mut().enumerate() {
+                ui.push_id(index, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("[{}]", index));
+                        changed |= value_ui(ui, item);
+                    });
+                });
+            }
+            changed
+        }
+        Value::Object(map) => fields_ui(ui, map.iter_mut()),
+        Value::Null => {
+            ui.weak("none");
+            false
+        }
+    }
+}
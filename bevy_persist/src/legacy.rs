@@ -0,0 +1,166 @@
+//! Moving files from where earlier versions of the game placed them.
+
+use crate::{
+    create_parent_dir, resolve_with, write_error, PathResolver, PersistManager, PersistMode,
+    PersistResult,
+};
+use bevy::prelude::*;
+use log::{debug, error, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Name the marker left after migrating is resolved under
+const MARKER_NAME: &str = "persist_migrated";
+
+impl PersistManager {
+    /// Moves production files from where earlier versions of the game put
+    /// them, the first time the game loads.
+    ///
+    /// Each location is a [`PathResolver`] describing an old scheme, most
+    /// often the platform directories under a previous organization or
+    /// app name:
+    ///
+    /// ```ignore
+    /// PersistPlugin::new("NewStudio", "MyGame").with_legacy_locations(vec![
+    ///     Arc::new(PlatformPathResolver::new("OldStudio", "MyGame")),
+    /// ])
+    /// ```
+    ///
+    /// Before anything loads, each type's or group's file that doesn't
+    /// exist yet is moved from the first location that has it. A marker
+    /// file is then left beside the new files so this only runs once; if a
+    /// move fails, the marker isn't written and the next start tries again.
+    pub fn with_legacy_locations(mut self, locations: Vec<Arc<dyn PathResolver>>) -> Self {
+        self.legacy_locations = locations;
+        self
+    }
+
+    /// Marker left once files have been moved from the legacy locations.
+    pub fn legacy_marker_path(&self) -> PathBuf {
+        self.resolve_path(MARKER_NAME, PersistMode::Dynamic, "marker")
+    }
+
+    /// Moves files from the legacy locations, unless that's already been done.
+    ///
+    /// Runs on startup when legacy locations are set. Only production
+    /// files are moved, and nothing is moved while read-only or with a
+    /// [`PersistBackend`](crate::PersistBackend). Returns the new paths of
+    /// the files moved.
+    pub fn migrate_legacy_locations(&self) -> PersistResult<Vec<PathBuf>> {
+        if self.legacy_locations.is_empty() || !self.is_production() || self.backend.is_some() {
+            return Ok(Vec::new());
+        }
+        if self.read_only {
+            debug!("Read-only, not moving files from legacy locations");
+            return Ok(Vec::new());
+        }
+        let marker = self.legacy_marker_path();
+        if marker.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut moved = Vec::new();
+        let mut log = String::new();
+        let mut failed = None;
+        for resolver in &self.legacy_locations {
+            for (path, legacy) in self.legacy_paths(&**resolver) {
+                if path.exists() || !legacy.is_file() || path == legacy {
+                    continue;
+                }
+                match move_file(&legacy, &path) {
+                    Ok(()) => {
+                        info!("Moved {:?} from its legacy location {:?}", path, legacy);
+                        log.push_str(&format!("{} -> {}\n", legacy.display(), path.display()));
+                        moved.push(path);
+                    }
+                    Err(e) => {
+                        error!("Failed to move {:?} to {:?}: {}", legacy, path, e);
+                        failed.get_or_insert(e);
+                    }
+                }
+            }
+        }
+        if let Some(e) = failed {
+            return Err(e);
+        }
+        fs::write(&marker, log).map_err(|e| write_error(e).at(&marker))?;
+        Ok(moved)
+    }
+
+    /// Each production file of the registered types, paired with where
+    /// `resolver` would have put it.
+    ///
+    /// Mirrors [`get_resource_path`](Self::get_resource_path) for pinned
+    /// files, groups and types' own files.
+    fn legacy_paths(&self, resolver: &dyn PathResolver) -> Vec<(PathBuf, PathBuf)> {
+        let mut type_names: Vec<&String> = self.persist_modes.keys().collect();
+        type_names.sort();
+        let mut paths: Vec<(PathBuf, PathBuf)> = Vec::new();
+        for type_name in type_names {
+            let mode = self.get_type_mode(type_name);
+            let format = self.get_type_format(type_name);
+            let pair = if let Some(file) = self.type_files.get(type_name.as_str()) {
+                let resolves = matches!(
+                    mode,
+                    PersistMode::Dynamic | PersistMode::Secure | PersistMode::Obfuscated
+                );
+                if file.is_absolute() || !resolves {
+                    continue;
+                }
+                let (Some(path), Some(dir)) = (
+                    self.get_type_file_path(type_name, mode),
+                    resolver
+                        .resolve(self.get_type_name(type_name), mode)
+                        .parent()
+                        .map(|dir| dir.join(file)),
+                ) else {
+                    continue;
+                };
+                (path, dir)
+            } else if let Some(group) = self.resolve_type_group(type_name, mode) {
+                let legacy = if self.is_single_file(group) {
+                    resolve_with(resolver, group, PersistMode::Secure, "ron")
+                } else {
+                    resolve_with(resolver, group, mode, format.extension())
+                };
+                (self.get_group_path(group, format, mode), legacy)
+            } else if mode.saves_to_resource_path() {
+                let extension = match mode {
+                    PersistMode::Dynamic => format.extension(),
+                    _ => "dat",
+                };
+                (
+                    self.get_resource_path(type_name, mode),
+                    resolve_with(resolver, self.get_type_name(type_name), mode, extension),
+                )
+            } else {
+                continue;
+            };
+            if !paths.iter().any(|(path, _)| *path == pair.0) {
+                paths.push(pair);
+            }
+        }
+        paths
+    }
+}
+
+/// Moves a file, copying it when it's on another file system.
+fn move_file(from: &Path, to: &Path) -> PersistResult<()> {
+    create_parent_dir(to)?;
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| write_error(e).at(to))?;
+    if let Err(e) = fs::remove_file(from) {
+        warn!("Moved {:?}, but couldn't remove the old copy: {}", from, e);
+    }
+    Ok(())
+}
+
+/// Moves files from legacy locations before anything loads.
+pub(crate) fn migrate_legacy_locations(manager: Res<PersistManager>) {
+    if let Err(e) = manager.migrate_legacy_locations() {
+        error!("Failed to move files from legacy locations: {}", e);
+    }
+}
@@ -33,12 +33,115 @@
 //! }
 //! ```
 
+use bevy::ecs::event::EventCursor;
+use bevy::platform::time::Instant;
 use bevy::prelude::*;
-use log::{debug, error, info};
+use chrono::{DateTime, Utc};
+use content::{Content, ContentError, FieldValue};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{BufWriter, Read, Write};
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{field, info_span, Span};
+
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "assets")]
+mod assets;
+mod autosave;
+mod components;
+#[cfg(feature = "compression")]
+mod compress;
+mod content;
+mod diagnostics;
+mod field_docs;
+#[cfg(feature = "ini")]
+mod ini;
+mod legacy;
+mod load_state;
+mod obfuscate;
+mod parallel;
+#[cfg(feature = "preflight")]
+mod preflight;
+mod progress;
+mod quick_save;
+#[cfg(feature = "redb")]
+mod redb_backend;
+mod repair;
+mod ron_json;
+mod roundtrip;
+mod scope;
+mod slots;
+#[cfg(feature = "sqlite")]
+mod sqlite_backend;
+mod sync;
+#[cfg(feature = "text-backend")]
+mod text_backend;
+mod transaction;
+#[cfg(feature = "assets")]
+pub use assets::{resolve_persist_assets, PersistAsset, PersistAssets};
+pub use autosave::PersistAutosave;
+pub use components::{
+    load_persisted_components, persist_component_system, register_persist_component,
+    PersistComponent, PersistId,
+};
+pub use diagnostics::PersistDiagnosticsPlugin;
+pub use load_state::{all_persist_loaded, persist_loaded, PersistLoadState};
+pub use parallel::PersistBatchSaved;
+#[cfg(feature = "preflight")]
+pub use preflight::PersistPreflightFailed;
+pub use progress::{PersistOperation, PersistProgress};
+pub use quick_save::{QuickLoad, QuickSave};
+#[cfg(feature = "redb")]
+pub use redb_backend::RedbBackend;
+pub use repair::PersistRepairReport;
+pub use roundtrip::check_roundtrip;
+pub use scope::PersistScope;
+pub use slots::{SaveSlot, SaveSlots};
+#[cfg(feature = "sqlite")]
+pub use sqlite_backend::{PersistRevision, SqliteBackend};
+pub use sync::{ConflictStrategy, MergeFn, PersistSyncConflict, PersistSynced, SyncResolution};
+#[cfg(feature = "text-backend")]
+pub use text_backend::{PersistTextStore, TextBackend};
+pub use transaction::PersistTransaction;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+#[cfg(feature = "hot-reload")]
+pub use hot_reload::PersistWatcher;
+#[cfg(feature = "lifecycle")]
+mod lifecycle;
+#[cfg(feature = "share-codes")]
+mod share_code;
+#[cfg(feature = "shutdown")]
+mod shutdown;
+#[cfg(feature = "shutdown")]
+pub use shutdown::PersistShutdown;
+#[cfg(feature = "test-utils")]
+mod test_utils;
+#[cfg(feature = "test-utils")]
+pub use test_utils::{PersistTestDir, PersistTestPlugin};
+#[cfg(feature = "tweak-server")]
+mod tweak_server;
+#[cfg(feature = "tweak-server")]
+pub use tweak_server::PersistTweakServer;
+#[cfg(feature = "inspector")]
+mod inspector;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "inspector")]
+pub use inspector::PersistInspectorPlugin;
+#[cfg(feature = "scene")]
+mod snapshot;
+#[cfg(feature = "scene")]
+pub use snapshot::WorldSnapshot;
 
 #[cfg(feature = "secure")]
 use aes_gcm::{
@@ -47,88 +150,764 @@ use aes_gcm::{
 };
 #[cfg(feature = "secure")]
 use argon2::Argon2;
-#[cfg(feature = "prod")]
 use directories::ProjectDirs;
 
 // Re-export the derive macro
-pub use bevy_persist_derive::Persist;
+pub use bevy_persist_derive::{Persist, PersistComponent};
 
 // For auto-registration
 pub use inventory;
 
+// Used by code generated from the derive macro
+pub use serde;
+
 pub mod prelude {
     pub use crate::{
-        Persist, PersistData, PersistError, PersistFile, PersistManager, PersistMode,
-        PersistPlugin, PersistResult, Persistable,
+        all_persist_loaded, persist_loaded, ConflictStrategy, DowngradePolicy, FileLayout,
+        FileNameStrategy, PathResolver, Persist, PersistAppExt, PersistAutosave, PersistBackend,
+        PersistBatchSaved, PersistClock, PersistCommandsExt, PersistComponent, PersistData,
+        PersistDeletionReport, PersistDiagnosticsPlugin, PersistEnvironment, PersistError,
+        PersistFellBackToMemory, PersistFile, PersistFileInfo, PersistFormat, PersistId,
+        PersistLoadState, PersistLoaded, PersistManager, PersistMerge, PersistMode,
+        PersistOperation, PersistPlugin, PersistProgress, PersistQuotaExceeded,
+        PersistRepairReport, PersistResourceLoaded, PersistResult, PersistRetriesExhausted,
+        PersistSaveFailed, PersistSaved, PersistSet, PersistStats, PersistStyle,
+        PersistSyncConflict, PersistSynced, PersistTelemetry, PersistTransaction, PersistTypeInfo,
+        PersistValidationFailed, PersistVersionEvent, PersistWorldExt, Persistable, QuickLoad,
+        QuickSave, QuotaPolicy, RequestSave, RetryPolicy, SaveSlot, SaveSlots, SyncResolution,
+        ValidationPolicy,
     };
+
+    #[cfg(feature = "inspector")]
+    pub use crate::PersistInspectorPlugin;
+    #[cfg(feature = "preflight")]
+    pub use crate::PersistPreflightFailed;
+    #[cfg(feature = "shutdown")]
+    pub use crate::PersistShutdown;
+    #[cfg(feature = "tweak-server")]
+    pub use crate::PersistTweakServer;
+    #[cfg(feature = "hot-reload")]
+    pub use crate::PersistWatcher;
+    #[cfg(feature = "redb")]
+    pub use crate::RedbBackend;
+    #[cfg(feature = "sqlite")]
+    pub use crate::SqliteBackend;
+    #[cfg(feature = "scene")]
+    pub use crate::WorldSnapshot;
+    #[cfg(feature = "assets")]
+    pub use crate::{PersistAsset, PersistAssets};
+    #[cfg(feature = "test-utils")]
+    pub use crate::{PersistTestDir, PersistTestPlugin};
+    #[cfg(feature = "text-backend")]
+    pub use crate::{PersistTextStore, TextBackend};
 }
 
 /// Result type for persistence operations
 pub type PersistResult<T> = Result<T, PersistError>;
 
 /// Errors that can occur during persistence operations
+///
+/// Variants carry what's known about where the error happened, such as the
+/// persisted type, the file and the field that failed to parse. The
+/// accessors ([`type_name`](Self::type_name), [`path`](Self::path), ...)
+/// read these without matching on every variant.
 #[derive(Debug, Clone)]
 pub enum PersistError {
     /// Failed to read/write file
-    IoError(String),
+    IoError {
+        /// What was being done, e.g. "Failed to read file"
+        message: String,
+        /// Kind of the underlying I/O error
+        kind: std::io::ErrorKind,
+        /// File or directory involved
+        path: Option<PathBuf>,
+        /// Type whose data was being read or written
+        type_name: Option<String>,
+        /// The underlying I/O error, if there was one
+        source: Option<Arc<std::io::Error>>,
+    },
     /// Failed to serialize/deserialize
-    SerializationError(String),
+    SerializationError {
+        /// What went wrong, as reported by the format
+        message: String,
+        /// Path of the field that failed, e.g. `inventory[2].count`
+        field: Option<String>,
+        /// File the data was read from or written to
+        path: Option<PathBuf>,
+        /// Type whose data was being read or written
+        type_name: Option<String>,
+    },
     /// Resource not found
     ResourceNotFound(String),
     /// Failed to encrypt/decrypt data
     #[cfg(feature = "secure")]
-    EncryptionError(String),
+    EncryptionError {
+        /// What went wrong
+        message: String,
+        /// File the data was read from or written to
+        path: Option<PathBuf>,
+        /// Type whose data was being read or written
+        type_name: Option<String>,
+    },
+    /// A save's file or directory isn't writable
+    #[cfg(feature = "preflight")]
+    PermissionDenied {
+        /// The read-only file or directory
+        path: PathBuf,
+        /// Type that was being saved
+        type_name: Option<String>,
+    },
+    /// Not enough free disk space for a save
+    #[cfg(feature = "preflight")]
+    InsufficientSpace {
+        /// Where the save was going
+        path: PathBuf,
+        /// Bytes that had to be free
+        needed: u64,
+        /// Bytes that were free
+        available: u64,
+        /// Type that was being saved
+        type_name: Option<String>,
+    },
+    /// A save was bigger than its size quota
+    QuotaExceeded {
+        /// Type that was being saved
+        type_name: String,
+        /// Size of the serialized data
+        bytes: u64,
+        /// The type's quota
+        quota: u64,
+    },
+    /// A [`PersistBackend`] failed to read, write or remove data
+    BackendError {
+        /// What went wrong, as reported by the backend
+        message: String,
+        /// Type whose data was being read or written
+        type_name: Option<String>,
+    },
+}
+
+impl PersistError {
+    /// An I/O error from `source` while doing what `message` says.
+    pub(crate) fn io(message: impl Into<String>, source: std::io::Error) -> Self {
+        Self::IoError {
+            message: message.into(),
+            kind: source.kind(),
+            path: None,
+            type_name: None,
+            source: Some(Arc::new(source)),
+        }
+    }
+
+    /// An I/O error with no underlying [`std::io::Error`].
+    pub(crate) fn io_other(message: impl Into<String>, kind: std::io::ErrorKind) -> Self {
+        Self::IoError {
+            message: message.into(),
+            kind,
+            path: None,
+            type_name: None,
+            source: None,
+        }
+    }
+
+    /// A failure reported by a [`PersistBackend`].
+    pub fn backend(message: impl std::fmt::Display) -> Self {
+        Self::BackendError {
+            message: message.to_string(),
+            type_name: None,
+        }
+    }
+
+    /// A serialization error with no known field.
+    pub(crate) fn serialization(message: impl std::fmt::Display) -> Self {
+        Self::SerializationError {
+            message: message.to_string(),
+            field: None,
+            path: None,
+            type_name: None,
+        }
+    }
+
+    /// A deserialization error, with the field it happened at.
+    fn deserialization<E: std::fmt::Display>(
+        context: &str,
+        error: serde_path_to_error::Error<E>,
+    ) -> Self {
+        // The root path prints as "."
+        let field = error.path().iter().next().map(|_| error.path().to_string());
+        Self::SerializationError {
+            message: format!("{}{}", context, error.inner()),
+            field,
+            path: None,
+            type_name: None,
+        }
+    }
+
+    #[cfg(feature = "secure")]
+    pub(crate) fn encryption(message: impl Into<String>) -> Self {
+        Self::EncryptionError {
+            message: message.into(),
+            path: None,
+            type_name: None,
+        }
+    }
+
+    /// Records the file the error happened with, unless it already has one.
+    pub(crate) fn at(mut self, file: impl AsRef<Path>) -> Self {
+        let slot = match &mut self {
+            Self::IoError { path, .. } | Self::SerializationError { path, .. } => path,
+            #[cfg(feature = "secure")]
+            Self::EncryptionError { path, .. } => path,
+            _ => return self,
+        };
+        slot.get_or_insert_with(|| file.as_ref().to_path_buf());
+        self
+    }
+
+    /// Records the type the error happened with, unless it already has one.
+    pub(crate) fn for_type(mut self, name: &str) -> Self {
+        let slot = match &mut self {
+            Self::IoError { type_name, .. }
+            | Self::SerializationError { type_name, .. }
+            | Self::BackendError { type_name, .. } => type_name,
+            #[cfg(feature = "secure")]
+            Self::EncryptionError { type_name, .. } => type_name,
+            #[cfg(feature = "preflight")]
+            Self::PermissionDenied { type_name, .. }
+            | Self::InsufficientSpace { type_name, .. } => type_name,
+            _ => return self,
+        };
+        slot.get_or_insert_with(|| name.to_string());
+        self
+    }
+
+    /// The persisted type the error happened with, if known.
+    pub fn type_name(&self) -> Option<&str> {
+        match self {
+            Self::IoError { type_name, .. }
+            | Self::SerializationError { type_name, .. }
+            | Self::BackendError { type_name, .. } => type_name.as_deref(),
+            #[cfg(feature = "secure")]
+            Self::EncryptionError { type_name, .. } => type_name.as_deref(),
+            #[cfg(feature = "preflight")]
+            Self::PermissionDenied { type_name, .. }
+            | Self::InsufficientSpace { type_name, .. } => type_name.as_deref(),
+            Self::ResourceNotFound(type_name) | Self::QuotaExceeded { type_name, .. } => {
+                Some(type_name)
+            }
+        }
+    }
+
+    /// The file or directory the error happened with, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::IoError { path, .. } | Self::SerializationError { path, .. } => path.as_deref(),
+            #[cfg(feature = "secure")]
+            Self::EncryptionError { path, .. } => path.as_deref(),
+            #[cfg(feature = "preflight")]
+            Self::PermissionDenied { path, .. } | Self::InsufficientSpace { path, .. } => {
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Kind of the underlying I/O error, for I/O errors.
+    pub fn io_kind(&self) -> Option<std::io::ErrorKind> {
+        match self {
+            Self::IoError { kind, .. } => Some(*kind),
+            #[cfg(feature = "preflight")]
+            Self::PermissionDenied { .. } => Some(std::io::ErrorKind::PermissionDenied),
+            _ => None,
+        }
+    }
+
+    /// Path of the field that failed to deserialize, e.g. `inventory[2].count`.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::SerializationError { field, .. } => field.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Appends " for <type>" and " in <file>", where known
+fn write_context(
+    f: &mut std::fmt::Formatter<'_>,
+    type_name: &Option<String>,
+    path: Option<&Path>,
+) -> std::fmt::Result {
+    if let Some(type_name) = type_name {
+        write!(f, " for {}", type_name)?;
+    }
+    if let Some(path) = path {
+        write!(f, " in {}", path.display())?;
+    }
+    Ok(())
 }
 
 impl std::fmt::Display for PersistError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::IoError(e) => write!(f, "IO error: {}", e),
-            Self::SerializationError(e) => write!(f, "Serialization error: {}", e),
+            Self::IoError {
+                message,
+                path,
+                type_name,
+                source,
+                ..
+            } => {
+                write!(f, "IO error: {}", message)?;
+                if let Some(path) = path {
+                    write!(f, " {}", path.display())?;
+                }
+                write_context(f, type_name, None)?;
+                match source {
+                    Some(source) => write!(f, ": {}", source),
+                    None => Ok(()),
+                }
+            }
+            Self::SerializationError {
+                message,
+                field,
+                path,
+                type_name,
+            } => {
+                write!(f, "Serialization error: {}", message)?;
+                if let Some(field) = field {
+                    write!(f, " at `{}`", field)?;
+                }
+                write_context(f, type_name, path.as_deref())
+            }
             Self::ResourceNotFound(e) => write!(f, "Resource not found: {}", e),
             #[cfg(feature = "secure")]
-            Self::EncryptionError(e) => write!(f, "Encryption error: {}", e),
+            Self::EncryptionError {
+                message,
+                path,
+                type_name,
+            } => {
+                write!(f, "Encryption error: {}", message)?;
+                write_context(f, type_name, path.as_deref())
+            }
+            #[cfg(feature = "preflight")]
+            Self::PermissionDenied { path, type_name } => {
+                write!(f, "Permission denied: {} isn't writable", path.display())?;
+                write_context(f, type_name, None)
+            }
+            #[cfg(feature = "preflight")]
+            Self::InsufficientSpace {
+                path,
+                needed,
+                available,
+                type_name,
+            } => {
+                write!(
+                    f,
+                    "Insufficient space: {} needs {} bytes free, {} are available",
+                    path.display(),
+                    needed,
+                    available
+                )?;
+                write_context(f, type_name, None)
+            }
+            Self::QuotaExceeded {
+                type_name,
+                bytes,
+                quota,
+            } => write!(
+                f,
+                "Quota exceeded: {} is {} bytes, over its quota of {}",
+                type_name, bytes, quota
+            ),
+            Self::BackendError { message, type_name } => {
+                write!(f, "Backend error: {}", message)?;
+                write_context(f, type_name, None)
+            }
         }
     }
 }
 
-impl std::error::Error for PersistError {}
+impl std::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IoError {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 /// Data structure for persisting parameter values.
 ///
-/// This is used internally to store serialized resource data
-/// in a generic format that can be saved to JSON or RON.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Holds a resource's fields, each kept in the form it came from. Fields
+/// captured from a live resource are written natively in whichever format
+/// the file uses, so RON keeps its enums, tuples and options. Fields read
+/// from a file are kept as written and deserialized straight from that
+/// text. [`to_json`](Self::to_json) and [`from_json`](Self::from_json) give
+/// a format-independent view for tooling.
+///
+/// Fields are kept sorted by name, so saving the same values always
+/// produces the same file.
+#[derive(Debug, Clone, Default)]
 pub struct PersistData {
-    pub values: HashMap<String, serde_json::Value>,
+    values: BTreeMap<String, FieldValue>,
+    /// Doc comments written above fields in development RON files
+    docs: FieldDocs,
+    /// When this entry was last written, if its file recorded it
+    last_saved: Option<DateTime<Utc>>,
+}
+
+/// Result of [`PersistData::merge3`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistMerge {
+    /// The merged fields, with `local`'s value for conflicts
+    pub data: PersistData,
+    /// Fields both sides changed to different values, in sorted order
+    pub conflicts: Vec<String>,
+}
+
+impl PersistMerge {
+    /// Whether every field merged without a conflict.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Doc comments of a type's fields, as `(field, docs)` pairs.
+pub type FieldDocs = &'static [(&'static str, &'static str)];
+
+// Docs and timestamps only annotate the output, they aren't part of the data
+impl PartialEq for PersistData {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+/// Shape of one type's entry in a RON file, keeping each field's text.
+struct RonData {
+    values: HashMap<String, Box<ron::value::RawValue>>,
+    last_saved: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for RonData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RonDataVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for RonDataVisitor {
+            type Value = RonData;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("persisted data with `values`")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<RonData, A::Error> {
+                let mut values = None;
+                let mut last_saved = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "values" => values = Some(map.next_value()?),
+                        "last_saved" => last_saved = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                let values = values.ok_or_else(|| serde::de::Error::missing_field("values"))?;
+                Ok(RonData { values, last_saved })
+            }
+        }
+
+        // Struct or map syntax, since JSON-style files also parse as RON
+        deserializer.deserialize_any(RonDataVisitor)
+    }
+}
+
+/// Shape of one type's entry in a JSON or TOML file.
+#[derive(Deserialize)]
+struct JsonData {
+    values: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    last_saved: Option<String>,
 }
 
 impl PersistData {
     /// Creates a new, empty PersistData instance.
     pub fn new() -> Self {
-        Self {
-            values: HashMap::new(),
-        }
+        Self::default()
     }
 
     /// Inserts a serializable value with the given key.
     pub fn insert<T: serde::Serialize>(&mut self, key: impl Into<String>, value: T) {
-        if let Ok(json_value) = serde_json::to_value(value) {
-            self.values.insert(key.into(), json_value);
+        if let Ok(content) = Content::capture(&value) {
+            self.values
+                .insert(key.into(), FieldValue::Captured(content));
         }
     }
 
     /// Retrieves and deserializes a value by key.
     pub fn get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.values.get(key)?;
+        value
+            .deserialize()
+            .ok()
+            // Values written before fields were stored natively
+            .or_else(|| serde_json::from_value(value.to_json()).ok())
+    }
+
+    /// Captures every field of a serializable struct.
+    ///
+    /// Values that don't serialize to a struct or map (or fail to
+    /// serialize) produce empty data.
+    pub fn from_serialize<T: Serialize>(value: &T) -> Self {
+        Content::capture(value)
+            .map(Self::from_content)
+            .unwrap_or_default()
+    }
+
+    fn from_content(content: Content) -> Self {
+        let values = match content {
+            Content::Struct(_, fields) => fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), FieldValue::Captured(value)))
+                .collect(),
+            Content::Map(entries) => entries
+                .into_iter()
+                .filter_map(|(key, value)| match key {
+                    Content::String(key) => Some((key, FieldValue::Captured(value))),
+                    _ => None,
+                })
+                .collect(),
+            Content::NewtypeStruct(_, inner) => return Self::from_content(*inner),
+            _ => BTreeMap::new(),
+        };
+        Self::from_values(values)
+    }
+
+    /// Deserializes the stored fields back into a complete value.
+    ///
+    /// Each field is read with the deserializer of the format it was
+    /// stored in. Data written before fields were stored natively falls
+    /// back to being read as JSON.
+    ///
+    /// Errors name the field that failed, e.g. `inventory[2].count`.
+    pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> PersistResult<T> {
+        self.deserialize_fields().or_else(|e| {
+            serde_json::from_value(serde_json::Value::Object(self.to_json())).map_err(|_| e)
+        })
+    }
+
+    fn deserialize_fields<T: serde::de::DeserializeOwned>(&self) -> PersistResult<T> {
+        let sources = self
+            .values
+            .iter()
+            .map(|(key, value)| {
+                value
+                    .source()
+                    .map(|source| (key.as_str(), source))
+                    .map_err(|e| PersistError::SerializationError {
+                        message: e.to_string(),
+                        field: Some(key.clone()),
+                        path: None,
+                        type_name: None,
+                    })
+            })
+            .collect::<PersistResult<Vec<_>>>()?;
+        serde_path_to_error::deserialize(serde::de::value::MapDeserializer::<_, ContentError>::new(
+            sources
+                .iter()
+                .map(|(key, source)| (*key, source.deserializer())),
+        ))
+        .map_err(|e| PersistError::deserialization("", e))
+    }
+
+    /// Converts the fields to JSON values, e.g. for editors and remote tools.
+    pub fn to_json(&self) -> serde_json::Map<String, serde_json::Value> {
         self.values
-            .get(key)
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .iter()
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect()
+    }
+
+    /// Builds data from JSON values, e.g. a patch sent by a remote tool.
+    pub fn from_json(values: impl IntoIterator<Item = (String, serde_json::Value)>) -> Self {
+        Self::from_values(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, FieldValue::Json(value)))
+                .collect(),
+        )
+    }
+
+    /// Replaces fields with the ones in `other`, keeping the rest.
+    pub fn merge(&mut self, other: PersistData) {
+        self.values.extend(other.values);
+        self.last_saved = other.last_saved.or(self.last_saved);
+    }
+
+    /// When this entry was last written to its file.
+    ///
+    /// Each entry has its own timestamp, so in a file shared by several
+    /// types it tells which of them changed recently. `None` for data that
+    /// was never saved, or read from files written before entries had one.
+    pub fn last_saved(&self) -> Option<DateTime<Utc>> {
+        self.last_saved
+    }
+
+    /// Three-way merges two edits of the same data.
+    ///
+    /// `base` is what both sides started from, e.g. the last synced copy.
+    /// Each field takes the side that changed it, so edits to different
+    /// fields on two devices both survive; removing a field counts as a
+    /// change. Fields both sides changed to different values are conflicts:
+    /// they keep `local`'s value and are listed in the result.
+    pub fn merge3(base: &PersistData, local: &PersistData, remote: &PersistData) -> PersistMerge {
+        let keys: BTreeSet<&String> = base
+            .values
+            .keys()
+            .chain(local.values.keys())
+            .chain(remote.values.keys())
+            .collect();
+
+        let mut merged = PersistMerge {
+            data: local.clone(),
+            conflicts: Vec::new(),
+        };
+        for key in keys {
+            let (base_value, local_value, remote_value) = (
+                base.values.get(key),
+                local.values.get(key),
+                remote.values.get(key),
+            );
+            if local_value == remote_value || remote_value == base_value {
+                continue;
+            }
+            if local_value != base_value {
+                merged.conflicts.push(key.clone());
+                continue;
+            }
+            match remote_value {
+                Some(value) => merged.data.values.insert(key.clone(), value.clone()),
+                None => merged.data.values.remove(key),
+            };
+        }
+        merged
+    }
+
+    /// Attaches doc comments to write above fields in RON files.
+    ///
+    /// The manager does this for registered types with
+    /// [`Persistable::field_docs`] when saving in development.
+    pub fn with_field_docs(mut self, docs: FieldDocs) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    /// The doc comment attached to a field, if any.
+    pub fn field_doc(&self, key: &str) -> Option<&'static str> {
+        self.docs
+            .iter()
+            .find(|(field, _)| *field == key)
+            .map(|(_, doc)| *doc)
+    }
+
+    /// Removes a field, returning whether it was present.
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.values.remove(key).is_some()
+    }
+
+    /// Checks if a field is present.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Names of the stored fields, in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Number of stored fields.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no fields are stored.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Parses a single entry written by [`to_string_as`](Self::to_string_as).
+    pub fn parse_as(content: &str, format: PersistFormat) -> PersistResult<Self> {
+        match format {
+            PersistFormat::Ron => {
+                let data: RonData = format.deserialize(content)?;
+                Ok(Self::from_ron(data))
+            }
+            _ => {
+                let data: JsonData = format.deserialize(content)?;
+                Ok(Self::from_json(data.values))
+            }
+        }
+    }
+
+    /// Serializes a single entry in the given format.
+    pub fn to_string_as(&self, format: PersistFormat) -> PersistResult<String> {
+        self.to_string_styled(format, &PersistStyle::default())
+    }
+
+    /// Serializes a single entry in the given format and style.
+    pub fn to_string_styled(
+        &self,
+        format: PersistFormat,
+        style: &PersistStyle,
+    ) -> PersistResult<String> {
+        format.serialize_styled(&*self.for_format(format), style)
+    }
+
+    fn from_values(values: BTreeMap<String, FieldValue>) -> Self {
+        Self {
+            values,
+            ..Self::default()
+        }
+    }
+
+    fn from_ron(data: RonData) -> Self {
+        let mut parsed = Self::from_values(
+            data.values
+                .into_iter()
+                .map(|(key, value)| (key, FieldValue::Ron(value)))
+                .collect(),
+        );
+        parsed.last_saved = data.last_saved.as_deref().and_then(parse_timestamp);
+        parsed
+    }
+
+    /// Converts RON text read from a file when writing to another format.
+    fn for_format(&self, format: PersistFormat) -> Cow<'_, Self> {
+        if format == PersistFormat::Ron || !self.values.values().any(FieldValue::is_ron) {
+            return Cow::Borrowed(self);
+        }
+        let mut converted = Self::from_json(self.to_json());
+        converted.last_saved = self.last_saved;
+        Cow::Owned(converted)
     }
 }
 
-impl Default for PersistData {
-    fn default() -> Self {
-        Self::new()
+impl Serialize for PersistData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let len = 1 + usize::from(self.last_saved.is_some());
+        let mut data = serializer.serialize_struct("PersistData", len)?;
+        data.serialize_field("values", &self.values)?;
+        match &self.last_saved {
+            Some(saved) => data.serialize_field("last_saved", &saved.to_rfc3339())?,
+            None => data.skip_field("last_saved")?,
+        }
+        data.end()
     }
 }
 
@@ -136,10 +915,11 @@ impl Default for PersistData {
 ///
 /// This represents the entire contents of a persistence file,
 /// including all persisted resources, metadata, and versioning information.
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// Entries are sorted by key so files diff cleanly under version control.
+#[derive(Debug, Serialize, Default)]
 pub struct PersistFile {
     #[serde(flatten)]
-    pub type_data: HashMap<String, PersistData>,
+    pub type_data: BTreeMap<String, PersistData>,
     pub last_saved: String,
     pub version: String,
 }
@@ -148,78 +928,339 @@ impl PersistFile {
     /// Creates a new PersistFile with current timestamp and version.
     pub fn new() -> Self {
         Self {
-            type_data: HashMap::new(),
+            type_data: BTreeMap::new(),
             last_saved: chrono::Utc::now().to_rfc3339(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         }
     }
 
     /// Loads a PersistFile from disk. Creates a new one if the file doesn't exist.
-    /// Automatically detects format based on file extension (.ron or .json).
+    /// Automatically detects format based on file extension (.ron, .json or .toml).
     pub fn load_from_file(path: impl AsRef<Path>) -> PersistResult<Self> {
         let path = path.as_ref();
+        Self::load_from_file_as(path, PersistFormat::from_path(path))
+    }
+
+    /// Loads a PersistFile from disk in the given format, ignoring the file extension.
+    /// Creates a new one if the file doesn't exist.
+    pub fn load_from_file_as(path: impl AsRef<Path>, format: PersistFormat) -> PersistResult<Self> {
+        let path = path.as_ref();
 
         if !path.exists() {
             return Ok(Self::new());
         }
 
-        let content = fs::read_to_string(path)
-            .map_err(|e| PersistError::IoError(format!("Failed to read file: {}", e)))?;
+        let content = fs::read_to_string(path).map_err(|e| read_error(path, e))?;
 
-        // Try RON first, fallback to JSON
-        if path.extension().is_some_and(|ext| ext == "ron") {
-            ron::from_str(&content)
-                .map_err(|e| PersistError::SerializationError(format!("RON parse error: {}", e)))
-        } else {
-            serde_json::from_str(&content)
-                .map_err(|e| PersistError::SerializationError(format!("JSON parse error: {}", e)))
+        Self::parse_as(&content, format).map_err(|e| e.at(path))
+    }
+
+    /// Loads a PersistFile from `reader` in the given format, reading it to
+    /// the end, e.g. from an in-memory buffer, a socket or a platform's blob
+    /// storage.
+    pub fn load_from_reader(reader: &mut impl Read, format: PersistFormat) -> PersistResult<Self> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .map_err(|e| PersistError::io("Failed to read data", e))?;
+        Self::parse_as(&content, format)
+    }
+
+    /// Parses a PersistFile from a string in the given format.
+    ///
+    /// RON entries keep each field's text as written, so types load straight
+    /// from it without losing enum variants or number precision.
+    pub fn parse_as(content: &str, format: PersistFormat) -> PersistResult<Self> {
+        let mut file = Self::new();
+        let parse_error = |e: ron::error::SpannedError| {
+            PersistError::serialization(format!("RON parse error: {}", e))
+        };
+
+        match format {
+            PersistFormat::Ron => {
+                let entries: HashMap<String, Box<ron::value::RawValue>> =
+                    format.deserialize(content)?;
+                for (key, raw) in entries {
+                    match key.as_str() {
+                        "last_saved" => file.last_saved = raw.into_rust().map_err(parse_error)?,
+                        "version" => file.version = raw.into_rust().map_err(parse_error)?,
+                        _ => {
+                            let data = raw.into_rust().map_err(parse_error)?;
+                            file.type_data.insert(key, PersistData::from_ron(data));
+                        }
+                    }
+                }
+            }
+            _ => {
+                let entries: HashMap<String, serde_json::Value> = format.deserialize(content)?;
+                for (key, value) in entries {
+                    match (key.as_str(), value) {
+                        ("last_saved", serde_json::Value::String(value)) => file.last_saved = value,
+                        ("version", serde_json::Value::String(value)) => file.version = value,
+                        (_, value) => {
+                            let data: JsonData = serde_json::from_value(value)
+                                .map_err(PersistError::serialization)?;
+                            let mut parsed = PersistData::from_json(data.values);
+                            parsed.last_saved =
+                                data.last_saved.as_deref().and_then(parse_timestamp);
+                            file.type_data.insert(key, parsed);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// Serializes the PersistFile to a string in the given format.
+    pub fn to_string_as(&self, format: PersistFormat) -> PersistResult<String> {
+        self.to_string_styled(format, &PersistStyle::default())
+    }
+
+    /// Serializes the PersistFile to a string in the given format and style.
+    ///
+    /// Pretty-printed RON gets the [field docs](PersistData::with_field_docs)
+    /// of each entry as comments.
+    pub fn to_string_styled(
+        &self,
+        format: PersistFormat,
+        style: &PersistStyle,
+    ) -> PersistResult<String> {
+        let mut buffer = Vec::new();
+        self.write_styled(&mut buffer, format, style)?;
+        Ok(String::from_utf8(buffer).expect("serializers write UTF-8"))
+    }
+
+    /// Serializes the PersistFile straight into `writer` in the given format
+    /// and style, like [`to_string_styled`](Self::to_string_styled).
+    ///
+    /// RON files with field docs are still built as a string first, since
+    /// the comments are added to the finished text.
+    pub fn write_styled<W: Write>(
+        &self,
+        mut writer: W,
+        format: PersistFormat,
+        style: &PersistStyle,
+    ) -> PersistResult<()> {
+        if format == PersistFormat::Ron {
+            if !style.pretty || self.type_data.values().all(|data| data.docs.is_empty()) {
+                return format.serialize_styled_to(writer, self, style);
+            }
+            let content = format.serialize_styled(self, style)?;
+            let content = field_docs::write_field_docs(&content, &style.indent, &self.type_data);
+            return writer.write_all(content.as_bytes()).map_err(write_error);
         }
+
+        let needs_conversion = self
+            .type_data
+            .values()
+            .any(|data| matches!(data.for_format(format), Cow::Owned(_)));
+        if !needs_conversion {
+            return format.serialize_styled_to(writer, self, style);
+        }
+
+        let converted = PersistFile {
+            type_data: self
+                .type_data
+                .iter()
+                .map(|(key, data)| (key.clone(), data.for_format(format).into_owned()))
+                .collect(),
+            last_saved: self.last_saved.clone(),
+            version: self.version.clone(),
+        };
+        format.serialize_styled_to(writer, &converted, style)
     }
 
     /// Saves the PersistFile to disk.
-    /// Format is determined by file extension (.ron for RON, .json for JSON).
+    /// Format is determined by file extension (.ron for RON, .toml for TOML, .json for JSON).
     pub fn save_to_file(&mut self, path: impl AsRef<Path>) -> PersistResult<()> {
         let path = path.as_ref();
+        self.save_to_file_as(path, PersistFormat::from_path(path))
+    }
 
-        // Update timestamp
-        self.last_saved = chrono::Utc::now().to_rfc3339();
-
-        // Create parent directory if needed
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| PersistError::IoError(format!("Failed to create directory: {}", e)))?;
+    /// Re-saves a file in another format, chosen by each path's extension.
+    ///
+    /// For migrating existing files after a type's format changes, e.g.
+    /// `settings.ron` to `settings.json`. Unlike loading, a missing `src` is
+    /// an error rather than an empty file.
+    pub fn convert(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> PersistResult<()> {
+        let src = src.as_ref();
+        if !src.exists() {
+            let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "it doesn't exist");
+            return Err(PersistError::io("Failed to read file", missing).at(src));
         }
+        Self::load_from_file(src)?.save_to_file(dst)
+    }
 
-        let content = if path.extension().is_some_and(|ext| ext == "ron") {
-            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|e| {
-                PersistError::SerializationError(format!("RON serialization error: {}", e))
-            })?
-        } else {
-            serde_json::to_string_pretty(self).map_err(|e| {
-                PersistError::SerializationError(format!("JSON serialization error: {}", e))
-            })?
+    /// Saves the PersistFile to disk in the given format, ignoring the file extension.
+    pub fn save_to_file_as(
+        &mut self,
+        path: impl AsRef<Path>,
+        format: PersistFormat,
+    ) -> PersistResult<()> {
+        self.save_to_file_styled(path, format, &PersistStyle::default())
+    }
+
+    /// Saves the PersistFile to disk in the given format and style.
+    pub fn save_to_file_styled(
+        &mut self,
+        path: impl AsRef<Path>,
+        format: PersistFormat,
+        style: &PersistStyle,
+    ) -> PersistResult<()> {
+        self.save_to_file_at(path, format, style, Utc::now())
+    }
+
+    /// Like [`save_to_file_styled`](Self::save_to_file_styled), stamping the
+    /// file as saved at `saved_at` instead of now.
+    pub fn save_to_file_at(
+        &mut self,
+        path: impl AsRef<Path>,
+        format: PersistFormat,
+        style: &PersistStyle,
+        saved_at: DateTime<Utc>,
+    ) -> PersistResult<()> {
+        let path = path.as_ref();
+        self.last_saved = saved_at.to_rfc3339();
+        create_parent_dir(path)?;
+
+        // TOML can't be streamed, so it's built as one string
+        let content = match format {
+            PersistFormat::Toml => Some(
+                self.to_string_styled(format, style)
+                    .map_err(|e| e.at(path))?,
+            ),
+            _ => None,
         };
 
-        fs::write(path, content)
-            .map_err(|e| PersistError::IoError(format!("Failed to write file: {}", e)))?;
+        let span = info_span!(
+            "persist_write",
+            path = %path.display(),
+            bytes = field::Empty
+        )
+        .entered();
+        // Written next to the old file and renamed over it, so a save that
+        // fails halfway leaves the last good one in place
+        let temp = temp_path(path);
+        let written = Self::write_temp(&temp, |writer| match content {
+            Some(content) => writer.write_all(content.as_bytes()).map_err(write_error),
+            // Serialized straight into the file, never as one big string
+            None => self.write_styled(writer, format, style),
+        })
+        .and_then(|bytes| {
+            fs::rename(&temp, path).map_err(write_error)?;
+            Ok(bytes)
+        });
+        let bytes = match written {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let _ = fs::remove_file(&temp);
+                return Err(e.at(path));
+            }
+        };
+        span.record("bytes", bytes);
 
         debug!("Saved settings to {}", path.display());
         Ok(())
     }
 
+    /// Creates `temp` and fills it through `write`, returning its size once
+    /// it's flushed to disk.
+    fn write_temp(
+        temp: &Path,
+        write: impl FnOnce(&mut BufWriter<fs::File>) -> PersistResult<()>,
+    ) -> PersistResult<u64> {
+        let file = fs::File::create(temp).map_err(write_error)?;
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        let file = writer
+            .into_inner()
+            .map_err(|e| write_error(e.into_error()))?;
+        file.sync_all().map_err(write_error)?;
+        Ok(file.metadata().map_or(0, |meta| meta.len()))
+    }
+
+    /// Saves the PersistFile into `writer` in the given format, e.g. an
+    /// in-memory buffer, a socket or a platform's blob storage.
+    ///
+    /// Stamps the file as saved now, like [`save_to_file`](Self::save_to_file),
+    /// and flushes the writer once everything is written.
+    pub fn save_to_writer(
+        &mut self,
+        writer: &mut impl Write,
+        format: PersistFormat,
+    ) -> PersistResult<()> {
+        self.last_saved = Utc::now().to_rfc3339();
+        self.write_styled(&mut *writer, format, &PersistStyle::default())?;
+        writer.flush().map_err(write_error)
+    }
+
     /// Gets the persistence data for a specific type.
     pub fn get_type_data(&self, type_name: &str) -> Option<&PersistData> {
         self.type_data.get(type_name)
     }
 
-    /// Sets the persistence data for a specific type.
+    /// Gets the persistence data stored under `key`, falling back to
+    /// `legacy_key` for files written before keys were namespaced.
+    pub fn get_type_data_or_legacy(&self, key: &str, legacy_key: &str) -> Option<&PersistData> {
+        self.type_data
+            .get(key)
+            .or_else(|| self.type_data.get(legacy_key))
+    }
+
+    /// Iterates the entries as `(type_name, data)`, in key order.
+    ///
+    /// Type names are the keys written in the file, which include the
+    /// namespace when the plugin has one, e.g. `my_game::Settings`.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &PersistData)> {
+        self.type_data
+            .iter()
+            .map(|(type_name, data)| (type_name.as_str(), data))
+    }
+
+    /// Deserializes the entry for `type_name` into a complete `T`.
+    ///
+    /// Returns `Ok(None)` if there's no such entry, and an error naming the
+    /// type and field if the entry doesn't hold a valid `T`, e.g. because
+    /// fields are missing or of the wrong type.
+    pub fn try_get_as<T: serde::de::DeserializeOwned>(
+        &self,
+        type_name: &str,
+    ) -> PersistResult<Option<T>> {
+        self.type_data
+            .get(type_name)
+            .map(|data| data.deserialize().map_err(|e| e.for_type(type_name)))
+            .transpose()
+    }
+
+    /// Sets the persistence data for a specific type, stamped as saved now.
     pub fn set_type_data(&mut self, type_name: String, data: PersistData) {
+        self.set_type_data_at(type_name, data, Utc::now());
+    }
+
+    /// Sets the persistence data for a specific type, stamped as saved at `saved_at`.
+    pub fn set_type_data_at(
+        &mut self,
+        type_name: String,
+        mut data: PersistData,
+        saved_at: DateTime<Utc>,
+    ) {
+        data.last_saved = Some(saved_at);
         self.type_data.insert(type_name, data);
     }
+
+    /// Gives entries their field docs, keyed like the entries.
+    fn attach_field_docs(&mut self, docs: &HashMap<String, FieldDocs>) {
+        for (key, data) in &mut self.type_data {
+            if let Some(docs) = docs.get(key) {
+                data.docs = docs;
+            }
+        }
+    }
 }
 
 /// Persistence mode for a resource
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum PersistMode {
     /// Development mode - saves to local files for tweaking
     Dev,
@@ -229,789 +1270,7035 @@ pub enum PersistMode {
     Dynamic,
     /// Secure mode - encrypted/obfuscated save data
     Secure,
+    /// Obfuscated mode - like secure, but scrambled with a fast reversible
+    /// transform and a checksum instead of encrypted, without the `secure`
+    /// feature's crypto dependencies
+    Obfuscated,
+    /// Session mode - kept in memory only and discarded when the app exits
+    Session,
 }
 
-/// Trait for types that can be persisted.
+impl PersistMode {
+    /// Whether production saves go to the type's own file in a platform directory.
+    fn saves_to_resource_path(self) -> bool {
+        matches!(
+            self,
+            PersistMode::Dynamic | PersistMode::Secure | PersistMode::Obfuscated
+        )
+    }
+}
+
+/// How production files are split, set with [`PersistPlugin::with_file_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileLayout {
+    /// Each dynamic and secure type gets its own file
+    #[default]
+    PerType,
+    /// Dynamic and secure types share one `savegame.ron` in the platform
+    /// data directory, like the dev file. Types pinned to their own file or
+    /// a group keep it, and obfuscated types keep their scrambled files.
+    Single,
+}
+
+/// How file names are derived from type and group names, set with
+/// [`PersistPlugin::with_file_names`].
 ///
-/// This trait is typically implemented automatically by the `#[derive(Persist)]` macro.
-/// Manual implementation is possible but not recommended.
-pub trait Persistable: Resource + Serialize + for<'de> Deserialize<'de> {
-    /// Get the type name for persistence
-    fn type_name() -> &'static str;
+/// A type's own file is named after its [storage
+/// key](PersistManager::get_storage_key) with `::` as `_`, e.g.
+/// `my_game_playerstats` with the default strategy, or after the bare type
+/// name when the plugin has a namespace. Only `Preserve` keeps names that
+/// differ just in case apart, e.g. `PlayerHP` and `PlayerHp`; a warning is
+/// logged when two registered types would share a file. Embedded files are
+/// named at build time and always use the lowercase type name.
+#[derive(Clone, Default)]
+pub enum FileNameStrategy {
+    /// `PlayerStats` is saved as `playerstats`
+    #[default]
+    Lowercase,
+    /// `PlayerStats` is saved as `player_stats`
+    SnakeCase,
+    /// `PlayerStats` is saved as `player-stats`
+    KebabCase,
+    /// `PlayerStats` is saved as `PlayerStats`
+    Preserve,
+    /// The name is whatever the function returns
+    Custom(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
 
-    /// Get the persistence mode
-    fn persist_mode() -> PersistMode {
-        PersistMode::Dev
+impl FileNameStrategy {
+    /// Names files with `f`, given the type or group name.
+    pub fn custom(f: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        FileNameStrategy::Custom(Arc::new(f))
     }
 
-    /// Get embedded data if available
-    fn embedded_data() -> Option<&'static str> {
-        None
+    /// File name, without extension, for a type or group.
+    pub fn file_name(&self, name: &str) -> String {
+        match self {
+            FileNameStrategy::Lowercase => name.to_lowercase(),
+            FileNameStrategy::SnakeCase => separate_words(name, '_'),
+            FileNameStrategy::KebabCase => separate_words(name, '-'),
+            FileNameStrategy::Preserve => name.to_string(),
+            FileNameStrategy::Custom(f) => f(name),
+        }
     }
+}
 
-    /// Convert to persistence data
-    fn to_persist_data(&self) -> PersistData;
+impl std::fmt::Debug for FileNameStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileNameStrategy::Lowercase => f.write_str("Lowercase"),
+            FileNameStrategy::SnakeCase => f.write_str("SnakeCase"),
+            FileNameStrategy::KebabCase => f.write_str("KebabCase"),
+            FileNameStrategy::Preserve => f.write_str("Preserve"),
+            FileNameStrategy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
 
-    /// Load from persistence data
-    fn load_from_persist_data(&mut self, data: &PersistData);
+/// Lowercases `name`, putting `separator` between its words.
+///
+/// Words start at each capital after a lowercase letter or digit, and at
+/// the last capital of an acronym, so `HTTPServer` becomes `http_server`.
+/// Path separators, spaces, dashes and underscores all become `separator`.
+fn separate_words(name: &str, separator: char) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, ':' | ' ' | '-' | '_') {
+            if !out.is_empty() && !out.ends_with(separator) {
+                out.push(separator);
+            }
+            continue;
+        }
+        if c.is_uppercase() && !out.is_empty() && !out.ends_with(separator) {
+            let previous = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_lower)
+            {
+                out.push(separator);
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
 }
 
-/// Registration data for auto-discovered Persist types.
+/// Whether persistence behaves as in development or in a shipped game
 ///
-/// Used internally by the derive macro for automatic registration.
-#[derive(Debug)]
-pub struct PersistRegistration {
-    pub type_name: &'static str,
-    pub persist_mode: &'static str,
-    pub auto_save: bool,
-    pub embed_file: Option<&'static str>,
-    pub register_fn: fn(&mut App),
+/// Defaults to `Production` when built with the `prod` feature and `Dev`
+/// otherwise, but can be chosen at runtime with
+/// [`PersistPlugin::with_environment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistEnvironment {
+    /// Everything is saved to the local dev file, and embedded resources
+    /// are written to their asset files
+    Dev,
+    /// Dynamic and secure resources are saved in platform directories,
+    /// and embedded resources are read-only
+    Production,
 }
 
-inventory::collect!(PersistRegistration);
+impl Default for PersistEnvironment {
+    fn default() -> Self {
+        if cfg!(feature = "prod") {
+            PersistEnvironment::Production
+        } else {
+            PersistEnvironment::Dev
+        }
+    }
+}
 
-/// Resource that manages persistence.
+/// What happens to a resource whose loaded data fails [`Persistable::validate`].
 ///
-/// This resource is automatically added by `PersistPlugin` and handles
-/// all saving and loading operations for persistent resources.
-#[derive(Resource)]
-pub struct PersistManager {
-    /// Development file path (only used when not in production mode)
-    #[cfg(not(feature = "prod"))]
-    pub dev_file: PathBuf,
-    /// Application info for platform-specific paths
-    pub app_name: String,
-    pub organization: String,
-    /// Cached persist file
-    persist_file: PersistFile,
-    /// Whether auto-save is enabled globally
-    pub auto_save: bool,
-    /// Track which types have auto-save enabled
-    auto_save_types: HashMap<String, bool>,
-    /// Track persistence modes for types
-    persist_modes: HashMap<String, PersistMode>,
-    /// Track embed file paths for types
-    embed_files: HashMap<String, String>,
-    /// Secret for encrypting secure persistence (optional)
-    #[cfg(feature = "secure")]
-    secret: Option<String>,
+/// Either way a [`PersistValidationFailed`] event is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationPolicy {
+    /// Keeps the loaded values as `validate` left them, e.g. clamped
+    #[default]
+    Clamp,
+    /// Replaces the resource with its default
+    Reset,
 }
 
-impl PersistManager {
-    /// Creates a new PersistManager.
-    pub fn new(organization: impl Into<String>, app_name: impl Into<String>) -> Self {
-        let organization = organization.into();
-        let app_name = app_name.into();
-
-        // In dev mode, load from the dev file if it exists
-        #[cfg(not(feature = "prod"))]
-        let dev_file = PathBuf::from(format!(
-            "{}_dev.ron",
-            app_name.to_lowercase().replace(" ", "_")
-        ));
+/// What happens to a type whose file was written by a newer version of the game.
+///
+/// Only checked once the game's version is set with
+/// [`PersistPlugin::with_app_version`]. Either way a [`PersistVersionEvent`]
+/// is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DowngradePolicy {
+    /// Leaves the resource at its default and never overwrites the file,
+    /// so the save is intact once the player updates again
+    #[default]
+    Refuse,
+    /// Loads whatever fields this version understands
+    BestEffort,
+    /// Copies the file next to itself as a `.bak`, then starts from the
+    /// default and saves over it
+    BackupAndReset,
+}
 
-        #[cfg(not(feature = "prod"))]
-        let persist_file = PersistFile::load_from_file(&dev_file).unwrap_or_else(|e| {
-            debug!("No existing dev file found: {}", e);
-            PersistFile::new()
-        });
+/// What happens to a save bigger than its size quota.
+///
+/// Quotas are set with [`PersistPlugin::with_size_quota`] or per type with
+/// [`PersistResourceBuilder::size_quota`], and measure the serialized data.
+/// A [`PersistQuotaExceeded`] event is sent for every refused save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuotaPolicy {
+    /// Fails the save with [`PersistError::QuotaExceeded`], keeping the old file
+    #[default]
+    Refuse,
+    /// Gzips the file if that brings it under the quota, and refuses it
+    /// otherwise. Types saved into a shared file can't be compressed on
+    /// their own, so they're always refused.
+    #[cfg(feature = "compression")]
+    Compress,
+}
 
-        #[cfg(feature = "prod")]
-        let persist_file = PersistFile::new();
+/// How failed auto-saves are retried, set with [`PersistPlugin::with_retry_policy`].
+///
+/// Each retry waits twice as long as the one before, up to `max_delay`.
+/// Defaults to three retries, the first after half a second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times a failed save is retried before giving up
+    pub max_retries: u32,
+    /// Wait before the first retry
+    pub initial_delay: Duration,
+    /// Longest wait between two retries
+    pub max_delay: Duration,
+}
 
+impl Default for RetryPolicy {
+    fn default() -> Self {
         Self {
-            #[cfg(not(feature = "prod"))]
-            dev_file,
-            app_name,
-            organization,
-            persist_file,
-            auto_save: true,
-            auto_save_types: HashMap::new(),
-            persist_modes: HashMap::new(),
-            embed_files: HashMap::new(),
-            #[cfg(feature = "secure")]
-            secret: None,
+            max_retries: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
         }
     }
+}
 
-    /// Set the secret for encrypting secure persistence
-    #[cfg(feature = "secure")]
-    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
-        self.secret = Some(secret.into());
-        self
+impl RetryPolicy {
+    /// Never retries; failed saves wait for the next change.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
     }
 
-    /// Derive an encryption key from the secret and a salt
-    #[cfg(feature = "secure")]
-    fn derive_key(&self, salt: &[u8]) -> Option<[u8; 32]> {
-        if let Some(secret) = &self.secret {
-            let mut key = [0u8; 32];
-            // Use Argon2 to derive a key from the secret
-            let argon2 = Argon2::default();
-            argon2
-                .hash_password_into(secret.as_bytes(), salt, &mut key)
-                .ok()?;
-            Some(key)
-        } else {
-            None
-        }
+    /// Wait before a retry, counting from zero.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
     }
+}
 
-    /// Encrypt data for secure persistence
-    #[cfg(feature = "secure")]
-    fn encrypt_data(&self, data: &[u8]) -> PersistResult<Vec<u8>> {
-        use aes_gcm::aead::rand_core::RngCore;
+/// How files are laid out when written, set with [`PersistPlugin::with_style`].
+///
+/// Defaults to pretty-printing with four-space indents and no struct names.
+/// Encrypted secure files are always compact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistStyle {
+    /// Whether to spread values over several lines; compact files are one line
+    pub pretty: bool,
+    /// Indentation of each nesting level when pretty-printing (RON and JSON)
+    pub indent: String,
+    /// Nesting level past which values are kept on one line (RON)
+    pub depth_limit: Option<usize>,
+    /// Whether structs are written with their names, as in `Audio(volume: 0.5)` (RON)
+    pub struct_names: bool,
+}
 
-        if self.secret.is_none() {
-            return Err(PersistError::EncryptionError(
-                "No secret configured for secure persistence".to_string(),
-            ));
+impl Default for PersistStyle {
+    fn default() -> Self {
+        Self {
+            pretty: true,
+            indent: "    ".to_string(),
+            depth_limit: None,
+            struct_names: false,
         }
+    }
+}
 
-        // Generate a random salt and nonce
-        let mut salt = [0u8; 16];
-        let mut nonce_bytes = [0u8; 12];
-        let mut rng = aes_gcm::aead::OsRng;
-        rng.fill_bytes(&mut salt);
-        rng.fill_bytes(&mut nonce_bytes);
-
-        // Derive key from secret
-        let key = self.derive_key(&salt).ok_or_else(|| {
-            PersistError::EncryptionError("Failed to derive encryption key".to_string())
-        })?;
-
-        // Encrypt using AES-256-GCM
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
-        let nonce = Nonce::from_slice(&nonce_bytes);
+impl PersistStyle {
+    /// Everything on one line, for files nobody edits by hand.
+    pub fn compact() -> Self {
+        Self {
+            pretty: false,
+            ..Self::default()
+        }
+    }
 
-        let ciphertext = cipher
-            .encrypt(nonce, data)
-            .map_err(|e| PersistError::EncryptionError(format!("Encryption failed: {}", e)))?;
+    /// Sets the indentation of each nesting level.
+    pub fn with_indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
 
-        // Prepend salt and nonce to the ciphertext
-        let mut result = Vec::with_capacity(salt.len() + nonce_bytes.len() + ciphertext.len());
-        result.extend_from_slice(&salt);
-        result.extend_from_slice(&nonce_bytes);
-        result.extend_from_slice(&ciphertext);
+    /// Keeps values nested deeper than `limit` on one line.
+    pub fn with_depth_limit(mut self, limit: usize) -> Self {
+        self.depth_limit = Some(limit);
+        self
+    }
 
-        Ok(result)
+    /// Sets whether RON structs are written with their names.
+    pub fn with_struct_names(mut self, enabled: bool) -> Self {
+        self.struct_names = enabled;
+        self
     }
+}
 
-    /// Decrypt data from secure persistence
-    #[cfg(feature = "secure")]
-    fn decrypt_data(&self, encrypted: &[u8]) -> PersistResult<Vec<u8>> {
-        if self.secret.is_none() {
-            return Err(PersistError::EncryptionError(
-                "No secret configured for secure persistence".to_string(),
-            ));
-        }
+/// On-disk serialization format for a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum PersistFormat {
+    /// Rusty Object Notation (default)
+    #[default]
+    Ron,
+    /// JSON
+    Json,
+    /// TOML (requires the `toml` feature)
+    Toml,
+}
 
-        if encrypted.len() < 28 {
-            // 16 (salt) + 12 (nonce)
-            return Err(PersistError::EncryptionError(
-                "Invalid encrypted data format".to_string(),
-            ));
+impl PersistFormat {
+    /// Parses a format name as used by `#[persist(format = "...")]`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ron" => Some(Self::Ron),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
         }
+    }
 
-        // Extract salt, nonce, and ciphertext
-        let salt = &encrypted[0..16];
-        let nonce_bytes = &encrypted[16..28];
-        let ciphertext = &encrypted[28..];
-
-        // Derive key from secret
-        let key = self.derive_key(salt).ok_or_else(|| {
-            PersistError::EncryptionError("Failed to derive decryption key".to_string())
-        })?;
+    /// Guesses the format from a file extension, falling back to JSON.
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+            .unwrap_or(Self::Json)
+    }
 
-        // Decrypt using AES-256-GCM
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
-        let nonce = Nonce::from_slice(nonce_bytes);
+    /// File extension used for files in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ron => "ron",
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
 
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| PersistError::EncryptionError(format!("Decryption failed: {}", e)))?;
+    /// Serializes a value to a pretty-printed string in this format.
+    pub fn serialize<T: Serialize>(self, value: &T) -> PersistResult<String> {
+        self.serialize_styled(value, &PersistStyle::default())
+    }
 
-        Ok(plaintext)
+    /// Serializes a value to a string in this format, laid out as `style` says.
+    pub fn serialize_styled<T: Serialize>(
+        self,
+        value: &T,
+        style: &PersistStyle,
+    ) -> PersistResult<String> {
+        let mut buffer = Vec::new();
+        self.serialize_styled_to(&mut buffer, value, style)?;
+        Ok(String::from_utf8(buffer).expect("serializers write UTF-8"))
     }
 
-    /// Get the appropriate path for a resource based on its mode
-    pub fn get_resource_path(&self, type_name: &str, mode: PersistMode) -> PathBuf {
-        #[cfg(feature = "prod")]
-        {
-            match mode {
-                PersistMode::Dev => {
-                    // In production, dev mode resources shouldn't exist
-                    // But if they do, save to a local file as fallback
-                    PathBuf::from(format!(
-                        "{}_dev.ron",
-                        self.app_name.to_lowercase().replace(" ", "_")
-                    ))
-                }
-                PersistMode::Dynamic => {
-                    if let Some(proj_dirs) =
-                        ProjectDirs::from("", &self.organization, &self.app_name)
-                    {
-                        let config_dir = proj_dirs.config_dir();
-                        fs::create_dir_all(config_dir).ok();
-                        config_dir.join(format!("{}.ron", type_name.to_lowercase()))
-                    } else {
-                        // Fallback to current directory if platform dirs unavailable
-                        PathBuf::from(format!("{}.ron", type_name.to_lowercase()))
-                    }
-                }
-                PersistMode::Secure => {
-                    if let Some(proj_dirs) =
-                        ProjectDirs::from("", &self.organization, &self.app_name)
-                    {
-                        let data_dir = proj_dirs.data_dir();
-                        fs::create_dir_all(data_dir).ok();
-                        data_dir.join(format!("{}.dat", type_name.to_lowercase()))
+    /// Serializes a value straight into `writer` in this format, laid out as
+    /// `style` says.
+    ///
+    /// RON and JSON are written as they're serialized, so large values are
+    /// never held in memory as a whole. TOML is built as a string first.
+    pub fn serialize_styled_to<W: Write, T: Serialize>(
+        self,
+        writer: W,
+        value: &T,
+        style: &PersistStyle,
+    ) -> PersistResult<()> {
+        match self {
+            Self::Ron => {
+                let config = ron::ser::PrettyConfig::new()
+                    .indentor(style.indent.clone())
+                    .struct_names(style.struct_names);
+                // Nothing deeper than the limit gets its own line
+                let config = match (style.pretty, style.depth_limit) {
+                    (false, _) => config.depth_limit(0),
+                    (true, Some(limit)) => config.depth_limit(limit),
+                    (true, None) => config,
+                };
+                ron::Options::default()
+                    .to_io_writer_pretty(writer, value, config)
+                    .map_err(|e| match e {
+                        ron::Error::Io(e) => PersistError::io_other(
+                            format!("Failed to write file: {}", e),
+                            std::io::ErrorKind::Other,
+                        ),
+                        e => PersistError::serialization(format!("RON serialization error: {}", e)),
+                    })
+            }
+            Self::Json => {
+                let result = if style.pretty {
+                    let formatter =
+                        serde_json::ser::PrettyFormatter::with_indent(style.indent.as_bytes());
+                    let mut serializer = serde_json::Serializer::with_formatter(writer, formatter);
+                    value.serialize(&mut serializer)
+                } else {
+                    serde_json::to_writer(writer, value)
+                };
+                result.map_err(|e| {
+                    if e.is_io() {
+                        write_error(e.into())
                     } else {
-                        // Fallback to current directory if platform dirs unavailable
-                        PathBuf::from(format!("{}.dat", type_name.to_lowercase()))
+                        PersistError::serialization(format!("JSON serialization error: {}", e))
                     }
-                }
-                PersistMode::Embed => {
-                    // Embedded resources don't save to disk in prod
-                    PathBuf::new()
-                }
+                })
             }
-        }
-        #[cfg(not(feature = "prod"))]
-        {
-            // In dev mode, everything goes to the dev file
-            let _ = (type_name, mode); // Suppress warnings
-            self.dev_file.clone()
+            #[cfg(feature = "toml")]
+            Self::Toml => {
+                let result = if style.pretty {
+                    toml::to_string_pretty(value)
+                } else {
+                    toml::to_string(value)
+                };
+                let content = result.map_err(|e| {
+                    PersistError::serialization(format!("TOML serialization error: {}", e))
+                })?;
+                let mut writer = writer;
+                writer.write_all(content.as_bytes()).map_err(write_error)
+            }
+            #[cfg(not(feature = "toml"))]
+            Self::Toml => Err(PersistError::serialization(
+                "TOML support requires the `toml` feature".to_string(),
+            )),
         }
     }
 
-    /// Saves all persistent data to the file.
-    pub fn save(&mut self) -> PersistResult<()> {
-        #[cfg(not(feature = "prod"))]
-        return self.persist_file.save_to_file(&self.dev_file);
-
-        #[cfg(feature = "prod")]
-        {
-            // In production, this is only used as a fallback for dev mode resources
-            let fallback_path = PathBuf::from(format!(
-                "{}_dev.ron",
-                self.app_name.to_lowercase().replace(" ", "_")
-            ));
-            self.persist_file.save_to_file(&fallback_path)
+    /// Deserializes a value from a string in this format.
+    ///
+    /// Errors name the field that failed, where the format can tell.
+    pub fn deserialize<T: for<'de> Deserialize<'de>>(self, content: &str) -> PersistResult<T> {
+        match self {
+            Self::Ron => {
+                let parse_error =
+                    |e| PersistError::serialization(format!("RON parse error: {}", e));
+                let mut deserializer = ron::Deserializer::from_str(content).map_err(parse_error)?;
+                let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+                    let field = e.path().iter().next().map(|_| e.path().to_string());
+                    let spanned = deserializer.span_error(e.into_inner());
+                    PersistError::SerializationError {
+                        message: format!("RON parse error: {}", spanned),
+                        field,
+                        path: None,
+                        type_name: None,
+                    }
+                })?;
+                deserializer
+                    .end()
+                    .map_err(|e| parse_error(deserializer.span_error(e)))?;
+                Ok(value)
+            }
+            Self::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(content);
+                let value = serde_path_to_error::deserialize(&mut deserializer)
+                    .map_err(|e| PersistError::deserialization("JSON parse error: ", e))?;
+                deserializer
+                    .end()
+                    .map_err(|e| PersistError::serialization(format!("JSON parse error: {}", e)))?;
+                Ok(value)
+            }
+            #[cfg(feature = "toml")]
+            Self::Toml => serde_path_to_error::deserialize(toml::Deserializer::new(content))
+                .map_err(|e| PersistError::deserialization("TOML parse error: ", e)),
+            #[cfg(not(feature = "toml"))]
+            Self::Toml => Err(PersistError::serialization(
+                "TOML support requires the `toml` feature",
+            )),
         }
     }
+}
 
-    /// Reloads persistent data from the file.
-    pub fn load(&mut self) -> PersistResult<()> {
-        #[cfg(not(feature = "prod"))]
-        {
-            self.persist_file = PersistFile::load_from_file(&self.dev_file)?;
-            Ok(())
-        }
+/// Trait for types that can be persisted.
+///
+/// This trait is typically implemented automatically by the `#[derive(Persist)]` macro.
+/// Manual implementation is possible but not recommended.
+pub trait Persistable: Resource + Serialize + for<'de> Deserialize<'de> {
+    /// Get the type name for persistence
+    fn type_name() -> &'static str;
 
-        #[cfg(feature = "prod")]
-        {
-            // In production, this would only be called for fallback scenarios
-            let fallback_path = PathBuf::from(format!(
-                "{}_dev.ron",
-                self.app_name.to_lowercase().replace(" ", "_")
-            ));
-            self.persist_file = PersistFile::load_from_file(&fallback_path)?;
-            Ok(())
-        }
+    /// Get the fully qualified path of the type, used to namespace storage keys
+    fn type_path() -> &'static str {
+        Self::type_name()
     }
 
-    /// Gets a reference to the underlying persist file.
-    pub fn get_persist_file(&self) -> &PersistFile {
-        &self.persist_file
+    /// Get the persistence mode
+    fn persist_mode() -> PersistMode {
+        PersistMode::Dev
     }
 
-    /// Gets a mutable reference to the underlying persist file.
-    pub fn get_persist_file_mut(&mut self) -> &mut PersistFile {
-        &mut self.persist_file
+    /// Get the file this type is pinned to, if any
+    fn persist_file() -> Option<&'static str> {
+        None
     }
 
-    /// Checks if auto-save is enabled for a specific type.
-    pub fn is_auto_save_enabled(&self, type_name: &str) -> bool {
-        self.auto_save && self.auto_save_types.get(type_name).copied().unwrap_or(true)
+    /// Get the group whose shared file this type is saved into, if any
+    fn persist_group() -> Option<&'static str> {
+        None
     }
 
-    /// Sets whether auto-save is enabled for a specific type.
-    pub fn set_type_auto_save(&mut self, type_name: String, enabled: bool) {
-        self.auto_save_types.insert(type_name, enabled);
+    /// Get the subdirectory this type's production file is nested in, if any
+    fn persist_dir() -> Option<&'static str> {
+        None
     }
 
-    /// Sets the persistence mode for a specific type.
-    pub fn set_type_mode(&mut self, type_name: String, mode: PersistMode) {
-        self.persist_modes.insert(type_name, mode);
+    /// Get the on-disk format for files owned by this type
+    fn persist_format() -> PersistFormat {
+        PersistFormat::Ron
     }
 
-    /// Gets the persistence mode for a specific type.
-    pub fn get_type_mode(&self, type_name: &str) -> PersistMode {
-        self.persist_modes
-            .get(type_name)
-            .copied()
-            .unwrap_or(PersistMode::Dev)
+    /// Whether this type auto-saves when no explicit preference is given
+    fn default_auto_save() -> bool {
+        true
     }
-    
-    /// Sets the embed file path for a specific type.
-    pub fn set_type_embed_file(&mut self, type_name: String, file_path: String) {
-        self.embed_files.insert(type_name, file_path);
+
+    /// Asset file that embedded data is read from and written to in dev
+    fn embed_file() -> Option<&'static str> {
+        None
     }
-    
-    /// Gets the embed file path for a specific type.
-    pub fn get_type_embed_file(&self, type_name: &str) -> Option<&String> {
-        self.embed_files.get(type_name)
+
+    /// Get embedded data if available
+    fn embedded_data() -> Option<&'static str> {
+        None
     }
 
-    /// Save a resource to disk based on its persistence mode
-    #[cfg(feature = "prod")]
-    pub fn save_resource(
-        &self,
-        type_name: &str,
-        data: &PersistData,
-        mode: PersistMode,
-    ) -> PersistResult<()> {
-        match mode {
-            PersistMode::Embed => {
-                // Embedded resources don't save in production
-                Ok(())
-            }
-            PersistMode::Secure => {
-                #[cfg(feature = "secure")]
-                {
-                    // Serialize to RON first
-                    let ron_string = ron::to_string(data)
-                        .map_err(|e| PersistError::SerializationError(e.to_string()))?;
+    /// Embedded data as a blob from [`PersistManager::encode_embedded`].
+    ///
+    /// Set with `#[persist(embed_binary = "path")]` when deriving, and
+    /// decoded at startup in place of [`embedded_data`](Self::embedded_data).
+    fn embedded_bytes() -> Option<&'static [u8]> {
+        None
+    }
 
-                    // Encrypt the data if secret is available
-                    let final_data = if self.secret.is_some() {
-                        self.encrypt_data(ron_string.as_bytes())?
-                    } else {
-                        // If no secret, just obfuscate with base64
-                        use base64::{engine::general_purpose, Engine as _};
-                        general_purpose::STANDARD
-                            .encode(ron_string.as_bytes())
-                            .into_bytes()
-                    };
+    /// Compiled-in defaults that saved data is layered over, if any.
+    ///
+    /// Set with `#[persist(defaults = "path")]` when deriving. See
+    /// [`PersistManager::set_type_defaults`].
+    fn defaults_data() -> Option<&'static str> {
+        None
+    }
 
-                    // Write to .dat file
-                    let path = self.get_resource_path(type_name, mode);
-                    fs::write(&path, final_data).map_err(|e| {
-                        PersistError::IoError(format!(
-                            "Failed to write secure file {}: {}",
-                            path.display(),
-                            e
-                        ))
-                    })?;
-                    Ok(())
-                }
-                #[cfg(not(feature = "secure"))]
-                {
-                    // Without secure feature, fall back to dynamic
-                    self.save_resource(type_name, data, PersistMode::Dynamic)
-                }
-            }
-            _ => {
-                // Dynamic and Dev modes save as RON
-                let path = self.get_resource_path(type_name, mode);
-                let ron_string =
-                    ron::ser::to_string_pretty(data, ron::ser::PrettyConfig::default())
-                        .map_err(|e| PersistError::SerializationError(e.to_string()))?;
-                fs::write(&path, ron_string).map_err(|e| {
-                    PersistError::IoError(format!("Failed to write file {}: {}", path.display(), e))
-                })?;
-                Ok(())
-            }
-        }
+    /// Whether only fields differing from `Default` are saved.
+    ///
+    /// Loading applies the saved fields over `Default`, so fields added in
+    /// an update pick up their defaults. Set with `#[persist(delta)]` when
+    /// deriving. Shipped [`defaults_data`](Self::defaults_data) take its place.
+    fn persist_delta() -> bool {
+        false
     }
 
-    /// Load a resource from disk based on its persistence mode
-    #[cfg(feature = "prod")]
-    pub fn load_resource(&self, type_name: &str, mode: PersistMode) -> PersistResult<PersistData> {
-        match mode {
-            PersistMode::Embed => {
-                // This should be handled by embedded_data() in the Persist trait
-                Err(PersistError::ResourceNotFound(format!(
-                    "Embedded resource {} should use embedded_data()",
-                    type_name
-                )))
-            }
-            PersistMode::Secure => {
-                #[cfg(feature = "secure")]
-                {
-                    let path = self.get_resource_path(type_name, mode);
-                    let encrypted = fs::read(&path).map_err(|e| {
-                        PersistError::IoError(format!(
-                            "Failed to read secure file {}: {}",
-                            path.display(),
-                            e
-                        ))
-                    })?;
+    /// Convert to persistence data
+    fn to_persist_data(&self) -> PersistData;
 
-                    // Decrypt the data if secret is available
-                    let ron_bytes = if self.secret.is_some() {
-                        self.decrypt_data(&encrypted)?
-                    } else {
-                        // If no secret, assume it's just base64 encoded
-                        use base64::{engine::general_purpose, Engine as _};
-                        general_purpose::STANDARD.decode(&encrypted).map_err(|e| {
-                            PersistError::EncryptionError(format!("Failed to decode base64: {}", e))
-                        })?
-                    };
+    /// Load from persistence data
+    fn load_from_persist_data(&mut self, data: &PersistData);
 
-                    // Deserialize from RON
-                    let ron_string = String::from_utf8(ron_bytes).map_err(|e| {
-                        PersistError::SerializationError(format!(
-                            "Invalid UTF-8 in decrypted data: {}",
-                            e
-                        ))
-                    })?;
-                    ron::from_str(&ron_string)
-                        .map_err(|e| PersistError::SerializationError(e.to_string()))
-                }
-                #[cfg(not(feature = "secure"))]
-                {
-                    // Without secure feature, fall back to dynamic
-                    self.load_resource(type_name, PersistMode::Dynamic)
-                }
-            }
-            _ => {
-                // Dynamic and Dev modes load as RON
-                let path = self.get_resource_path(type_name, mode);
-                let contents = fs::read_to_string(&path).map_err(|e| {
-                    PersistError::IoError(format!("Failed to read file {}: {}", path.display(), e))
-                })?;
-                ron::from_str(&contents)
-                    .map_err(|e| PersistError::SerializationError(e.to_string()))
-            }
+    /// Checks loaded values, fixing them in place where possible.
+    ///
+    /// Runs right after [`load_from_persist_data`](Self::load_from_persist_data).
+    /// On `Err`, the manager's [`ValidationPolicy`] decides what happens to
+    /// the resource and a [`PersistValidationFailed`] event is sent. The
+    /// derive macro builds it from `#[persist(validate = "my_fn")]` and
+    /// fields marked `#[persist(range(min = .., max = ..))]`.
+    fn validate(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Tidies the resource up right before it is saved.
+    ///
+    /// Runs ahead of [`to_persist_data`](Self::to_persist_data) whenever the
+    /// manager saves the resource, so round floats, sort collections or drop
+    /// session-only entries here to keep files clean and stable. Changes made
+    /// here don't count as changes for auto-save. Set with
+    /// `#[persist(before_save = "my_fn")]` when deriving.
+    fn before_save(&mut self) {}
+
+    /// Doc comments of the persisted fields, as `(field, docs)` pairs.
+    ///
+    /// The derive macro fills this in from `///` comments so they show up
+    /// above each field in development RON files.
+    fn field_docs() -> FieldDocs {
+        &[]
+    }
+
+    /// Fields encrypted inside otherwise readable production files.
+    ///
+    /// The derive macro fills this in from `#[persist(encrypt)]` fields. See
+    /// [`PersistManager::set_type_encrypted_fields`].
+    fn encrypted_fields() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Clamps a loaded field into `min..=max`, describing what changed.
+///
+/// Used by the derive macro for `#[persist(range(...))]`. Values that can't
+/// be compared, like NaN, go to `min` (or `max` without one).
+pub fn clamp_field<T: PartialOrd + Copy + std::fmt::Display>(
+    name: &str,
+    value: &mut T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Option<String> {
+    use std::cmp::Ordering::{Greater, Less};
+
+    let loaded = *value;
+    let clamped = match (min, max) {
+        (Some(min), _) if matches!(loaded.partial_cmp(&min), None | Some(Less)) => min,
+        (_, Some(max)) if matches!(loaded.partial_cmp(&max), None | Some(Greater)) => max,
+        _ => return None,
+    };
+    *value = clamped;
+    Some(format!("{} {} clamped to {}", name, loaded, clamped))
+}
+
+/// Builds the short name of one instantiation of a generic type.
+///
+/// `base` replaces the type's own name and the generic arguments are appended
+/// with their module paths stripped, so `game::Scores<game::Level>` becomes
+/// `Scores<Level>`. The result is cached per concrete type and base.
+///
+/// The arguments are spelled by [`std::any::type_name`], whose output isn't
+/// guaranteed and may change between compiler releases. Storage keys under a
+/// [namespace](PersistPlugin::with_namespace) are built from this name, so
+/// data saved by one toolchain could be missed by another.
+pub fn generic_type_name<T: 'static>(base: &'static str) -> &'static str {
+    generic_name::<T>(base, true)
+}
+
+/// Builds the type path of one instantiation of a generic type.
+///
+/// Like [`generic_type_name`], but the generic arguments keep their full
+/// module paths, so `Scores<game::Level>` and `Scores<editor::Level>` get
+/// different storage keys. The same caveat about the format applies.
+pub fn generic_type_path<T: 'static>(base: &'static str) -> &'static str {
+    generic_name::<T>(base, false)
+}
+
+/// `base` followed by the generic arguments of `T`, cached per concrete
+/// type, base and whether module paths are stripped.
+fn generic_name<T: 'static>(base: &'static str, strip: bool) -> &'static str {
+    type NameCache = HashMap<(TypeId, &'static str, bool), &'static str>;
+    static NAMES: OnceLock<Mutex<NameCache>> = OnceLock::new();
+
+    let mut names = NAMES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    names
+        .entry((TypeId::of::<T>(), base, strip))
+        .or_insert_with(|| {
+            let full = std::any::type_name::<T>();
+            let args = full.find('<').map(|i| &full[i..]).unwrap_or("");
+            let args = if strip {
+                strip_module_paths(args)
+            } else {
+                args.to_string()
+            };
+            Box::leak(format!("{}{}", base, args).into_boxed_str())
+        })
+}
+
+/// Removes `module::` prefixes from every path inside a type name.
+fn strip_module_paths(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut segment = String::new();
+
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            result.push_str(segment.rsplit("::").next().unwrap_or(""));
+            segment.clear();
+            result.push(c);
         }
     }
+    result.push_str(segment.rsplit("::").next().unwrap_or(""));
+    result
 }
 
-/// Plugin for automatic persistence.
+/// Registration data for auto-discovered Persist types.
 ///
-/// Add this plugin to your Bevy app to enable automatic persistence
-/// for resources marked with `#[derive(Persist)]`.
+/// Used internally by the derive macro for automatic registration.
+#[derive(Debug)]
+pub struct PersistRegistration {
+    pub type_name: &'static str,
+    pub type_path: &'static str,
+    pub persist_mode: &'static str,
+    pub auto_save: bool,
+    pub embed_file: Option<&'static str>,
+    pub file: Option<&'static str>,
+    pub group: Option<&'static str>,
+    pub store: Option<&'static str>,
+    pub register_fn: fn(&mut App),
+}
+
+inventory::collect!(PersistRegistration);
+
+/// Decides where production files live.
 ///
-/// # Example
+/// Implement this to take over file placement, e.g. for launcher-managed
+/// save directories or platform-mandated paths, and install it with
+/// [`PersistPlugin::with_path_resolver`]. Group files are resolved the same
+/// way, with the group name in place of the type name.
+///
+/// If the returned path has no extension, one matching the type's format
+/// (or `dat` for secure data) is added.
+pub trait PathResolver: Send + Sync + 'static {
+    /// Returns the file path for a type (or group) persisted with `mode`.
+    fn resolve(&self, type_name: &str, mode: PersistMode) -> PathBuf;
+
+    /// Like [`resolve`](Self::resolve), given the file name the plugin's
+    /// [`FileNameStrategy`] picked for the type from its storage key.
+    ///
+    /// Resolvers that name files after the type should override this to use
+    /// `file_name`. By default the strategy is ignored.
+    fn resolve_file(&self, type_name: &str, file_name: &str, mode: PersistMode) -> PathBuf {
+        let _ = file_name;
+        self.resolve(type_name, mode)
+    }
+}
+
+/// Receives persistence activity, e.g. to ship save health metrics to an
+/// analytics backend.
+///
+/// Install it with [`PersistPlugin::with_telemetry`]. Every method does
+/// nothing by default, so only the interesting ones need implementing. They
+/// run on the thread doing the save or load, so anything slow should be
+/// queued rather than sent right away.
 ///
 /// ```ignore
-/// // Recommended: Always provide app info for proper paths
-/// app.add_plugins(
-///     PersistPlugin::new("MyCompany", "MyGame")
-/// );
+/// struct Analytics(Sender<Metric>);
 ///
-/// // Optional: Disable auto-save for manual control
-/// app.add_plugins(
-///     PersistPlugin::new("MyCompany", "MyGame")
-///         .with_auto_save(false)
-/// );
+/// impl PersistTelemetry for Analytics {
+///     fn on_error(&self, type_name: &str, error: &PersistError) {
+///         self.0.send(Metric::SaveError(type_name.to_string(), error.to_string())).ok();
+///     }
+/// }
 /// ```
-pub struct PersistPlugin {
-    /// Organization name (e.g., "MyCompany")
-    pub organization: String,
-    /// Application name (e.g., "MyGame")
-    pub app_name: String,
-    /// Whether to enable auto-save on changes
-    pub auto_save: bool,
-    /// Secret for encrypting secure persistence (optional)
-    #[cfg(feature = "secure")]
-    secret: Option<String>,
+pub trait PersistTelemetry: Send + Sync + 'static {
+    /// Called after a type's data is written, with the size of the file it went into.
+    fn on_save(&self, type_name: &str, duration: Duration, bytes: u64) {
+        let _ = (type_name, duration, bytes);
+    }
+
+    /// Called after a type's data is read, with its size in the type's format.
+    fn on_load(&self, type_name: &str, duration: Duration, bytes: u64) {
+        let _ = (type_name, duration, bytes);
+    }
+
+    /// Called when saving a type or reading its file fails.
+    fn on_error(&self, type_name: &str, error: &PersistError) {
+        let _ = (type_name, error);
+    }
 }
 
-impl Default for PersistPlugin {
-    fn default() -> Self {
+/// Tells the manager what time it is, for the timestamps written into files.
+///
+/// Install one with [`PersistPlugin::with_clock`] to control `last_saved`
+/// values, e.g. for snapshot tests of file output or replays. Closures
+/// returning a [`DateTime<Utc>`] are clocks too.
+///
+/// ```ignore
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+/// app.add_plugins(PersistPlugin::new("MyCompany", "MyGame").with_clock(move || start));
+/// ```
+pub trait PersistClock: Send + Sync + 'static {
+    /// The current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+impl<F> PersistClock for F
+where
+    F: Fn() -> DateTime<Utc> + Send + Sync + 'static,
+{
+    fn now(&self) -> DateTime<Utc> {
+        self()
+    }
+}
+
+/// Somewhere other than files to keep persisted data, e.g. a key-value store.
+///
+/// Install one with [`PersistPlugin::with_backend`]. Each type's data is
+/// then read and written under its [storage key](PersistManager::get_storage_key)
+/// instead of going to the dev, group, pinned or production files, encoded
+/// the way its own file would be. Session data stays in memory, and
+/// embedded resources keep their asset files.
+///
+/// Saves can be binary, e.g. obfuscated or compressed, so they must be
+/// stored as bytes. Stores whose writes take `&mut self` can sit behind a
+/// [`Mutex`]:
+///
+/// ```ignore
+/// struct PkvBackend(Mutex<PkvStore>);
+///
+/// impl PersistBackend for PkvBackend {
+///     fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+///         match self.0.lock().unwrap().get::<Vec<u8>>(key) {
+///             Ok(bytes) => Ok(Some(bytes)),
+///             Err(GetError::NotFound) => Ok(None),
+///             Err(e) => Err(PersistError::backend(e)),
+///         }
+///     }
+///     // ...
+/// }
+/// ```
+///
+/// Stores that only keep strings can use `TextBackend` (`text-backend`
+/// feature), which stores saves as base64.
+pub trait PersistBackend: Send + Sync + 'static {
+    /// Returns what's stored under `key`, or `None` if nothing is.
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>>;
+
+    /// Stores `bytes` under `key`, replacing anything already there.
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()>;
+
+    /// Removes what's stored under `key`, if anything.
+    fn remove(&self, key: &str) -> PersistResult<()>;
+
+    /// Stores `bytes` under `key` only if it still holds `expected`, or
+    /// nothing for `None`. Returns whether it was written.
+    ///
+    /// Cloud sync pushes with this, so a device can't overwrite a revision
+    /// it hasn't seen. The default reads and then writes, which another
+    /// writer can slip in between; stores with transactions should do both
+    /// in one.
+    fn write_if(&self, key: &str, expected: Option<&[u8]>, bytes: &[u8]) -> PersistResult<bool> {
+        if self.read(key)?.as_deref() != expected {
+            return Ok(false);
+        }
+        self.write(key, bytes)?;
+        Ok(true)
+    }
+}
+
+/// Shares a backend with code that uses it directly, e.g. to restore history.
+impl<B: PersistBackend + ?Sized> PersistBackend for Arc<B> {
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+        (**self).read(key)
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()> {
+        (**self).write(key, bytes)
+    }
+
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        (**self).remove(key)
+    }
+
+    fn write_if(&self, key: &str, expected: Option<&[u8]>, bytes: &[u8]) -> PersistResult<bool> {
+        (**self).write_if(key, expected, bytes)
+    }
+}
+
+/// Default [`PersistClock`], reading the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl PersistClock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Default [`PathResolver`] using the platform's standard directories.
+///
+/// Secure data goes in the data dir and everything else in the config dir,
+/// falling back to the working directory if they're unavailable.
+#[derive(Debug, Clone)]
+pub struct PlatformPathResolver {
+    config_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+}
+
+impl PlatformPathResolver {
+    /// Creates a resolver for the given organization and app name.
+    pub fn new(organization: &str, app_name: &str) -> Self {
+        let proj_dirs = ProjectDirs::from("", organization, app_name);
         Self {
-            organization: "DefaultOrg".to_string(),
-            app_name: "DefaultApp".to_string(),
-            auto_save: true,
-            #[cfg(feature = "secure")]
-            secret: None,
+            config_dir: proj_dirs
+                .as_ref()
+                .map(|dirs| dirs.config_dir().to_path_buf()),
+            data_dir: proj_dirs.as_ref().map(|dirs| dirs.data_dir().to_path_buf()),
         }
     }
+
+    /// Nests config files in `config` and data files in `data`, e.g.
+    /// `settings` and `saves` for `config/settings/` and `data/saves/`.
+    ///
+    /// Types given their own [subdirectory](PersistManager::set_type_dir)
+    /// are nested inside these.
+    pub fn with_subdirectories(mut self, config: impl AsRef<Path>, data: impl AsRef<Path>) -> Self {
+        let nest = |dir: Option<PathBuf>, sub: &Path| match dir {
+            Some(dir) => dir.join(sub),
+            None => sub.to_path_buf(),
+        };
+        self.config_dir = Some(nest(self.config_dir, config.as_ref()));
+        self.data_dir = Some(nest(self.data_dir, data.as_ref()));
+        self
+    }
+}
+
+impl PathResolver for PlatformPathResolver {
+    fn resolve(&self, type_name: &str, mode: PersistMode) -> PathBuf {
+        self.resolve_file(type_name, &type_name.to_lowercase(), mode)
+    }
+
+    fn resolve_file(&self, _type_name: &str, file_name: &str, mode: PersistMode) -> PathBuf {
+        let dir = if matches!(mode, PersistMode::Secure | PersistMode::Obfuscated) {
+            &self.data_dir
+        } else {
+            &self.config_dir
+        };
+        match dir {
+            Some(dir) => dir.join(file_name),
+            None => PathBuf::from(file_name),
+        }
+    }
+}
+
+/// Resource that manages persistence.
+///
+/// This resource is automatically added by `PersistPlugin` and handles
+/// all saving and loading operations for persistent resources.
+///
+/// Per-type state is kept under each type's [storage
+/// key](Self::get_storage_key), so same-named types from different modules
+/// don't share it. Methods taking a type name accept the key, the short type
+/// name or the type path.
+#[derive(Resource)]
+pub struct PersistManager {
+    /// Development file path (only a fallback in production)
+    pub dev_file: PathBuf,
+    /// Whether paths and embedding follow development or production rules
+    environment: PersistEnvironment,
+    /// Places per-type and group files in production
+    path_resolver: Arc<dyn PathResolver>,
+    /// Derives file names from type and group names
+    file_names: FileNameStrategy,
+    /// Receiver of save and load activity, if any
+    telemetry: Option<Arc<dyn PersistTelemetry>>,
+    /// Source of the timestamps written into files
+    clock: Arc<dyn PersistClock>,
+    /// Store data is kept in instead of files, if any
+    backend: Option<Arc<dyn PersistBackend>>,
+    /// Remote store saved data is synced with, if any
+    sync: Option<sync::SyncConfig>,
+    /// Directory relative paths are kept under instead of the working directory
+    root_dir: Option<PathBuf>,
+    /// Application info for platform-specific paths
+    pub app_name: String,
+    pub organization: String,
+    /// Store this manager belongs to, when an app has more than one
+    store: Option<String>,
+    /// Cached persist file
+    persist_file: PersistFile,
+    /// Whether auto-save is enabled globally
+    pub auto_save: bool,
+    /// Track which types have auto-save enabled
+    auto_save_types: HashMap<String, bool>,
+    /// Track persistence modes for types
+    persist_modes: HashMap<String, PersistMode>,
+    /// Track on-disk formats for types
+    formats: HashMap<String, PersistFormat>,
+    /// Track embed file paths for types
+    embed_files: HashMap<String, String>,
+    /// Track per-type file overrides from `#[persist(file = "...")]`
+    type_files: HashMap<String, PathBuf>,
+    /// Track per-type subdirectories from `#[persist(dir = "...")]`
+    type_dirs: HashMap<String, PathBuf>,
+    /// Track group membership from `#[persist(group = "...")]`
+    type_groups: HashMap<String, String>,
+    /// Track which groups have auto-save disabled or enabled
+    group_auto_save: HashMap<String, bool>,
+    /// Cached group files by path, so saving one member keeps the others' data.
+    /// Members with different formats or modes write to different files.
+    group_files: HashMap<PathBuf, PersistFile>,
+    /// Namespace used instead of module paths for storage keys
+    namespace: Option<String>,
+    /// Type names of registered types, keyed by storage key
+    type_names: HashMap<String, String>,
+    /// Fully qualified paths of registered types, keyed by storage key
+    type_paths: HashMap<String, String>,
+    /// Types last read from an entry or file under their type name, to
+    /// move under their storage key on the next save
+    legacy_reads: Mutex<HashSet<String>>,
+    /// Extract/apply closures used to reach registered resources by name
+    hooks: HashMap<String, PersistHooks>,
+    /// Field doc comments of registered types, written in development RON files
+    field_docs: HashMap<String, FieldDocs>,
+    /// Fields of each type that are encrypted in production files
+    encrypted_fields: HashMap<String, &'static [&'static str]>,
+    /// Layout of written files
+    style: PersistStyle,
+    /// How production files are split
+    file_layout: FileLayout,
+    /// What happens to resources whose loaded data fails validation
+    validation_policy: ValidationPolicy,
+    /// How failed auto-saves are retried
+    retry_policy: RetryPolicy,
+    /// Version of the game, written into files to catch downgrades
+    app_version: Option<String>,
+    /// What happens to files written by a newer version of the game
+    downgrade_policy: DowngradePolicy,
+    /// Downgrade policies of types that don't use the default
+    type_downgrade_policies: HashMap<String, DowngradePolicy>,
+    /// Types whose file is from a newer version and mustn't be overwritten
+    refused_downgrades: Mutex<BTreeSet<String>>,
+    /// Files from newer versions found while reading, waiting to be sent as events
+    version_events: Mutex<Vec<PersistVersionEvent>>,
+    /// Largest serialized size of a type without its own quota, if limited
+    size_quota: Option<u64>,
+    /// Size quotas of types that don't use the default
+    type_size_quotas: HashMap<String, u64>,
+    /// What happens to saves bigger than their quota
+    quota_policy: QuotaPolicy,
+    /// Saves refused for their size, waiting to be sent as events
+    quota_events: Mutex<Vec<PersistQuotaExceeded>>,
+    /// Failed auto-saves waiting to be retried
+    retries: HashMap<String, PendingRetry>,
+    /// Shortest time between two automatic writes of the same file, if limited
+    min_write_interval: Option<Duration>,
+    /// Time the end-of-frame flush may spend writing, if limited
+    frame_budget: Option<Duration>,
+    /// Dirty types in the order the frame budget writes them
+    budget_queue: VecDeque<String>,
+    /// How long each type's last write took, to estimate the frame budget
+    write_costs: HashMap<String, Duration>,
+    /// How often to write an autosave file, if at all
+    autosave_interval: Option<Duration>,
+    /// How many autosave files to keep
+    autosave_limit: usize,
+    /// When the last autosave file was written
+    last_autosave: Option<Instant>,
+    /// Where earlier versions of the game placed production files
+    legacy_locations: Vec<Arc<dyn PathResolver>>,
+    /// When each file was last written
+    file_writes: HashMap<PathBuf, DateTime<Utc>>,
+    /// When each type was last written
+    last_saved: HashMap<String, DateTime<Utc>>,
+    /// When each type was last loaded into its resource
+    last_loaded: HashMap<String, DateTime<Utc>>,
+    /// Stable hash of the data each type last loaded, and when that data was saved
+    loaded_saves: HashMap<String, (u64, DateTime<Utc>)>,
+    /// Hash of the data last written or loaded for each type
+    content_hashes: HashMap<String, u64>,
+    /// Loaded fields each type's resource didn't recognize, written back on save
+    unknown_fields: HashMap<String, PersistData>,
+    /// Shipped defaults each type's saved data is layered over
+    type_defaults: HashMap<String, PersistData>,
+    /// Types waiting to be written by the next flush
+    dirty: BTreeSet<String>,
+    /// Whether dirty types wait for an explicit flush instead of the end of the frame
+    manual_flush: bool,
+    /// Whether saves and deletions are skipped, leaving files untouched
+    read_only: bool,
+    /// Whether saves of a file move to memory once it can't be written
+    memory_fallback: bool,
+    /// Files whose saves are kept in memory, with when writing each was last tried
+    memory_paths: HashMap<PathBuf, DateTime<Utc>>,
+    /// Whether end-of-frame flushes are held back until resumed
+    paused: bool,
+    /// Data of session-mode types, which never touches disk
+    session_store: HashMap<String, PersistData>,
+    /// Data kept in memory per [scope](Self::scoped) and type, for types that never touch disk
+    scoped_memory: HashMap<String, HashMap<String, PersistData>>,
+    /// Types written to the in-memory dev file during a flush but not yet to disk
+    deferred_dev_saves: Option<Vec<String>>,
+    /// Types' own files held back to be written together, while flushing
+    deferred_file_writes: Option<Vec<parallel::PendingWrite>>,
+    /// Whether a flush writes types' own files in parallel
+    parallel_saves: bool,
+    /// Files read in the background, taken by the next read of their path
+    prefetched: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    /// Types whose file is being written in the background, held back
+    /// from auto-saves until it's done
+    background_saves: HashSet<String>,
+    /// Save activity for diagnostics
+    stats: PersistStats,
+    /// In-memory states of every registered type, most recent last
+    checkpoints: Vec<BTreeMap<String, PersistData>>,
+    /// Prefix of environment variables overriding loaded fields, if enabled
+    env_prefix: Option<String>,
+    /// Fields of each type overridden from the environment
+    env_overrides: HashMap<String, EnvOverrides>,
+    /// Secret for encrypting secure persistence (optional)
+    #[cfg(feature = "secure")]
+    secret: Option<String>,
+    /// Earlier secrets, still accepted when decrypting
+    #[cfg(feature = "secure")]
+    previous_secrets: Vec<String>,
+    /// Types read with one of the previous secrets, to re-encrypt once loaded
+    #[cfg(feature = "secure")]
+    stale_secret_types: Mutex<BTreeSet<String>>,
+    /// Bytes that must stay free after a save, if saves are checked first
+    #[cfg(feature = "preflight")]
+    min_free_space: Option<u64>,
+    /// Saves stopped by preflight checks, waiting to be sent as events
+    #[cfg(feature = "preflight")]
+    preflight_failures: Mutex<Vec<PersistPreflightFailed>>,
+}
+
+/// A failed auto-save waiting to be retried.
+#[derive(Debug, Clone, Copy)]
+struct PendingRetry {
+    /// Retries scheduled so far
+    retries: u32,
+    /// When the next retry is due, or `None` while it's being written
+    due: Option<Instant>,
+}
+
+/// Fields of one type overridden from the environment.
+#[derive(Debug, Clone, Default)]
+struct EnvOverrides {
+    /// Each field's value as set from the environment
+    applied: serde_json::Map<String, serde_json::Value>,
+    /// What the fields held before, written in their place
+    shadowed: PersistData,
+}
+
+impl PersistManager {
+    /// Creates a new PersistManager.
+    pub fn new(organization: impl Into<String>, app_name: impl Into<String>) -> Self {
+        let organization = organization.into();
+        let app_name = app_name.into();
+
+        let dev_file = PathBuf::from(format!(
+            "{}_dev.ron",
+            app_name.to_lowercase().replace(" ", "_")
+        ));
+        let environment = PersistEnvironment::default();
+        let persist_file = Self::initial_persist_file(&dev_file, environment);
+
+        let path_resolver = Arc::new(PlatformPathResolver::new(&organization, &app_name));
+
+        Self {
+            dev_file,
+            environment,
+            path_resolver,
+            telemetry: None,
+            clock: Arc::new(SystemClock),
+            backend: None,
+            sync: None,
+            root_dir: None,
+            app_name,
+            organization,
+            store: None,
+            persist_file,
+            auto_save: true,
+            auto_save_types: HashMap::new(),
+            persist_modes: HashMap::new(),
+            formats: HashMap::new(),
+            embed_files: HashMap::new(),
+            type_files: HashMap::new(),
+            type_dirs: HashMap::new(),
+            type_groups: HashMap::new(),
+            group_auto_save: HashMap::new(),
+            group_files: HashMap::new(),
+            namespace: None,
+            type_names: HashMap::new(),
+            type_paths: HashMap::new(),
+            legacy_reads: Mutex::new(HashSet::new()),
+            hooks: HashMap::new(),
+            field_docs: HashMap::new(),
+            encrypted_fields: HashMap::new(),
+            style: PersistStyle::default(),
+            file_layout: FileLayout::default(),
+            file_names: FileNameStrategy::default(),
+            validation_policy: ValidationPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            parallel_saves: true,
+            app_version: None,
+            downgrade_policy: DowngradePolicy::default(),
+            type_downgrade_policies: HashMap::new(),
+            refused_downgrades: Mutex::new(BTreeSet::new()),
+            version_events: Mutex::new(Vec::new()),
+            size_quota: None,
+            type_size_quotas: HashMap::new(),
+            quota_policy: QuotaPolicy::default(),
+            quota_events: Mutex::new(Vec::new()),
+            retries: HashMap::new(),
+            min_write_interval: None,
+            frame_budget: None,
+            budget_queue: VecDeque::new(),
+            write_costs: HashMap::new(),
+            autosave_interval: None,
+            autosave_limit: autosave::DEFAULT_AUTOSAVE_LIMIT,
+            last_autosave: None,
+            legacy_locations: Vec::new(),
+            file_writes: HashMap::new(),
+            last_saved: HashMap::new(),
+            last_loaded: HashMap::new(),
+            loaded_saves: HashMap::new(),
+            content_hashes: HashMap::new(),
+            unknown_fields: HashMap::new(),
+            type_defaults: HashMap::new(),
+            dirty: BTreeSet::new(),
+            manual_flush: false,
+            read_only: false,
+            memory_fallback: false,
+            memory_paths: HashMap::new(),
+            paused: false,
+            session_store: HashMap::new(),
+            scoped_memory: HashMap::new(),
+            deferred_dev_saves: None,
+            deferred_file_writes: None,
+            prefetched: Mutex::new(HashMap::new()),
+            background_saves: HashSet::new(),
+            stats: PersistStats::default(),
+            checkpoints: Vec::new(),
+            env_prefix: None,
+            env_overrides: HashMap::new(),
+            #[cfg(feature = "secure")]
+            secret: None,
+            #[cfg(feature = "secure")]
+            previous_secrets: Vec::new(),
+            #[cfg(feature = "secure")]
+            stale_secret_types: Mutex::new(BTreeSet::new()),
+            #[cfg(feature = "preflight")]
+            min_free_space: None,
+            #[cfg(feature = "preflight")]
+            preflight_failures: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Sets the environment, reloading the dev file when switching to development.
+    pub fn with_environment(mut self, environment: PersistEnvironment) -> Self {
+        if environment != self.environment {
+            self.environment = environment;
+            self.persist_file = Self::initial_persist_file(&self.dev_file, environment);
+        }
+        self
+    }
+
+    /// Makes this the manager of a named store, with its own dev file.
+    ///
+    /// The dev file becomes `<app>_<store>_dev.ron`, so a store living in
+    /// a sub-app doesn't write over the main app's data.
+    pub fn with_store(mut self, store: impl Into<String>) -> Self {
+        let store = store.into();
+        self.dev_file = self.rooted(PathBuf::from(format!(
+            "{}_{}_dev.ron",
+            self.app_name.to_lowercase().replace(" ", "_"),
+            store.to_lowercase().replace(" ", "_")
+        )));
+        self.persist_file = Self::initial_persist_file(&self.dev_file, self.environment);
+        self.store = Some(store);
+        self
+    }
+
+    /// Keeps the dev file and every other relative path under `dir` instead
+    /// of the working directory, e.g. a temporary directory in tests.
+    ///
+    /// Production files are placed by the [`PathResolver`] as usual.
+    pub fn with_root_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        if self.dev_file.is_relative() {
+            self.dev_file = dir.join(&self.dev_file);
+        }
+        self.root_dir = Some(dir);
+        self.persist_file = Self::initial_persist_file(&self.dev_file, self.environment);
+        self
+    }
+
+    /// Directory relative paths are kept under, if not the working directory.
+    pub fn root_dir(&self) -> Option<&Path> {
+        self.root_dir.as_deref()
+    }
+
+    /// Puts a relative path under the [root directory](Self::with_root_dir), if set.
+    fn rooted(&self, path: PathBuf) -> PathBuf {
+        match &self.root_dir {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path,
+        }
+    }
+
+    /// Gets the store this manager belongs to, if it was given one.
+    pub fn store(&self) -> Option<&str> {
+        self.store.as_deref()
+    }
+
+    /// Gets the environment persistence is running in.
+    pub fn environment(&self) -> PersistEnvironment {
+        self.environment
+    }
+
+    /// Whether production rules apply to paths and embedded resources.
+    pub fn is_production(&self) -> bool {
+        self.environment == PersistEnvironment::Production
+    }
+
+    /// In development, starts from the dev file if it exists.
+    fn initial_persist_file(dev_file: &Path, environment: PersistEnvironment) -> PersistFile {
+        if environment == PersistEnvironment::Production {
+            return PersistFile::new();
+        }
+        PersistFile::load_from_file(dev_file).unwrap_or_else(|e| {
+            debug!("No existing dev file found: {}", e);
+            PersistFile::new()
+        })
+    }
+
+    /// Sets how written files are laid out.
+    pub fn with_style(mut self, style: PersistStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets how production files are split.
+    pub fn with_file_layout(mut self, layout: FileLayout) -> Self {
+        self.file_layout = layout;
+        self
+    }
+
+    /// Sets how file names are derived from type and group names.
+    pub fn with_file_names(mut self, strategy: FileNameStrategy) -> Self {
+        self.file_names = strategy;
+        self
+    }
+
+    /// How file names are derived from type and group names.
+    pub fn file_names(&self) -> &FileNameStrategy {
+        &self.file_names
+    }
+
+    /// How production files are split.
+    pub fn file_layout(&self) -> FileLayout {
+        self.file_layout
+    }
+
+    /// How written files are laid out.
+    pub fn style(&self) -> &PersistStyle {
+        &self.style
+    }
+
+    /// Sets what happens to resources whose loaded data fails validation.
+    pub fn with_validation_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.validation_policy = policy;
+        self
+    }
+
+    /// What happens to resources whose loaded data fails validation.
+    pub fn validation_policy(&self) -> ValidationPolicy {
+        self.validation_policy
+    }
+
+    /// Sets how failed auto-saves are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// How failed auto-saves are retried.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Keeps the end-of-frame flush from writing any file more often than
+    /// once per `interval`, e.g. to spare SSDs from a value changing every frame.
+    ///
+    /// Types whose file was written too recently stay dirty, and their
+    /// latest data is written once the interval has passed on the
+    /// [clock](Self::with_clock). Explicit flushes, save requests and
+    /// retries aren't held back.
+    pub fn with_min_write_interval(mut self, interval: Duration) -> Self {
+        self.min_write_interval = Some(interval);
+        self
+    }
+
+    /// Shortest time between two automatic writes of the same file, if limited.
+    pub fn min_write_interval(&self) -> Option<Duration> {
+        self.min_write_interval
+    }
+
+    /// Spreads the end-of-frame flush across frames, writing only as many
+    /// dirty types as fit in `budget`, e.g. 0.5 ms.
+    ///
+    /// Each type's cost is estimated from how long its last write took.
+    /// Types are taken in the order they became dirty, at least one per
+    /// frame, and written together; the rest stay dirty for the next frame. Spreads out the
+    /// cost of many changes at once, like restoring default settings.
+    /// Explicit flushes and save requests still write everything right away.
+    pub fn with_frame_budget(mut self, budget: Duration) -> Self {
+        self.frame_budget = Some(budget);
+        self
+    }
+
+    /// Sets or removes the [frame budget](Self::with_frame_budget), e.g. to
+    /// only spread saves out during gameplay.
+    pub fn set_frame_budget(&mut self, budget: Option<Duration>) {
+        self.frame_budget = budget;
+    }
+
+    /// Time the end-of-frame flush may spend writing, if limited.
+    pub fn frame_budget(&self) -> Option<Duration> {
+        self.frame_budget
+    }
+
+    /// Takes the dirty types that are due to be written and fit in the
+    /// [frame budget](Self::with_frame_budget), longest waiting first.
+    ///
+    /// Types that haven't been written yet count as the whole budget. At
+    /// least one type is taken if any is due.
+    fn budgeted_types(&mut self, budget: Duration) -> BTreeSet<String> {
+        let due = self.dirty_types_due();
+        let dirty = &self.dirty;
+        self.budget_queue
+            .retain(|type_name| dirty.contains(type_name));
+        for type_name in dirty {
+            if !self.budget_queue.contains(type_name) {
+                self.budget_queue.push_back(type_name.clone());
+            }
+        }
+
+        let mut types = BTreeSet::new();
+        let mut spent = Duration::ZERO;
+        let mut index = 0;
+        while let Some(type_name) = self.budget_queue.get(index) {
+            if due.as_ref().is_some_and(|due| !due.contains(type_name)) {
+                index += 1;
+                continue;
+            }
+            let cost = self.write_costs.get(type_name).copied().unwrap_or(budget);
+            if !types.is_empty() && spent + cost >= budget {
+                break;
+            }
+            spent += cost;
+            types.extend(self.budget_queue.remove(index));
+        }
+        types
+    }
+
+    /// Dirty types the end-of-frame flush may write now, or `None` if
+    /// neither the [minimum write interval](Self::with_min_write_interval)
+    /// nor a [background save](Self::save_in_background) holds any back.
+    fn dirty_types_due(&self) -> Option<BTreeSet<String>> {
+        if self.min_write_interval.is_none() && self.background_saves.is_empty() {
+            return None;
+        }
+        let now = self.now();
+        let (due, held): (BTreeSet<String>, BTreeSet<String>) =
+            self.dirty.iter().cloned().partition(|type_name| {
+                if self.background_saves.contains(type_name) {
+                    return false;
+                }
+                let Some(interval) = self.min_write_interval else {
+                    return true;
+                };
+                self.save_target(type_name)
+                    .and_then(|path| self.file_writes.get(&path))
+                    .map_or(true, |written| {
+                        // A clock that went back doesn't hold writes back
+                        (now - *written)
+                            .to_std()
+                            .map_or(true, |elapsed| elapsed >= interval)
+                    })
+            });
+        (!held.is_empty()).then_some(due)
+    }
+
+    /// File a type's next save goes to, or `None` if it isn't written to disk.
+    fn save_target(&self, type_name: &str) -> Option<PathBuf> {
+        let mode = self.get_type_mode(type_name);
+        if mode == PersistMode::Session || (self.is_production() && mode == PersistMode::Embed) {
+            return None;
+        }
+        let type_name = type_name.to_string();
+        [
+            DataLocation::TypeFile(type_name.clone()),
+            DataLocation::GroupFile(type_name.clone()),
+            DataLocation::TypeResource(type_name),
+        ]
+        .iter()
+        .filter_map(|location| self.location_path(location))
+        .find(|path| !path.as_os_str().is_empty())
+        .or_else(|| Some(self.dev_file.clone()))
+    }
+
+    /// Sets the game's version, written into every file instead of the crate's.
+    ///
+    /// Files written by a newer version are then handled by the
+    /// [`DowngradePolicy`] of their type. Versions are compared as semver.
+    pub fn with_app_version(mut self, version: impl Into<String>) -> Self {
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// The game's version, if set.
+    pub fn app_version(&self) -> Option<&str> {
+        self.app_version.as_deref()
+    }
+
+    /// Sets what happens to files written by a newer version of the game.
+    pub fn with_downgrade_policy(mut self, policy: DowngradePolicy) -> Self {
+        self.downgrade_policy = policy;
+        self
+    }
+
+    /// Sets the downgrade policy of one type, overriding the default.
+    pub fn set_type_downgrade_policy(&mut self, type_name: String, policy: DowngradePolicy) {
+        let key = self.get_storage_key(&type_name);
+        self.type_downgrade_policies.insert(key, policy);
+    }
+
+    /// What happens to a type's files written by a newer version of the game.
+    pub fn get_type_downgrade_policy(&self, type_name: &str) -> DowngradePolicy {
+        self.type_downgrade_policies
+            .get(&*self.key(type_name))
+            .copied()
+            .unwrap_or(self.downgrade_policy)
+    }
+
+    /// Limits the serialized size of every type's data, in bytes.
+    ///
+    /// What happens to bigger saves is up to the [`QuotaPolicy`].
+    pub fn with_size_quota(mut self, max_bytes: u64) -> Self {
+        self.size_quota = Some(max_bytes);
+        self
+    }
+
+    /// Sets the size quota of one type, overriding the default.
+    pub fn set_type_size_quota(&mut self, type_name: String, max_bytes: u64) {
+        let key = self.get_storage_key(&type_name);
+        self.type_size_quotas.insert(key, max_bytes);
+    }
+
+    /// Largest serialized size of a type's data, if limited.
+    pub fn get_type_size_quota(&self, type_name: &str) -> Option<u64> {
+        self.type_size_quotas
+            .get(&*self.key(type_name))
+            .copied()
+            .or(self.size_quota)
+    }
+
+    /// Sets what happens to saves bigger than their size quota.
+    pub fn with_quota_policy(mut self, policy: QuotaPolicy) -> Self {
+        self.quota_policy = policy;
+        self
+    }
+
+    /// What happens to saves bigger than their size quota.
+    pub fn quota_policy(&self) -> QuotaPolicy {
+        self.quota_policy
+    }
+
+    /// Checks a type's data against its size quota before it's written.
+    ///
+    /// Returns whether the file has to be compressed to fit.
+    fn check_size_quota(&mut self, type_name: &str, data: &PersistData) -> PersistResult<bool> {
+        let Some(quota) = self.get_type_size_quota(type_name) else {
+            return Ok(false);
+        };
+        let content = self.type_file_content(type_name, data.clone())?;
+        let bytes = content.len() as u64;
+        if bytes <= quota {
+            return Ok(false);
+        }
+
+        #[cfg(feature = "compression")]
+        if self.quota_policy == QuotaPolicy::Compress && self.saves_standalone(type_name) {
+            let compressed = compress::compress(content.as_bytes()).len() as u64;
+            if compressed <= quota {
+                info!(
+                    "Compressing {}, its {} bytes are over its {} byte quota",
+                    type_name, bytes, quota
+                );
+                return Ok(true);
+            }
+        }
+
+        warn!(
+            "Not saving {}, its {} bytes are over its {} byte quota",
+            type_name, bytes, quota
+        );
+        let type_name = self.get_type_name(type_name).to_string();
+        self.quota_events
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(PersistQuotaExceeded {
+                type_name: type_name.clone(),
+                bytes,
+                quota,
+            });
+        Err(PersistError::QuotaExceeded {
+            type_name,
+            bytes,
+            quota,
+        })
+    }
+
+    /// A file holding just `data`, serialized in the type's format.
+    fn type_file_content(&self, type_name: &str, data: PersistData) -> PersistResult<String> {
+        let mut file = PersistFile::new();
+        file.set_type_data_at(self.get_storage_key(type_name), data, self.now());
+        file.to_string_styled(self.get_type_format(type_name), &self.style)
+    }
+
+    /// Whether a type is written to a file of its own, not one it shares.
+    #[cfg(feature = "compression")]
+    fn saves_standalone(&self, type_name: &str) -> bool {
+        let mode = self.get_type_mode(type_name);
+        if self.get_type_file_path(type_name, mode).is_some() {
+            return true;
+        }
+        self.resolve_type_group(type_name, mode).is_none()
+            && self.is_production()
+            && mode.saves_to_resource_path()
+            && !self
+                .get_resource_path(type_name, mode)
+                .as_os_str()
+                .is_empty()
+    }
+
+    /// Version written into files: the game's if set, otherwise the crate's.
+    fn file_version(&self) -> String {
+        self.app_version
+            .clone()
+            .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string())
+    }
+
+    /// Applies the downgrade policy to a type read from a file, returning
+    /// whether its data should be loaded.
+    fn check_file_version(&self, type_name: &str, file: &PersistFile, path: &Path) -> bool {
+        let Some(app_version) = &self.app_version else {
+            return true;
+        };
+        if !is_newer_version(&file.version, app_version) {
+            return true;
+        }
+        let policy = self.get_type_downgrade_policy(type_name);
+        warn!(
+            "{} in {:?} was saved by version {}, newer than {} ({:?})",
+            type_name, path, file.version, app_version, policy
+        );
+        self.version_events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(PersistVersionEvent {
+                type_name: self.get_type_name(type_name).to_string(),
+                file_version: file.version.clone(),
+                app_version: app_version.clone(),
+                policy,
+            });
+
+        let refuse = match policy {
+            DowngradePolicy::BestEffort => return true,
+            DowngradePolicy::Refuse => true,
+            DowngradePolicy::BackupAndReset => {
+                let backup = downgrade_backup_path(path, &file.version);
+                match fs::copy(path, &backup) {
+                    Ok(_) => {
+                        info!("Backed up {:?} to {:?}", path, backup);
+                        false
+                    }
+                    Err(e) => {
+                        // Without a backup, the newer save mustn't be overwritten
+                        error!("Failed to back up {:?}, keeping it: {}", path, e);
+                        true
+                    }
+                }
+            }
+        };
+        if refuse {
+            self.refused_downgrades
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(type_name.to_string());
+        }
+        false
+    }
+
+    /// Checks if a failed save of a type is waiting to be retried.
+    pub fn is_retry_pending(&self, type_name: &str) -> bool {
+        self.retries.contains_key(&*self.key(type_name))
+    }
+
+    /// Queues another attempt at a failed save, returning how many attempts
+    /// were made if the policy allows no more.
+    fn schedule_retry(&mut self, type_name: &str) -> Option<u32> {
+        let retries = self.retries.get(type_name).map_or(0, |retry| retry.retries);
+        if retries >= self.retry_policy.max_retries {
+            self.retries.remove(type_name);
+            return Some(retries + 1);
+        }
+        let delay = self.retry_policy.delay(retries);
+        debug!("Retrying save of {} in {:?}", type_name, delay);
+        self.retries.insert(
+            type_name.to_string(),
+            PendingRetry {
+                retries: retries + 1,
+                due: Some(Instant::now() + delay),
+            },
+        );
+        None
+    }
+
+    /// Set the secret for encrypting secure persistence
+    #[cfg(feature = "secure")]
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Set the secret for encrypting, plus older secrets still accepted for decrypting.
+    ///
+    /// Lets a release rotate the secret without losing saves written under
+    /// the old one. Files that only an older secret opens are re-encrypted
+    /// with `current` the next time they're saved, which happens right
+    /// after they load.
+    #[cfg(feature = "secure")]
+    pub fn with_secrets<S: Into<String>>(
+        mut self,
+        current: impl Into<String>,
+        previous: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.secret = Some(current.into());
+        self.previous_secrets = previous.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Derive an encryption key from a secret and a salt
+    #[cfg(feature = "secure")]
+    fn derive_key(secret: &str, salt: &[u8]) -> Option<[u8; 32]> {
+        let mut key = [0u8; 32];
+        // Use Argon2 to derive a key from the secret
+        let argon2 = Argon2::default();
+        argon2
+            .hash_password_into(secret.as_bytes(), salt, &mut key)
+            .ok()?;
+        Some(key)
+    }
+
+    /// Encrypt data for secure persistence
+    #[cfg(feature = "secure")]
+    fn encrypt_data(&self, data: &[u8]) -> PersistResult<Vec<u8>> {
+        use aes_gcm::aead::rand_core::RngCore;
+
+        let Some(secret) = &self.secret else {
+            return Err(PersistError::encryption(
+                "No secret configured for secure persistence".to_string(),
+            ));
+        };
+
+        // Generate a random salt and nonce
+        let mut salt = [0u8; 16];
+        let mut nonce_bytes = [0u8; 12];
+        let mut rng = aes_gcm::aead::OsRng;
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce_bytes);
+
+        // Derive key from secret
+        let key = Self::derive_key(secret, &salt).ok_or_else(|| {
+            PersistError::encryption("Failed to derive encryption key".to_string())
+        })?;
+
+        // Encrypt using AES-256-GCM
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(nonce, data)
+            .map_err(|e| PersistError::encryption(format!("Encryption failed: {}", e)))?;
+
+        // Prepend salt and nonce to the ciphertext
+        let mut result = Vec::with_capacity(salt.len() + nonce_bytes.len() + ciphertext.len());
+        result.extend_from_slice(&salt);
+        result.extend_from_slice(&nonce_bytes);
+        result.extend_from_slice(&ciphertext);
+
+        Ok(result)
+    }
+
+    /// Decrypt data from secure persistence
+    #[cfg(feature = "secure")]
+    fn decrypt_data(&self, encrypted: &[u8]) -> PersistResult<Vec<u8>> {
+        self.decrypt_with_any_secret(encrypted)
+            .map(|(plaintext, _)| plaintext)
+    }
+
+    /// Decrypt a type's data, noting it for re-encryption if an old secret opened it
+    #[cfg(feature = "secure")]
+    fn decrypt_type_data(&self, type_name: &str, encrypted: &[u8]) -> PersistResult<Vec<u8>> {
+        let (plaintext, current) = self.decrypt_with_any_secret(encrypted)?;
+        if !current {
+            self.stale_secret_types
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(type_name.to_string());
+        }
+        Ok(plaintext)
+    }
+
+    /// Decrypt with the current secret, then each previous one.
+    ///
+    /// Also returns whether the current secret was the one that worked.
+    #[cfg(feature = "secure")]
+    fn decrypt_with_any_secret(&self, encrypted: &[u8]) -> PersistResult<(Vec<u8>, bool)> {
+        let Some(current) = &self.secret else {
+            return Err(PersistError::encryption(
+                "No secret configured for secure persistence".to_string(),
+            ));
+        };
+
+        if encrypted.len() < 28 {
+            // 16 (salt) + 12 (nonce)
+            return Err(PersistError::encryption(
+                "Invalid encrypted data format".to_string(),
+            ));
+        }
+
+        // Extract salt, nonce, and ciphertext
+        let salt = &encrypted[0..16];
+        let nonce_bytes = &encrypted[16..28];
+        let ciphertext = &encrypted[28..];
+
+        let nonce = Nonce::from_slice(nonce_bytes);
+        let mut failure = None;
+        for secret in std::iter::once(current).chain(&self.previous_secrets) {
+            // Derive key from secret
+            let key = Self::derive_key(secret, salt).ok_or_else(|| {
+                PersistError::encryption("Failed to derive decryption key".to_string())
+            })?;
+
+            // Decrypt using AES-256-GCM
+            let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+            match cipher.decrypt(nonce, ciphertext) {
+                Ok(plaintext) => return Ok((plaintext, secret == current)),
+                Err(e) => failure = Some(e),
+            }
+        }
+
+        Err(PersistError::encryption(format!(
+            "Decryption failed: {}",
+            failure.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+
+    /// Encodes a file's contents for `#[persist(embed_binary = "...")]`.
+    ///
+    /// Encrypted with the secret when there is one, otherwise base64
+    /// encoded, so shipped values aren't plain text in the binary. Without
+    /// the `prod` feature nothing is embedded and the contents are kept as is.
+    pub fn encode_embedded(&self, content: &[u8]) -> PersistResult<Vec<u8>> {
+        #[cfg(feature = "secure")]
+        if self.secret.is_some() {
+            return self.encrypt_data(content);
+        }
+        #[cfg(feature = "prod")]
+        {
+            use base64::{engine::general_purpose, Engine as _};
+            Ok(general_purpose::STANDARD.encode(content).into_bytes())
+        }
+        #[cfg(not(feature = "prod"))]
+        Ok(content.to_vec())
+    }
+
+    /// Decodes a blob from [`encode_embedded`](Self::encode_embedded) back into text.
+    pub fn decode_embedded(&self, blob: &[u8]) -> PersistResult<String> {
+        #[cfg(feature = "secure")]
+        let decrypted = match &self.secret {
+            Some(_) => Some(self.decrypt_data(blob)?),
+            None => None,
+        };
+        #[cfg(not(feature = "secure"))]
+        let decrypted: Option<Vec<u8>> = None;
+
+        let bytes = match decrypted {
+            Some(bytes) => bytes,
+            #[cfg(feature = "prod")]
+            None => {
+                use base64::{engine::general_purpose, Engine as _};
+                general_purpose::STANDARD.decode(blob).map_err(|e| {
+                    PersistError::serialization(format!("Failed to decode base64: {}", e))
+                })?
+            }
+            #[cfg(not(feature = "prod"))]
+            None => blob.to_vec(),
+        };
+        String::from_utf8(bytes).map_err(|e| {
+            PersistError::serialization(format!("Invalid UTF-8 in embedded data: {}", e))
+        })
+    }
+
+    /// A type's compiled-in data as text, decoding an embedded blob.
+    fn embedded_text<T: Persistable>(&self) -> Option<Cow<'static, str>> {
+        if let Some(data) = T::embedded_data() {
+            return Some(Cow::Borrowed(data));
+        }
+        match self.decode_embedded(T::embedded_bytes()?) {
+            Ok(data) => Some(Cow::Owned(data)),
+            Err(e) => {
+                error!(
+                    "Failed to decode embedded data for {}: {}",
+                    T::type_name(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Get the appropriate path for a resource based on its mode
+    pub fn get_resource_path(&self, type_name: &str, mode: PersistMode) -> PathBuf {
+        let type_name = &*self.key(type_name);
+        if let Some(path) = self.get_type_file_path(type_name, mode) {
+            return path;
+        }
+
+        if let Some(group) = self.resolve_type_group(type_name, mode) {
+            return self.get_group_path(group, self.get_type_format(type_name), mode);
+        }
+
+        if mode == PersistMode::Session {
+            return PathBuf::new();
+        }
+
+        if !self.is_production() {
+            // In dev mode, everything goes to the dev file
+            return self.dev_file.clone();
+        }
+
+        match mode {
+            PersistMode::Dev => {
+                // In production, dev mode resources shouldn't exist
+                // But if they do, save to a local file as fallback
+                self.dev_file.clone()
+            }
+            PersistMode::Dynamic => {
+                self.resolve_type_path(type_name, mode, self.get_type_format(type_name).extension())
+            }
+            PersistMode::Secure | PersistMode::Obfuscated => {
+                self.resolve_type_path(type_name, mode, "dat")
+            }
+            PersistMode::Embed | PersistMode::Session => {
+                // Embedded resources don't save to disk in prod
+                PathBuf::new()
+            }
+        }
+    }
+
+    /// Resolves a production path through the configured [`PathResolver`].
+    ///
+    /// Adds `extension` if the resolver returned a path without one, and
+    /// makes sure the parent directory exists.
+    fn resolve_path(&self, name: &str, mode: PersistMode, extension: &str) -> PathBuf {
+        let path = with_default_extension(self.resolve_name(name, mode), extension);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        path
+    }
+
+    /// Like [`resolve_path`](Self::resolve_path) for a type's own file,
+    /// nested in the type's [subdirectory](Self::set_type_dir) if it has one.
+    fn resolve_type_path(&self, type_name: &str, mode: PersistMode, extension: &str) -> PathBuf {
+        let path =
+            self.place_type_file(type_name, &self.type_file_name(type_name), mode, extension);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        path
+    }
+
+    /// Where the [`PathResolver`] puts a type's own file named `file_name`,
+    /// nested in the type's [subdirectory](Self::set_type_dir) if it has one.
+    fn place_type_file(
+        &self,
+        type_name: &str,
+        file_name: &str,
+        mode: PersistMode,
+        extension: &str,
+    ) -> PathBuf {
+        let resolved =
+            self.path_resolver
+                .resolve_file(self.get_type_name(type_name), file_name, mode);
+        let path = with_default_extension(resolved, extension);
+        match (
+            self.type_dirs.get(type_name),
+            path.parent(),
+            path.file_name(),
+        ) {
+            (Some(sub), Some(parent), Some(file_name)) => parent.join(sub).join(file_name),
+            _ => path,
+        }
+    }
+
+    /// Name of a type's own file, without extension.
+    ///
+    /// Taken from the storage key, so same-named types from different
+    /// modules get different files. Keys in a namespace differ by type
+    /// name alone, so that's used instead.
+    fn type_file_name(&self, type_name: &str) -> String {
+        match &self.namespace {
+            Some(_) => self.file_names.file_name(self.get_type_name(type_name)),
+            None => self.file_names.file_name(&type_name.replace("::", "_")),
+        }
+    }
+
+    /// Where a type's own file was before files were named after storage
+    /// keys, if it's still there.
+    fn legacy_resource_path(&self, type_name: &str, mode: PersistMode) -> Option<PathBuf> {
+        let extension = match mode {
+            PersistMode::Dynamic => self.get_type_format(type_name).extension(),
+            _ => "dat",
+        };
+        let legacy_name = self.file_names.file_name(self.get_type_name(type_name));
+        let legacy = self.place_type_file(type_name, &legacy_name, mode, extension);
+        let current =
+            self.place_type_file(type_name, &self.type_file_name(type_name), mode, extension);
+        (legacy != current && legacy.is_file()).then_some(legacy)
+    }
+
+    /// Path the [`PathResolver`] gives a group, or another file that isn't
+    /// a type's own, named by the [`FileNameStrategy`].
+    fn resolve_name(&self, name: &str, mode: PersistMode) -> PathBuf {
+        self.path_resolver
+            .resolve_file(name, &self.file_names.file_name(name), mode)
+    }
+
+    /// Sets the resolver that places production files.
+    pub fn with_path_resolver(mut self, resolver: impl PathResolver) -> Self {
+        self.path_resolver = Arc::new(resolver);
+        self
+    }
+
+    /// Sets the resolver that places production files from a shared handle.
+    pub fn set_path_resolver(&mut self, resolver: Arc<dyn PathResolver>) {
+        self.path_resolver = resolver;
+    }
+
+    /// Reports saves, loads and errors to `telemetry`.
+    pub fn with_telemetry(mut self, telemetry: impl PersistTelemetry) -> Self {
+        self.telemetry = Some(Arc::new(telemetry));
+        self
+    }
+
+    /// Replaces the telemetry receiver.
+    pub fn set_telemetry(&mut self, telemetry: Arc<dyn PersistTelemetry>) {
+        self.telemetry = Some(telemetry);
+    }
+
+    /// Sets the clock that timestamps saves and loads.
+    pub fn with_clock(mut self, clock: impl PersistClock) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Sets the clock that timestamps saves and loads from a shared handle.
+    pub fn set_clock(&mut self, clock: Arc<dyn PersistClock>) {
+        self.clock = clock;
+    }
+
+    /// Keeps data in `backend` instead of files.
+    pub fn with_backend(mut self, backend: impl PersistBackend) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Keeps data in a shared backend instead of files.
+    pub fn set_backend(&mut self, backend: Arc<dyn PersistBackend>) {
+        self.backend = Some(backend);
+    }
+
+    /// Checks if data is kept in a [`PersistBackend`] instead of files.
+    pub fn has_backend(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    /// The current time, according to the manager's [`PersistClock`].
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Reports a failed save or load to the telemetry receiver, if any.
+    fn report_error(&self, type_name: &str, error: &PersistError) {
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.on_error(self.get_type_name(type_name), error);
+        }
+    }
+
+    /// Saves all persistent data to the dev file.
+    ///
+    /// In production this is only used as a fallback for dev mode resources.
+    pub fn save(&mut self) -> PersistResult<()> {
+        if self.read_only {
+            debug!("Read-only, not saving {:?}", self.dev_file);
+            return Ok(());
+        }
+        if self.memory_paths.contains_key(&self.dev_file) {
+            debug!("Saves are kept in memory, not saving {:?}", self.dev_file);
+            return Ok(());
+        }
+        if self.backend.is_some() {
+            debug!("Saves go to the backend, not saving {:?}", self.dev_file);
+            return Ok(());
+        }
+        let docs = self.field_docs_by_key();
+        self.persist_file.attach_field_docs(&docs);
+        self.persist_file.version = self.file_version();
+        let now = self.now();
+        self.persist_file.save_to_file_at(
+            &self.dev_file,
+            PersistFormat::from_path(&self.dev_file),
+            &self.style,
+            now,
+        )
+    }
+
+    /// Reloads persistent data from the dev file.
+    pub fn load(&mut self) -> PersistResult<()> {
+        self.persist_file = PersistFile::load_from_file(&self.dev_file)?;
+        Ok(())
+    }
+
+    /// Path of the per-type file that embedded resources are tuned in during development.
+    ///
+    /// That's the type's [embed file](Self::set_type_embed_file) if it has one,
+    /// so development saves go where release builds embed from. Otherwise
+    /// files live in `assets/persist/` under the [root
+    /// directory](Self::with_root_dir), `BEVY_ASSET_ROOT` or
+    /// `CARGO_MANIFEST_DIR` if set, otherwise under the working directory.
+    pub fn get_embed_asset_path(&self, type_name: &str) -> PathBuf {
+        let type_name = &*self.key(type_name);
+        if let Some(file) = self.embed_files.get(type_name) {
+            return self.rooted(PathBuf::from(file));
+        }
+        let base_path = match &self.root_dir {
+            Some(root) => root.clone(),
+            None => std::env::var("BEVY_ASSET_ROOT")
+                .or_else(|_| std::env::var("CARGO_MANIFEST_DIR"))
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from(".")),
+        };
+
+        base_path
+            .join("assets")
+            .join("persist")
+            .join(self.embed_file_name(type_name))
+    }
+
+    /// File name a type is embedded from, without its directory.
+    fn embed_file_name(&self, type_name: &str) -> String {
+        if let Some(name) = self
+            .embed_files
+            .get(type_name)
+            .and_then(|file| Path::new(file).file_name())
+        {
+            return name.to_string_lossy().into_owned();
+        }
+        // Must match the name the derive macro embeds from
+        format!(
+            "{}.{}",
+            self.get_type_name(type_name)
+                .to_lowercase()
+                .replace("::", "_"),
+            self.get_type_format(type_name).extension()
+        )
+    }
+
+    /// Writes `T`'s saved data to `path`, ready to embed.
+    ///
+    /// The file holds just this type, in its format and under its storage
+    /// key, exactly as `#[persist(embed = "...")]` reads it. Only saved data
+    /// is exported, so [flush](Self::flush) first to include unsaved changes.
+    pub fn export_for_embed<T: Persistable>(&self, path: impl AsRef<Path>) -> PersistResult<()> {
+        self.export_named_for_embed(&self.storage_key_of::<T>(), path)
+    }
+
+    /// Like [`export_for_embed`](Self::export_for_embed), by type name.
+    pub fn export_named_for_embed(
+        &self,
+        type_name: &str,
+        path: impl AsRef<Path>,
+    ) -> PersistResult<()> {
+        let type_name = &*self.key(type_name);
+        let data = self
+            .read_saved_type_data(type_name, None)
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        let mut file = PersistFile::new();
+        file.set_type_data_at(self.get_storage_key(type_name), data, self.now());
+        file.attach_field_docs(&self.field_docs_by_key());
+        file.save_to_file_at(
+            path,
+            self.get_type_format(type_name),
+            &self.style,
+            self.now(),
+        )?;
+        info!("Exported {} for embedding", type_name);
+        Ok(())
+    }
+
+    /// Exports every embedded type into `dir`, one file each.
+    ///
+    /// Files are named as the derive looks for them by default, so `dir` can
+    /// be the embed root. Types with nothing saved yet are skipped. Returns
+    /// the files written.
+    pub fn export_all_for_embed(&self, dir: impl AsRef<Path>) -> PersistResult<Vec<PathBuf>> {
+        let mut type_names: Vec<&String> = self
+            .persist_modes
+            .iter()
+            .filter(|(_, mode)| **mode == PersistMode::Embed)
+            .map(|(type_name, _)| type_name)
+            .collect();
+        type_names.sort();
+
+        let mut written = Vec::new();
+        for type_name in type_names {
+            if self.read_saved_type_data(type_name, None).is_none() {
+                warn!("Nothing saved for {}, not exporting it", type_name);
+                continue;
+            }
+            let path = dir.as_ref().join(self.embed_file_name(type_name));
+            self.export_named_for_embed(type_name, &path)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Writes a type's data to wherever its registration says it belongs.
+    ///
+    /// Pinned files take precedence, then group files, then the mode's
+    /// location: per-type files for dynamic/secure data in production and
+    /// the shared dev file otherwise. In development, embedded resources are
+    /// also written to their asset file for embedding in production builds.
+    ///
+    /// Session-mode types are kept in memory instead, and with a
+    /// [`PersistBackend`] everything but embedded resources goes there.
+    ///
+    /// Returns the path written (the storage key for a backend), or `None`
+    /// when nothing is saved (embedded
+    /// resources in production, session-mode types, or a
+    /// [read-only](Self::set_read_only) manager).
+    pub fn write_type_data(
+        &mut self,
+        type_name: &str,
+        data: PersistData,
+    ) -> PersistResult<Option<PathBuf>> {
+        let type_name = &*self.key(type_name);
+        let data = self.data_to_write(type_name, data);
+        if self.get_type_mode(type_name) == PersistMode::Session || self.saves_to_memory(type_name)
+        {
+            self.session_store.insert(type_name.to_string(), data);
+            return Ok(None);
+        }
+        if self.read_only {
+            debug!(
+                "Read-only, not saving {}: {}",
+                type_name,
+                serde_json::Value::Object(data.to_json())
+            );
+            return Ok(None);
+        }
+        if self
+            .refused_downgrades
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(type_name)
+        {
+            warn!(
+                "Not saving {}, its file is from a newer version of the game",
+                type_name
+            );
+            return Ok(None);
+        }
+        let hash = content_hash(&data);
+        // Sealed after hashing, since encrypting the same value differs every time
+        let data = self
+            .seal_fields(type_name, data)
+            .map_err(|e| e.for_type(self.get_type_name(type_name)))?;
+        let compress = self.check_size_quota(type_name, &data)?;
+        #[cfg(feature = "preflight")]
+        if self.backend.is_none() {
+            let path = self.get_resource_path(type_name, self.get_type_mode(type_name));
+            if !path.as_os_str().is_empty() {
+                let bytes = serde_json::to_vec(&data.to_json()).map_or(0, |json| json.len());
+                self.preflight(type_name, &path, bytes as u64)?;
+            }
+        }
+        let started = bevy::platform::time::Instant::now();
+        let path = self
+            .write_type_data_inner(type_name, data, compress)
+            .map_err(|e| e.for_type(self.get_type_name(type_name)))?;
+        if let Some(path) = &path {
+            let now = self.now();
+            self.last_saved.insert(type_name.to_string(), now);
+            self.file_writes.insert(path.clone(), now);
+            self.content_hashes.insert(type_name.to_string(), hash);
+            // Saved to disk again after being kept in memory
+            self.session_store.remove(type_name);
+
+            let deferred = self
+                .deferred_dev_saves
+                .as_ref()
+                .is_some_and(|types| types.iter().any(|name| name == type_name))
+                || self.is_write_deferred(type_name);
+            if !deferred {
+                self.record_save(&[type_name], path, started.elapsed());
+            }
+        }
+        Ok(path)
+    }
+
+    /// Adds a write of `path` holding `type_names` to the save stats.
+    fn record_save(&mut self, type_names: &[&str], path: &Path, duration: Duration) {
+        let bytes = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        self.stats.saves += type_names.len() as u64;
+        self.stats.bytes_written += bytes;
+        self.stats.last_save_duration = Some(duration);
+        for type_name in type_names {
+            let cost = duration / type_names.len() as u32;
+            self.write_costs.insert(type_name.to_string(), cost);
+            let type_name = self.get_type_name(type_name).to_string();
+            if let Some(telemetry) = &self.telemetry {
+                telemetry.on_save(&type_name, duration, bytes);
+            }
+            *self.stats.saves_per_type.entry(type_name).or_default() += 1;
+        }
+    }
+
+    /// Holds back dev file writes until [`write_deferred_saves`](Self::write_deferred_saves).
+    fn defer_dev_saves(&mut self) {
+        self.deferred_dev_saves.get_or_insert_with(Vec::new);
+    }
+
+    /// Writes the dev file once for every type saved since [`defer_dev_saves`](Self::defer_dev_saves).
+    fn write_deferred_saves(&mut self) -> PersistResult<()> {
+        let Some(types) = self.deferred_dev_saves.take() else {
+            return Ok(());
+        };
+        if types.is_empty() {
+            return Ok(());
+        }
+
+        let started = bevy::platform::time::Instant::now();
+        if let Err(e) = self.save() {
+            for type_name in &types {
+                self.last_saved.remove(type_name);
+                self.content_hashes.remove(type_name);
+            }
+            return Err(e);
+        }
+        info!("Auto-saved {} to dev file", types.join(", "));
+        let names: Vec<&str> = types.iter().map(String::as_str).collect();
+        let dev_file = self.dev_file.clone();
+        self.record_save(&names, &dev_file, started.elapsed());
+        Ok(())
+    }
+
+    /// Marks a type to be written by the next flush.
+    ///
+    /// Changed resources are marked automatically while auto-save is on;
+    /// this also works for types with auto-save turned off.
+    pub fn mark_dirty<T: Persistable>(&mut self) {
+        let key = self.storage_key_of::<T>();
+        self.mark_dirty_named(&key);
+    }
+
+    /// Marks a type to be written by the next flush, by name.
+    pub fn mark_dirty_named(&mut self, type_name: &str) {
+        let type_name = &*self.key(type_name);
+        self.dirty.insert(type_name.to_string());
+    }
+
+    /// Checks if a type is waiting to be written.
+    pub fn is_dirty<T: Persistable>(&self) -> bool {
+        self.is_dirty_named(&self.storage_key_of::<T>())
+    }
+
+    /// Checks if a type is waiting to be written, by name.
+    pub fn is_dirty_named(&self, type_name: &str) -> bool {
+        let type_name = &*self.key(type_name);
+        self.dirty.contains(type_name)
+    }
+
+    /// Sets whether dirty types wait for [`flush`](Self::flush) instead of
+    /// being written at the end of every frame.
+    ///
+    /// Keeps disk writes out of gameplay, e.g. to only save on loading
+    /// screens or level transitions.
+    pub fn set_manual_flush(&mut self, manual: bool) {
+        self.manual_flush = manual;
+    }
+
+    /// Checks if dirty types wait for an explicit [`flush`](Self::flush).
+    pub fn is_manual_flush(&self) -> bool {
+        self.manual_flush
+    }
+
+    /// Sets whether every save and deletion is skipped.
+    ///
+    /// Loading works as usual, but files are never written or removed. What
+    /// would have been saved is logged at debug level. For demo kiosks,
+    /// replays and spectator builds that mustn't touch real save data.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Checks if saves and deletions are skipped.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Sets whether saves of a file move to memory once it can't be
+    /// written (defaults to `false`).
+    ///
+    /// A file counts as unwritable when an auto-save to it runs out of
+    /// [retries](RetryPolicy) because permission was denied or the
+    /// filesystem is read-only, e.g. on a read-only Flatpak or in a
+    /// sandboxed web wrapper. From then on the types saved to that file are
+    /// kept in memory like [session](PersistMode::Session) types, so
+    /// reloads and resets still see the latest values, and a single
+    /// [`PersistFellBackToMemory`] event is sent instead of an error for
+    /// every change. Other files are written as usual.
+    ///
+    /// Every 30 seconds, the next save to the file checks whether it can be
+    /// written again, and if so writes it and everything kept in memory for it.
+    pub fn with_memory_fallback(mut self, enabled: bool) -> Self {
+        self.memory_fallback = enabled;
+        self
+    }
+
+    /// Checks if saves of any file have moved to memory because it couldn't
+    /// be written.
+    pub fn is_in_memory(&self) -> bool {
+        !self.memory_paths.is_empty()
+    }
+
+    /// Checks if a type's saves are kept in memory because its file couldn't
+    /// be written.
+    pub fn is_type_in_memory(&self, type_name: &str) -> bool {
+        self.memory_path(&self.key(type_name)).is_some()
+    }
+
+    /// The file a type's saves are kept in memory for, if it couldn't be
+    /// written.
+    fn memory_path(&self, type_name: &str) -> Option<PathBuf> {
+        if self.memory_paths.is_empty() {
+            return None;
+        }
+        self.save_target(type_name)
+            .filter(|path| self.memory_paths.contains_key(path))
+    }
+
+    /// Whether a type's next save goes to memory, first checking if its
+    /// file can be written again when it's been a while since the last try.
+    fn saves_to_memory(&mut self, type_name: &str) -> bool {
+        let Some(path) = self.memory_path(type_name) else {
+            return false;
+        };
+        let now = self.now();
+        let tried = self.memory_paths[&path];
+        if (now - tried)
+            .to_std()
+            .is_ok_and(|elapsed| elapsed < MEMORY_PROBE_INTERVAL)
+        {
+            return true;
+        }
+        if !can_write(&path) {
+            self.memory_paths.insert(path, now);
+            return true;
+        }
+
+        info!("{:?} can be written again, saving it to disk", path);
+        self.memory_paths.remove(&path);
+        // Whatever else was kept in memory for the file goes with the next flush
+        let waiting: Vec<String> = self
+            .session_store
+            .keys()
+            .filter(|name| name.as_str() != type_name)
+            .filter(|name| self.get_type_mode(name) != PersistMode::Session)
+            .filter(|name| self.save_target(name).as_ref() == Some(&path))
+            .cloned()
+            .collect();
+        for name in waiting {
+            self.mark_dirty_named(&name);
+        }
+        false
+    }
+
+    /// Moves saves of `type_name`'s file to memory after `error` stopped it
+    /// from being written, returning the file if that just happened.
+    fn fall_back_to_memory(&mut self, type_name: &str, error: &PersistError) -> Option<PathBuf> {
+        if !self.memory_fallback || !is_unwritable(error) {
+            return None;
+        }
+        let path = self.save_target(type_name)?;
+        if self.memory_paths.contains_key(&path) {
+            return None;
+        }
+        warn!(
+            "{:?} can't be written, keeping its saves in memory: {}",
+            path, error
+        );
+        let now = self.now();
+        self.memory_paths.insert(path.clone(), now);
+        // The data that failed to save goes to memory with the next flush
+        self.mark_dirty_named(type_name);
+        Some(path)
+    }
+
+    /// Holds back auto-saving until [`resume`](Self::resume).
+    ///
+    /// Changes keep being marked dirty but aren't written at the end of the
+    /// frame, e.g. while batch-importing settings or playing back a replay.
+    /// Explicit saves and [`flush`](Self::flush) still write.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Lets auto-saving continue, writing what was queued while paused at
+    /// the end of the frame.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Checks if auto-saving is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Writes every dirty type now, saving each shared file only once.
+    ///
+    /// Sends [`PersistSaved`] or [`PersistSaveFailed`] for each type and
+    /// returns the first failure.
+    pub fn flush(world: &mut World) -> PersistResult<()> {
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        if manager.dirty.is_empty() {
+            return Ok(());
+        }
+        let dirty = std::mem::take(&mut manager.dirty);
+        Self::write_types(world, dirty, false)
+    }
+
+    /// Extracts `type_names` from the world and writes them, saving each
+    /// shared file only once.
+    ///
+    /// With `journaled`, the extracted data is first written to the
+    /// [transaction journal](Self::transaction), which is removed once the
+    /// writes are done.
+    fn write_types(
+        world: &mut World,
+        type_names: impl IntoIterator<Item = String>,
+        journaled: bool,
+    ) -> PersistResult<()> {
+        let type_names: Vec<String> = type_names.into_iter().collect();
+        let manager = world.resource::<PersistManager>();
+        let hooks: Vec<_> = type_names
+            .iter()
+            .filter_map(|type_name| manager.get_type_hooks(type_name).cloned())
+            .collect();
+        for hooks in &hooks {
+            hooks.before_save_in_world(world);
+        }
+
+        let manager = world.resource::<PersistManager>();
+        let mut staged = Vec::new();
+        for type_name in type_names {
+            let span = save_span(&type_name);
+            let data = span.in_scope(|| {
+                let _span = info_span!("persist_extract", type_name = type_name.as_str()).entered();
+                manager
+                    .get_type_hooks(&type_name)?
+                    .extract_from_world(world)
+            });
+            if let Some(data) = data {
+                staged.push((type_name, span, data));
+            }
+        }
+
+        let mut manager = world.resource_mut::<PersistManager>();
+        if journaled {
+            let entries = staged
+                .iter()
+                .map(|(type_name, _, data): &(String, Span, PersistData)| (type_name, data));
+            manager.write_journal(entries)?;
+        }
+        manager.defer_dev_saves();
+        manager.defer_file_writes();
+        let mut results: Vec<_> = staged
+            .into_iter()
+            .map(|(type_name, span, data)| {
+                let result = span.in_scope(|| manager.write_changed_type_data(&type_name, data));
+                (type_name, span, result)
+            })
+            .collect();
+        if let Err(e) = manager.write_deferred_saves() {
+            let dev_file = manager.dev_file.clone();
+            for (_, _, result) in &mut results {
+                if result
+                    .as_ref()
+                    .is_ok_and(|path| path.as_ref() == Some(&dev_file))
+                {
+                    *result = Err(e.clone());
+                }
+            }
+        }
+        let (batch, mut write_errors) = manager.write_deferred_files();
+        for (type_name, _, result) in &mut results {
+            if let Some(e) = write_errors.remove(type_name.as_str()) {
+                *result = Err(e);
+            }
+        }
+
+        let mut outcome = Ok(());
+        for (type_name, span, result) in results {
+            if let (Ok(()), Err(e)) = (&outcome, &result) {
+                outcome = Err(e.clone());
+            }
+            span.in_scope(|| report_save(world, &type_name, result));
+        }
+        if let Some(batch) = batch {
+            world.send_event(batch);
+        }
+        if journaled {
+            world.resource::<PersistManager>().remove_journal();
+        }
+        outcome
+    }
+
+    /// Writes every registered type now, not just the dirty ones.
+    ///
+    /// Goes through each type's registered hooks, so no type parameters are
+    /// needed, and ignores manual flushing and [`pause`](Self::pause). Types
+    /// whose file already holds their current data aren't rewritten.
+    pub fn save_all(world: &mut World) -> PersistResult<()> {
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let type_names: Vec<String> = manager.hooks.keys().cloned().collect();
+        manager.dirty.extend(type_names);
+        Self::flush(world)
+    }
+
+    /// Like [`write_type_data`](Self::write_type_data), but skips the write
+    /// when the data matches what was last written or loaded.
+    ///
+    /// Auto-save uses this, since change detection fires on any mutable
+    /// access even if nothing was modified.
+    pub fn write_changed_type_data(
+        &mut self,
+        type_name: &str,
+        data: PersistData,
+    ) -> PersistResult<Option<PathBuf>> {
+        let type_name = &*self.key(type_name);
+        let data = self.data_to_write(type_name, data);
+        if self.content_hashes.get(type_name) == Some(&content_hash(&data)) {
+            debug!("Skipping save of {}, its content is unchanged", type_name);
+            self.stats.skipped_saves += 1;
+            return Ok(None);
+        }
+        self.write_type_data(type_name, data)
+    }
+
+    fn write_type_data_inner(
+        &mut self,
+        type_name: &str,
+        data: PersistData,
+        compress: bool,
+    ) -> PersistResult<Option<PathBuf>> {
+        let mode = self.get_type_mode(type_name);
+
+        let format = self.get_type_format(type_name);
+        let key = self.get_storage_key(type_name);
+        let now = self.now();
+
+        // Don't save embedded resources in production
+        if self.is_production() && mode == PersistMode::Embed {
+            return Ok(None);
+        }
+
+        // A backend takes the place of every file but embed assets
+        if let (Some(backend), false) = (&self.backend, mode == PersistMode::Embed) {
+            let mut file = PersistFile::new();
+            file.set_type_data_at(key.clone(), data, now);
+            let bytes = self.encode_standalone(type_name, &mut file, format, compress)?;
+            backend.write(&key, &bytes)?;
+            debug!("Saved {} to the backend under {}", type_name, key);
+            return Ok(Some(PathBuf::from(key)));
+        }
+
+        // Types pinned to their own file always save there
+        if let Some(path) = self.get_type_file_path(type_name, mode) {
+            let mut file = PersistFile::new();
+            file.set_type_data_at(key, data, now);
+            file.attach_field_docs(&self.field_docs_by_key());
+            self.save_standalone(type_name, &mut file, &path, format, compress)?;
+            debug!("Saved {} to {:?}", type_name, path);
+            return Ok(Some(path));
+        }
+
+        // Grouped types save into their group's shared file
+        if let Some(group) = self.resolve_type_group(type_name, mode).map(str::to_string) {
+            let path = self.save_group_data(&group, type_name, data, mode)?;
+            debug!("Saved {} to group file {:?}", type_name, path);
+            return Ok(Some(path));
+        }
+
+        // In production, save to mode-specific paths
+        if self.is_production() && mode.saves_to_resource_path() {
+            let path = self.get_resource_path(type_name, mode);
+            if !path.as_os_str().is_empty() {
+                let mut file = PersistFile::new();
+                file.set_type_data_at(key, data, now);
+                if let Some(writes) = &mut self.deferred_file_writes {
+                    writes.push(parallel::PendingWrite {
+                        type_name: type_name.to_string(),
+                        file,
+                        path: path.clone(),
+                        format,
+                        compress,
+                    });
+                    return Ok(Some(path));
+                }
+                self.save_standalone(type_name, &mut file, &path, format, compress)?;
+                debug!("Saved {} to {:?}", type_name, path);
+                self.remove_legacy_file(type_name, mode);
+                return Ok(Some(path));
+            }
+        }
+
+        // Default behavior for dev mode
+        debug!("{}: Attempting to save to dev file", type_name);
+
+        // In dev mode, if this resource will be embedded in prod, also save it to a separate file
+        if !self.is_production() && mode == PersistMode::Embed {
+            let embed_path = self.get_embed_asset_path(type_name);
+            let mut embed_file = PersistFile::new();
+            embed_file.set_type_data_at(key.clone(), data.clone(), now);
+            embed_file.attach_field_docs(&self.field_docs_by_key());
+
+            if let Err(e) = embed_file.save_to_file_at(&embed_path, format, &self.style, now) {
+                error!(
+                    "Failed to save {} to embed file {:?}: {}",
+                    type_name, embed_path, e
+                );
+            } else {
+                info!(
+                    "Saved {} to embed file {:?} for production embedding",
+                    type_name, embed_path
+                );
+            }
+        }
+
+        // Also save to the main dev file for hot-reloading, replacing the
+        // entry under the pre-namespacing short name once it's been read
+        if self.take_legacy_read(&key) {
+            let legacy = self.get_type_name(&key).to_string();
+            self.persist_file.type_data.remove(&legacy);
+        }
+        self.persist_file.set_type_data_at(key, data, now);
+        if let Some(deferred) = &mut self.deferred_dev_saves {
+            deferred.push(type_name.to_string());
+            return Ok(Some(self.dev_file.clone()));
+        }
+        self.save()?;
+        info!("Auto-saved {} to dev file", type_name);
+        Ok(Some(self.dev_file.clone()))
+    }
+
+    /// Reads a type's data from wherever its registration says it belongs.
+    ///
+    /// `embedded` is the compiled-in data for embedded resources, used in
+    /// production builds. Types with [shipped defaults](Self::set_type_defaults)
+    /// get the saved fields layered over them. Returns `None` if nothing has
+    /// been persisted yet (and there are no defaults) or the stored data
+    /// couldn't be read.
+    pub fn read_type_data(&self, type_name: &str, embedded: Option<&str>) -> Option<PersistData> {
+        let type_name = &*self.key(type_name);
+        let started = Instant::now();
+        let saved = self
+            .read_saved_type_data(type_name, embedded)
+            .map(|data| self.unseal_fields(type_name, data));
+        if let (Some(telemetry), Some(data)) = (&self.telemetry, &saved) {
+            let bytes = self
+                .type_file_content(type_name, data.clone())
+                .map_or(0, |content| content.len() as u64);
+            telemetry.on_load(self.get_type_name(type_name), started.elapsed(), bytes);
+        }
+        let Some(defaults) = self.type_defaults.get(type_name) else {
+            return saved;
+        };
+        let mut data = defaults.clone();
+        if let Some(saved) = saved {
+            data.merge(saved);
+        }
+        Some(data)
+    }
+
+    /// Reads just the saved data of a type, without its defaults.
+    fn read_saved_type_data(&self, type_name: &str, embedded: Option<&str>) -> Option<PersistData> {
+        let format = self.get_type_format(type_name);
+        let mode = self.get_type_mode(type_name);
+
+        if mode == PersistMode::Session {
+            return self.session_store.get(type_name).cloned();
+        }
+        // Saved after falling back to memory, so newer than anything on disk
+        if let Some(data) = self.session_store.get(type_name) {
+            return Some(data.clone());
+        }
+
+        // Try to load embedded data first in production
+        if self.is_production() && mode == PersistMode::Embed {
+            if let Some(embedded_str) = embedded {
+                if let Ok(file) = parse_shipped(embedded_str, format) {
+                    if let Some(data) = self.entry_in(&file, type_name) {
+                        info!("Loaded embedded data for {}", type_name);
+                        return Some(data.clone());
+                    }
+                }
+            }
+        }
+        if let (Some(backend), false) = (self.backend.as_deref(), mode == PersistMode::Embed) {
+            return self.read_backend(backend, type_name, type_name, format);
+        }
+        // Types pinned to their own file always load from there
+        if let Some(path) = self.get_type_file_path(type_name, mode) {
+            return self.read_standalone(type_name, &path, format);
+        }
+
+        // Grouped types load from their group's shared file
+        if let Some(group) = self.resolve_type_group(type_name, mode) {
+            let format = self.get_group_format(group, type_name);
+            let path = self.get_group_path(group, format, mode);
+            return self.read_standalone(type_name, &path, format);
+        }
+
+        // Load from disk for dynamic/secure modes in production
+        if self.is_production() && mode.saves_to_resource_path() {
+            let path = self.get_resource_path(type_name, mode);
+            if !path.as_os_str().is_empty() && path.exists() {
+                if let Some(data) = self.read_standalone(type_name, &path, format) {
+                    return Some(data);
+                }
+            } else if let Some(legacy) = self.legacy_resource_path(type_name, mode) {
+                if let Some(data) = self.read_standalone(type_name, &legacy, format) {
+                    self.note_legacy_read(type_name);
+                    return Some(data);
+                }
+            }
+        }
+
+        // In dev mode, check if this is an embed resource and try to load from its file
+        if !self.is_production() && mode == PersistMode::Embed {
+            let embed_path = self.get_embed_asset_path(type_name);
+            if embed_path.exists() {
+                if let Some(data) = self.read_standalone(type_name, &embed_path, format) {
+                    return Some(data);
+                }
+            } else {
+                debug!(
+                    "Embed file {:?} does not exist, will be created on first save",
+                    embed_path
+                );
+            }
+        }
+
+        // Default behavior - load from main persist file (dev mode)
+        if !self.check_file_version(type_name, &self.persist_file, &self.dev_file) {
+            return None;
+        }
+        let data = self.entry_in(&self.persist_file, type_name).cloned();
+        if data.is_some() {
+            info!("Loaded persisted data for {}", type_name);
+        }
+        data
+    }
+
+    /// Reads one type's entry from a file that isn't the shared dev file.
+    fn read_standalone(
+        &self,
+        type_name: &str,
+        path: &Path,
+        format: PersistFormat,
+    ) -> Option<PersistData> {
+        if !path.exists() {
+            return None;
+        }
+        let prefetched = self
+            .prefetched
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(path);
+        let loaded = if let Some(bytes) = prefetched {
+            self.decode_standalone(type_name, bytes)
+                .and_then(|content| PersistFile::parse_as(&content, format))
+        } else if self.is_obfuscated(type_name) || cfg!(feature = "compression") {
+            fs::read(path)
+                .map_err(|e| read_error(path, e))
+                .and_then(|bytes| self.decode_standalone(type_name, bytes))
+                .and_then(|content| PersistFile::parse_as(&content, format))
+        } else {
+            PersistFile::load_from_file_as(path, format)
+        }
+        .map_err(|e| e.for_type(self.get_type_name(type_name)).at(path));
+        match loaded {
+            Ok(file) => {
+                if !self.check_file_version(type_name, &file, path) {
+                    return None;
+                }
+                let data = self.entry_in(&file, type_name).cloned();
+                if data.is_some() {
+                    info!("Loaded {} from {:?}", type_name, path);
+                }
+                data
+            }
+            Err(e) => {
+                error!("Failed to load {} from {:?}: {}", type_name, path, e);
+                self.report_error(type_name, &e);
+                None
+            }
+        }
+    }
+
+    /// A type's entry in `file`, falling back to one under its type name
+    /// from before keys were namespaced.
+    ///
+    /// Falling back is noted, so the type's next save replaces the old entry.
+    pub(crate) fn entry_in<'f>(&self, file: &'f PersistFile, key: &str) -> Option<&'f PersistData> {
+        if let Some(data) = file.get_type_data(key) {
+            return Some(data);
+        }
+        let type_name = self.get_type_name(key);
+        if type_name == key {
+            return None;
+        }
+        let data = file.get_type_data(type_name)?;
+        self.note_legacy_read(key);
+        Some(data)
+    }
+
+    /// Notes that a type was read from where it was kept before keys were
+    /// namespaced.
+    fn note_legacy_read(&self, key: &str) {
+        self.legacy_reads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string());
+    }
+
+    /// Takes the note that a type was last read from its legacy entry or file.
+    fn take_legacy_read(&mut self, key: &str) -> bool {
+        self.legacy_reads
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key)
+    }
+
+    /// Removes a type's own file from before files were named after storage
+    /// keys, once the type has been read from it and saved anew.
+    pub(crate) fn remove_legacy_file(&mut self, type_name: &str, mode: PersistMode) {
+        if !self.take_legacy_read(type_name) {
+            return;
+        }
+        if let Some(legacy) = self.legacy_resource_path(type_name, mode) {
+            match remove_file_if_exists(&legacy) {
+                Ok(()) => info!("Moved {} out of {:?}", type_name, legacy),
+                Err(e) => warn!("Failed to remove {:?}: {}", legacy, e),
+            }
+        }
+    }
+
+    /// Undoes the scrambling and compression of a standalone file's bytes.
+    fn decode_standalone(&self, type_name: &str, bytes: Vec<u8>) -> PersistResult<String> {
+        let bytes = if self.is_obfuscated(type_name) {
+            obfuscate::deobfuscate(&bytes)?
+        } else {
+            bytes
+        };
+        #[cfg(feature = "compression")]
+        let bytes = if compress::is_compressed(&bytes) {
+            compress::decompress(&bytes)?
+        } else {
+            bytes
+        };
+        String::from_utf8(bytes)
+            .map_err(|e| PersistError::serialization(format!("Invalid UTF-8: {}", e)))
+    }
+
+    /// Writes a file holding one type, scrambled if the type is obfuscated
+    /// and gzipped if `compress` is set.
+    fn save_standalone(
+        &self,
+        type_name: &str,
+        file: &mut PersistFile,
+        path: &Path,
+        format: PersistFormat,
+        compress: bool,
+    ) -> PersistResult<()> {
+        if !self.is_obfuscated(type_name) && !compress {
+            file.version = self.file_version();
+            return file.save_to_file_at(path, format, &self.style, self.now());
+        }
+        let bytes = self.encode_standalone(type_name, file, format, compress)?;
+        create_parent_dir(path)?;
+        fs::write(path, bytes).map_err(|e| write_error(e).at(path))
+    }
+
+    /// The bytes of a file holding one type, as [`save_standalone`](Self::save_standalone)
+    /// writes them.
+    fn encode_standalone(
+        &self,
+        type_name: &str,
+        file: &mut PersistFile,
+        format: PersistFormat,
+        compress: bool,
+    ) -> PersistResult<Vec<u8>> {
+        file.version = self.file_version();
+        file.last_saved = self.now().to_rfc3339();
+        let mut bytes = file.to_string_styled(format, &self.style)?.into_bytes();
+        #[cfg(feature = "compression")]
+        if compress {
+            bytes = compress::compress(&bytes);
+        }
+        #[cfg(not(feature = "compression"))]
+        let _ = compress;
+        if self.is_obfuscated(type_name) {
+            bytes = obfuscate::obfuscate(&bytes);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads a type's entry from what the [`PersistBackend`] stores under `key`.
+    fn read_backend(
+        &self,
+        backend: &dyn PersistBackend,
+        type_name: &str,
+        key: &str,
+        format: PersistFormat,
+    ) -> Option<PersistData> {
+        let loaded = backend
+            .read(key)
+            .and_then(|bytes| match bytes {
+                Some(bytes) => self
+                    .decode_standalone(type_name, bytes)
+                    .and_then(|content| PersistFile::parse_as(&content, format))
+                    .map(Some),
+                None => Ok(None),
+            })
+            .map_err(|e| e.for_type(self.get_type_name(type_name)));
+        match loaded {
+            Ok(Some(file)) => {
+                if !self.check_file_version(type_name, &file, Path::new(key)) {
+                    return None;
+                }
+                let data = self.entry_in(&file, type_name).cloned();
+                if data.is_some() {
+                    info!("Loaded {} from the backend", type_name);
+                }
+                data
+            }
+            Ok(None) => None,
+            Err(e) => {
+                error!("Failed to load {} from the backend: {}", type_name, e);
+                self.report_error(type_name, &e);
+                None
+            }
+        }
+    }
+
+    /// Whether a type's files are scrambled, which only happens in production.
+    fn is_obfuscated(&self, type_name: &str) -> bool {
+        self.is_production() && self.get_type_mode(type_name) == PersistMode::Obfuscated
+    }
+
+    /// When a type was last saved by this manager, e.g. for "Last saved 2 minutes ago".
+    pub fn last_saved(&self, type_name: &str) -> Option<DateTime<Utc>> {
+        let type_name = &*self.key(type_name);
+        self.last_saved.get(type_name).copied()
+    }
+
+    /// When a type's data was last written, as recorded in its file.
+    ///
+    /// Unlike [`last_saved`](Self::last_saved), this covers saves made in
+    /// earlier runs or by another process. Reads the type's file.
+    pub fn saved_at(&self, type_name: &str) -> Option<DateTime<Utc>> {
+        let type_name = &*self.key(type_name);
+        self.read_saved_type_data(type_name, None)?.last_saved()
+    }
+
+    /// Names of the registered types whose files record a save after `since`,
+    /// e.g. for "which settings changed recently".
+    pub fn types_saved_since(&self, since: DateTime<Utc>) -> Vec<&str> {
+        let mut types: Vec<&str> = self
+            .persist_modes
+            .keys()
+            .filter(|key| self.saved_at(key).is_some_and(|saved| saved > since))
+            .map(|key| self.get_type_name(key))
+            .collect();
+        types.sort_unstable();
+        types
+    }
+
+    /// When persisted data was last loaded into a type's resource.
+    pub fn last_loaded(&self, type_name: &str) -> Option<DateTime<Utc>> {
+        let type_name = &*self.key(type_name);
+        self.last_loaded.get(type_name).copied()
+    }
+
+    /// Save activity since the manager was created.
+    pub fn stats(&self) -> &PersistStats {
+        &self.stats
+    }
+
+    /// Records that a type's data was just applied to its resource.
+    pub fn mark_loaded(&mut self, type_name: &str) {
+        let type_name = &*self.key(type_name);
+        self.last_loaded.insert(type_name.to_string(), self.now());
+
+        // Read with a previous secret, so write it back under the current one
+        #[cfg(feature = "secure")]
+        if self
+            .stale_secret_types
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(type_name)
+            && !self.read_only
+        {
+            info!("Re-encrypting {} with the current secret", type_name);
+            self.content_hashes.remove(type_name);
+            self.mark_dirty_named(type_name);
+        }
+    }
+
+    /// Skips auto-saves of a type while its data is still `data`.
+    ///
+    /// Applying data read from disk marks the resource as changed; this keeps
+    /// auto-save from writing the same data straight back.
+    pub fn suppress_echo_save(&mut self, type_name: &str, data: PersistData) {
+        let type_name = &*self.key(type_name);
+        let data = self.data_to_write(type_name, data);
+        self.content_hashes
+            .insert(type_name.to_string(), content_hash(&data));
+    }
+
+    /// Remembers the fields of `loaded` that a type's resource didn't recognize.
+    ///
+    /// `applied` is the resource's data after loading `loaded`. Fields only
+    /// the file has, like ones written by a newer build, are added back on
+    /// every later save instead of being dropped.
+    pub fn keep_unknown_fields(
+        &mut self,
+        type_name: &str,
+        loaded: &PersistData,
+        applied: &PersistData,
+    ) {
+        let type_name = &*self.key(type_name);
+        let unknown: BTreeMap<_, _> = loaded
+            .values
+            .iter()
+            .filter(|(key, _)| !applied.values.contains_key(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if unknown.is_empty() {
+            self.unknown_fields.remove(type_name);
+        } else {
+            debug!("Keeping {} unknown fields of {}", unknown.len(), type_name);
+            self.unknown_fields
+                .insert(type_name.to_string(), PersistData::from_values(unknown));
+        }
+    }
+
+    /// Remembers when the data a type just loaded was saved.
+    ///
+    /// Loading marks the resource as changed, so the file may be written
+    /// again with a new save time. As long as the resource still holds
+    /// `applied`, [`loaded_save_time`](Self::loaded_save_time) reports the
+    /// original one.
+    pub(crate) fn remember_loaded_save(
+        &mut self,
+        type_name: &str,
+        loaded: &PersistData,
+        applied: &PersistData,
+    ) {
+        let type_name = &*self.key(type_name);
+        match loaded.last_saved() {
+            Some(saved) => {
+                self.loaded_saves
+                    .insert(type_name.to_string(), (stable_hash(applied), saved));
+            }
+            None => {
+                self.loaded_saves.remove(type_name);
+            }
+        }
+    }
+
+    /// When the data a type loaded was saved, if `current` is still that data.
+    pub(crate) fn loaded_save_time(
+        &self,
+        type_name: &str,
+        current: &PersistData,
+    ) -> Option<DateTime<Utc>> {
+        let (hash, saved) = self.loaded_saves.get(type_name)?;
+        (*hash == stable_hash(current)).then_some(*saved)
+    }
+
+    /// Adds the unknown fields kept for a type to data about to be written.
+    fn with_unknown_fields(&self, type_name: &str, mut data: PersistData) -> PersistData {
+        if let Some(unknown) = self.unknown_fields.get(type_name) {
+            for (key, value) in &unknown.values {
+                data.values
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+        data
+    }
+
+    /// What is actually written for a type's data.
+    ///
+    /// Unknown fields are added back, environment overrides undone and
+    /// fields matching the type's shipped defaults left out.
+    fn data_to_write(&self, type_name: &str, data: PersistData) -> PersistData {
+        let mut data =
+            self.with_unknown_fields(type_name, self.without_env_overrides(type_name, data));
+        if let Some(defaults) = self.type_defaults.get(type_name) {
+            // Compared as text too, so a captured `0.8f32` matches the `0.8` shipped
+            data.values
+                .retain(|key, value| match defaults.values.get(key) {
+                    Some(default) => default != value && default.canonical() != value.canonical(),
+                    None => true,
+                });
+        }
+        data
+    }
+
+    /// Puts back what overridden fields held, unless the game changed them since.
+    fn without_env_overrides(&self, type_name: &str, mut data: PersistData) -> PersistData {
+        let Some(overrides) = self.env_overrides.get(type_name) else {
+            return data;
+        };
+        for (key, applied) in &overrides.applied {
+            let unchanged = data
+                .values
+                .get(key)
+                .is_some_and(|value| value.to_json() == *applied);
+            if unchanged {
+                match overrides.shadowed.values.get(key) {
+                    Some(value) => data.values.insert(key.clone(), value.clone()),
+                    None => data.values.remove(key),
+                };
+            }
+        }
+        data
+    }
+
+    /// Lists every registered type with its configuration, sorted by name.
+    ///
+    /// Intended for debug overlays and tooling. The path is where the type's
+    /// data currently resolves to (`None` for embedded resources in
+    /// production).
+    pub fn registered_types(&self) -> impl Iterator<Item = PersistTypeInfo<'_>> {
+        let mut types: Vec<_> = self
+            .persist_modes
+            .iter()
+            .map(|(key, &mode)| {
+                let path = self.get_resource_path(key, mode);
+                PersistTypeInfo {
+                    type_name: self.get_type_name(key),
+                    storage_key: key.as_str(),
+                    mode,
+                    format: self.get_type_format(key),
+                    auto_save: self.is_auto_save_enabled(key),
+                    path: (!path.as_os_str().is_empty()).then_some(path),
+                    last_saved: self.last_saved(key),
+                    last_loaded: self.last_loaded(key),
+                }
+            })
+            .collect();
+        types.sort_by_key(|info| (info.type_name, info.storage_key));
+        types.into_iter()
+    }
+
+    /// Gets a reference to the underlying persist file.
+    pub fn get_persist_file(&self) -> &PersistFile {
+        &self.persist_file
+    }
+
+    /// Gets a mutable reference to the underlying persist file.
+    pub fn get_persist_file_mut(&mut self) -> &mut PersistFile {
+        &mut self.persist_file
+    }
+
+    /// Checks if auto-save is enabled for a specific type.
+    ///
+    /// Auto-save must be enabled globally, for the type's group (if any),
+    /// and for the type itself.
+    pub fn is_auto_save_enabled(&self, type_name: &str) -> bool {
+        let type_name = &*self.key(type_name);
+        let group_enabled = self
+            .get_type_group(type_name)
+            .map(|group| self.is_group_auto_save_enabled(group))
+            .unwrap_or(true);
+        self.auto_save
+            && group_enabled
+            && self.auto_save_types.get(type_name).copied().unwrap_or(true)
+    }
+
+    /// Checks if auto-save is enabled for a group.
+    pub fn is_group_auto_save_enabled(&self, group: &str) -> bool {
+        self.group_auto_save.get(group).copied().unwrap_or(true)
+    }
+
+    /// Sets whether auto-save is enabled for every type in a group.
+    pub fn set_group_auto_save(&mut self, group: impl Into<String>, enabled: bool) {
+        self.group_auto_save.insert(group.into(), enabled);
+    }
+
+    /// Assigns a type to a group file.
+    pub fn set_type_group(&mut self, type_name: String, group: impl Into<String>) {
+        let type_name = self.get_storage_key(&type_name);
+        self.type_groups.insert(type_name, group.into());
+    }
+
+    /// Gets the group a type belongs to, if any.
+    pub fn get_type_group(&self, type_name: &str) -> Option<&str> {
+        let type_name = &*self.key(type_name);
+        self.type_groups.get(type_name).map(String::as_str)
+    }
+
+    /// Gets the group a type is stored in for the given mode.
+    ///
+    /// Embedded resources are compiled in for production, so they're never
+    /// read from or written to a group file there. Session-mode types never are.
+    pub fn resolve_type_group(&self, type_name: &str, mode: PersistMode) -> Option<&str> {
+        let type_name = &*self.key(type_name);
+        if mode == PersistMode::Session || (self.is_production() && mode == PersistMode::Embed) {
+            return None;
+        }
+
+        self.get_type_group(type_name).or_else(|| {
+            // The single save file is a group every other production type joins
+            let single = self.is_production()
+                && self.file_layout == FileLayout::Single
+                && matches!(mode, PersistMode::Dynamic | PersistMode::Secure)
+                && !self.type_files.contains_key(type_name);
+            single.then_some(SINGLE_FILE_GROUP)
+        })
+    }
+
+    /// Gets the names of all types registered to a group.
+    pub fn get_group_types(&self, group: &str) -> Vec<&str> {
+        self.type_groups
+            .iter()
+            .filter(|(_, g)| g.as_str() == group)
+            .map(|(key, _)| self.get_type_name(key))
+            .collect()
+    }
+
+    /// Get the path of a group's shared file.
+    ///
+    /// In production, the [`PathResolver`] places the group file as if the
+    /// group were a type with the given mode. In development, group files
+    /// sit next to the dev file.
+    pub fn get_group_path(&self, group: &str, format: PersistFormat, mode: PersistMode) -> PathBuf {
+        if !self.is_production() {
+            return self.rooted(PathBuf::from(format!(
+                "{}.{}",
+                self.file_names.file_name(group),
+                format.extension()
+            )));
+        }
+        if self.is_single_file(group) {
+            return self.resolve_path(group, PersistMode::Secure, PersistFormat::Ron.extension());
+        }
+
+        self.resolve_path(group, mode, format.extension())
+    }
+
+    /// Whether `group` is the single save file of [`FileLayout::Single`].
+    fn is_single_file(&self, group: &str) -> bool {
+        self.file_layout == FileLayout::Single && group == SINGLE_FILE_GROUP
+    }
+
+    /// Format of a group's file, which is the type's own unless it's the single save file.
+    fn get_group_format(&self, group: &str, type_name: &str) -> PersistFormat {
+        if self.is_single_file(group) {
+            PersistFormat::Ron
+        } else {
+            self.get_type_format(type_name)
+        }
+    }
+
+    /// Stores a type's data in its group file and writes the whole group to disk.
+    ///
+    /// The group file is read from disk the first time it's touched so that
+    /// data from other members (or earlier runs) isn't lost.
+    pub fn save_group_data(
+        &mut self,
+        group: &str,
+        type_name: &str,
+        data: PersistData,
+        mode: PersistMode,
+    ) -> PersistResult<PathBuf> {
+        let type_name = &*self.key(type_name);
+        let format = self.get_group_format(group, type_name);
+        let path = self.get_group_path(group, format, mode);
+
+        if !self.group_files.contains_key(&path) {
+            let existing = PersistFile::load_from_file_as(&path, format).unwrap_or_else(|e| {
+                debug!("Starting a fresh group file for {}: {}", group, e);
+                PersistFile::new()
+            });
+            self.group_files.insert(path.clone(), existing);
+        }
+
+        let legacy = self
+            .take_legacy_read(type_name)
+            .then(|| self.get_type_name(type_name).to_string());
+        let docs = self.field_docs_by_key();
+        let version = self.file_version();
+        let now = self.now();
+        let file = self
+            .group_files
+            .get_mut(&path)
+            .expect("group file was just inserted");
+        // Replaces the entry under the pre-namespacing short name once it's been read
+        if let Some(legacy) = legacy {
+            file.type_data.remove(&legacy);
+        }
+        file.set_type_data_at(type_name.to_string(), data, now);
+        file.attach_field_docs(&docs);
+        file.version = version;
+        file.save_to_file_at(&path, format, &self.style, now)?;
+        Ok(path)
+    }
+
+    /// Sets whether auto-save is enabled for a specific type.
+    pub fn set_type_auto_save(&mut self, type_name: String, enabled: bool) {
+        let type_name = self.get_storage_key(&type_name);
+        self.auto_save_types.insert(type_name, enabled);
+    }
+
+    /// Sets the extract/apply closures used to persist a type.
+    pub fn set_type_hooks(&mut self, type_name: String, hooks: PersistHooks) {
+        let type_name = self.get_storage_key(&type_name);
+        self.hooks.insert(type_name, hooks);
+    }
+
+    /// Sets the doc comments written above a type's fields in development RON files.
+    pub fn set_type_field_docs(&mut self, type_name: String, docs: FieldDocs) {
+        let type_name = self.get_storage_key(&type_name);
+        self.field_docs.insert(type_name, docs);
+    }
+
+    /// Sets which of a type's fields are encrypted in production files.
+    ///
+    /// The rest of the file stays readable for debugging. Each listed field
+    /// is written as a string: encrypted with the secret when there is one
+    /// (`secure` feature), otherwise base64 encoded. Development files and
+    /// builds without the `prod` feature keep every field in the clear.
+    pub fn set_type_encrypted_fields(
+        &mut self,
+        type_name: String,
+        fields: &'static [&'static str],
+    ) {
+        let type_name = self.get_storage_key(&type_name);
+        if fields.is_empty() {
+            self.encrypted_fields.remove(&type_name);
+        } else {
+            self.encrypted_fields.insert(type_name, fields);
+        }
+    }
+
+    /// Replaces a type's encrypted fields with their sealed strings.
+    fn seal_fields(&self, type_name: &str, data: PersistData) -> PersistResult<PersistData> {
+        #[cfg(feature = "prod")]
+        if let (true, Some(fields)) = (self.is_production(), self.encrypted_fields.get(type_name)) {
+            let mut data = data;
+            for field in fields.iter() {
+                if let Some(value) = data.values.get(*field) {
+                    let sealed = self.seal_value(value)?;
+                    data.values
+                        .insert(field.to_string(), FieldValue::Json(sealed.into()));
+                }
+            }
+            return Ok(data);
+        }
+        #[cfg(not(feature = "prod"))]
+        let _ = type_name;
+        Ok(data)
+    }
+
+    /// Puts back the values of a type's sealed fields.
+    ///
+    /// Fields that can't be unsealed, e.g. with the wrong secret, are dropped
+    /// so the rest of the type still loads.
+    fn unseal_fields(&self, type_name: &str, data: PersistData) -> PersistData {
+        #[cfg(feature = "prod")]
+        if let Some(fields) = self.encrypted_fields.get(type_name) {
+            let mut data = data;
+            for field in fields.iter() {
+                let Some(sealed) = data
+                    .values
+                    .get(*field)
+                    .and_then(|value| value.deserialize::<String>().ok())
+                else {
+                    continue;
+                };
+                match self.unseal_value(type_name, &sealed) {
+                    Ok(Some(value)) => {
+                        data.values.insert(field.to_string(), value);
+                    }
+                    // Written in the clear, e.g. before the field was encrypted
+                    Ok(None) => {}
+                    Err(e) => {
+                        error!("Failed to decrypt {}.{}: {}", type_name, field, e);
+                        data.values.remove(*field);
+                    }
+                }
+            }
+            return data;
+        }
+        #[cfg(not(feature = "prod"))]
+        let _ = type_name;
+        data
+    }
+
+    /// Seals one field value, keeping whether it was RON or JSON.
+    #[cfg(feature = "prod")]
+    fn seal_value(&self, value: &FieldValue) -> PersistResult<String> {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let plain = match value {
+            FieldValue::Json(json) => format!("j{}", json),
+            _ => format!("r{}", value.canonical()),
+        };
+        #[cfg(feature = "secure")]
+        if self.secret.is_some() {
+            let encrypted = self.encrypt_data(plain.as_bytes())?;
+            return Ok(format!(
+                "{}{}",
+                SEALED_ENCRYPTED,
+                general_purpose::STANDARD.encode(encrypted)
+            ));
+        }
+        Ok(format!(
+            "{}{}",
+            SEALED_ENCODED,
+            general_purpose::STANDARD.encode(plain)
+        ))
+    }
+
+    /// Opens a sealed field value, or `None` if it isn't sealed.
+    #[cfg(feature = "prod")]
+    fn unseal_value(&self, type_name: &str, sealed: &str) -> PersistResult<Option<FieldValue>> {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let decode = |encoded: &str| {
+            general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| PersistError::serialization(format!("Failed to decode base64: {}", e)))
+        };
+        let plain = if let Some(encoded) = sealed.strip_prefix(SEALED_ENCODED) {
+            decode(encoded)?
+        } else if let Some(encrypted) = sealed.strip_prefix(SEALED_ENCRYPTED) {
+            #[cfg(feature = "secure")]
+            {
+                self.decrypt_type_data(type_name, &decode(encrypted)?)?
+            }
+            #[cfg(not(feature = "secure"))]
+            {
+                let _ = (type_name, encrypted);
+                return Err(PersistError::serialization(
+                    "Field is encrypted, which needs the secure feature".to_string(),
+                ));
+            }
+        } else {
+            return Ok(None);
+        };
+
+        let plain = String::from_utf8(plain).map_err(|e| {
+            PersistError::serialization(format!("Invalid UTF-8 in sealed field: {}", e))
+        })?;
+        let invalid = || PersistError::serialization("Malformed sealed field".to_string());
+        let (tag, text) = (plain.get(..1), plain.get(1..).unwrap_or_default());
+        let value = match tag {
+            Some("j") => FieldValue::Json(serde_json::from_str(text).map_err(|_| invalid())?),
+            Some("r") => FieldValue::Ron(
+                ron::value::RawValue::from_boxed_ron(text.into()).map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+        Ok(Some(value))
+    }
+
+    /// Field docs of registered types, keyed by storage key.
+    ///
+    /// Empty in production, where files are for players rather than
+    /// tuning by hand.
+    fn field_docs_by_key(&self) -> HashMap<String, FieldDocs> {
+        if self.is_production() {
+            return HashMap::new();
+        }
+        self.field_docs
+            .iter()
+            .map(|(type_name, docs)| (self.get_storage_key(type_name), *docs))
+            .collect()
+    }
+
+    /// Gets the extract/apply closures registered for a type.
+    pub fn get_type_hooks(&self, type_name: &str) -> Option<&PersistHooks> {
+        let type_name = &*self.key(type_name);
+        self.hooks.get(type_name)
+    }
+
+    /// Sets a namespace that prefixes every storage key instead of the module path.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Gets the configured storage key namespace, if any.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Lets `<PREFIX>__<TYPE>__<FIELD>` environment variables override loaded fields.
+    ///
+    /// See [`apply_env_overrides`](Self::apply_env_overrides).
+    pub fn with_env_overrides(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Gets the prefix of overriding environment variables, if enabled.
+    pub fn env_prefix(&self) -> Option<&str> {
+        self.env_prefix.as_deref()
+    }
+
+    /// Fields of a type currently overridden from the environment.
+    pub fn env_overridden_fields(&self, type_name: &str) -> impl Iterator<Item = &str> {
+        let type_name = &*self.key(type_name);
+        self.env_overrides
+            .get(type_name)
+            .into_iter()
+            .flat_map(|overrides| overrides.applied.keys().map(String::as_str))
+    }
+
+    /// Registers the storage key for a type from its fully qualified path.
+    ///
+    /// With a namespace configured the key becomes `namespace::TypeName`,
+    /// otherwise it is the type's module path.
+    pub fn set_type_path(&mut self, type_name: String, type_path: &str) {
+        let key = self.key_for(&type_name, type_path);
+        self.type_paths.insert(key.clone(), type_path.to_string());
+        self.type_names.insert(key, type_name);
+    }
+
+    /// Gets the fully qualified path of a registered type.
+    pub fn get_type_path(&self, type_name: &str) -> Option<&str> {
+        let key = self.key(type_name);
+        self.type_paths.get(&*key).map(String::as_str)
+    }
+
+    /// Gets the key a type's data is stored under inside persist files.
+    ///
+    /// Older files keyed by the short type name are still read through
+    /// [`PersistFile::get_type_data_or_legacy`].
+    pub fn get_storage_key(&self, type_name: &str) -> String {
+        self.key(type_name).into_owned()
+    }
+
+    /// Gets the short type name of a type, given its storage key.
+    pub fn get_type_name<'a>(&'a self, key: &'a str) -> &'a str {
+        if let Some(type_name) = self.type_names.get(key) {
+            return type_name;
+        }
+        match &self.namespace {
+            Some(namespace) => key
+                .strip_prefix(namespace.as_str())
+                .and_then(|rest| rest.strip_prefix("::"))
+                .unwrap_or(key),
+            None => key,
+        }
+    }
+
+    /// Storage key of a type with the given short name and module path.
+    pub(crate) fn key_for(&self, type_name: &str, type_path: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}::{}", namespace, type_name),
+            None => type_path.to_string(),
+        }
+    }
+
+    /// Storage key of `T`.
+    pub fn storage_key_of<T: Persistable>(&self) -> String {
+        self.key_for(T::type_name(), T::type_path())
+    }
+
+    /// Storage key of the type a caller named, by key, short name or type path.
+    ///
+    /// A short name shared by several registered types picks the first of
+    /// their keys in sorted order. Names of types that aren't registered
+    /// get the namespace, if any.
+    fn key<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if self.type_names.contains_key(name) {
+            return Cow::Borrowed(name);
+        }
+        let registered = self
+            .type_names
+            .iter()
+            .filter(|(key, type_name)| {
+                type_name.as_str() == name
+                    || self.type_paths.get(*key).is_some_and(|path| path == name)
+            })
+            .map(|(key, _)| key)
+            .min();
+        if let Some(key) = registered {
+            return Cow::Owned(key.clone());
+        }
+        match &self.namespace {
+            Some(namespace) if !name.starts_with(&format!("{}::", namespace)) => {
+                Cow::Owned(format!("{}::{}", namespace, name))
+            }
+            _ => Cow::Borrowed(name),
+        }
+    }
+
+    /// Sets the persistence mode for a specific type.
+    pub fn set_type_mode(&mut self, type_name: String, mode: PersistMode) {
+        let type_name = self.get_storage_key(&type_name);
+        self.persist_modes.insert(type_name, mode);
+    }
+
+    /// Gets the persistence mode for a specific type.
+    pub fn get_type_mode(&self, type_name: &str) -> PersistMode {
+        let type_name = &*self.key(type_name);
+        self.persist_modes
+            .get(type_name)
+            .copied()
+            .unwrap_or(PersistMode::Dev)
+    }
+
+    /// Sets the on-disk format for a specific type.
+    pub fn set_type_format(&mut self, type_name: String, format: PersistFormat) {
+        let type_name = self.get_storage_key(&type_name);
+        self.formats.insert(type_name, format);
+    }
+
+    /// Gets the on-disk format for a specific type.
+    pub fn get_type_format(&self, type_name: &str) -> PersistFormat {
+        let type_name = &*self.key(type_name);
+        self.formats.get(type_name).copied().unwrap_or_default()
+    }
+
+    /// Pins a specific type to its own file instead of the shared/default location.
+    pub fn set_type_file(&mut self, type_name: String, path: impl Into<PathBuf>) {
+        let type_name = self.get_storage_key(&type_name);
+        self.type_files.insert(type_name, path.into());
+    }
+
+    /// Gets the file override for a specific type, as given.
+    pub fn get_type_file(&self, type_name: &str) -> Option<&Path> {
+        let type_name = &*self.key(type_name);
+        self.type_files.get(type_name).map(PathBuf::as_path)
+    }
+
+    /// Nests a type's production file in a subdirectory of where the
+    /// [`PathResolver`] puts it, e.g. `settings` for `config/settings/`.
+    ///
+    /// Applies to the type's own file and to a relative pinned
+    /// [file](Self::set_type_file); group files stay where they are.
+    pub fn set_type_dir(&mut self, type_name: String, dir: impl Into<PathBuf>) {
+        let type_name = self.get_storage_key(&type_name);
+        self.type_dirs.insert(type_name, dir.into());
+    }
+
+    /// Gets the subdirectory a type's production file is nested in, if any.
+    pub fn get_type_dir(&self, type_name: &str) -> Option<&Path> {
+        let type_name = &*self.key(type_name);
+        self.type_dirs.get(type_name).map(PathBuf::as_path)
+    }
+
+    /// Warns when another registered type would be saved to the same file
+    /// as `type_name`.
+    fn warn_file_name_collision(&self, type_name: &str) {
+        let has_own_file = |name: &str| {
+            self.get_type_mode(name).saves_to_resource_path()
+                && !self.type_files.contains_key(name)
+                && !self.type_groups.contains_key(name)
+        };
+        if !has_own_file(type_name) {
+            return;
+        }
+        let file_name = self.type_file_name(type_name);
+        if let Some(other) = self.persist_modes.keys().find(|other| {
+            *other != type_name
+                && has_own_file(other)
+                && self.type_dirs.get(*other) == self.type_dirs.get(type_name)
+                && self.type_file_name(other) == file_name
+        }) {
+            warn!(
+                "{} and {} are both saved as {:?}; use a different FileNameStrategy or pin one to its own file",
+                other, type_name, file_name
+            );
+        }
+    }
+
+    /// Directory the [`PathResolver`] picks for a type, inside its
+    /// [subdirectory](Self::set_type_dir) if it has one.
+    fn resolve_type_dir(&self, type_name: &str, mode: PersistMode) -> Option<PathBuf> {
+        let dir = self
+            .path_resolver
+            .resolve_file(
+                self.get_type_name(type_name),
+                &self.type_file_name(type_name),
+                mode,
+            )
+            .parent()?
+            .to_path_buf();
+        Some(match self.type_dirs.get(type_name) {
+            Some(sub) => dir.join(sub),
+            None => dir,
+        })
+    }
+
+    /// Resolves the file override for a type, if one is set.
+    ///
+    /// Absolute paths are used as-is. In production, relative paths for
+    /// dynamic and secure data are placed in the directory the
+    /// [`PathResolver`] picks for the type; in development they're relative
+    /// to the working directory like the dev file. Embedded resources never
+    /// resolve to a file in production, and session-mode types never do.
+    pub fn get_type_file_path(&self, type_name: &str, mode: PersistMode) -> Option<PathBuf> {
+        let type_name = &*self.key(type_name);
+        let file = self.type_files.get(type_name)?;
+
+        if mode == PersistMode::Session {
+            return None;
+        }
+
+        if !self.is_production() {
+            return Some(self.rooted(file.clone()));
+        }
+
+        if mode == PersistMode::Embed {
+            return None;
+        }
+        if file.is_absolute() {
+            return Some(file.clone());
+        }
+        let base = match mode {
+            PersistMode::Dynamic | PersistMode::Secure | PersistMode::Obfuscated => {
+                self.resolve_type_dir(type_name, mode)
+            }
+            _ => None,
+        };
+        Some(match base {
+            Some(base) => base.join(file),
+            None => self.rooted(file.clone()),
+        })
+    }
+
+    /// Layers a type's saved data over shipped, read-only defaults.
+    ///
+    /// Loading starts from `defaults` and applies the saved fields on top;
+    /// saving leaves out every field still matching its default. User files
+    /// then hold only what the player changed, so a patch shipping new
+    /// defaults isn't masked by stale copies of the old ones.
+    pub fn set_type_defaults(&mut self, type_name: String, defaults: PersistData) {
+        let type_name = self.get_storage_key(&type_name);
+        self.type_defaults.insert(type_name, defaults);
+    }
+
+    /// Gets the shipped defaults a type's saved data is layered over, if any.
+    pub fn get_type_defaults(&self, type_name: &str) -> Option<&PersistData> {
+        let type_name = &*self.key(type_name);
+        self.type_defaults.get(type_name)
+    }
+
+    /// Parses a type's shipped defaults from a persist file's contents.
+    ///
+    /// The entry is looked up under the type's storage key, like any
+    /// persist file. Used for compiled-in `#[persist(defaults = "...")]`.
+    pub fn set_type_defaults_from_str(
+        &mut self,
+        type_name: &str,
+        content: &str,
+    ) -> PersistResult<()> {
+        let type_name = &*self.key(type_name);
+        let file = parse_shipped(content, self.get_type_format(type_name))?;
+        let defaults = file
+            .get_type_data_or_legacy(type_name, self.get_type_name(type_name))
+            .cloned()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        self.set_type_defaults(type_name.to_string(), defaults);
+        Ok(())
+    }
+
+    /// Reads a type's shipped defaults from a file.
+    pub fn load_type_defaults(
+        &mut self,
+        type_name: &str,
+        path: impl AsRef<Path>,
+    ) -> PersistResult<()> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| {
+            PersistError::io("Failed to read defaults", e)
+                .for_type(self.get_type_name(type_name))
+                .at(path)
+        })?;
+        self.set_type_defaults_from_str(type_name, &content)
+            .map_err(|e| e.at(path))
+    }
+
+    /// Sets the embed file path for a specific type.
+    pub fn set_type_embed_file(&mut self, type_name: String, file_path: String) {
+        let type_name = self.get_storage_key(&type_name);
+        self.embed_files.insert(type_name, file_path);
+    }
+    
+    /// Gets the embed file path for a specific type.
+    pub fn get_type_embed_file(&self, type_name: &str) -> Option<&String> {
+        let type_name = &*self.key(type_name);
+        self.embed_files.get(type_name)
+    }
+
+    /// Re-reads the dev file and drops cached group files so the next
+    /// read sees what's on disk.
+    fn refresh_from_disk(&mut self) -> PersistResult<()> {
+        if self.dev_file.exists() {
+            self.persist_file = PersistFile::load_from_file(&self.dev_file)?;
+        }
+        self.group_files.clear();
+        // The files may no longer hold what was last written
+        self.content_hashes.clear();
+        Ok(())
+    }
+
+    /// Re-reads a type's data from disk and applies it to the live resource.
+    ///
+    /// Discards unsaved changes, e.g. for a "Revert changes" button. Returns
+    /// whether any persisted data was found; without it the resource is left
+    /// untouched.
+    pub fn reload<T: Persistable>(world: &mut World) -> PersistResult<bool> {
+        let type_name = T::type_name();
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        manager.refresh_from_disk()?;
+        let key = manager.storage_key_of::<T>();
+
+        let embedded = manager.embedded_text::<T>();
+        let Some(data) = manager.read_type_data(&key, embedded.as_deref()) else {
+            return Ok(false);
+        };
+        manager.mark_loaded(&key);
+        let mut resource = world
+            .get_resource_mut::<T>()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        resource.load_from_persist_data(&data);
+        let applied = resource.to_persist_data();
+        let mut manager = world.resource_mut::<PersistManager>();
+        manager.keep_unknown_fields(&key, &data, &applied);
+        manager.remember_loaded_save(&key, &data, &applied);
+        let policy = manager.validation_policy();
+        if let Some(hooks) = manager.get_type_hooks(&key).cloned() {
+            validate_loaded(world, &key, &hooks, policy);
+        }
+        world.send_event(PersistLoaded {
+            type_name: type_name.to_string(),
+        });
+        Ok(true)
+    }
+
+    /// Re-reads every registered type from disk and applies it to the live resources.
+    ///
+    /// Useful after something else changed the files, like a cloud sync.
+    /// Embedded resources are skipped in production since their data can't
+    /// change. Returns how many resources were reloaded.
+    pub fn reload_all(world: &mut World) -> PersistResult<usize> {
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        manager.refresh_from_disk()?;
+
+        let manager = world.resource::<PersistManager>();
+        let pending: Vec<_> = manager
+            .hooks
+            .iter()
+            .filter(|(type_name, _)| {
+                !(manager.is_production() && manager.get_type_mode(type_name) == PersistMode::Embed)
+            })
+            .filter_map(|(type_name, hooks)| {
+                let data = manager.read_type_data(type_name, None)?;
+                Some((type_name.clone(), hooks.clone(), data))
+            })
+            .collect();
+
+        let mut reloaded = 0;
+        for (type_name, hooks, data) in pending {
+            if hooks.apply_to_world(world, &data) {
+                let applied = hooks.extract_from_world(world).unwrap_or_default();
+                let mut manager = world.resource_mut::<PersistManager>();
+                manager.mark_loaded(&type_name);
+                manager.keep_unknown_fields(&type_name, &data, &applied);
+                manager.remember_loaded_save(&type_name, &data, &applied);
+                let policy = manager.validation_policy();
+                validate_loaded(world, &type_name, &hooks, policy);
+                world.send_event(PersistLoaded {
+                    type_name: display_name(world, &type_name),
+                });
+                reloaded += 1;
+            }
+        }
+        Ok(reloaded)
+    }
+
+    /// Loads every registered type from disk, the counterpart of
+    /// [`save_all`](Self::save_all).
+    ///
+    /// Same as [`reload_all`](Self::reload_all).
+    pub fn load_all(world: &mut World) -> PersistResult<usize> {
+        Self::reload_all(world)
+    }
+
+    /// Reads the live value of a registered resource by name.
+    ///
+    /// Meant for tooling that doesn't know the resource's type.
+    pub fn live_data(world: &World, type_name: &str) -> PersistResult<PersistData> {
+        world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?
+            .get_type_hooks(type_name)
+            .and_then(|hooks| hooks.extract_from_world(world))
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))
+    }
+
+    /// Replaces the fields named in `patch` on a registered resource.
+    ///
+    /// The resource is marked as changed, so auto-save picks the patch up
+    /// like any other change. Returns the resource's data after patching.
+    pub fn patch_live_data(
+        world: &mut World,
+        type_name: &str,
+        patch: PersistData,
+    ) -> PersistResult<PersistData> {
+        let mut data = Self::live_data(world, type_name)?;
+        data.merge(patch);
+
+        let hooks = world
+            .resource::<PersistManager>()
+            .get_type_hooks(type_name)
+            .cloned()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        if !hooks.apply_to_world(world, &data) {
+            return Err(PersistError::ResourceNotFound(type_name.to_string()));
+        }
+        // Fields that failed to deserialize were left as they were
+        Self::live_data(world, type_name)
+    }
+
+    /// Overrides fields of registered resources from environment variables.
+    ///
+    /// With a prefix set by [`with_env_overrides`](Self::with_env_overrides),
+    /// `BEVY_PERSIST__USERSETTINGS__VOLUME=0.2` sets `volume` on the type
+    /// named `UserSettings`. Type and field names match case-insensitively;
+    /// a type's full path also matches, with `::` written as `__`. Values
+    /// are parsed as JSON, falling back to a plain string.
+    ///
+    /// Overridden fields aren't saved: files keep the value the field held
+    /// before, unless the game changes the field to something else.
+    /// Returns how many fields were overridden.
+    pub fn apply_env_overrides(world: &mut World) -> usize {
+        let Some(manager) = world.get_resource::<PersistManager>() else {
+            return 0;
+        };
+        let Some(prefix) = manager.env_prefix() else {
+            return 0;
+        };
+        let prefix = format!("{}__", prefix);
+        let type_names: Vec<String> = manager
+            .registered_types()
+            .map(|info| info.storage_key.to_string())
+            .collect();
+
+        let mut overridden = 0;
+        for (name, value) in std::env::vars() {
+            let Some((type_key, field_key)) = name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.rsplit_once("__"))
+            else {
+                continue;
+            };
+            let Some(type_name) = type_names.iter().find(|type_name| {
+                let short_name = type_name.rsplit("::").next().unwrap_or(type_name);
+                short_name.eq_ignore_ascii_case(type_key)
+                    || type_name.replace("::", "__").eq_ignore_ascii_case(type_key)
+            }) else {
+                warn!("Ignoring {}: no persisted type is named {}", name, type_key);
+                continue;
+            };
+            let Ok(live) = Self::live_data(world, type_name) else {
+                continue;
+            };
+            let Some(field) = live
+                .keys()
+                .find(|field| field.eq_ignore_ascii_case(field_key))
+                .map(str::to_string)
+            else {
+                warn!(
+                    "Ignoring {}: {} has no field {}",
+                    name, type_name, field_key
+                );
+                continue;
+            };
+
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            let patch = PersistData::from_json([(field.clone(), value.clone())]);
+            let data = match Self::patch_live_data(world, type_name, patch) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Failed to apply {}: {}", name, e);
+                    continue;
+                }
+            };
+            let before = live.values.get(&field).map(FieldValue::to_json);
+            let after = data.values.get(&field).map(FieldValue::to_json);
+            // Values that don't deserialize leave the field as it was
+            if after == before && after.as_ref() != Some(&value) {
+                warn!(
+                    "Ignoring {}: {}.{} didn't take the value",
+                    name, type_name, field
+                );
+                continue;
+            }
+
+            let mut manager = world.resource_mut::<PersistManager>();
+            let overrides = manager.env_overrides.entry(type_name.clone()).or_default();
+            if let Some(after) = after {
+                // Applying twice mustn't lose what the file held
+                if !overrides.applied.contains_key(&field) {
+                    if let Some(before) = live.values.get(&field) {
+                        overrides
+                            .shadowed
+                            .values
+                            .insert(field.clone(), before.clone());
+                    }
+                }
+                overrides.applied.insert(field.clone(), after);
+            }
+            // The override itself isn't a change worth saving
+            manager.suppress_echo_save(type_name, data);
+            info!("Overrode {}.{} from {}", type_name, field, name);
+            overridden += 1;
+        }
+        overridden
+    }
+
+    /// Saves a registered resource by name, ignoring auto-save settings.
+    pub fn save_named(world: &mut World, type_name: &str) -> PersistResult<()> {
+        let hooks = world
+            .get_resource::<PersistManager>()
+            .and_then(|manager| manager.get_type_hooks(type_name).cloned());
+        if let Some(hooks) = hooks {
+            hooks.before_save_in_world(world);
+        }
+        let data = Self::live_data(world, type_name)?;
+        save_to_world(world, type_name, data)
+    }
+
+    /// Re-reads a registered resource's data from disk by name.
+    ///
+    /// Like [`reload`](Self::reload), but without compiled-in embedded data,
+    /// so embedded resources only reload in development.
+    pub fn reload_named(world: &mut World, type_name: &str) -> PersistResult<bool> {
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        manager.refresh_from_disk()?;
+
+        let hooks = manager
+            .get_type_hooks(type_name)
+            .cloned()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        let Some(data) = manager.read_type_data(type_name, None) else {
+            return Ok(false);
+        };
+        manager.mark_loaded(type_name);
+        if !hooks.apply_to_world(world, &data) {
+            return Err(PersistError::ResourceNotFound(type_name.to_string()));
+        }
+        let applied = hooks.extract_from_world(world).unwrap_or_default();
+        let mut manager = world.resource_mut::<PersistManager>();
+        manager.keep_unknown_fields(type_name, &data, &applied);
+        manager.remember_loaded_save(type_name, &data, &applied);
+        let policy = manager.validation_policy();
+        validate_loaded(world, type_name, &hooks, policy);
+        world.send_event(PersistLoaded {
+            type_name: display_name(world, type_name),
+        });
+        Ok(true)
+    }
+
+    /// Restores a registered resource to its default by name and clears its persisted data.
+    ///
+    /// See [`reset`](Self::reset). Fails for resources persisted with
+    /// [`PersistAppExt::persist_external`], which have no known default.
+    pub fn reset_named(world: &mut World, type_name: &str) -> PersistResult<()> {
+        let hooks = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?
+            .get_type_hooks(type_name)
+            .cloned()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        if !hooks.reset_in_world(world) {
+            return Err(PersistError::ResourceNotFound(type_name.to_string()));
+        }
+        world
+            .resource_mut::<PersistManager>()
+            .remove_type_data(type_name)
+    }
+
+    /// Pushes the current state of every registered type onto the checkpoint stack.
+    ///
+    /// Nothing is written to disk. Pair with [`rollback`](Self::rollback)
+    /// for retry-from-checkpoint mechanics or undoing a tuning session.
+    pub fn checkpoint(world: &mut World) -> PersistResult<()> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let checkpoint: BTreeMap<_, _> = manager
+            .hooks
+            .iter()
+            .filter_map(|(type_name, hooks)| {
+                let data = hooks.extract_from_world(world)?;
+                Some((type_name.clone(), data))
+            })
+            .collect();
+        world
+            .resource_mut::<PersistManager>()
+            .checkpoints
+            .push(checkpoint);
+        Ok(())
+    }
+
+    /// Pops the most recent checkpoint and writes it back into the world.
+    ///
+    /// Returns `false` if there was no checkpoint. Restored resources are
+    /// marked as changed, so auto-save writes them like any other change;
+    /// persisted components are written back to their entities by id.
+    pub fn rollback(world: &mut World) -> PersistResult<bool> {
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let Some(checkpoint) = manager.checkpoints.pop() else {
+            return Ok(false);
+        };
+        let restores: Vec<_> = checkpoint
+            .into_iter()
+            .filter_map(|(type_name, data)| {
+                let hooks = manager.get_type_hooks(&type_name)?.clone();
+                Some((hooks, data))
+            })
+            .collect();
+        for (hooks, data) in restores {
+            hooks.apply_to_world(world, &data);
+        }
+        Ok(true)
+    }
+
+    /// Number of checkpoints waiting to be rolled back to.
+    pub fn checkpoint_count(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Drops every checkpoint without restoring any.
+    pub fn clear_checkpoints(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    /// Restores a resource to its default value and clears its persisted data.
+    ///
+    /// The default is applied without triggering change detection so that
+    /// auto-save doesn't write it straight back; the type stays absent from
+    /// disk until it's next changed. See [`remove_type_data`](Self::remove_type_data)
+    /// for what gets cleared.
+    pub fn reset<T: Persistable + Default>(world: &mut World) -> PersistResult<()> {
+        *world
+            .get_resource_mut::<T>()
+            .ok_or_else(|| PersistError::ResourceNotFound(T::type_name().to_string()))?
+            .bypass_change_detection() = T::default();
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let key = manager.storage_key_of::<T>();
+        manager.remove_type_data(&key)
+    }
+
+    /// Removes a type's persisted data from disk.
+    ///
+    /// Drops its entry from the dev file and its group file, and deletes its
+    /// pinned file and (in production) its standalone dynamic/secure file.
+    /// Embed asset files are left alone since they're part of the project.
+    pub fn remove_type_data(&mut self, type_name: &str) -> PersistResult<()> {
+        let type_name = &*self.key(type_name);
+        let in_memory = self.memory_path(type_name).is_some();
+        self.session_store.remove(type_name);
+        if self.read_only || in_memory {
+            debug!("Not removing the data of {} from disk", type_name);
+            return Ok(());
+        }
+        let mode = self.get_type_mode(type_name);
+        let format = self.get_type_format(type_name);
+        let key = self.get_storage_key(type_name);
+
+        if let (Some(backend), false) = (&self.backend, mode == PersistMode::Embed) {
+            backend
+                .remove(&key)
+                .map_err(|e| e.for_type(self.get_type_name(type_name)))?;
+        }
+
+        if let Some(path) = self.get_type_file_path(type_name, mode) {
+            remove_file_if_exists(&path)?;
+        }
+
+        if let Some(group) = self.resolve_type_group(type_name, mode).map(str::to_string) {
+            let path = self.get_group_path(&group, format, mode);
+            let docs = self.field_docs_by_key();
+            let now = self.now();
+            let file = self.group_files.entry(path.clone()).or_insert_with(|| {
+                PersistFile::load_from_file_as(&path, format).unwrap_or_else(|_| PersistFile::new())
+            });
+            let removed =
+                file.type_data.remove(&key).is_some() | file.type_data.remove(type_name).is_some();
+            if removed {
+                file.attach_field_docs(&docs);
+                file.save_to_file_at(&path, format, &self.style, now)?;
+            }
+        }
+
+        if self.is_production() && mode.saves_to_resource_path() {
+            remove_file_if_exists(&self.get_resource_path(type_name, mode))?;
+            if let Some(legacy) = self.legacy_resource_path(type_name, mode) {
+                remove_file_if_exists(&legacy)?;
+            }
+        }
+
+        let removed = self.persist_file.type_data.remove(&key).is_some()
+            | self.persist_file.type_data.remove(type_name).is_some();
+        if removed {
+            self.save()?;
+        }
+        self.last_saved.remove(type_name);
+        self.last_loaded.remove(type_name);
+        self.loaded_saves.remove(type_name);
+        self.content_hashes.remove(type_name);
+        self.unknown_fields.remove(type_name);
+        self.env_overrides.remove(type_name);
+        Ok(())
+    }
+
+    /// Every file on disk holding persisted data for registered types.
+    ///
+    /// Covers the same files as [`delete_all_data`](Self::delete_all_data),
+    /// each listed once with the types that save to it, so a "manage save
+    /// data" screen doesn't need to know how paths are resolved. Files that
+    /// haven't been written yet are left out. Sorted by path.
+    pub fn list_files(&self) -> Vec<PersistFileInfo> {
+        let mut files: Vec<PersistFileInfo> = Vec::new();
+        for location in self.data_locations() {
+            let Some(path) = self.location_path(&location) else {
+                continue;
+            };
+            if files.iter().any(|file| file.path == path) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let format = match &location {
+                DataLocation::DevFile => PersistFormat::from_path(&path),
+                DataLocation::GroupFile(type_name) => self
+                    .resolve_type_group(type_name, self.get_type_mode(type_name))
+                    .map_or(PersistFormat::Ron, |group| {
+                        self.get_group_format(group, type_name)
+                    }),
+                DataLocation::TypeFile(type_name) | DataLocation::TypeResource(type_name) => {
+                    self.get_type_format(type_name)
+                }
+            };
+            let mut type_names: Vec<String> = self
+                .persist_modes
+                .iter()
+                .filter(|(type_name, &mode)| self.get_resource_path(type_name, mode) == path)
+                .map(|(type_name, _)| type_name.clone())
+                .collect();
+            type_names.sort();
+            files.push(PersistFileInfo {
+                size: metadata.len(),
+                modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                format,
+                type_names,
+                path,
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    /// Deletes every file holding persisted data for registered types.
+    ///
+    /// Covers the dev file, pinned and group files, and the per-type files in
+    /// the platform config and data directories, then removes any of their
+    /// directories left empty. With a [`PersistBackend`], every registered
+    /// type's key is removed from it too. Embed asset files are project
+    /// sources and are kept. Meant for "delete save data" options and
+    /// privacy requests.
+    ///
+    /// Live resources keep their values; nothing is written back until they
+    /// change again. A [read-only](Self::set_read_only) manager deletes nothing.
+    pub fn delete_all_data(&mut self) -> PersistDeletionReport {
+        if self.read_only {
+            warn!("Read-only, not deleting persisted data");
+            return PersistDeletionReport::default();
+        }
+        let mut paths: Vec<PathBuf> = self
+            .data_locations()
+            .iter()
+            .filter_map(|location| self.location_path(location))
+            .collect();
+        paths.sort();
+        paths.dedup();
+
+        let mut report = PersistDeletionReport::default();
+        if let Some(backend) = &self.backend {
+            for (type_name, &mode) in &self.persist_modes {
+                if mode == PersistMode::Embed {
+                    continue;
+                }
+                let key = self.get_storage_key(type_name);
+                if let Err(e) = backend.remove(&key) {
+                    report.failed.push((
+                        PathBuf::from(key),
+                        e.for_type(self.get_type_name(type_name)),
+                    ));
+                }
+            }
+        }
+        for path in paths {
+            if !path.exists() {
+                continue;
+            }
+            match remove_file_if_exists(&path) {
+                Ok(()) => report.deleted.push(path),
+                Err(e) => report.failed.push((path, e)),
+            }
+        }
+
+        // Directories only go if deleting our files emptied them
+        let mut dirs: Vec<PathBuf> = report
+            .deleted
+            .iter()
+            .filter_map(|path| path.parent())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        for dir in dirs {
+            if fs::remove_dir(&dir).is_ok() {
+                report.deleted.push(dir);
+            }
+        }
+
+        self.persist_file = PersistFile::new();
+        self.group_files.clear();
+        self.last_saved.clear();
+        self.last_loaded.clear();
+        self.loaded_saves.clear();
+        self.content_hashes.clear();
+        self.unknown_fields.clear();
+        self.env_overrides.clear();
+        self.session_store.clear();
+        self.scoped_memory.clear();
+        info!("Deleted {} persisted paths", report.deleted.len());
+        report
+    }
+
+    /// Every location registered types may keep persisted data in.
+    ///
+    /// Embed asset files are project sources rather than player data, so
+    /// they aren't included.
+    fn data_locations(&self) -> Vec<DataLocation> {
+        let mut locations = vec![DataLocation::DevFile];
+        let mut type_names: Vec<&String> = self.persist_modes.keys().collect();
+        type_names.sort();
+        for type_name in type_names {
+            locations.push(DataLocation::TypeFile(type_name.clone()));
+            locations.push(DataLocation::GroupFile(type_name.clone()));
+            locations.push(DataLocation::TypeResource(type_name.clone()));
+        }
+        locations
+    }
+
+    /// Path of a data location on this machine, if the type uses it.
+    fn location_path(&self, location: &DataLocation) -> Option<PathBuf> {
+        let type_name = match location {
+            DataLocation::DevFile => return Some(self.dev_file.clone()),
+            DataLocation::TypeFile(type_name)
+            | DataLocation::GroupFile(type_name)
+            | DataLocation::TypeResource(type_name) => type_name,
+        };
+        if !self.persist_modes.contains_key(type_name) {
+            return None;
+        }
+        let mode = self.get_type_mode(type_name);
+        match location {
+            DataLocation::DevFile => None,
+            DataLocation::TypeFile(_) => self.get_type_file_path(type_name, mode),
+            DataLocation::GroupFile(_) => self
+                .resolve_type_group(type_name, mode)
+                .map(|group| self.get_group_path(group, self.get_type_format(type_name), mode)),
+            DataLocation::TypeResource(_) => (self.is_production()
+                && mode.saves_to_resource_path())
+            .then(|| self.get_resource_path(type_name, mode)),
+        }
+    }
+
+    /// Save a resource to disk based on its persistence mode
+    pub fn save_resource(
+        &self,
+        type_name: &str,
+        data: &PersistData,
+        mode: PersistMode,
+    ) -> PersistResult<()> {
+        if self.read_only {
+            debug!("Read-only, not saving {}", type_name);
+            return Ok(());
+        }
+        match mode {
+            PersistMode::Embed | PersistMode::Session => {
+                // Embedded resources don't save in production, session data never does
+                Ok(())
+            }
+            PersistMode::Secure => {
+                #[cfg(feature = "secure")]
+                {
+                    // Serialize to RON first
+                    let ron_string = ron::to_string(data).map_err(PersistError::serialization)?;
+
+                    // Encrypt the data if secret is available
+                    let final_data = if self.secret.is_some() {
+                        self.encrypt_data(ron_string.as_bytes())?
+                    } else {
+                        // If no secret, just obfuscate with base64
+                        use base64::{engine::general_purpose, Engine as _};
+                        general_purpose::STANDARD
+                            .encode(ron_string.as_bytes())
+                            .into_bytes()
+                    };
+
+                    // Write to .dat file
+                    let path = self.get_resource_path(type_name, mode);
+                    fs::write(&path, final_data).map_err(|e| {
+                        PersistError::io("Failed to write secure file", e)
+                            .for_type(self.get_type_name(type_name))
+                            .at(&path)
+                    })?;
+                    Ok(())
+                }
+                #[cfg(not(feature = "secure"))]
+                {
+                    // Without secure feature, fall back to dynamic
+                    self.save_resource(type_name, data, PersistMode::Dynamic)
+                }
+            }
+            PersistMode::Obfuscated => {
+                let ron_string = ron::to_string(data).map_err(PersistError::serialization)?;
+                let path = self.get_resource_path(type_name, mode);
+                fs::write(&path, obfuscate::obfuscate(ron_string.as_bytes())).map_err(|e| {
+                    PersistError::io("Failed to write obfuscated file", e)
+                        .for_type(self.get_type_name(type_name))
+                        .at(&path)
+                })
+            }
+            _ => {
+                // Dynamic and Dev modes save in the type's configured format
+                let path = self.get_resource_path(type_name, mode);
+                let content =
+                    data.to_string_styled(self.get_type_format(type_name), &self.style)?;
+                fs::write(&path, content).map_err(|e| {
+                    write_error(e)
+                        .for_type(self.get_type_name(type_name))
+                        .at(&path)
+                })?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Load a resource from disk based on its persistence mode
+    pub fn load_resource(&self, type_name: &str, mode: PersistMode) -> PersistResult<PersistData> {
+        let type_name = &*self.key(type_name);
+        match mode {
+            PersistMode::Embed => {
+                // This should be handled by embedded_data() in the Persist trait
+                Err(PersistError::ResourceNotFound(format!(
+                    "Embedded resource {} should use embedded_data()",
+                    type_name
+                )))
+            }
+            PersistMode::Session => Err(PersistError::ResourceNotFound(format!(
+                "Session resource {} is never saved to disk",
+                type_name
+            ))),
+            PersistMode::Secure => {
+                #[cfg(feature = "secure")]
+                {
+                    let path = self.get_resource_path(type_name, mode);
+                    let encrypted = fs::read(&path).map_err(|e| {
+                        PersistError::io("Failed to read secure file", e)
+                            .for_type(self.get_type_name(type_name))
+                            .at(&path)
+                    })?;
+
+                    // Decrypt the data if secret is available
+                    let ron_bytes = if self.secret.is_some() {
+                        self.decrypt_type_data(type_name, &encrypted)?
+                    } else {
+                        // If no secret, assume it's just base64 encoded
+                        use base64::{engine::general_purpose, Engine as _};
+                        general_purpose::STANDARD.decode(&encrypted).map_err(|e| {
+                            PersistError::encryption(format!("Failed to decode base64: {}", e))
+                        })?
+                    };
+
+                    // Deserialize from RON
+                    let ron_string = String::from_utf8(ron_bytes).map_err(|e| {
+                        PersistError::serialization(format!(
+                            "Invalid UTF-8 in decrypted data: {}",
+                            e
+                        ))
+                    })?;
+                    PersistData::parse_as(&ron_string, PersistFormat::Ron)
+                }
+                #[cfg(not(feature = "secure"))]
+                {
+                    // Without secure feature, fall back to dynamic
+                    self.load_resource(type_name, PersistMode::Dynamic)
+                }
+            }
+            PersistMode::Obfuscated => {
+                let path = self.get_resource_path(type_name, mode);
+                let obfuscated = fs::read(&path).map_err(|e| {
+                    PersistError::io("Failed to read obfuscated file", e)
+                        .for_type(self.get_type_name(type_name))
+                        .at(&path)
+                })?;
+                let ron_string =
+                    String::from_utf8(obfuscate::deobfuscate(&obfuscated)?).map_err(|e| {
+                        PersistError::serialization(format!(
+                            "Invalid UTF-8 in obfuscated data: {}",
+                            e
+                        ))
+                    })?;
+                PersistData::parse_as(&ron_string, PersistFormat::Ron)
+            }
+            _ => {
+                // Dynamic and Dev modes load in the type's configured format
+                let path = self.get_resource_path(type_name, mode);
+                let contents = fs::read_to_string(&path)
+                    .map_err(|e| read_error(&path, e).for_type(self.get_type_name(type_name)))?;
+                PersistData::parse_as(&contents, self.get_type_format(type_name))
+                    .map_err(|e| e.for_type(self.get_type_name(type_name)).at(&path))
+            }
+        }
+    }
+}
+
+/// System sets for ordering your own systems around persistence.
+///
+/// ```ignore
+/// app.add_systems(PreStartup, apply_window_settings.after(PersistSet::Load));
+/// ```
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PersistSet {
+    /// Loads persisted resources, in `PreStartup`
+    Load,
+    /// Finds changed resources to auto-save, in `PostUpdate`
+    Save,
+    /// Writes the resources found in [`Save`](Self::Save), once per file, in `PostUpdate`
+    Flush,
+}
+
+/// Sent when a resource is auto-saved.
+#[derive(Event, Debug, Clone)]
+pub struct PersistSaved {
+    /// Name of the saved type
+    pub type_name: String,
+    /// File the data was written to
+    pub path: PathBuf,
+    /// Size of the written file
+    pub bytes: u64,
+}
+
+/// Sent when auto-saving a resource fails.
+#[derive(Event, Debug, Clone)]
+pub struct PersistSaveFailed {
+    /// Name of the type that failed to save
+    pub type_name: String,
+    /// Why the save failed
+    pub error: PersistError,
+}
+
+/// Sent when a failed save has been retried as often as the
+/// [`RetryPolicy`] allows without succeeding.
+///
+/// The data isn't written until the type changes again.
+#[derive(Event, Debug, Clone)]
+pub struct PersistRetriesExhausted {
+    /// Name of the type that failed to save
+    pub type_name: String,
+    /// How many times saving was attempted
+    pub attempts: u32,
+    /// Why the last attempt failed
+    pub error: PersistError,
+}
+
+/// Sent once when saves of a file move to memory because it can't be written.
+///
+/// See [`PersistManager::with_memory_fallback`]. Nothing is written to the
+/// file until it can be written again, so this is the time to tell the
+/// player their progress may not be kept.
+#[derive(Event, Debug, Clone)]
+pub struct PersistFellBackToMemory {
+    /// Name of the type whose save ran out of retries
+    pub type_name: String,
+    /// File that couldn't be written
+    pub path: PathBuf,
+    /// Why its last attempt failed
+    pub error: PersistError,
+}
+
+/// Sent when persisted data is loaded into a resource on startup.
+#[derive(Event, Debug, Clone)]
+pub struct PersistLoaded {
+    /// Name of the loaded type
+    pub type_name: String,
+}
+
+/// Send to save registered types without naming them generically.
+///
+/// Handled at the end of `PostUpdate`, so changes made earlier in the
+/// frame are included. Only the requested types are written, even when
+/// flushing is manual or paused; other pending saves keep waiting. Types
+/// whose data hasn't changed since it was last written are skipped, like
+/// any flush.
+///
+/// ```ignore
+/// fn on_checkpoint(mut saves: EventWriter<RequestSave>) {
+///     saves.write(RequestSave::only(["PlayerProgress"]));
+/// }
+/// ```
+#[derive(Event, Debug, Clone, Default)]
+pub struct RequestSave {
+    /// Names of the types to save, or every registered type if `None`
+    pub type_names: Option<Vec<String>>,
+}
+
+impl RequestSave {
+    /// Saves every registered type.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Saves just the named types.
+    pub fn only(type_names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            type_names: Some(type_names.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Saves just `T`.
+    pub fn of<T: Persistable>() -> Self {
+        Self::only([T::type_path()])
+    }
+}
+
+/// Sent when loaded data fails [`Persistable::validate`].
+///
+/// The resource has already been handled according to the manager's
+/// [`ValidationPolicy`] by the time this is read.
+#[derive(Event, Debug, Clone)]
+pub struct PersistValidationFailed {
+    /// Name of the type whose data was invalid
+    pub type_name: String,
+    /// What `validate` reported
+    pub error: String,
+    /// Policy that was applied
+    pub policy: ValidationPolicy,
+}
+
+/// Sent when a type's file was written by a newer version of the game.
+///
+/// The type's [`DowngradePolicy`] has already been applied by the time this
+/// is read, e.g. to tell the player their save needs the latest update.
+#[derive(Event, Debug, Clone)]
+pub struct PersistVersionEvent {
+    /// Name of the type whose file is newer
+    pub type_name: String,
+    /// Version that wrote the file
+    pub file_version: String,
+    /// Version of the game that's running
+    pub app_version: String,
+    /// Policy that was applied
+    pub policy: DowngradePolicy,
+}
+
+/// Sent when a save is refused because it's bigger than its size quota.
+///
+/// [`PersistSaveFailed`] is sent as well; this one carries the sizes, e.g.
+/// to report a resource that keeps growing.
+#[derive(Event, Debug, Clone)]
+pub struct PersistQuotaExceeded {
+    /// Name of the type that wasn't saved
+    pub type_name: String,
+    /// Size of the serialized data in bytes
+    pub bytes: u64,
+    /// Quota it went over, in bytes
+    pub quota: u64,
+}
+
+/// Sent once for a specific resource after its startup load has run.
+///
+/// Unlike [`PersistLoaded`], this is sent even if nothing was persisted yet,
+/// so systems that apply loaded values run exactly once either way:
+///
+/// ```ignore
+/// app.add_systems(
+///     Update,
+///     apply_graphics_settings.run_if(on_event::<PersistResourceLoaded<UserPreferences>>),
+/// );
+/// ```
+#[derive(Event)]
+pub struct PersistResourceLoaded<T: Resource> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Resource> PersistResourceLoaded<T> {
+    fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Plugin for automatic persistence.
+///
+/// Add this plugin to your Bevy app to enable automatic persistence
+/// for resources marked with `#[derive(Persist)]`.
+///
+/// # Example
+///
+/// ```ignore
+/// // Recommended: Always provide app info for proper paths
+/// app.add_plugins(
+///     PersistPlugin::new("MyCompany", "MyGame")
+/// );
+///
+/// // Optional: Disable auto-save for manual control
+/// app.add_plugins(
+///     PersistPlugin::new("MyCompany", "MyGame")
+///         .with_auto_save(false)
+/// );
+/// ```
+pub struct PersistPlugin {
+    /// Organization name (e.g., "MyCompany")
+    pub organization: String,
+    /// Application name (e.g., "MyGame")
+    pub app_name: String,
+    /// Whether to enable auto-save on changes
+    pub auto_save: bool,
+    /// Namespace for storage keys (defaults to each type's module path)
+    pub namespace: Option<String>,
+    /// Whether to register every `#[derive(Persist)]` type automatically
+    pub auto_register: bool,
+    /// Store whose types this plugin registers (the default store if `None`)
+    pub store: Option<String>,
+    /// Prefix of environment variables overriding loaded fields, if any
+    pub env_prefix: Option<String>,
+    /// Whether every save is skipped, leaving files untouched
+    pub read_only: bool,
+    /// Whether saves of a file move to memory once it can't be written
+    pub memory_fallback: bool,
+    /// Development or production behavior (defaults from the `prod` feature)
+    pub environment: PersistEnvironment,
+    /// Custom placement of production files (defaults to platform directories)
+    pub path_resolver: Option<Arc<dyn PathResolver>>,
+    /// Receiver of save and load activity, if any
+    pub telemetry: Option<Arc<dyn PersistTelemetry>>,
+    /// Directory relative paths are kept under instead of the working directory
+    pub root_dir: Option<PathBuf>,
+    /// Source of the timestamps written into files (defaults to the system time)
+    pub clock: Option<Arc<dyn PersistClock>>,
+    /// Store data is kept in instead of files, if any
+    pub backend: Option<Arc<dyn PersistBackend>>,
+    /// Remote store saved data is synced with, how conflicts are resolved
+    /// and how often it syncs, if at all
+    pub cloud_sync: Option<(Arc<dyn PersistBackend>, ConflictStrategy, Duration)>,
+    /// Whether to reapply persisted files when they're edited on disk
+    #[cfg(feature = "hot-reload")]
+    pub hot_reload: bool,
+    /// Address to serve the WebSocket tweak protocol on, if any
+    #[cfg(feature = "tweak-server")]
+    pub tweak_server: Option<String>,
+    /// Whether dirty types are written as soon as the app is suspended
+    #[cfg(feature = "lifecycle")]
+    pub flush_on_suspend: bool,
+    /// Whether dirty types are written when the primary window loses focus
+    #[cfg(feature = "lifecycle")]
+    pub flush_on_focus_lost: bool,
+    /// Whether SIGINT/SIGTERM flush every dirty type before exiting
+    #[cfg(feature = "shutdown")]
+    pub flush_on_shutdown: bool,
+    /// Layout of written files
+    pub style: PersistStyle,
+    /// How production files are split
+    pub file_layout: FileLayout,
+    /// How file names are derived from type and group names
+    pub file_names: FileNameStrategy,
+    /// What happens to resources whose loaded data fails validation
+    pub validation_policy: ValidationPolicy,
+    /// How failed auto-saves are retried
+    pub retry_policy: RetryPolicy,
+    /// Whether a flush writes types' own files in parallel
+    pub parallel_saves: bool,
+    /// Version of the game written into files, if set
+    pub app_version: Option<String>,
+    /// What happens to files written by a newer version of the game
+    pub downgrade_policy: DowngradePolicy,
+    /// Largest serialized size of a type's data, if limited
+    pub size_quota: Option<u64>,
+    /// What happens to saves bigger than their quota
+    pub quota_policy: QuotaPolicy,
+    /// Shortest time between two automatic writes of the same file, if limited
+    pub min_write_interval: Option<Duration>,
+    /// Time the end-of-frame flush may spend writing, if limited
+    pub frame_budget: Option<Duration>,
+    /// How often to write an autosave file, and how many to keep
+    pub autosaves: Option<(Duration, usize)>,
+    /// Where earlier versions of the game placed production files
+    pub legacy_locations: Vec<Arc<dyn PathResolver>>,
+    /// Adds the end-of-frame flush gated by [`save_run_if`](Self::save_run_if)
+    save_condition: Option<Arc<AddFlushFn>>,
+    /// Secret for encrypting secure persistence (optional)
+    #[cfg(feature = "secure")]
+    secret: Option<String>,
+    /// Earlier secrets, still accepted when decrypting
+    #[cfg(feature = "secure")]
+    previous_secrets: Vec<String>,
+    /// Bytes that must stay free after a save, if saves are checked first
+    #[cfg(feature = "preflight")]
+    pub min_free_space: Option<u64>,
+    /// Legacy INI file imported into types with nothing saved yet
+    #[cfg(feature = "ini")]
+    pub ini_import: Option<PathBuf>,
+}
+
+type AddFlushFn = dyn Fn(&mut App) + Send + Sync;
+
+impl Default for PersistPlugin {
+    fn default() -> Self {
+        Self {
+            organization: "DefaultOrg".to_string(),
+            app_name: "DefaultApp".to_string(),
+            auto_save: true,
+            namespace: None,
+            auto_register: true,
+            store: None,
+            env_prefix: None,
+            read_only: false,
+            memory_fallback: false,
+            environment: PersistEnvironment::default(),
+            path_resolver: None,
+            telemetry: None,
+            root_dir: None,
+            clock: None,
+            backend: None,
+            cloud_sync: None,
+            #[cfg(feature = "hot-reload")]
+            hot_reload: false,
+            #[cfg(feature = "tweak-server")]
+            tweak_server: None,
+            #[cfg(feature = "lifecycle")]
+            flush_on_suspend: true,
+            #[cfg(feature = "lifecycle")]
+            flush_on_focus_lost: false,
+            #[cfg(feature = "shutdown")]
+            flush_on_shutdown: false,
+            style: PersistStyle::default(),
+            file_layout: FileLayout::default(),
+            file_names: FileNameStrategy::default(),
+            validation_policy: ValidationPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            parallel_saves: true,
+            app_version: None,
+            downgrade_policy: DowngradePolicy::default(),
+            size_quota: None,
+            quota_policy: QuotaPolicy::default(),
+            min_write_interval: None,
+            frame_budget: None,
+            autosaves: None,
+            legacy_locations: Vec::new(),
+            save_condition: None,
+            #[cfg(feature = "secure")]
+            secret: None,
+            #[cfg(feature = "secure")]
+            previous_secrets: Vec::new(),
+            #[cfg(feature = "preflight")]
+            min_free_space: None,
+            #[cfg(feature = "ini")]
+            ini_import: None,
+        }
+    }
+}
+
+impl PersistPlugin {
+    /// Creates a new PersistPlugin with organization and app name.
+    ///
+    /// These are used for:
+    /// - Platform-specific paths in production (e.g., ~/Library/Application Support/MyCompany/MyGame/)
+    /// - Dev file naming (e.g., mygame_dev.ron)
+    pub fn new(organization: impl Into<String>, app_name: impl Into<String>) -> Self {
+        Self {
+            organization: organization.into(),
+            app_name: app_name.into(),
+            auto_save: true,
+            namespace: None,
+            auto_register: true,
+            store: None,
+            env_prefix: None,
+            read_only: false,
+            memory_fallback: false,
+            environment: PersistEnvironment::default(),
+            path_resolver: None,
+            telemetry: None,
+            root_dir: None,
+            clock: None,
+            backend: None,
+            cloud_sync: None,
+            #[cfg(feature = "hot-reload")]
+            hot_reload: false,
+            #[cfg(feature = "tweak-server")]
+            tweak_server: None,
+            #[cfg(feature = "lifecycle")]
+            flush_on_suspend: true,
+            #[cfg(feature = "lifecycle")]
+            flush_on_focus_lost: false,
+            #[cfg(feature = "shutdown")]
+            flush_on_shutdown: false,
+            style: PersistStyle::default(),
+            file_layout: FileLayout::default(),
+            file_names: FileNameStrategy::default(),
+            validation_policy: ValidationPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            parallel_saves: true,
+            app_version: None,
+            downgrade_policy: DowngradePolicy::default(),
+            size_quota: None,
+            quota_policy: QuotaPolicy::default(),
+            min_write_interval: None,
+            frame_budget: None,
+            autosaves: None,
+            legacy_locations: Vec::new(),
+            save_condition: None,
+            #[cfg(feature = "secure")]
+            secret: None,
+            #[cfg(feature = "secure")]
+            previous_secrets: Vec::new(),
+            #[cfg(feature = "preflight")]
+            min_free_space: None,
+            #[cfg(feature = "ini")]
+            ini_import: None,
+        }
+    }
+
+    /// Sets whether auto-save is enabled globally.
+    pub fn with_auto_save(mut self, enabled: bool) -> Self {
+        self.auto_save = enabled;
+        self
+    }
+
+    /// Sets a namespace for storage keys.
+    ///
+    /// By default each type's data is keyed by its full module path so that
+    /// same-named resources from different crates don't collide. A namespace
+    /// replaces the module path with `namespace::TypeName`, which keeps keys
+    /// stable when types move between modules.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Lets environment variables override loaded fields, e.g.
+    /// `BEVY_PERSIST__USERSETTINGS__VOLUME=0.2` with the prefix `"BEVY_PERSIST"`.
+    ///
+    /// Overrides are applied once loading at startup is done and aren't
+    /// written back unless the game changes them. Handy for CI, server
+    /// deployments and reproducing bugs. See
+    /// [`PersistManager::apply_env_overrides`].
+    pub fn with_env_overrides(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets whether every save is skipped (defaults to `false`).
+    ///
+    /// Everything loads normally, but nothing is ever written or deleted.
+    /// For demo kiosks, replays and spectator builds that mustn't touch the
+    /// player's real save data. See [`PersistManager::set_read_only`].
+    pub fn with_read_only(mut self, enabled: bool) -> Self {
+        self.read_only = enabled;
+        self
+    }
+
+    /// Sets whether saves of a file move to memory once it can't be written
+    /// (defaults to `false`). See [`PersistManager::with_memory_fallback`].
+    pub fn with_memory_fallback(mut self, enabled: bool) -> Self {
+        self.memory_fallback = enabled;
+        self
+    }
+
+    /// Sets whether persistence follows development or production rules.
+    ///
+    /// Useful for exercising production paths and embedding from a dev
+    /// build, e.g. in tests. Compiled-in embedded data still depends on the
+    /// `prod` feature of the crate deriving `Persist`.
+    pub fn with_environment(mut self, environment: PersistEnvironment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Sets the resolver that places production files.
+    ///
+    /// ```ignore
+    /// struct LauncherPaths(PathBuf);
+    ///
+    /// impl PathResolver for LauncherPaths {
+    ///     fn resolve(&self, type_name: &str, _mode: PersistMode) -> PathBuf {
+    ///         self.0.join(type_name.to_lowercase())
+    ///     }
+    /// }
+    ///
+    /// app.add_plugins(
+    ///     PersistPlugin::new("MyCompany", "MyGame")
+    ///         .with_path_resolver(LauncherPaths(launcher_save_dir)),
+    /// );
+    /// ```
+    pub fn with_path_resolver(mut self, resolver: impl PathResolver) -> Self {
+        self.path_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Keeps the dev file and every other relative path under `dir`.
+    ///
+    /// See [`PersistManager::with_root_dir`].
+    pub fn with_root_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.root_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the clock that timestamps saves and loads.
+    ///
+    /// See [`PersistClock`].
+    pub fn with_clock(mut self, clock: impl PersistClock) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Keeps data in `backend` instead of files.
+    ///
+    /// See [`PersistBackend`].
+    pub fn with_backend(mut self, backend: impl PersistBackend) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Reports every save, load and error to `telemetry`.
+    ///
+    /// See [`PersistTelemetry`].
+    pub fn with_telemetry(mut self, telemetry: impl PersistTelemetry) -> Self {
+        self.telemetry = Some(Arc::new(telemetry));
+        self
+    }
+
+    /// Syncs saved data with `remote` each `interval`, resolving conflicts
+    /// with `strategy`.
+    ///
+    /// See [`PersistManager::with_cloud_sync`].
+    pub fn with_cloud_sync(
+        mut self,
+        remote: impl PersistBackend,
+        strategy: ConflictStrategy,
+        interval: Duration,
+    ) -> Self {
+        self.cloud_sync = Some((Arc::new(remote), strategy, interval));
+        self
+    }
+
+    /// Only writes auto-saves while `condition` holds, e.g.
+    /// `in_state(GameState::Playing)`.
+    ///
+    /// Changes made meanwhile stay dirty and are written by the first
+    /// end-of-frame flush the condition allows, so loading screens and
+    /// cutscenes mutating lots of resources don't write every frame.
+    /// Explicit saves and flushes aren't affected.
+    pub fn save_run_if<M>(
+        mut self,
+        condition: impl Condition<M> + Clone + Send + Sync + 'static,
+    ) -> Self {
+        self.save_condition = Some(Arc::new(move |app: &mut App| {
+            app.add_systems(
+                PostUpdate,
+                flush_dirty
+                    .in_set(PersistSet::Flush)
+                    .run_if(condition.clone()),
+            );
+        }));
+        self
+    }
+
+    /// Sets whether derived types are registered automatically.
+    ///
+    /// Auto-registration relies on `inventory`, which doesn't work on every
+    /// target. When disabled, each type must be registered explicitly with
+    /// [`PersistAppExt::register_persist`].
+    pub fn with_auto_register(mut self, enabled: bool) -> Self {
+        self.auto_register = enabled;
+        self
+    }
+
+    /// Makes this plugin manage a named store instead of the default one.
+    ///
+    /// Each store needs its own world, so add the plugin to a sub-app:
+    /// an editor tool embedded in the game can then keep its own file and
+    /// registry next to the game's. Auto-registration only picks up types
+    /// marked `#[persist(store = "...")]` with the same name, and the dev
+    /// file becomes `<app>_<store>_dev.ron`.
+    pub fn with_store(mut self, store: impl Into<String>) -> Self {
+        self.store = Some(store.into());
+        self
+    }
+
+    /// Sets whether persisted files are watched for external edits.
+    ///
+    /// Edit `mygame_dev.ron` (or any file a registered type lives in) while
+    /// the game runs and the new values are applied to the live resources.
+    /// Reloads run in [`PersistSet::Load`] during `PreUpdate`.
+    #[cfg(feature = "hot-reload")]
+    pub fn with_hot_reload(mut self, enabled: bool) -> Self {
+        self.hot_reload = enabled;
+        self
+    }
+
+    /// Sets whether dirty types are written when the app is suspended (defaults to `true`).
+    ///
+    /// Mobile apps can be killed any time after going to the background.
+    /// With this on, Bevy's [`AppLifecycle`](bevy::window::AppLifecycle)
+    /// `WillSuspend` and `Suspended` events flush every dirty type right
+    /// away, even with [manual flushing](PersistManager::set_manual_flush).
+    #[cfg(feature = "lifecycle")]
+    pub fn with_flush_on_suspend(mut self, enabled: bool) -> Self {
+        self.flush_on_suspend = enabled;
+        self
+    }
+
+    /// Sets whether dirty types are written when the primary window loses
+    /// focus or is minimized (defaults to `false`).
+    ///
+    /// Protects desktop games from crashes and task-manager kills without
+    /// saving every frame.
+    #[cfg(feature = "lifecycle")]
+    pub fn with_flush_on_focus_lost(mut self, enabled: bool) -> Self {
+        self.flush_on_focus_lost = enabled;
+        self
+    }
+
+    /// Sets whether SIGINT (Ctrl-C) and SIGTERM flush every dirty type and
+    /// exit the app cleanly, instead of killing the process (defaults to `false`).
+    ///
+    /// Meant for headless servers. See [`PersistShutdown`].
+    #[cfg(feature = "shutdown")]
+    pub fn with_flush_on_shutdown(mut self, enabled: bool) -> Self {
+        self.flush_on_shutdown = enabled;
+        self
+    }
+
+    /// Serves registered types over WebSocket on `address` (e.g. `"127.0.0.1:7878"`).
+    ///
+    /// External tools can list, read and patch resources at runtime; see
+    /// [`PersistTweakServer`] for the protocol. Clients need the token the
+    /// server logs when it starts, but it's still best bound to localhost.
+    #[cfg(feature = "tweak-server")]
+    pub fn with_tweak_server(mut self, address: impl Into<String>) -> Self {
+        self.tweak_server = Some(address.into());
+        self
+    }
+
+    /// Sets how written files are laid out, e.g. to match a project's config conventions.
+    ///
+    /// ```ignore
+    /// app.add_plugins(
+    ///     PersistPlugin::new("MyCompany", "MyGame")
+    ///         .with_style(PersistStyle::default().with_indent("  ").with_struct_names(true)),
+    /// );
+    /// ```
+    pub fn with_style(mut self, style: PersistStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets how production files are split, e.g. [`FileLayout::Single`]
+    /// for one save file instead of one per type.
+    pub fn with_file_layout(mut self, layout: FileLayout) -> Self {
+        self.file_layout = layout;
+        self
+    }
+
+    /// Sets how file names are derived from type and group names, e.g.
+    /// [`FileNameStrategy::SnakeCase`] for `player_stats.ron` instead of
+    /// `playerstats.ron`.
+    ///
+    /// Applies to production files placed by [`PlatformPathResolver`] and
+    /// resolvers that override [`PathResolver::resolve_file`], and to group
+    /// files in development. Files saved under the old names can be moved
+    /// with [`with_legacy_locations`](Self::with_legacy_locations).
+    pub fn with_file_names(mut self, strategy: FileNameStrategy) -> Self {
+        self.file_names = strategy;
+        self
+    }
+
+    /// Sets what happens to resources whose loaded data fails [`Persistable::validate`].
+    pub fn with_validation_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.validation_policy = policy;
+        self
+    }
+
+    /// Sets how failed auto-saves are retried, e.g. [`RetryPolicy::none`]
+    /// to give up right away.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Sets whether a flush writes types' own files in parallel on the
+    /// IO task pool (defaults to `true`).
+    ///
+    /// Cuts the stall when many types save at once, e.g. on exit. A
+    /// [`PersistBatchSaved`] event follows each parallel batch.
+    pub fn with_parallel_saves(mut self, enabled: bool) -> Self {
+        self.parallel_saves = enabled;
+        self
+    }
+
+    /// Sets the game's version, so files from newer versions are caught.
+    ///
+    /// See [`PersistManager::with_app_version`].
+    pub fn with_app_version(mut self, version: impl Into<String>) -> Self {
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// Sets what happens to files written by a newer version of the game.
+    pub fn with_downgrade_policy(mut self, policy: DowngradePolicy) -> Self {
+        self.downgrade_policy = policy;
+        self
+    }
+
+    /// Limits the serialized size of every type's data, in bytes.
+    ///
+    /// Keeps a runaway collection in a resource from filling the player's
+    /// disk. Types can set their own with
+    /// [`PersistResourceBuilder::size_quota`].
+    pub fn with_size_quota(mut self, max_bytes: u64) -> Self {
+        self.size_quota = Some(max_bytes);
+        self
+    }
+
+    /// Sets what happens to saves bigger than their size quota.
+    pub fn with_quota_policy(mut self, policy: QuotaPolicy) -> Self {
+        self.quota_policy = policy;
+        self
+    }
+
+    /// Writes each file at most once per `interval` at the end of a frame.
+    ///
+    /// See [`PersistManager::with_min_write_interval`].
+    pub fn with_min_write_interval(mut self, interval: Duration) -> Self {
+        self.min_write_interval = Some(interval);
+        self
+    }
+
+    /// Caps the time the end-of-frame flush spends writing, spreading many
+    /// changes at once across frames.
+    ///
+    /// ```ignore
+    /// app.add_plugins(
+    ///     PersistPlugin::new("MyCompany", "MyGame").with_frame_budget(Duration::from_micros(500)),
+    /// );
+    /// ```
+    ///
+    /// See [`PersistManager::with_frame_budget`].
+    pub fn with_frame_budget(mut self, budget: Duration) -> Self {
+        self.frame_budget = Some(budget);
+        self
+    }
+
+    /// Writes every resource to a new autosave file each `interval`,
+    /// keeping the newest `keep`.
+    ///
+    /// See [`PersistManager::with_autosaves`].
+    pub fn with_autosaves(mut self, interval: Duration, keep: usize) -> Self {
+        self.autosaves = Some((interval, keep));
+        self
+    }
+
+    /// Moves files left where earlier versions of the game put them on first load.
+    ///
+    /// See [`PersistManager::with_legacy_locations`].
+    pub fn with_legacy_locations(mut self, locations: Vec<Arc<dyn PathResolver>>) -> Self {
+        self.legacy_locations = locations;
+        self
+    }
+
+    /// Sets the secret for encrypting secure persistence
+    #[cfg(feature = "secure")]
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Sets the secret for encrypting, plus older secrets still accepted for decrypting.
+    ///
+    /// See [`PersistManager::with_secrets`].
+    #[cfg(feature = "secure")]
+    pub fn with_secrets<S: Into<String>>(
+        mut self,
+        current: impl Into<String>,
+        previous: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.secret = Some(current.into());
+        self.previous_secrets = previous.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Checks before each save that its directory is writable and has room.
+    ///
+    /// See [`PersistManager::with_preflight_checks`].
+    #[cfg(feature = "preflight")]
+    pub fn with_preflight_checks(mut self, min_free_space: u64) -> Self {
+        self.min_free_space = Some(min_free_space);
+        self
+    }
+
+    /// Imports a legacy INI file on startup, into types with nothing saved yet.
+    ///
+    /// Runs after loading and before the first save, so each type is
+    /// imported once. See [`PersistManager::import_ini`].
+    #[cfg(feature = "ini")]
+    pub fn with_ini_import(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ini_import = Some(path.into());
+        self
+    }
+}
+
+impl Plugin for PersistPlugin {
+    fn build(&self, app: &mut App) {
+        let mut manager = PersistManager::new(self.organization.clone(), self.app_name.clone())
+            .with_environment(self.environment)
+            .with_style(self.style.clone())
+            .with_file_layout(self.file_layout)
+            .with_file_names(self.file_names.clone())
+            .with_validation_policy(self.validation_policy)
+            .with_retry_policy(self.retry_policy)
+            .with_parallel_saves(self.parallel_saves)
+            .with_downgrade_policy(self.downgrade_policy)
+            .with_quota_policy(self.quota_policy);
+        if let Some(dir) = &self.root_dir {
+            manager = manager.with_root_dir(dir.clone());
+        }
+        if let Some(version) = &self.app_version {
+            manager = manager.with_app_version(version.clone());
+        }
+        if let Some(max_bytes) = self.size_quota {
+            manager = manager.with_size_quota(max_bytes);
+        }
+        if let Some(interval) = self.min_write_interval {
+            manager = manager.with_min_write_interval(interval);
+        }
+        if let Some(budget) = self.frame_budget {
+            manager = manager.with_frame_budget(budget);
+        }
+        if let Some((interval, keep)) = self.autosaves {
+            manager = manager.with_autosaves(interval, keep);
+        }
+        if !self.legacy_locations.is_empty() {
+            manager = manager.with_legacy_locations(self.legacy_locations.clone());
+        }
+        manager.auto_save = self.auto_save;
+        manager.set_read_only(self.read_only);
+        manager.memory_fallback = self.memory_fallback;
+        if let Some(resolver) = &self.path_resolver {
+            manager.set_path_resolver(resolver.clone());
+        }
+        if let Some(telemetry) = &self.telemetry {
+            manager.set_telemetry(telemetry.clone());
+        }
+        if let Some(clock) = &self.clock {
+            manager.set_clock(clock.clone());
+        }
+        if let Some(backend) = &self.backend {
+            manager.set_backend(backend.clone());
+        }
+        if let Some((remote, strategy, interval)) = &self.cloud_sync {
+            manager.set_cloud_sync(remote.clone(), strategy.clone(), *interval);
+        }
+        if let Some(namespace) = &self.namespace {
+            manager = manager.with_namespace(namespace.clone());
+        }
+        if let Some(store) = &self.store {
+            manager = manager.with_store(store.clone());
+        }
+        if let Some(prefix) = &self.env_prefix {
+            manager = manager.with_env_overrides(prefix.clone());
+        }
+
+        #[cfg(feature = "secure")]
+        if let Some(secret) = &self.secret {
+            manager = manager.with_secrets(secret.clone(), self.previous_secrets.clone());
+        }
+        #[cfg(feature = "preflight")]
+        if let Some(min_free_space) = self.min_free_space {
+            manager = manager.with_preflight_checks(min_free_space);
+        }
+
+        app.insert_resource(manager);
+        app.init_resource::<PersistLoadState>();
+        app.add_event::<PersistSaved>()
+            .add_event::<PersistSaveFailed>()
+            .add_event::<PersistRetriesExhausted>()
+            .add_event::<PersistBatchSaved>()
+            .add_event::<PersistFellBackToMemory>()
+            .add_event::<PersistLoaded>()
+            .add_event::<PersistProgress>()
+            .add_event::<PersistSynced>()
+            .add_event::<PersistSyncConflict>()
+            .add_event::<PersistValidationFailed>()
+            .add_event::<PersistVersionEvent>()
+            .add_event::<PersistQuotaExceeded>()
+            .add_event::<RequestSave>()
+            .add_event::<QuickSave>()
+            .add_event::<QuickLoad>()
+            .configure_sets(PostUpdate, PersistSet::Flush.after(PersistSet::Save))
+            .add_systems(
+                PreStartup,
+                (
+                    legacy::migrate_legacy_locations,
+                    transaction::replay_journal,
+                )
+                    .chain()
+                    .before(PersistSet::Load),
+            )
+            .add_systems(PreUpdate, send_version_events.after(PersistSet::Load))
+            .add_systems(PostUpdate, send_quota_events.after(PersistSet::Flush))
+            .add_systems(
+                PostUpdate,
+                progress::poll_background_jobs.after(PersistSet::Flush),
+            )
+            .add_systems(
+                PostUpdate,
+                (handle_save_requests, retry_failed_saves)
+                    .in_set(PersistSet::Flush)
+                    .before(flush_dirty),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    quick_save::handle_quick_saves,
+                    quick_save::handle_quick_loads,
+                )
+                    .chain()
+                    .in_set(PersistSet::Flush),
+            );
+        match &self.save_condition {
+            Some(add_flush) => add_flush(app),
+            None => {
+                app.add_systems(PostUpdate, flush_dirty.in_set(PersistSet::Flush));
+            }
+        }
+
+        if self.env_prefix.is_some() {
+            app.add_systems(PreStartup, apply_env_overrides.after(PersistSet::Load));
+        }
+        #[cfg(feature = "ini")]
+        if let Some(path) = self.ini_import.clone() {
+            app.add_systems(
+                PreStartup,
+                (move |world: &mut World| {
+                    if let Err(e) = PersistManager::import_ini(world, &path) {
+                        error!("Failed to import {:?}: {}", path, e);
+                    }
+                })
+                .after(PersistSet::Load),
+            );
+        }
+        if self.autosaves.is_some() {
+            app.add_systems(PostUpdate, autosave::run_autosaves.after(PersistSet::Flush));
+        }
+        if self.cloud_sync.is_some() {
+            app.add_systems(PostUpdate, sync::run_sync.after(PersistSet::Flush));
+        }
+
+        #[cfg(feature = "preflight")]
+        app.add_event::<PersistPreflightFailed>().add_systems(
+            PostUpdate,
+            preflight::send_preflight_events.after(PersistSet::Flush),
+        );
+
+        #[cfg(feature = "hot-reload")]
+        if self.hot_reload {
+            app.add_systems(PostStartup, hot_reload::start_watching)
+                .add_systems(
+                    PreUpdate,
+                    hot_reload::apply_file_changes.in_set(PersistSet::Load),
+                );
+        }
+
+        #[cfg(feature = "tweak-server")]
+        if let Some(address) = &self.tweak_server {
+            tweak_server::start(app, address);
+        }
+
+        #[cfg(feature = "shutdown")]
+        if self.flush_on_shutdown {
+            shutdown::start(app);
+        }
+
+        #[cfg(feature = "lifecycle")]
+        if self.flush_on_suspend {
+            app.add_systems(
+                PostUpdate,
+                lifecycle::flush_on_suspend
+                    .in_set(PersistSet::Flush)
+                    .before(flush_dirty),
+            );
+        }
+        #[cfg(feature = "lifecycle")]
+        if self.flush_on_focus_lost {
+            app.add_systems(
+                PostUpdate,
+                lifecycle::flush_on_focus_lost
+                    .in_set(PersistSet::Flush)
+                    .before(flush_dirty),
+            );
+        }
+
+        if self.auto_register {
+            // Types of other stores belong to other plugins
+            let registrations: Vec<&PersistRegistration> = inventory::iter::<PersistRegistration>
+                .into_iter()
+                .filter(|registration| registration.store == self.store.as_deref())
+                .collect();
+
+            // Two types with one storage key would share files and manager state
+            let keys: Vec<(String, &str)> = app
+                .world()
+                .get_resource::<PersistManager>()
+                .map(|manager| {
+                    registrations
+                        .iter()
+                        .map(|registration| {
+                            let key =
+                                manager.key_for(registration.type_name, registration.type_path);
+                            (key, registration.type_path)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let collisions =
+                find_storage_key_collisions(keys.iter().map(|(key, path)| (key.as_str(), *path)));
+            for (key, type_paths) in &collisions {
+                report_storage_key_collision(key, type_paths);
+            }
+
+            // Auto-register all Persist types that have been defined
+            for registration in registrations {
+                debug!(
+                    "Auto-registering persist type: {} (mode: {}, embed_file: {:?}, file: {:?}, group: {:?})",
+                    registration.type_name,
+                    registration.persist_mode,
+                    registration.embed_file,
+                    registration.file,
+                    registration.group
+                );
+
+                // Call the registration function first to set up the resource and systems
+                (registration.register_fn)(app);
+
+                // Then store the mode for this type
+                if let Some(mut manager) = app.world_mut().get_resource_mut::<PersistManager>() {
+                    let mode = match registration.persist_mode {
+                        "embed" => PersistMode::Embed,
+                        "dynamic" => PersistMode::Dynamic,
+                        "secure" => PersistMode::Secure,
+                        "obfuscated" => PersistMode::Obfuscated,
+                        "session" => PersistMode::Session,
+                        _ => PersistMode::Dev,
+                    };
+                    let key = manager.key_for(registration.type_name, registration.type_path);
+                    manager.set_type_mode(key.clone(), mode);
+
+                    // Store the pinned file path if specified
+                    if let Some(file) = registration.file {
+                        manager.set_type_file(key.clone(), file);
+                    }
+
+                    // Store the group if specified
+                    if let Some(group) = registration.group {
+                        manager.set_type_group(key.clone(), group);
+                    }
+
+                    // Store embed file path if specified
+                    if let Some(embed_file) = registration.embed_file {
+                        manager.set_type_embed_file(key, embed_file.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Register a Persist type with the system.
+///
+/// This is called automatically by the derive macro and typically
+/// doesn't need to be called manually.
+pub fn register_persist_type<T: Resource + Persistable + Default>(app: &mut App, auto_save: bool) {
+    let type_name = T::type_name();
+
+    let world = app.world_mut();
+
+    // Ensure resource exists
+    if !world.contains_resource::<T>() {
+        world.init_resource::<T>();
+    }
+    let key = storage_key_of::<T>(world);
+    world
+        .get_resource_or_init::<PersistLoadState>()
+        .expect::<T>(&key, type_name);
+
+    // Set auto-save preference and format for this type
+    if let Some(mut manager) = world.get_resource_mut::<PersistManager>() {
+        manager.set_type_path(type_name.to_string(), T::type_path());
+        manager.set_type_auto_save(key.clone(), auto_save);
+        manager.set_type_mode(key.clone(), T::persist_mode());
+        manager.set_type_format(key.clone(), T::persist_format());
+        if let Some(file) = T::persist_file() {
+            manager.set_type_file(key.clone(), file);
+        }
+        if let Some(group) = T::persist_group() {
+            manager.set_type_group(key.clone(), group);
+        }
+        if let Some(dir) = T::persist_dir() {
+            manager.set_type_dir(key.clone(), dir);
+        }
+        if let Some(embed_file) = T::embed_file() {
+            manager.set_type_embed_file(key.clone(), embed_file.to_string());
+        }
+        if T::persist_delta() {
+            manager.set_type_defaults(key.clone(), T::default().to_persist_data());
+        }
+        if let Some(defaults) = T::defaults_data() {
+            if let Err(e) = manager.set_type_defaults_from_str(&key, defaults) {
+                error!("Failed to read shipped defaults of {}: {}", type_name, e);
+            }
+        }
+        manager.set_type_hooks(
+            key.clone(),
+            PersistHooks::for_persistable::<T>().with_reset::<T>(),
+        );
+        manager.set_type_field_docs(key.clone(), T::field_docs());
+        manager.set_type_encrypted_fields(key.clone(), T::encrypted_fields());
+        manager.warn_file_name_collision(&key);
+    }
+
+    // Add systems for this type
+    app.add_event::<PersistResourceLoaded<T>>();
+    // Load persisted data first in PreStartup
+    app.add_systems(PreStartup, load_persisted::<T>.in_set(PersistSet::Load));
+    // Run persist_system in PostUpdate to ensure it runs after all user systems
+    app.add_systems(PostUpdate, persist_system::<T>.in_set(PersistSet::Save));
+}
+
+/// Finds storage keys claimed by more than one distinct type.
+///
+/// Takes `(storage_key, type_path)` pairs and returns each colliding key
+/// with the sorted paths of every type using it. Types sharing a name
+/// only collide when a namespace gives them the same key.
+pub(crate) fn find_storage_key_collisions<'a>(
+    types: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<(&'a str, Vec<&'a str>)> {
+    let mut by_key: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (key, type_path) in types {
+        let paths = by_key.entry(key).or_default();
+        if !paths.contains(&type_path) {
+            paths.push(type_path);
+        }
+    }
+
+    let mut collisions: Vec<_> = by_key
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(key, mut paths)| {
+            paths.sort_unstable();
+            (key, paths)
+        })
+        .collect();
+    collisions.sort_unstable();
+    collisions
+}
+
+/// Reports a collision if a type other than `type_path` already has the
+/// storage key `type_name` gets.
+fn check_storage_key(manager: &PersistManager, type_name: &str, type_path: &str) {
+    let key = manager.key_for(type_name, type_path);
+    if let Some(existing) = manager.type_paths.get(&key) {
+        if existing != type_path {
+            report_storage_key_collision(&key, &[existing, type_path]);
+        }
+    }
+}
+
+/// Reports a storage key collision: panics in debug builds, logs an error otherwise.
+fn report_storage_key_collision(key: &str, type_paths: &[&str]) {
+    let message = format!(
+        "Multiple Persist types are stored under `{}`: {}. \
+         Their data would overwrite each other; give them distinct names with \
+         #[persist(name = \"...\")]",
+        key,
+        type_paths.join(", ")
+    );
+    if cfg!(debug_assertions) {
+        panic!("{}", message);
+    }
+    error!("{}", message);
+}
+
+/// Extension methods on `App` for registering persistent resources explicitly.
+pub trait PersistAppExt {
+    /// Registers a Persist type that isn't picked up by auto-registration.
+    ///
+    /// Generic resources can't be auto-registered, so every instantiation
+    /// that should persist needs to be registered with this after adding
+    /// `PersistPlugin`:
+    ///
+    /// ```ignore
+    /// app.add_plugins(PersistPlugin::new("MyCompany", "MyGame"))
+    ///     .register_persist::<Scores<Arcade>>()
+    ///     .register_persist::<Scores<Campaign>>();
+    /// ```
+    fn register_persist<T: Persistable + Default>(&mut self) -> &mut Self;
+
+    /// Registers a [`PersistComponent`] that isn't picked up by auto-registration.
+    fn register_persist_component<T: PersistComponent>(&mut self) -> &mut Self;
+
+    /// Resolves a resource's [`PersistAsset`] handles after it loads or changes.
+    ///
+    /// Runs after [`PersistSet::Load`] at startup and on every frame, so
+    /// reloaded paths are picked up too.
+    #[cfg(feature = "assets")]
+    fn resolve_persist_assets<R: Resource + PersistAssets>(&mut self) -> &mut Self;
+
+    /// Persists a resource that doesn't derive `Persist`.
+    ///
+    /// Works for any resource implementing `Serialize` and `Deserialize`,
+    /// including foreign types. The type is keyed by its short type name;
+    /// use [`persist_resource_as`](Self::persist_resource_as) to choose one.
+    /// Must be called after adding `PersistPlugin`.
+    ///
+    /// ```ignore
+    /// app.persist_resource::<ClearColor>()
+    ///     .mode(PersistMode::Dynamic)
+    ///     .auto_save(false)
+    ///     .file("clear_color.ron");
+    /// ```
+    fn persist_resource<T>(&mut self) -> PersistResourceBuilder<'_>
+    where
+        T: Resource + FromWorld + Serialize + for<'de> Deserialize<'de>;
+
+    /// Persists a resource that doesn't derive `Persist` under the given type name.
+    fn persist_resource_as<T>(
+        &mut self,
+        type_name: impl Into<String>,
+    ) -> PersistResourceBuilder<'_>
+    where
+        T: Resource + FromWorld + Serialize + for<'de> Deserialize<'de>;
+
+    /// Persists a resource you can't implement traits on, via closures.
+    ///
+    /// `extract` turns the resource into [`PersistData`] when it changes,
+    /// and `apply` writes loaded data back into it on startup. The closures
+    /// are stored in the [`PersistManager`]. The resource isn't created if
+    /// it doesn't exist; nothing is loaded or saved until it does.
+    ///
+    /// ```ignore
+    /// app.persist_external::<Window>(
+    ///     |window| {
+    ///         let mut data = PersistData::new();
+    ///         data.insert("width", window.resolution.width());
+    ///         data
+    ///     },
+    ///     |window, data| {
+    ///         if let Some(width) = data.get::<f32>("width") {
+    ///             window.resolution.set(width, window.resolution.height());
+    ///         }
+    ///     },
+    /// );
+    /// ```
+    fn persist_external<T: Resource>(
+        &mut self,
+        extract: impl Fn(&T) -> PersistData + Send + Sync + 'static,
+        apply: impl Fn(&mut T, &PersistData) + Send + Sync + 'static,
+    ) -> PersistResourceBuilder<'_>;
+}
+
+impl PersistAppExt for App {
+    fn register_persist<T: Persistable + Default>(&mut self) -> &mut Self {
+        if let Some(manager) = self.world().get_resource::<PersistManager>() {
+            check_storage_key(manager, T::type_name(), T::type_path());
+        }
+
+        register_persist_type::<T>(self, T::default_auto_save());
+        self
+    }
+
+    fn register_persist_component<T: PersistComponent>(&mut self) -> &mut Self {
+        if let Some(manager) = self.world().get_resource::<PersistManager>() {
+            check_storage_key(manager, T::type_name(), T::type_path());
+        }
+
+        register_persist_component::<T>(self, T::default_auto_save());
+        self
+    }
+
+    #[cfg(feature = "assets")]
+    fn resolve_persist_assets<R: Resource + PersistAssets>(&mut self) -> &mut Self {
+        self.add_systems(
+            PreStartup,
+            resolve_persist_assets::<R>.after(PersistSet::Load),
+        )
+        .add_systems(
+            PreUpdate,
+            resolve_persist_assets::<R>.after(PersistSet::Load),
+        )
+    }
+
+    fn persist_resource<T>(&mut self) -> PersistResourceBuilder<'_>
+    where
+        T: Resource + FromWorld + Serialize + for<'de> Deserialize<'de>,
+    {
+        let type_name = strip_module_paths(std::any::type_name::<T>());
+        self.persist_resource_as::<T>(type_name)
+    }
+
+    fn persist_resource_as<T>(&mut self, type_name: impl Into<String>) -> PersistResourceBuilder<'_>
+    where
+        T: Resource + FromWorld + Serialize + for<'de> Deserialize<'de>,
+    {
+        let type_name = type_name.into();
+        let type_path = std::any::type_name::<T>();
+
+        let world = self.world_mut();
+        if !world.contains_resource::<T>() {
+            world.init_resource::<T>();
+        }
+
+        if let Some(mut manager) = world.get_resource_mut::<PersistManager>() {
+            check_storage_key(&manager, &type_name, type_path);
+            manager.set_type_path(type_name.clone(), type_path);
+            let key = manager.key_for(&type_name, type_path);
+            manager.set_type_mode(key, PersistMode::Dev);
+        }
+
+        let apply_name = type_name.clone();
+        let hooks = PersistHooks::new(
+            |resource: &T| PersistData::from_serialize(resource),
+            move |resource: &mut T, data| match data.deserialize() {
+                Ok(value) => *resource = value,
+                Err(e) => error!("Failed to apply persisted {}: {}", apply_name, e),
+            },
+        )
+        .with_reset::<T>();
+        add_hooked_resource::<T>(self, &type_name, hooks)
+    }
+
+    fn persist_external<T: Resource>(
+        &mut self,
+        extract: impl Fn(&T) -> PersistData + Send + Sync + 'static,
+        apply: impl Fn(&mut T, &PersistData) + Send + Sync + 'static,
+    ) -> PersistResourceBuilder<'_> {
+        let type_name = strip_module_paths(std::any::type_name::<T>());
+        let type_path = std::any::type_name::<T>();
+
+        if let Some(mut manager) = self.world_mut().get_resource_mut::<PersistManager>() {
+            check_storage_key(&manager, &type_name, type_path);
+            manager.set_type_path(type_name.clone(), type_path);
+            let key = manager.key_for(&type_name, type_path);
+            manager.set_type_mode(key, PersistMode::Dev);
+        }
+
+        add_hooked_resource::<T>(self, &type_name, PersistHooks::new(extract, apply))
+    }
+}
+
+/// Type-erased extract/apply closures for persisting a resource.
+///
+/// Every registered type gets a set, so the manager can reach resources it
+/// only knows by name. [`PersistAppExt::persist_resource`] and
+/// [`PersistAppExt::persist_external`] build them from serde or
+/// user-supplied closures for types that don't implement [`Persistable`].
+#[derive(Clone)]
+pub struct PersistHooks {
+    extract: Arc<ExtractFn>,
+    apply: Arc<ApplyFn>,
+    apply_to_world: Arc<ApplyToWorldFn>,
+    extract_from_world: Arc<ExtractFromWorldFn>,
+    reset: Option<Arc<ResetFn>>,
+    validate: Option<Arc<ValidateFn>>,
+    before_save: Option<Arc<BeforeSaveFn>>,
+}
+
+type ExtractFn = dyn Fn(&dyn Any) -> Option<PersistData> + Send + Sync;
+type ApplyFn = dyn Fn(&mut dyn Any, &PersistData) + Send + Sync;
+type ApplyToWorldFn = dyn Fn(&mut World, &PersistData) -> bool + Send + Sync;
+type ExtractFromWorldFn = dyn Fn(&World) -> Option<PersistData> + Send + Sync;
+type ResetFn = dyn Fn(&mut World) -> bool + Send + Sync;
+type ValidateFn = dyn Fn(&mut World) -> Result<(), String> + Send + Sync;
+type BeforeSaveFn = dyn Fn(&mut World) + Send + Sync;
+
+impl PersistHooks {
+    /// Wraps typed closures for a resource of type `T`.
+    pub fn new<T: Resource>(
+        extract: impl Fn(&T) -> PersistData + Send + Sync + 'static,
+        apply: impl Fn(&mut T, &PersistData) + Send + Sync + 'static,
+    ) -> Self {
+        let extract = Arc::new(extract);
+        let extract_world = extract.clone();
+        let apply = Arc::new(apply);
+        let apply_any = apply.clone();
+        Self {
+            extract: Arc::new(move |value| value.downcast_ref::<T>().map(|value| extract(value))),
+            apply: Arc::new(move |value, data| {
+                if let Some(value) = value.downcast_mut::<T>() {
+                    apply_any(value, data);
+                }
+            }),
+            apply_to_world: Arc::new(move |world, data| match world.get_resource_mut::<T>() {
+                Some(mut resource) => {
+                    apply(&mut resource, data);
+                    true
+                }
+                None => false,
+            }),
+            extract_from_world: Arc::new(move |world| {
+                world.get_resource::<T>().map(|value| extract_world(value))
+            }),
+            reset: None,
+            validate: None,
+            before_save: None,
+        }
+    }
+
+    /// Lets the hooked resource be reset by name, rebuilding it with [`FromWorld`].
+    pub fn with_reset<T: Resource + FromWorld>(mut self) -> Self {
+        self.reset = Some(Arc::new(|world| {
+            let value = T::from_world(world);
+            match world.get_resource_mut::<T>() {
+                Some(mut resource) => {
+                    *resource.bypass_change_detection() = value;
+                    true
+                }
+                None => false,
+            }
+        }));
+        self
+    }
+
+    /// Hooks that persist a [`Persistable`] resource through its own methods.
+    pub fn for_persistable<T: Persistable>() -> Self {
+        let mut hooks = Self::new(
+            |resource: &T| resource.to_persist_data(),
+            |resource: &mut T, data| resource.load_from_persist_data(data),
+        );
+        hooks.validate = Some(Arc::new(|world| match world.get_resource_mut::<T>() {
+            Some(mut resource) => resource.validate(),
+            None => Ok(()),
+        }));
+        hooks.before_save = Some(Arc::new(|world| {
+            if let Some(mut resource) = world.get_resource_mut::<T>() {
+                resource.bypass_change_detection().before_save();
+            }
+        }));
+        hooks
+    }
+
+    /// Applies persist data to the hooked resource in a world.
+    ///
+    /// Returns `false` if the resource doesn't exist.
+    pub fn apply_to_world(&self, world: &mut World, data: &PersistData) -> bool {
+        (self.apply_to_world)(world, data)
+    }
+
+    /// Restores the hooked resource to its default without triggering change detection.
+    ///
+    /// Returns `false` if the resource doesn't exist or the hooks have no
+    /// default (see [`with_reset`](Self::with_reset)).
+    pub fn reset_in_world(&self, world: &mut World) -> bool {
+        self.reset.as_ref().is_some_and(|reset| reset(world))
+    }
+
+    /// Runs the hooked resource's [`Persistable::validate`] in a world.
+    ///
+    /// Passes if the hooks have no validation or the resource doesn't exist.
+    pub fn validate_in_world(&self, world: &mut World) -> Result<(), String> {
+        self.validate
+            .as_ref()
+            .map_or(Ok(()), |validate| validate(world))
+    }
+
+    /// Runs the hooked resource's [`Persistable::before_save`] in a world.
+    ///
+    /// Does nothing if the hooks have no such step or the resource doesn't exist.
+    pub fn before_save_in_world(&self, world: &mut World) {
+        if let Some(before_save) = &self.before_save {
+            before_save(world);
+        }
+    }
+
+    /// Extracts persist data from the hooked resource in a world.
+    ///
+    /// Returns `None` if the resource doesn't exist.
+    pub fn extract_from_world(&self, world: &World) -> Option<PersistData> {
+        (self.extract_from_world)(world)
+    }
+
+    /// Extracts persist data from a value, if it is of the hooked type.
+    pub fn extract(&self, value: &dyn Any) -> Option<PersistData> {
+        (self.extract)(value)
+    }
+
+    /// Applies persist data to a value, if it is of the hooked type.
+    pub fn apply(&self, value: &mut dyn Any, data: &PersistData) {
+        (self.apply)(value, data)
+    }
+}
+
+/// Adds the load and save systems for a resource persisted through hooks.
+fn add_hooked_resource<'a, T: Resource>(
+    app: &'a mut App,
+    type_name: &str,
+    hooks: PersistHooks,
+) -> PersistResourceBuilder<'a> {
+    let key = match app.world_mut().get_resource_mut::<PersistManager>() {
+        Some(mut manager) => {
+            let key = manager.key_for(type_name, std::any::type_name::<T>());
+            manager.set_type_hooks(key.clone(), hooks);
+            key
+        }
+        None => type_name.to_string(),
+    };
+    app.world_mut()
+        .get_resource_or_init::<PersistLoadState>()
+        .expect::<T>(&key, type_name);
+    app.add_event::<PersistResourceLoaded<T>>();
+
+    let load_name = key.clone();
+    let load = move |mut manager: ResMut<PersistManager>,
+                     resource: Option<ResMut<T>>,
+                     mut load_state: ResMut<PersistLoadState>,
+                     mut loaded: EventWriter<PersistLoaded>,
+                     mut typed_loaded: EventWriter<PersistResourceLoaded<T>>| {
+        // A resource that doesn't exist has nothing to wait for
+        load_state.finish(&load_name);
+        let (Some(mut resource), Some(hooks)) =
+            (resource, manager.get_type_hooks(&load_name).cloned())
+        else {
+            return;
+        };
+        let _span = info_span!("persist_load", type_name = load_name.as_str()).entered();
+        if let Some(data) = manager.read_type_data(&load_name, None) {
+            hooks.apply(&mut *resource, &data);
+            let applied = hooks.extract(&*resource).unwrap_or_default();
+            manager.mark_loaded(&load_name);
+            manager.keep_unknown_fields(&load_name, &data, &applied);
+            manager.remember_loaded_save(&load_name, &data, &applied);
+            loaded.write(PersistLoaded {
+                type_name: manager.get_type_name(&load_name).to_string(),
+            });
+        }
+        typed_loaded.write(PersistResourceLoaded::new());
+    };
+    app.add_systems(PreStartup, load.in_set(PersistSet::Load));
+
+    let save_name = key.clone();
+    let save = move |mut manager: ResMut<PersistManager>, resource: Option<Res<T>>| {
+        let Some(resource) = resource else {
+            return;
+        };
+        if resource.is_changed() && manager.is_auto_save_enabled(&save_name) {
+            manager.mark_dirty_named(&save_name);
+        }
+    };
+    app.add_systems(PostUpdate, save.in_set(PersistSet::Save));
+
+    PersistResourceBuilder {
+        app,
+        type_name: type_name.to_string(),
+        key,
+    }
+}
+
+/// One-line manual persistence for `Persist` resources on a [`World`].
+///
+/// These ignore auto-save settings, which makes them the way to save
+/// resources marked `#[persist(auto_save = false)]`.
+pub trait PersistWorldExt {
+    /// Saves a resource now, wherever its registration says it belongs.
+    fn save_persistent<T: Persistable>(&mut self) -> PersistResult<()>;
+
+    /// Reloads a resource from disk, returning whether any data was found.
+    fn load_persistent<T: Persistable>(&mut self) -> PersistResult<bool>;
+
+    /// Resets a resource to its default value and clears its persisted data.
+    fn reset_persistent<T: Persistable + Default>(&mut self) -> PersistResult<()>;
+}
+
+impl PersistWorldExt for World {
+    fn save_persistent<T: Persistable>(&mut self) -> PersistResult<()> {
+        let type_name = T::type_name();
+        let mut resource = self
+            .get_resource_mut::<T>()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        let resource = resource.bypass_change_detection();
+        resource.before_save();
+        let data = resource.to_persist_data();
+        save_to_world(self, &storage_key_of::<T>(self), data)
+    }
+
+    fn load_persistent<T: Persistable>(&mut self) -> PersistResult<bool> {
+        PersistManager::reload::<T>(self)
+    }
+
+    fn reset_persistent<T: Persistable + Default>(&mut self) -> PersistResult<()> {
+        PersistManager::reset::<T>(self)
+    }
+}
+
+/// Queues [`PersistWorldExt`] operations from systems.
+///
+/// ```ignore
+/// fn on_checkpoint(mut commands: Commands) {
+///     commands.save_persistent::<PlayerProgress>();
+/// }
+/// ```
+///
+/// Failures are logged and reported through [`PersistSaveFailed`].
+pub trait PersistCommandsExt {
+    /// Saves a resource once commands are applied.
+    fn save_persistent<T: Persistable>(&mut self);
+
+    /// Reloads a resource from disk once commands are applied.
+    fn load_persistent<T: Persistable>(&mut self);
+
+    /// Resets a resource to its default value and clears its persisted data
+    /// once commands are applied.
+    fn reset_persistent<T: Persistable + Default>(&mut self);
+
+    /// Saves a resource in the background once commands are applied,
+    /// sending [`PersistProgress`] events.
+    ///
+    /// See [`PersistManager::save_in_background`].
+    fn save_persistent_in_background<T: Persistable>(&mut self);
+
+    /// Reloads a resource in the background once commands are applied,
+    /// sending [`PersistProgress`] events.
+    ///
+    /// See [`PersistManager::load_in_background`].
+    fn load_persistent_in_background<T: Persistable>(&mut self);
+}
+
+impl PersistCommandsExt for Commands<'_, '_> {
+    fn save_persistent<T: Persistable>(&mut self) {
+        self.queue(|world: &mut World| {
+            if let Err(e) = world.save_persistent::<T>() {
+                error!("Failed to save {}: {}", T::type_name(), e);
+            }
+        });
+    }
+
+    fn load_persistent<T: Persistable>(&mut self) {
+        self.queue(|world: &mut World| {
+            if let Err(e) = world.load_persistent::<T>() {
+                error!("Failed to load {}: {}", T::type_name(), e);
+            }
+        });
+    }
+
+    fn reset_persistent<T: Persistable + Default>(&mut self) {
+        self.queue(|world: &mut World| {
+            if let Err(e) = world.reset_persistent::<T>() {
+                error!("Failed to reset {}: {}", T::type_name(), e);
+            }
+        });
+    }
+
+    fn save_persistent_in_background<T: Persistable>(&mut self) {
+        self.queue(|world: &mut World| {
+            if let Err(e) = PersistManager::save_in_background::<T>(world) {
+                error!("Failed to save {}: {}", T::type_name(), e);
+            }
+        });
+    }
+
+    fn load_persistent_in_background<T: Persistable>(&mut self) {
+        self.queue(|world: &mut World| {
+            if let Err(e) = PersistManager::load_in_background::<T>(world) {
+                error!("Failed to load {}: {}", T::type_name(), e);
+            }
+        });
+    }
+}
+
+/// Configures a resource registered with [`PersistAppExt::persist_resource`].
+///
+/// Settings are applied to the [`PersistManager`] immediately, so the
+/// builder can simply be dropped when done.
+pub struct PersistResourceBuilder<'a> {
+    app: &'a mut App,
+    type_name: String,
+    key: String,
+}
+
+impl PersistResourceBuilder<'_> {
+    /// The name the resource is persisted under.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Sets the persistence mode (defaults to `PersistMode::Dev`).
+    pub fn mode(mut self, mode: PersistMode) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_mode(key, mode);
+        self
+    }
+
+    /// Sets whether the resource auto-saves when it changes (defaults to `true`).
+    pub fn auto_save(mut self, enabled: bool) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_auto_save(key, enabled);
+        self
+    }
+
+    /// Pins the resource to its own file.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_file(key, path);
+        self
+    }
+
+    /// Nests the resource's production file in a subdirectory.
+    ///
+    /// See [`PersistManager::set_type_dir`].
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_dir(key, dir);
+        self
+    }
+
+    /// Sets the on-disk format (defaults to RON).
+    pub fn format(mut self, format: PersistFormat) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_format(key, format);
+        self
+    }
+
+    /// Layers the saved data over read-only defaults shipped in `path`.
+    ///
+    /// Only fields differing from the defaults are saved. See
+    /// [`PersistManager::set_type_defaults`].
+    pub fn defaults(mut self, path: impl AsRef<Path>) -> Self {
+        let key = self.key.clone();
+        if let Err(e) = self.manager().load_type_defaults(&key, path) {
+            error!("Failed to read shipped defaults of {}: {}", key, e);
+        }
+        self
+    }
+
+    /// Saves only the fields that differ from the resource's value right now.
+    ///
+    /// Call it while the resource still holds its default. Loading applies
+    /// the saved fields over that value, so fields added in an update pick
+    /// up their defaults.
+    pub fn delta(mut self) -> Self {
+        let key = self.key.clone();
+        match PersistManager::live_data(self.app.world(), &key) {
+            Ok(defaults) => self.manager().set_type_defaults(key, defaults),
+            Err(e) => error!("Failed to capture the defaults of {}: {}", key, e),
+        }
+        self
+    }
+
+    /// Saves the resource into a shared group file.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_group(key, group);
+        self
+    }
+
+    /// Sets what happens when the resource's file is from a newer version of the game.
+    pub fn downgrade_policy(mut self, policy: DowngradePolicy) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_downgrade_policy(key, policy);
+        self
+    }
+
+    /// Limits the serialized size of this resource's data, in bytes.
+    ///
+    /// Overrides [`PersistPlugin::with_size_quota`] for this resource.
+    pub fn size_quota(mut self, max_bytes: u64) -> Self {
+        let key = self.key.clone();
+        self.manager().set_type_size_quota(key, max_bytes);
+        self
+    }
+
+    fn manager(&mut self) -> Mut<'_, PersistManager> {
+        self.app
+            .world_mut()
+            .get_resource_mut::<PersistManager>()
+            .expect("PersistPlugin must be added before persisting resources")
+    }
+}
+
+/// Generic system to mark a resource for saving when it changes
+///
+/// The actual write happens in [`PersistSet::Flush`], so several resources
+/// changed in the same frame only rewrite a shared file once.
+pub fn persist_system<T: Persistable>(mut manager: ResMut<PersistManager>, resource: Res<T>) {
+    // Save on any change, even if just added
+    // The load system runs in PreStartup, so if we have user changes in the first frame,
+    // we should save them even though the resource is still marked as "added"
+    if !resource.is_changed() {
+        return;
+    }
+    let key = manager.storage_key_of::<T>();
+    if manager.is_auto_save_enabled(&key) {
+        manager.mark_dirty_named(&key);
+    }
+}
+
+/// Sends the files from newer versions found while loading as events.
+fn send_version_events(manager: Res<PersistManager>, mut events: EventWriter<PersistVersionEvent>) {
+    let found = std::mem::take(
+        &mut *manager
+            .version_events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()),
+    );
+    events.write_batch(found);
+}
+
+/// Sends the saves refused for their size as events.
+fn send_quota_events(manager: Res<PersistManager>, mut events: EventWriter<PersistQuotaExceeded>) {
+    let refused = std::mem::take(
+        &mut *manager
+            .quota_events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()),
+    );
+    events.write_batch(refused);
+}
+
+/// Overrides loaded fields from environment variables.
+fn apply_env_overrides(world: &mut World) {
+    PersistManager::apply_env_overrides(world);
+}
+
+/// Saves the types asked for by [`RequestSave`] events.
+fn handle_save_requests(world: &mut World, mut cursor: Local<EventCursor<RequestSave>>) {
+    let Some(events) = world.get_resource::<Events<RequestSave>>() else {
+        return;
+    };
+    let requests: Vec<RequestSave> = cursor.read(events).cloned().collect();
+    if requests.is_empty() {
+        return;
+    }
+
+    let manager = world.resource::<PersistManager>();
+    let mut requested = BTreeSet::new();
+    for request in requests {
+        let type_names: Vec<String> = match request.type_names {
+            Some(type_names) => type_names,
+            None => manager.hooks.keys().cloned().collect(),
+        };
+        for type_name in type_names {
+            if manager.get_type_hooks(&type_name).is_some() {
+                requested.insert(manager.get_storage_key(&type_name));
+            } else {
+                warn!("Save requested for unregistered type {}", type_name);
+            }
+        }
+    }
+    flush_types(world, requested);
+}
+
+/// Retries failed saves whose backoff has passed, unless saving is paused.
+fn retry_failed_saves(world: &mut World) {
+    let Some(mut manager) = world.get_resource_mut::<PersistManager>() else {
+        return;
+    };
+    if manager.is_paused() {
+        return;
+    }
+    let now = Instant::now();
+    let mut due = BTreeSet::new();
+    for (type_name, retry) in &mut manager.retries {
+        if retry.due.is_some_and(|at| at <= now) {
+            retry.due = None;
+            due.insert(type_name.clone());
+        }
+    }
+    if due.is_empty() {
+        return;
+    }
+
+    flush_types(world, due);
+    // Saved, rescheduled or given up; anything left couldn't be extracted
+    world
+        .resource_mut::<PersistManager>()
+        .retries
+        .retain(|_, retry| retry.due.is_some());
+}
+
+/// Flushes just the given types, leaving other dirty types for their usual flush.
+fn flush_types(world: &mut World, type_names: BTreeSet<String>) {
+    let mut manager = world.resource_mut::<PersistManager>();
+    let pending: BTreeSet<String> = std::mem::take(&mut manager.dirty)
+        .difference(&type_names)
+        .cloned()
+        .collect();
+    manager.dirty = type_names;
+    // Failures are already logged and sent as events
+    PersistManager::flush(world).ok();
+    if let Some(mut manager) = world.get_resource_mut::<PersistManager>() {
+        manager.dirty.extend(pending);
+    }
 }
 
-impl PersistPlugin {
-    /// Creates a new PersistPlugin with organization and app name.
-    ///
-    /// These are used for:
-    /// - Platform-specific paths in production (e.g., ~/Library/Application Support/MyCompany/MyGame/)
-    /// - Dev file naming (e.g., mygame_dev.ron)
-    pub fn new(organization: impl Into<String>, app_name: impl Into<String>) -> Self {
-        Self {
-            organization: organization.into(),
-            app_name: app_name.into(),
-            auto_save: true,
-            #[cfg(feature = "secure")]
-            secret: None,
+/// Flushes the types marked dirty this frame, unless flushing is manual or paused.
+fn flush_dirty(world: &mut World) {
+    let Some(manager) = world.get_resource::<PersistManager>() else {
+        return;
+    };
+    if manager.is_manual_flush() || manager.is_paused() {
+        return;
+    }
+    if let Some(budget) = manager.frame_budget() {
+        flush_within_budget(world, budget);
+        return;
+    }
+    match manager.dirty_types_due() {
+        // Types whose file was written too recently wait for a later frame
+        Some(due) => flush_types(world, due),
+        // Failures are already logged and sent as events
+        None => {
+            PersistManager::flush(world).ok();
         }
     }
+}
 
-    /// Sets whether auto-save is enabled globally.
-    pub fn with_auto_save(mut self, enabled: bool) -> Self {
-        self.auto_save = enabled;
-        self
+/// Flushes as many dirty types as are estimated to fit in `budget`,
+/// leaving the rest for later frames.
+fn flush_within_budget(world: &mut World, budget: Duration) {
+    let types = world
+        .resource_mut::<PersistManager>()
+        .budgeted_types(budget);
+    if !types.is_empty() {
+        flush_types(world, types);
     }
+}
 
-    /// Sets the secret for encrypting secure persistence
-    #[cfg(feature = "secure")]
-    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
-        self.secret = Some(secret.into());
-        self
-    }
+/// Span around an auto-save; [`report_save`] fills in where it went.
+fn save_span(type_name: &str) -> Span {
+    info_span!(
+        "persist_save",
+        type_name,
+        path = field::Empty,
+        bytes = field::Empty
+    )
 }
 
-impl Plugin for PersistPlugin {
-    fn build(&self, app: &mut App) {
-        let mut manager = PersistManager::new(self.organization.clone(), self.app_name.clone());
-        manager.auto_save = self.auto_save;
+/// A registered type as reported by [`PersistManager::registered_types`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PersistTypeInfo<'a> {
+    /// Name the type is registered under
+    pub type_name: &'a str,
+    /// Key the type's data and configuration are kept under
+    pub storage_key: &'a str,
+    /// Persistence mode
+    pub mode: PersistMode,
+    /// On-disk format
+    pub format: PersistFormat,
+    /// Whether changes are currently auto-saved
+    pub auto_save: bool,
+    /// File the type's data resolves to, if it's stored on disk
+    pub path: Option<PathBuf>,
+    /// When this manager last saved the type
+    pub last_saved: Option<DateTime<Utc>>,
+    /// When this manager last loaded the type into its resource
+    pub last_loaded: Option<DateTime<Utc>>,
+}
 
-        #[cfg(feature = "secure")]
-        if let Some(secret) = &self.secret {
-            manager = manager.with_secret(secret.clone());
-        }
+/// A file of persisted data as reported by [`PersistManager::list_files`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PersistFileInfo {
+    /// Where the file is on this machine
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+    /// When the file was last modified, if the platform reports it
+    pub modified: Option<DateTime<Utc>>,
+    /// Format the data is written in, beneath any encryption or scrambling
+    pub format: PersistFormat,
+    /// Registered types that save to the file, sorted
+    pub type_names: Vec<String>,
+}
 
-        app.insert_resource(manager);
+/// Save activity reported by [`PersistManager::stats`].
+///
+/// Counts every write made through the manager, whether from auto-save or
+/// a manual save. [`PersistDiagnosticsPlugin`] publishes these as Bevy
+/// diagnostics.
+#[derive(Debug, Default, Clone)]
+pub struct PersistStats {
+    /// Number of saves
+    pub saves: u64,
+    /// Total size of the files written, in bytes
+    pub bytes_written: u64,
+    /// How long the most recent save took
+    pub last_save_duration: Option<Duration>,
+    /// Number of saves of each type
+    pub saves_per_type: HashMap<String, u64>,
+    /// Number of auto-saves skipped because the content was unchanged
+    pub skipped_saves: u64,
+}
 
-        // Auto-register all Persist types that have been defined
-        for registration in inventory::iter::<PersistRegistration> {
-            debug!(
-                "Auto-registering persist type: {} (mode: {}, embed_file: {:?})",
-                registration.type_name, registration.persist_mode, registration.embed_file
-            );
+/// Parses data shipped with the game in a type's format.
+///
+/// Falls back to sniffing RON vs JSON for files whose extension doesn't
+/// match the format.
+fn parse_shipped(content: &str, format: PersistFormat) -> PersistResult<PersistFile> {
+    let sniffed = if content.contains('(') {
+        PersistFormat::Ron
+    } else {
+        PersistFormat::Json
+    };
+    PersistFile::parse_as(content, format).or_else(|_| PersistFile::parse_as(content, sniffed))
+}
 
-            // Call the registration function first to set up the resource and systems
-            (registration.register_fn)(app);
+/// Group of the one save file used with [`FileLayout::Single`]
+const SINGLE_FILE_GROUP: &str = "savegame";
 
-            // Then store the mode for this type
-            if let Some(mut manager) = app.world_mut().get_resource_mut::<PersistManager>() {
-                let mode = match registration.persist_mode {
-                    "embed" => PersistMode::Embed,
-                    "dynamic" => PersistMode::Dynamic,
-                    "secure" => PersistMode::Secure,
-                    _ => PersistMode::Dev,
-                };
-                manager.set_type_mode(registration.type_name.to_string(), mode);
-                
-                // Store embed file path if specified
-                if let Some(embed_file) = registration.embed_file {
-                    manager.set_type_embed_file(registration.type_name.to_string(), embed_file.to_string());
-                }
-            }
-        }
-    }
+/// How long a file kept in memory waits before writing it is tried again
+const MEMORY_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Prefix of field values encrypted with the secret
+#[cfg(feature = "prod")]
+const SEALED_ENCRYPTED: &str = "enc:";
+
+/// Prefix of field values only base64 encoded, without a secret
+#[cfg(feature = "prod")]
+const SEALED_ENCODED: &str = "b64:";
+
+/// Parses an RFC 3339 timestamp as written into files.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
 }
 
-/// Register a Persist type with the system.
+/// Whether semver `version` is newer than `current`.
 ///
-/// This is called automatically by the derive macro and typically
-/// doesn't need to be called manually.
-pub fn register_persist_type<T: Resource + Persistable + Default>(app: &mut App, auto_save: bool) {
-    let type_name = T::type_name();
+/// Versions that don't parse are never newer, so odd files still load.
+fn is_newer_version(version: &str, current: &str) -> bool {
+    match (parse_version(version), parse_version(current)) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
+}
 
-    let world = app.world_mut();
+/// Parses `major.minor.patch`, ranking pre-releases below their release.
+fn parse_version(version: &str) -> Option<(u64, u64, u64, bool)> {
+    let version = version.trim();
+    let version = version
+        .split_once('+')
+        .map_or(version, |(version, _)| version);
+    let (release, pre) = match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    };
+    let mut parts = release.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let mut next = || parts.next().map_or(Some(0), |part| part.parse().ok());
+    let (minor, patch) = (next()?, next()?);
+    Some((major, minor, patch, pre.is_none()))
+}
 
-    // Ensure resource exists
-    if !world.contains_resource::<T>() {
-        world.init_resource::<T>();
+/// Where a file from a newer version is backed up, e.g. `save.ron.1.3.0.bak`.
+fn downgrade_backup_path(path: &Path, version: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is the same in every build.
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Hashes data independently of how each field is stored.
+fn content_hash(data: &PersistData) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for (key, value) in &data.values {
+        key.hash(&mut hasher);
+        value.canonical().hash(&mut hasher);
     }
+    hasher.finish()
+}
 
-    // Set auto-save preference for this type
-    if let Some(mut manager) = world.get_resource_mut::<PersistManager>() {
-        manager.set_type_auto_save(type_name.to_string(), auto_save);
+/// Like [`content_hash`], but with 64-bit FNV-1a so it's the same in every
+/// build and can be kept on disk.
+fn stable_hash(data: &PersistData) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut write = |bytes: &[u8]| {
+        // Each part ends with a zero byte, so moving text between parts changes the hash
+        for &byte in bytes.iter().chain(&[0]) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    for (key, value) in &data.values {
+        write(key.as_bytes());
+        write(value.canonical().as_bytes());
     }
+    hash
+}
 
-    // Add systems for this type
-    // Load persisted data first in PreStartup
-    app.add_systems(PreStartup, load_persisted::<T>);
-    // Run persist_system in PostUpdate to ensure it runs after all user systems
-    app.add_systems(PostUpdate, persist_system::<T>);
+/// A place the manager keeps persisted data, named independently of the
+/// machine so it can be found again elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum DataLocation {
+    /// The shared dev file
+    DevFile,
+    /// The file a type is pinned to
+    TypeFile(String),
+    /// The file of the group a type belongs to
+    GroupFile(String),
+    /// A type's own file in the platform directories, used in production
+    TypeResource(String),
 }
 
-/// Generic system to persist a resource when it changes
-pub fn persist_system<T: Persistable>(mut manager: ResMut<PersistManager>, resource: Res<T>) {
-    let type_name = T::type_name();
+/// What [`PersistManager::delete_all_data`] removed.
+#[derive(Debug, Default, Clone)]
+pub struct PersistDeletionReport {
+    /// Files and emptied directories that were deleted
+    pub deleted: Vec<PathBuf>,
+    /// Files that couldn't be deleted, with the reason
+    pub failed: Vec<(PathBuf, PersistError)>,
+}
 
-    // Save on any change, even if just added
-    // The load system runs in PreStartup, so if we have user changes in the first frame,
-    // we should save them even though the resource is still marked as "added"
-    if resource.is_changed() {
-        #[allow(unused_variables)] // Used in feature-gated code
-        let mode = T::persist_mode();
+impl PersistDeletionReport {
+    /// Whether every file was deleted.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
 
-        // Don't save embedded resources in production
-        #[cfg(feature = "prod")]
-        if mode == PersistMode::Embed {
-            return;
+/// Path `resolver` gives a type or group, with `extension` added if it has none.
+fn resolve_with(
+    resolver: &dyn PathResolver,
+    name: &str,
+    mode: PersistMode,
+    extension: &str,
+) -> PathBuf {
+    with_default_extension(resolver.resolve(name, mode), extension)
+}
+
+/// `path` with `extension` added if it has none.
+fn with_default_extension(mut path: PathBuf, extension: &str) -> PathBuf {
+    if path.extension().is_none() {
+        path.set_extension(extension);
+    }
+    path
+}
+
+/// Writes a type's data outside the save systems, emitting the matching event.
+fn save_to_world(world: &mut World, type_name: &str, data: PersistData) -> PersistResult<()> {
+    let mut manager = world
+        .get_resource_mut::<PersistManager>()
+        .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+
+    let result = manager.write_type_data(type_name, data);
+    let type_name = manager.get_type_name(type_name).to_string();
+    match result {
+        Ok(Some(path)) => {
+            let bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+            world.send_event(PersistSaved {
+                type_name: type_name.clone(),
+                path,
+                bytes,
+            });
+            Ok(())
         }
+        Ok(None) => Ok(()),
+        Err(e) => {
+            world.send_event(PersistSaveFailed {
+                type_name,
+                error: e.clone(),
+            });
+            Err(e)
+        }
+    }
+}
 
-        if manager.is_auto_save_enabled(type_name) {
-            let data = resource.to_persist_data();
+/// Error for a file that couldn't be written.
+fn write_error(e: std::io::Error) -> PersistError {
+    PersistError::io("Failed to write file", e)
+}
 
-            // In production, save to mode-specific paths
-            #[cfg(feature = "prod")]
-            {
-                if mode == PersistMode::Dynamic || mode == PersistMode::Secure {
-                    let path = manager.get_resource_path(type_name, mode);
-                    if !path.as_os_str().is_empty() {
-                        let mut file = PersistFile::new();
-                        file.set_type_data(type_name.to_string(), data);
-
-                        // For secure mode, we could add encryption here
-                        #[cfg(feature = "secure")]
-                        if mode == PersistMode::Secure {
-                            // TODO: Add encryption/obfuscation
-                        }
+fn read_error(path: &Path, e: std::io::Error) -> PersistError {
+    PersistError::io("Failed to read file", e).at(path)
+}
 
-                        if let Err(e) = file.save_to_file(&path) {
-                            error!("Failed to save {} to {:?}: {}", type_name, path, e);
-                        } else {
-                            debug!("Saved {} to {:?}", type_name, path);
-                        }
-                        return;
-                    }
-                }
-            }
+/// Creates the directory `path` goes in, if it doesn't exist.
+fn create_parent_dir(path: &Path) -> PersistResult<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent)
+            .map_err(|e| PersistError::io("Failed to create directory", e).at(parent)),
+        None => Ok(()),
+    }
+}
 
-            // Default behavior for dev mode
-            debug!("{}: Attempting to save to dev file", type_name);
-            
-            // In dev mode, if this resource will be embedded in prod, also save it to a separate file
-            #[cfg(not(feature = "prod"))]
-            if mode == PersistMode::Embed {
-                // For embed resources in dev mode, save to assets/persist/ directory
-                // This follows Bevy conventions and makes files easy to find
-                
-                // Use environment variable if set, otherwise use default relative path
-                // Users can set BEVY_ASSET_ROOT or CARGO_MANIFEST_DIR for custom paths
-                let base_path = std::env::var("BEVY_ASSET_ROOT")
-                    .or_else(|_| std::env::var("CARGO_MANIFEST_DIR"))
-                    .map(PathBuf::from)
-                    .unwrap_or_else(|_| PathBuf::from("."));
-                
-                let embed_file_name = format!("{}.ron", type_name.to_lowercase().replace("::", "_"));
-                let embed_path = base_path.join("assets").join("persist").join(embed_file_name);
-                
-                // Create the persist directory if it doesn't exist
-                if let Some(parent) = embed_path.parent() {
-                    if let Err(e) = fs::create_dir_all(parent) {
-                        error!("Failed to create persist directory {:?}: {}", parent, e);
-                    }
-                }
-                
-                let mut embed_file = PersistFile::new();
-                embed_file.set_type_data(type_name.to_string(), data.clone());
-                
-                if let Err(e) = embed_file.save_to_file(&embed_path) {
-                    error!("Failed to save {} to embed file {:?}: {}", type_name, embed_path, e);
-                } else {
-                    info!("Saved {} to embed file {:?} for production embedding", type_name, embed_path);
-                }
-            }
-            
-            // Also save to the main dev file for hot-reloading
-            manager
-                .get_persist_file_mut()
-                .set_type_data(type_name.to_string(), data);
+/// Sibling of `path` that a save is written to before it replaces `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
 
-            if let Err(e) = manager.save() {
-                error!("Failed to auto-save {}: {}", type_name, e);
-            } else {
-                info!("Auto-saved {} to dev file", type_name);
+/// Whether a save of `path` could be written now, tried with an empty
+/// temp file that's removed again.
+fn can_write(path: &Path) -> bool {
+    let probe = temp_path(path);
+    let writable = create_parent_dir(path).is_ok() && fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    writable
+}
+
+/// Whether `error` means a file can't be written at all, because permission
+/// was denied or its filesystem is read-only, rather than that one write
+/// went wrong.
+fn is_unwritable(error: &PersistError) -> bool {
+    // EROFS, the same on Linux, macOS and the BSDs
+    #[cfg(unix)]
+    const READ_ONLY_FILESYSTEM: i32 = 30;
+
+    match error {
+        PersistError::IoError { kind, source, .. } => {
+            if *kind == std::io::ErrorKind::PermissionDenied {
+                return true;
+            }
+            #[cfg(unix)]
+            if source.as_ref().and_then(|e| e.raw_os_error()) == Some(READ_ONLY_FILESYSTEM) {
+                return true;
             }
+            #[cfg(not(unix))]
+            let _ = source;
+            false
         }
+        #[cfg(feature = "preflight")]
+        PersistError::PermissionDenied { .. } => true,
+        _ => false,
     }
 }
 
-/// Load persisted values on startup
-pub fn load_persisted<T: Persistable>(manager: Res<PersistManager>, mut resource: ResMut<T>) {
-    let type_name = T::type_name();
-    #[allow(unused_variables)] // Used in feature-gated code
-    let mode = T::persist_mode();
+/// Deletes a file, treating one that's already gone as success.
+fn remove_file_if_exists(path: &Path) -> PersistResult<()> {
+    match fs::remove_file(path) {
+        Ok(()) => {
+            debug!("Removed {:?}", path);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(PersistError::io("Failed to remove file", e).at(path)),
+    }
+}
 
-    // Try to load embedded data first in production
-    #[cfg(feature = "prod")]
-    if mode == PersistMode::Embed {
-        if let Some(embedded_str) = T::embedded_data() {
-            // Parse the embedded data
-            if embedded_str.ends_with(".ron") || embedded_str.contains("(") {
-                // Looks like RON format
-                if let Ok(file) = ron::from_str::<PersistFile>(embedded_str) {
-                    if let Some(data) = file.get_type_data(type_name) {
-                        resource.load_from_persist_data(data);
-                        info!("Loaded embedded data for {}", type_name);
-                        return;
-                    }
-                }
-            } else {
-                // Try JSON format
-                if let Ok(file) = serde_json::from_str::<PersistFile>(embedded_str) {
-                    if let Some(data) = file.get_type_data(type_name) {
-                        resource.load_from_persist_data(data);
-                        info!("Loaded embedded data for {}", type_name);
-                        return;
-                    }
-                }
-            }
+/// Validates a resource just loaded through its hooks, applying `policy`.
+pub(crate) fn validate_loaded(
+    world: &mut World,
+    type_name: &str,
+    hooks: &PersistHooks,
+    policy: ValidationPolicy,
+) {
+    if let Err(error) = hooks.validate_in_world(world) {
+        if policy == ValidationPolicy::Reset {
+            hooks.reset_in_world(world);
         }
+        let type_name = display_name(world, type_name);
+        world.send_event(validation_failed(&type_name, error, policy));
     }
+}
 
-    // Load from disk for dynamic/secure modes in production
-    #[cfg(feature = "prod")]
-    if mode == PersistMode::Dynamic || mode == PersistMode::Secure {
-        let path = manager.get_resource_path(type_name, mode);
-        if !path.as_os_str().is_empty() && path.exists() {
-            if let Ok(file) = PersistFile::load_from_file(&path) {
-                if let Some(data) = file.get_type_data(type_name) {
-                    resource.load_from_persist_data(data);
-                    info!(
-                        "Loaded {} data for {} from {:?}",
-                        if mode == PersistMode::Secure {
-                            "secure"
-                        } else {
-                            "dynamic"
-                        },
-                        type_name,
-                        path
-                    );
-                    return;
+/// Storage key of `T`, or its type name before a manager is added.
+fn storage_key_of<T: Persistable>(world: &World) -> String {
+    world.get_resource::<PersistManager>().map_or_else(
+        || T::type_name().to_string(),
+        PersistManager::storage_key_of::<T>,
+    )
+}
+
+/// Name events and logs show for a type, given its storage key or name.
+pub(crate) fn display_name(world: &World, type_name: &str) -> String {
+    world
+        .get_resource::<PersistManager>()
+        .map_or(type_name, |manager| manager.get_type_name(type_name))
+        .to_string()
+}
+
+/// Logs invalid loaded data and builds the matching event.
+fn validation_failed(
+    type_name: &str,
+    error: String,
+    policy: ValidationPolicy,
+) -> PersistValidationFailed {
+    warn!("Loaded {} is invalid ({:?}): {}", type_name, policy, error);
+    PersistValidationFailed {
+        type_name: type_name.to_string(),
+        error,
+        policy,
+    }
+}
+
+/// Logs the outcome of an auto-save and emits the matching event.
+fn report_save(world: &mut World, type_name: &str, result: PersistResult<Option<PathBuf>>) {
+    if result.is_ok() {
+        world
+            .resource_mut::<PersistManager>()
+            .retries
+            .remove(type_name);
+    }
+    let name = display_name(world, type_name);
+    match result {
+        Ok(Some(path)) => {
+            let bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+            let span = Span::current();
+            span.record("path", field::display(path.display()));
+            span.record("bytes", bytes);
+            world.send_event(PersistSaved {
+                type_name: name,
+                path,
+                bytes,
+            });
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!("Failed to auto-save {}: {}", name, e);
+            world
+                .resource::<PersistManager>()
+                .report_error(type_name, &e);
+            world.send_event(PersistSaveFailed {
+                type_name: name.clone(),
+                error: e.clone(),
+            });
+            let exhausted = world
+                .resource_mut::<PersistManager>()
+                .schedule_retry(type_name);
+            if let Some(attempts) = exhausted {
+                error!("Giving up saving {} after {} attempts", name, attempts);
+                world.send_event(PersistRetriesExhausted {
+                    type_name: name.clone(),
+                    attempts,
+                    error: e.clone(),
+                });
+                if let Some(path) = world
+                    .resource_mut::<PersistManager>()
+                    .fall_back_to_memory(type_name, &e)
+                {
+                    world.send_event(PersistFellBackToMemory {
+                        type_name: name,
+                        path,
+                        error: e,
+                    });
                 }
             }
         }
     }
+}
 
-    // In dev mode, check if this is an embed resource and try to load from its file
-    #[cfg(not(feature = "prod"))]
-    if mode == PersistMode::Embed {
-        // Use environment variable if set, otherwise use default relative path
-        let base_path = std::env::var("BEVY_ASSET_ROOT")
-            .or_else(|_| std::env::var("CARGO_MANIFEST_DIR"))
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("."));
-        
-        let embed_file_name = format!("{}.ron", type_name.to_lowercase().replace("::", "_"));
-        let embed_path = base_path.join("assets").join("persist").join(embed_file_name);
-        
-        if embed_path.exists() {
-            // Load from the embed file if it exists
-            if let Ok(file) = PersistFile::load_from_file(&embed_path) {
-                if let Some(data) = file.get_type_data(type_name) {
-                    resource.load_from_persist_data(data);
-                    info!("Loaded {} from embed file: {:?}", type_name, embed_path);
-                    return;
-                }
+/// Load persisted values on startup
+pub fn load_persisted<T: Persistable + Default>(
+    mut manager: ResMut<PersistManager>,
+    mut resource: ResMut<T>,
+    mut loaded: EventWriter<PersistLoaded>,
+    mut typed_loaded: EventWriter<PersistResourceLoaded<T>>,
+    mut invalid: EventWriter<PersistValidationFailed>,
+    mut load_state: ResMut<PersistLoadState>,
+) {
+    let _span = info_span!("persist_load", type_name = T::type_name()).entered();
+    let key = manager.storage_key_of::<T>();
+    let embedded = manager.embedded_text::<T>();
+    if let Some(data) = manager.read_type_data(&key, embedded.as_deref()) {
+        resource.load_from_persist_data(&data);
+        if let Err(error) = resource.validate() {
+            let policy = manager.validation_policy();
+            if policy == ValidationPolicy::Reset {
+                *resource = T::default();
             }
-        } else {
-            debug!("Embed file {:?} does not exist, will be created on first save", embed_path);
+            invalid.write(validation_failed(T::type_name(), error, policy));
         }
+        let applied = resource.to_persist_data();
+        manager.mark_loaded(&key);
+        manager.keep_unknown_fields(&key, &data, &applied);
+        manager.remember_loaded_save(&key, &data, &applied);
+        loaded.write(PersistLoaded {
+            type_name: T::type_name().to_string(),
+        });
     }
-    
-    // Default behavior - load from main persist file (dev mode)
-    if let Some(data) = manager.get_persist_file().get_type_data(type_name) {
-        resource.load_from_persist_data(data);
-        info!("Loaded persisted data for {}", type_name);
-    }
+    load_state.finish(&key);
+    typed_loaded.write(PersistResourceLoaded::new());
 }
 
 #[cfg(test)]
@@ -1043,43 +8330,425 @@ mod tests {
             value: i32,
         }
 
-        let mut data = PersistData::new();
-        let test_struct = TestStruct {
-            name: "test".to_string(),
-            value: 100,
+        let mut data = PersistData::new();
+        let test_struct = TestStruct {
+            name: "test".to_string(),
+            value: 100,
+        };
+
+        data.insert("struct", &test_struct);
+
+        let retrieved = data.get::<TestStruct>("struct");
+        assert_eq!(retrieved, Some(test_struct));
+    }
+
+    #[test]
+    fn test_persist_data_json_view() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Quality {
+            Low,
+            High,
+        }
+
+        let mut data = PersistData::new();
+        data.insert("quality", Quality::High);
+        data.insert("scale", 1.5f32);
+        assert_eq!(data.to_json()["quality"], serde_json::json!("High"));
+
+        // A patch from a tool replaces just the fields it names
+        data.merge(PersistData::from_json([(
+            "quality".to_string(),
+            serde_json::json!("Low"),
+        )]));
+        assert_eq!(data.get::<Quality>("quality"), Some(Quality::Low));
+        assert_eq!(data.get::<f32>("scale"), Some(1.5));
+        assert_eq!(data.len(), 2);
+    }
+
+    #[test]
+    fn test_persist_file_new() {
+        let file = PersistFile::new();
+
+        assert!(file.type_data.is_empty());
+        assert!(!file.last_saved.is_empty());
+        assert_eq!(file.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_persist_file_streams_into_writer() {
+        /// Accepts a few bytes, then fails like a full disk.
+        struct FullDisk(usize);
+
+        impl Write for FullDisk {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(std::io::Error::other("no space left"));
+                }
+                let written = buf.len().min(self.0);
+                self.0 -= written;
+                Ok(written)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut file = PersistFile::new();
+        let mut data = PersistData::new();
+        data.insert("name", "streamed");
+        file.set_type_data("TestType".to_string(), data);
+
+        for format in [PersistFormat::Ron, PersistFormat::Json] {
+            let style = PersistStyle::default();
+            let mut buffer = Vec::new();
+            file.write_styled(&mut buffer, format, &style).unwrap();
+            let parsed =
+                PersistFile::parse_as(&String::from_utf8(buffer).unwrap(), format).unwrap();
+            assert_eq!(
+                parsed
+                    .get_type_data("TestType")
+                    .unwrap()
+                    .get::<String>("name"),
+                Some("streamed".to_string())
+            );
+
+            let result = file.write_styled(FullDisk(8), format, &style);
+            assert!(
+                matches!(result, Err(PersistError::IoError { .. })),
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_persist_file_saves_to_writer_and_loads_from_reader() {
+        let mut file = PersistFile::new();
+        let mut data = PersistData::new();
+        data.insert("volume", 0.25);
+        file.set_type_data("TestType".to_string(), data);
+
+        for format in [PersistFormat::Ron, PersistFormat::Json] {
+            let mut buffer = Vec::new();
+            file.save_to_writer(&mut buffer, format).unwrap();
+            let loaded =
+                PersistFile::load_from_reader(&mut std::io::Cursor::new(buffer), format).unwrap();
+            assert_eq!(
+                loaded
+                    .get_type_data("TestType")
+                    .unwrap()
+                    .get::<f64>("volume"),
+                Some(0.25)
+            );
+            assert_eq!(loaded.last_saved, file.last_saved);
+        }
+
+        let result =
+            PersistFile::load_from_reader(&mut "(".as_bytes(), PersistFormat::Ron).map(|_| ());
+        assert!(matches!(
+            result,
+            Err(PersistError::SerializationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_persist_file_convert() {
+        let temp_dir = TempDir::new().unwrap();
+        let ron_path = temp_dir.path().join("settings.ron");
+        let json_path = temp_dir.path().join("settings.json");
+        fs::write(
+            &ron_path,
+            r#"{"Settings": (values: {"mode": Windowed, "volume": 0.5})}"#,
+        )
+        .unwrap();
+
+        PersistFile::convert(&ron_path, &json_path).unwrap();
+        let content = fs::read_to_string(&json_path).unwrap();
+        assert!(content.contains("\"volume\": 0.5"), "{}", content);
+        let data = PersistFile::load_from_file(&json_path)
+            .unwrap()
+            .get_type_data("Settings")
+            .unwrap()
+            .clone();
+        assert_eq!(data.get::<String>("mode"), Some("Windowed".to_string()));
+
+        // And back again
+        let back = temp_dir.path().join("back.ron");
+        PersistFile::convert(&json_path, &back).unwrap();
+        let data = PersistFile::load_from_file(&back)
+            .unwrap()
+            .get_type_data("Settings")
+            .unwrap()
+            .clone();
+        assert_eq!(data.get::<f32>("volume"), Some(0.5));
+
+        assert!(PersistFile::convert(temp_dir.path().join("missing.ron"), &back).is_err());
+    }
+
+    #[test]
+    fn test_ron_fields_keep_variants_as_json() {
+        let ron = r#"{"Settings": (values: {
+            "mode": Windowed,
+            "size": Sized(2),
+            "custom": Custom(w: 3, h: 4),
+            "tiers": [Low, High],
+            "fallback": Some(Windowed),
+            "none": None,
+            "pair": (1, -2.5),
+            "name": r"raw",
+            "keys": {Up: 'w', Down: 's'},
+        })}"#;
+        let file = PersistFile::parse_as(ron, PersistFormat::Ron).unwrap();
+        let json = serde_json::Value::Object(file.get_type_data("Settings").unwrap().to_json());
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "mode": "Windowed",
+                "size": {"Sized": 2},
+                "custom": {"Custom": {"w": 3, "h": 4}},
+                "tiers": ["Low", "High"],
+                "fallback": "Windowed",
+                "none": null,
+                "pair": [1, -2.5],
+                "name": "raw",
+                "keys": {"Up": "w", "Down": "s"},
+            })
+        );
+    }
+
+    #[test]
+    fn test_persist_data_merge3() {
+        let data = |fields: &[(&str, i32)]| {
+            let mut data = PersistData::new();
+            for (key, value) in fields {
+                data.insert(*key, value);
+            }
+            data
+        };
+        let base = data(&[("volume", 5), ("fov", 90), ("gamma", 1), ("hints", 1)]);
+        let local = data(&[("volume", 7), ("fov", 90), ("gamma", 2)]);
+        let remote = data(&[("volume", 5), ("fov", 100), ("gamma", 3), ("hints", 1)]);
+
+        let merged = PersistData::merge3(&base, &local, &remote);
+        assert_eq!(merged.conflicts, ["gamma"]);
+        assert!(!merged.is_clean());
+        assert_eq!(merged.data.get::<i32>("volume"), Some(7));
+        assert_eq!(merged.data.get::<i32>("fov"), Some(100));
+        assert_eq!(merged.data.get::<i32>("gamma"), Some(2));
+        assert!(!merged.data.contains_key("hints"));
+
+        // Values read from a file compare equal to captured ones
+        let file =
+            PersistFile::parse_as(r#"{"T": (values: {"volume": 5})}"#, PersistFormat::Ron).unwrap();
+        let read = file.get_type_data("T").unwrap();
+        let merged = PersistData::merge3(&data(&[("volume", 5)]), read, &data(&[("volume", 6)]));
+        assert!(merged.is_clean());
+        assert_eq!(merged.data.get::<i32>("volume"), Some(6));
+    }
+
+    #[test]
+    fn test_persist_file_type_data() {
+        let mut file = PersistFile::new();
+        let mut data = PersistData::new();
+        data.insert("test_key", "test_value");
+
+        file.set_type_data("TestType".to_string(), data.clone());
+
+        let retrieved = file.get_type_data("TestType");
+        assert!(retrieved.is_some());
+        assert_eq!(
+            retrieved.unwrap().get::<String>("test_key"),
+            Some("test_value".to_string())
+        );
+
+        assert!(file.get_type_data("NonExistent").is_none());
+    }
+
+    #[test]
+    fn test_persist_file_entries_and_typed_access() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Audio {
+            volume: f32,
+            muted: bool,
+        }
+
+        let mut file = PersistFile::new();
+        let mut audio = PersistData::new();
+        audio.insert("volume", 0.5f32);
+        audio.insert("muted", true);
+        file.set_type_data("game::Audio".to_string(), audio);
+        let mut broken = PersistData::new();
+        broken.insert("volume", "loud");
+        file.set_type_data("Broken".to_string(), broken);
+        let names: Vec<&str> = file.entries().map(|(type_name, _)| type_name).collect();
+        assert_eq!(names, ["Broken", "game::Audio"]);
+
+        assert_eq!(
+            file.try_get_as::<Audio>("game::Audio").unwrap(),
+            Some(Audio {
+                volume: 0.5,
+                muted: true
+            })
+        );
+        assert_eq!(file.try_get_as::<Audio>("Missing").unwrap(), None);
+        match file.try_get_as::<Audio>("Broken") {
+            Err(PersistError::SerializationError { type_name, .. }) => {
+                assert_eq!(type_name.as_deref(), Some("Broken"));
+            }
+            other => panic!("expected a serialization error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_persist_file_load_lenient() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut file = PersistFile::new();
+        for (type_name, level) in [("Alpha", 1), ("Beta", 2), ("Gamma", 3)] {
+            let mut data = PersistData::new();
+            data.insert("level", level);
+            data.insert("note", "braces } and \"quotes\" in a string");
+            data.insert("path", vec![1, 2, 3]);
+            file.set_type_data(type_name.to_string(), data);
+        }
+        let levels = |file: &PersistFile| -> Vec<i32> {
+            file.entries()
+                .filter_map(|(_, data)| data.get::<i32>("level"))
+                .collect()
         };
 
-        data.insert("struct", &test_struct);
+        for format in [PersistFormat::Ron, PersistFormat::Json] {
+            let path = temp_dir.path().join(format!("save.{}", format.extension()));
+            file.save_to_file_as(&path, format).unwrap();
+            let content = fs::read_to_string(&path).unwrap();
+
+            let (intact, report) = PersistFile::load_lenient(&path).unwrap();
+            assert!(report.is_complete());
+            assert_eq!(levels(&intact), [1, 2, 3]);
+
+            // Cut short partway through the last type
+            let cut = content.find("\"Gamma\"").unwrap() + 30;
+            fs::write(&path, &content[..cut]).unwrap();
+            assert!(PersistFile::load_from_file_as(&path, format).is_err());
+            let (salvaged, report) = PersistFile::load_lenient(&path).unwrap();
+            assert_eq!(report.recovered, ["Alpha", "Beta"]);
+            assert_eq!(report.skipped.len(), 1);
+            assert_eq!(report.skipped[0].0.as_deref(), Some("Gamma"));
+            assert_eq!(levels(&salvaged), [1, 2]);
+
+            // A bracket lost in the middle of a type
+            let broken = content.replacen('[', "", 2);
+            fs::write(&path, broken).unwrap();
+            let (salvaged, report) = PersistFile::load_lenient(&path).unwrap();
+            assert_eq!(report.recovered, ["Gamma"]);
+            assert_eq!(levels(&salvaged), [3]);
+            let skipped: Vec<_> = report
+                .skipped
+                .iter()
+                .map(|(type_name, _)| type_name.as_deref())
+                .collect();
+            assert_eq!(skipped, [Some("Alpha"), Some("Beta")]);
+        }
+    }
 
-        let retrieved = data.get::<TestStruct>("struct");
-        assert_eq!(retrieved, Some(test_struct));
+    #[test]
+    fn test_persist_file_output_is_sorted() {
+        let mut file = PersistFile::new();
+        for type_name in ["Zebra", "Apple", "Mango"] {
+            let mut data = PersistData::new();
+            for key in ["zoom", "alpha", "middle"] {
+                data.insert(key, 1);
+            }
+            file.set_type_data(type_name.to_string(), data);
+        }
+
+        for format in [PersistFormat::Ron, PersistFormat::Json] {
+            let content = file.to_string_as(format).unwrap();
+            let positions: Vec<_> = ["Apple", "Mango", "Zebra", "alpha", "middle", "zoom"]
+                .iter()
+                .map(|name| content.find(&format!("\"{}\"", name)).unwrap())
+                .collect();
+            assert!(
+                positions[..3].windows(2).all(|w| w[0] < w[1]),
+                "{}",
+                content
+            );
+            assert!(
+                positions[3..].windows(2).all(|w| w[0] < w[1]),
+                "{}",
+                content
+            );
+            assert_eq!(content, file.to_string_as(format).unwrap());
+        }
     }
 
     #[test]
-    fn test_persist_file_new() {
-        let file = PersistFile::new();
+    fn test_clamp_field() {
+        let mut volume = 900.0_f32;
+        assert_eq!(
+            clamp_field("volume", &mut volume, Some(0.0), Some(1.0)),
+            Some("volume 900 clamped to 1".to_string())
+        );
+        assert_eq!(volume, 1.0);
 
-        assert!(file.type_data.is_empty());
-        assert!(!file.last_saved.is_empty());
-        assert_eq!(file.version, env!("CARGO_PKG_VERSION"));
+        let mut volume = f32::NAN;
+        assert!(clamp_field("volume", &mut volume, Some(0.0), Some(1.0)).is_some());
+        assert_eq!(volume, 0.0);
+
+        let mut lives = -3_i32;
+        assert!(clamp_field("lives", &mut lives, Some(0), None).is_some());
+        assert_eq!(lives, 0);
+        assert_eq!(clamp_field("lives", &mut lives, None, Some(9)), None);
     }
 
     #[test]
-    fn test_persist_file_type_data() {
+    fn test_persist_file_styles() {
+        #[derive(Serialize)]
+        struct Audio {
+            volume: f32,
+        }
+
         let mut file = PersistFile::new();
         let mut data = PersistData::new();
-        data.insert("test_key", "test_value");
-
-        file.set_type_data("TestType".to_string(), data.clone());
+        data.insert("audio", Audio { volume: 0.5 });
+        data.insert("muted", false);
+        file.set_type_data("Settings".to_string(), data);
+
+        let styles = [
+            PersistStyle::default(),
+            PersistStyle::compact(),
+            PersistStyle::default()
+                .with_indent("\t")
+                .with_depth_limit(2),
+            PersistStyle::default().with_struct_names(true),
+        ];
+        for format in [PersistFormat::Ron, PersistFormat::Json] {
+            for style in &styles {
+                let content = file.to_string_styled(format, style).unwrap();
+                assert_eq!(content.contains('\n'), style.pretty, "{}", content);
+                let loaded = PersistFile::parse_as(&content, format).unwrap();
+                let data = loaded.get_type_data("Settings").unwrap();
+                assert_eq!(data.get::<bool>("muted"), Some(false), "{}", content);
+            }
+        }
 
-        let retrieved = file.get_type_data("TestType");
-        assert!(retrieved.is_some());
-        assert_eq!(
-            retrieved.unwrap().get::<String>("test_key"),
-            Some("test_value".to_string())
+        let ron = file
+            .to_string_styled(PersistFormat::Ron, &styles[2])
+            .unwrap();
+        assert!(
+            ron.contains("\n\t\"Settings\": (\n\t\tvalues: {"),
+            "{}",
+            ron
         );
-
-        assert!(file.get_type_data("NonExistent").is_none());
+        assert!(ron.contains("\"audio\": (volume: 0.5),"), "{}", ron);
+        let ron = file
+            .to_string_styled(PersistFormat::Ron, &styles[3])
+            .unwrap();
+        assert!(ron.contains("Audio(\n"), "{}", ron);
+        let json = file
+            .to_string_styled(PersistFormat::Json, &styles[2])
+            .unwrap();
+        assert!(json.contains("\n\t\"Settings\": {"), "{}", json);
     }
 
     #[test]
@@ -1134,6 +8803,59 @@ mod tests {
         assert_eq!(loaded_data.get::<i32>("count"), Some(100));
     }
 
+    #[test]
+    fn test_failed_save_keeps_previous_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("keep.ron");
+
+        let mut file = PersistFile::new();
+        let mut data = PersistData::new();
+        data.insert("count", 1);
+        file.set_type_data("KeepResource".to_string(), data.clone());
+        file.save_to_file(&file_path).unwrap();
+
+        // Something in the way of the new file makes the next save fail
+        fs::create_dir(temp_dir.path().join("keep.ron.tmp")).unwrap();
+        data.insert("count", 2);
+        file.set_type_data("KeepResource".to_string(), data);
+        assert!(file.save_to_file(&file_path).is_err());
+
+        let loaded = PersistFile::load_from_file(&file_path).unwrap();
+        let loaded_data = loaded.get_type_data("KeepResource").unwrap();
+        assert_eq!(loaded_data.get::<i32>("count"), Some(1));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_entries_keep_their_own_timestamps() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut file = PersistFile::new();
+        file.set_type_data("Old".to_string(), PersistData::new());
+        let mut old = file.type_data["Old"].clone();
+        old.last_saved = parse_timestamp("2024-05-01T12:00:00+00:00");
+        file.type_data.insert("Old".to_string(), old);
+        file.set_type_data("New".to_string(), PersistData::new());
+
+        for name in ["times.ron", "times.json"] {
+            let path = temp_dir.path().join(name);
+            file.save_to_file(&path).unwrap();
+            let loaded = PersistFile::load_from_file(&path).unwrap();
+            assert_eq!(
+                loaded.type_data["Old"].last_saved(),
+                parse_timestamp("2024-05-01T12:00:00Z")
+            );
+            assert_eq!(
+                loaded.type_data["New"].last_saved(),
+                file.type_data["New"].last_saved()
+            );
+        }
+
+        // Entries from files that predate timestamps still load
+        let legacy = r#"{"Legacy": (values: {"a": 1})}"#;
+        let legacy = PersistFile::parse_as(legacy, PersistFormat::Ron).unwrap();
+        assert_eq!(legacy.type_data["Legacy"].last_saved(), None);
+    }
+
     #[test]
     fn test_persist_file_load_nonexistent() {
         let temp_dir = TempDir::new().unwrap();
@@ -1152,9 +8874,8 @@ mod tests {
         assert_eq!(manager.app_name, "TestApp");
         assert!(manager.auto_save);
         assert!(manager.auto_save_types.is_empty());
-
-        #[cfg(not(feature = "prod"))]
         assert_eq!(manager.dev_file, PathBuf::from("testapp_dev.ron"));
+        assert_eq!(manager.environment(), PersistEnvironment::default());
     }
 
     #[test]
@@ -1176,56 +8897,499 @@ mod tests {
 
     #[test]
     fn test_persist_manager_save_and_load() {
-        // This test requires being able to control file paths, which is only available in dev mode
-        #[cfg(not(feature = "prod"))]
-        {
-            let temp_dir = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+
+        // We need to write to a specific file for this test
+        // Create a manager with test org/app
+        let mut manager = PersistManager::new("TestOrg", "TestApp");
+
+        // For testing, override the dev file path
+        manager.dev_file = temp_dir.path().join("test.ron");
+
+        let mut data = PersistData::new();
+        data.insert("test", "data");
+        manager
+            .get_persist_file_mut()
+            .set_type_data("TestType".to_string(), data);
+
+        // Save
+        manager.save().unwrap();
 
-            // We need to write to a specific file for this test
-            // Create a manager with test org/app
-            let mut manager = PersistManager::new("TestOrg", "TestApp");
+        // Create new manager with same paths and load
+        let mut manager2 = PersistManager::new("TestOrg", "TestApp");
+        manager2.dev_file = temp_dir.path().join("test.ron");
+        manager2.load().unwrap();
 
-            // For testing, override the dev file path
-            manager.dev_file = temp_dir.path().join("test.ron");
+        let loaded_data = manager2.get_persist_file().get_type_data("TestType");
+        assert!(loaded_data.is_some());
+        assert_eq!(
+            loaded_data.unwrap().get::<String>("test"),
+            Some("data".to_string())
+        );
+    }
 
+    #[test]
+    fn test_journal_is_encoded_and_checked_before_replay() {
+        let temp_dir = TempDir::new().unwrap();
+        let progress_path = temp_dir.path().join("progress.ron");
+        let mut manager = PersistManager::new("TestOrg", "TestApp");
+        manager.dev_file = temp_dir.path().join("dev.ron");
+        manager.set_type_mode("Progress".to_string(), PersistMode::Dynamic);
+        manager.set_type_file("Progress".to_string(), &progress_path);
+        let type_name = "Progress".to_string();
+        let named = |name: &str| {
             let mut data = PersistData::new();
-            data.insert("test", "data");
+            data.insert("player_name", name.to_string());
+            data
+        };
+        let saved_name = |manager: &PersistManager| {
             manager
-                .get_persist_file_mut()
-                .set_type_data("TestType".to_string(), data);
+                .read_type_data("Progress", None)
+                .unwrap()
+                .get::<String>("player_name")
+        };
 
-            // Save
-            manager.save().unwrap();
+        let journal = manager.journal_path();
+        let ferris = named("Ferris");
+        manager
+            .write_journal(std::iter::once((&type_name, &ferris)))
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&fs::read(&journal).unwrap()).contains("Ferris"));
+        manager.replay_journal();
+        assert!(!journal.exists());
+        assert_eq!(saved_name(&manager), Some("Ferris".to_string()));
+
+        // A journal cut short isn't replayed
+        let corro = named("Corro");
+        manager
+            .write_journal(std::iter::once((&type_name, &corro)))
+            .unwrap();
+        let bytes = fs::read(&journal).unwrap();
+        fs::write(&journal, &bytes[..bytes.len() - 1]).unwrap();
+        manager.replay_journal();
+        assert!(!journal.exists());
+        assert_eq!(saved_name(&manager), Some("Ferris".to_string()));
+
+        // Nothing is journaled while nothing is written to files
+        manager.read_only = true;
+        manager
+            .write_journal(std::iter::once((&type_name, &corro)))
+            .unwrap();
+        assert!(!journal.exists());
+    }
 
-            // Create new manager with same paths and load
-            let mut manager2 = PersistManager::new("TestOrg", "TestApp");
-            manager2.dev_file = temp_dir.path().join("test.ron");
-            manager2.load().unwrap();
+    #[test]
+    fn test_memory_fallback_covers_only_the_unwritable_file() {
+        use std::sync::atomic::{AtomicI64, Ordering};
 
-            let loaded_data = manager2.get_persist_file().get_type_data("TestType");
-            assert!(loaded_data.is_some());
-            assert_eq!(
-                loaded_data.unwrap().get::<String>("test"),
-                Some("data".to_string())
-            );
+        let temp_dir = TempDir::new().unwrap();
+        let progress_path = temp_dir.path().join("progress.ron");
+        let settings_path = temp_dir.path().join("settings.ron");
+        let start = "2024-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let seconds = Arc::new(AtomicI64::new(0));
+        let clock = seconds.clone();
+        let mut manager = PersistManager::new("TestOrg", "TestApp")
+            .with_clock(move || start + chrono::TimeDelta::seconds(clock.load(Ordering::SeqCst)));
+        for (type_name, path) in [("Progress", &progress_path), ("Settings", &settings_path)] {
+            manager.set_type_mode(type_name.to_string(), PersistMode::Dynamic);
+            manager.set_type_file(type_name.to_string(), path);
         }
+        let level = |level: u32| {
+            let mut data = PersistData::new();
+            data.insert("level", level);
+            data
+        };
+        let denied = PersistError::io(
+            "Failed to write file",
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+        );
+        let missing = PersistError::io(
+            "Failed to write file",
+            std::io::Error::from(std::io::ErrorKind::NotFound),
+        );
 
-        // In production mode, just verify basic manager creation
-        #[cfg(feature = "prod")]
-        {
-            let manager = PersistManager::new("TestOrg", "TestApp");
-            assert_eq!(manager.organization, "TestOrg");
-            assert_eq!(manager.app_name, "TestApp");
-            // Platform-specific save/load testing would require actual directories
+        // Off unless turned on, and only for files that can't be written
+        assert_eq!(manager.fall_back_to_memory("Progress", &denied), None);
+        let mut manager = manager.with_memory_fallback(true);
+        assert_eq!(manager.fall_back_to_memory("Progress", &missing), None);
+        assert_eq!(
+            manager.fall_back_to_memory("Progress", &denied),
+            Some(progress_path.clone())
+        );
+        assert!(manager.is_type_in_memory("Progress"));
+        assert!(!manager.is_type_in_memory("Settings"));
+
+        assert_eq!(manager.write_type_data("Progress", level(4)).unwrap(), None);
+        assert!(!progress_path.exists());
+        assert_eq!(
+            manager
+                .read_type_data("Progress", None)
+                .unwrap()
+                .get::<u32>("level"),
+            Some(4)
+        );
+        // Other files are still written
+        assert_eq!(
+            manager.write_type_data("Settings", level(1)).unwrap(),
+            Some(settings_path.clone())
+        );
+
+        // Once the probe interval has passed, the file is tried again
+        seconds.store(30, Ordering::SeqCst);
+        assert_eq!(
+            manager.write_type_data("Progress", level(5)).unwrap(),
+            Some(progress_path.clone())
+        );
+        assert!(!manager.is_in_memory());
+        assert!(progress_path.exists());
+    }
+
+    #[test]
+    fn test_environment_selects_paths() {
+        let dev =
+            PersistManager::new("TestOrg", "TestApp").with_environment(PersistEnvironment::Dev);
+        assert!(!dev.is_production());
+        assert_eq!(
+            dev.get_resource_path("Settings", PersistMode::Dynamic),
+            PathBuf::from("testapp_dev.ron")
+        );
+
+        let mut prod = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production);
+        assert!(prod.is_production());
+        assert!(prod.get_persist_file().type_data.is_empty());
+        assert!(prod
+            .get_resource_path("Settings", PersistMode::Embed)
+            .as_os_str()
+            .is_empty());
+
+        // Embedded resources are read from compiled-in data and never written
+        prod.set_type_mode("Balance".to_string(), PersistMode::Embed);
+        let mut data = PersistData::new();
+        data.insert("damage", 12);
+        let mut file = PersistFile::new();
+        file.set_type_data("Balance".to_string(), data.clone());
+        let embedded = PersistFormat::Ron.serialize(&file).unwrap();
+        assert_eq!(
+            prod.read_type_data("Balance", Some(&embedded))
+                .and_then(|data| data.get::<i32>("damage")),
+            Some(12)
+        );
+        assert_eq!(prod.write_type_data("Balance", data).unwrap(), None);
+    }
+
+    #[test]
+    fn test_custom_path_resolver() {
+        struct SaveSlotPaths(PathBuf);
+
+        impl PathResolver for SaveSlotPaths {
+            fn resolve(&self, type_name: &str, mode: PersistMode) -> PathBuf {
+                let dir = if mode == PersistMode::Secure {
+                    "saves"
+                } else {
+                    "config"
+                };
+                self.0.join(dir).join(type_name.to_lowercase())
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let mut manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(SaveSlotPaths(root.clone()));
+        manager.set_type_format("Settings".to_string(), PersistFormat::Json);
+
+        assert_eq!(
+            manager.get_resource_path("Settings", PersistMode::Dynamic),
+            root.join("config").join("settings.json")
+        );
+        assert_eq!(
+            manager.get_resource_path("Progress", PersistMode::Secure),
+            root.join("saves").join("progress.dat")
+        );
+        assert_eq!(
+            manager.get_group_path("Audio", PersistFormat::Ron, PersistMode::Dynamic),
+            root.join("config").join("audio.ron")
+        );
+
+        manager.set_type_file("Progress".to_string(), "slot1.ron");
+        assert_eq!(
+            manager.get_type_file_path("Progress", PersistMode::Secure),
+            Some(root.join("saves").join("slot1.ron"))
+        );
+    }
+
+    #[test]
+    fn test_type_subdirectories() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let resolver = PlatformPathResolver {
+            config_dir: Some(root.join("config")),
+            data_dir: Some(root.join("data")),
+        }
+        .with_subdirectories("settings", "saves");
+        let mut manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(resolver);
+
+        assert_eq!(
+            manager.get_resource_path("Audio", PersistMode::Dynamic),
+            root.join("config").join("settings").join("audio.ron")
+        );
+        assert_eq!(
+            manager.get_resource_path("Progress", PersistMode::Secure),
+            root.join("data").join("saves").join("progress.dat")
+        );
+
+        manager.set_type_dir("Progress".to_string(), "campaign");
+        assert_eq!(
+            manager.get_resource_path("Progress", PersistMode::Secure),
+            root.join("data")
+                .join("saves")
+                .join("campaign")
+                .join("progress.dat")
+        );
+        assert!(root.join("data").join("saves").join("campaign").is_dir());
+        manager.set_type_file("Progress".to_string(), "slot1.dat");
+        assert_eq!(
+            manager.get_type_file_path("Progress", PersistMode::Secure),
+            Some(
+                root.join("data")
+                    .join("saves")
+                    .join("campaign")
+                    .join("slot1.dat")
+            )
+        );
+    }
+
+    #[test]
+    fn test_file_name_strategies() {
+        assert_eq!(
+            FileNameStrategy::Lowercase.file_name("PlayerHP"),
+            "playerhp"
+        );
+        assert_eq!(
+            FileNameStrategy::SnakeCase.file_name("PlayerHP"),
+            "player_hp"
+        );
+        assert_eq!(
+            FileNameStrategy::SnakeCase.file_name("PlayerHp"),
+            "player_hp"
+        );
+        assert_eq!(
+            FileNameStrategy::SnakeCase.file_name("HTTPServerConfig"),
+            "http_server_config"
+        );
+        assert_eq!(
+            FileNameStrategy::KebabCase.file_name("game::Save2Data"),
+            "game-save2-data"
+        );
+        assert_eq!(FileNameStrategy::Preserve.file_name("PlayerHP"), "PlayerHP");
+        let custom = FileNameStrategy::custom(|name| format!("v2_{}", name.to_lowercase()));
+        assert_eq!(custom.file_name("Audio"), "v2_audio");
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(PlatformPathResolver {
+                config_dir: Some(root.join("config")),
+                data_dir: Some(root.join("data")),
+            })
+            .with_file_names(FileNameStrategy::KebabCase);
+        assert_eq!(
+            manager.get_resource_path("PlayerStats", PersistMode::Dynamic),
+            root.join("config").join("player-stats.ron")
+        );
+        assert_eq!(
+            manager.get_group_path("AudioVideo", PersistFormat::Ron, PersistMode::Secure),
+            root.join("data").join("audio-video.ron")
+        );
+
+        // Resolvers that only implement `resolve` keep their own names
+        struct FixedPaths(PathBuf);
+
+        impl PathResolver for FixedPaths {
+            fn resolve(&self, type_name: &str, _mode: PersistMode) -> PathBuf {
+                self.0.join(type_name)
+            }
+        }
+        let manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(FixedPaths(root.clone()))
+            .with_file_names(FileNameStrategy::SnakeCase);
+        assert_eq!(
+            manager.get_resource_path("PlayerStats", PersistMode::Dynamic),
+            root.join("PlayerStats.ron")
+        );
+    }
+
+    #[test]
+    fn test_delete_all_data() {
+        struct TempPaths(PathBuf);
+
+        impl PathResolver for TempPaths {
+            fn resolve(&self, type_name: &str, _mode: PersistMode) -> PathBuf {
+                self.0.join("saves").join(type_name.to_lowercase())
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let mut manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(TempPaths(root.clone()));
+        manager.dev_file = root.join("testapp_dev.ron");
+        manager.set_type_mode("Settings".to_string(), PersistMode::Dynamic);
+        manager.set_type_mode("Scratch".to_string(), PersistMode::Dev);
+
+        let mut data = PersistData::new();
+        data.insert("value", 1);
+        let settings = manager.write_type_data("Settings", data.clone()).unwrap();
+        let scratch = manager.write_type_data("Scratch", data).unwrap();
+        assert_eq!(scratch, Some(manager.dev_file.clone()));
+
+        let report = manager.delete_all_data();
+        assert!(report.is_complete());
+        let settings = settings.unwrap();
+        assert!(report.deleted.contains(&settings));
+        assert!(report.deleted.contains(&root.join("saves")));
+        assert!(!settings.exists());
+        assert!(!manager.dev_file.exists());
+        assert!(manager.get_persist_file().type_data.is_empty());
+    }
+
+    #[test]
+    fn test_list_files() {
+        struct TempPaths(PathBuf);
+
+        impl PathResolver for TempPaths {
+            fn resolve(&self, type_name: &str, _mode: PersistMode) -> PathBuf {
+                self.0.join(type_name.to_lowercase())
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let mut manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(TempPaths(root.clone()));
+        manager.dev_file = root.join("testapp_dev.ron");
+        manager.set_type_mode("Settings".to_string(), PersistMode::Dynamic);
+        manager.set_type_format("Settings".to_string(), PersistFormat::Json);
+        manager.set_type_mode("Progress".to_string(), PersistMode::Secure);
+        manager.set_type_mode("Inventory".to_string(), PersistMode::Secure);
+        manager.set_type_group("Progress".to_string(), "slot1".to_string());
+        manager.set_type_group("Inventory".to_string(), "slot1".to_string());
+        manager.set_type_mode("Unsaved".to_string(), PersistMode::Dynamic);
+        assert!(manager.list_files().is_empty());
+
+        let mut data = PersistData::new();
+        data.insert("value", 1);
+        for type_name in ["Settings", "Progress", "Inventory"] {
+            manager.write_type_data(type_name, data.clone()).unwrap();
+        }
+
+        let files = manager.list_files();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, root.join("settings.json"));
+        assert_eq!(files[0].format, PersistFormat::Json);
+        assert_eq!(files[0].type_names, ["Settings"]);
+        assert_eq!(files[1].path, root.join("slot1.ron"));
+        assert_eq!(files[1].format, PersistFormat::Ron);
+        assert_eq!(files[1].type_names, ["Inventory", "Progress"]);
+        for file in &files {
+            assert_eq!(file.size, fs::metadata(&file.path).unwrap().len());
+            assert!(file.modified.is_some());
+        }
+    }
+
+    #[test]
+    fn test_single_file_layout() {
+        struct TempPaths(PathBuf);
+
+        impl PathResolver for TempPaths {
+            fn resolve(&self, type_name: &str, _mode: PersistMode) -> PathBuf {
+                self.0.join(type_name.to_lowercase())
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let mut manager = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_file_layout(FileLayout::Single)
+            .with_path_resolver(TempPaths(root.clone()));
+        manager.set_type_mode("Settings".to_string(), PersistMode::Dynamic);
+        manager.set_type_format("Settings".to_string(), PersistFormat::Json);
+        manager.set_type_mode("Progress".to_string(), PersistMode::Secure);
+        manager.set_type_mode("Replay".to_string(), PersistMode::Secure);
+        manager.set_type_file("Replay".to_string(), "replay.ron");
+
+        let mut data = PersistData::new();
+        data.insert("value", 1);
+        let savegame = root.join("savegame.ron");
+        for type_name in ["Settings", "Progress"] {
+            let path = manager.write_type_data(type_name, data.clone()).unwrap();
+            assert_eq!(path, Some(savegame.clone()));
         }
+        let replay = manager.write_type_data("Replay", data).unwrap();
+        assert_eq!(replay, Some(root.join("replay.ron")));
+
+        let file = PersistFile::load_from_file(&savegame).unwrap();
+        assert_eq!(file.type_data.len(), 2);
+        let mut reopened = PersistManager::new("TestOrg", "TestApp")
+            .with_environment(PersistEnvironment::Production)
+            .with_file_layout(FileLayout::Single)
+            .with_path_resolver(TempPaths(root.clone()));
+        reopened.set_type_mode("Settings".to_string(), PersistMode::Dynamic);
+        let settings = reopened.read_type_data("Settings", None).unwrap();
+        assert_eq!(settings.get::<i32>("value"), Some(1));
+    }
+
+    #[test]
+    fn test_registered_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager =
+            PersistManager::new("TestOrg", "TestApp").with_environment(PersistEnvironment::Dev);
+        manager.dev_file = temp_dir.path().join("testapp_dev.ron");
+        manager.set_type_mode("Video".to_string(), PersistMode::Dynamic);
+        manager.set_type_mode("Audio".to_string(), PersistMode::Dev);
+        manager.set_type_auto_save("Audio".to_string(), false);
+
+        manager
+            .write_type_data("Video", PersistData::new())
+            .unwrap();
+
+        let types: Vec<_> = manager.registered_types().collect();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0].type_name, "Audio");
+        assert!(!types[0].auto_save);
+        assert!(types[0].last_saved.is_none());
+        assert_eq!(types[1].type_name, "Video");
+        assert_eq!(types[1].mode, PersistMode::Dynamic);
+        assert_eq!(types[1].path.as_ref(), Some(&manager.dev_file));
+        assert!(types[1].last_saved.is_some());
     }
 
     #[test]
     fn test_persist_error_display() {
-        let io_error = PersistError::IoError("file not found".to_string());
-        assert_eq!(format!("{}", io_error), "IO error: file not found");
+        let io_error = PersistError::io(
+            "Failed to read file",
+            std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"),
+        )
+        .at("settings.ron")
+        .for_type("Settings");
+        assert_eq!(
+            format!("{}", io_error),
+            "IO error: Failed to read file settings.ron for Settings: file not found"
+        );
+        assert_eq!(io_error.io_kind(), Some(std::io::ErrorKind::NotFound));
+        assert_eq!(io_error.path(), Some(Path::new("settings.ron")));
+        assert!(std::error::Error::source(&io_error).is_some());
 
-        let ser_error = PersistError::SerializationError("invalid JSON".to_string());
+        let ser_error = PersistError::serialization("invalid JSON".to_string());
         assert_eq!(
             format!("{}", ser_error),
             "Serialization error: invalid JSON"
@@ -1235,6 +9399,86 @@ mod tests {
         assert_eq!(format!("{}", res_error), "Resource not found: MyResource");
     }
 
+    #[test]
+    fn test_persist_format_from_path() {
+        assert_eq!(
+            PersistFormat::from_path(Path::new("a/b.ron")),
+            PersistFormat::Ron
+        );
+        assert_eq!(
+            PersistFormat::from_path(Path::new("b.TOML")),
+            PersistFormat::Toml
+        );
+        assert_eq!(
+            PersistFormat::from_path(Path::new("b.json")),
+            PersistFormat::Json
+        );
+        assert_eq!(
+            PersistFormat::from_path(Path::new("b")),
+            PersistFormat::Json
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_persist_file_save_and_load_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.toml");
+
+        let mut file = PersistFile::new();
+        let mut data = PersistData::new();
+        data.insert("name", "Toml Test");
+        data.insert("count", 7);
+        file.set_type_data("TomlResource".to_string(), data);
+        file.save_to_file(&file_path).unwrap();
+
+        let loaded = PersistFile::load_from_file(&file_path).unwrap();
+        let loaded_data = loaded.get_type_data("TomlResource").unwrap();
+        assert_eq!(loaded_data.get::<i32>("count"), Some(7));
+    }
+
+    #[test]
+    fn test_find_storage_key_collisions() {
+        let collisions = find_storage_key_collisions([
+            ("ns::Settings", "game::Settings"),
+            ("ns::Progress", "game::Progress"),
+            ("ns::Settings", "editor::Settings"),
+            ("ns::Settings", "game::Settings"),
+        ]);
+        assert_eq!(
+            collisions,
+            vec![("ns::Settings", vec!["editor::Settings", "game::Settings"])]
+        );
+
+        // Without a namespace, same-named types have their own keys
+        assert!(find_storage_key_collisions([
+            ("game::Settings", "game::Settings"),
+            ("editor::Settings", "editor::Settings"),
+        ])
+        .is_empty());
+    }
+
+    #[test]
+    fn test_strip_module_paths() {
+        assert_eq!(strip_module_paths("<u32>"), "<u32>");
+        assert_eq!(
+            strip_module_paths("<game::levels::Arcade, alloc::vec::Vec<core::option::Option<u8>>>"),
+            "<Arcade, Vec<Option<u8>>>"
+        );
+        assert_eq!(strip_module_paths("<(a::B, [c::D; 4])>"), "<(B, [D; 4])>");
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("1.2.0", "1.1.9"));
+        assert!(is_newer_version("2.0", "1.9.9"));
+        assert!(is_newer_version("1.0.0", "1.0.0-beta.3"));
+        assert!(!is_newer_version("1.0.0-rc.1", "1.0.0"));
+        assert!(!is_newer_version("1.0.0+build.7", "1.0.0"));
+        assert!(!is_newer_version("1.10.0", "1.10.0"));
+        assert!(!is_newer_version("nightly", "1.0.0"));
+    }
+
     #[test]
     fn test_persist_plugin_default() {
         let plugin = PersistPlugin::default();
@@ -1254,7 +9498,7 @@ mod tests {
     #[test]
     fn test_persist_data_default() {
         let data = PersistData::default();
-        assert!(data.values.is_empty());
+        assert!(data.is_empty());
     }
 
     #[test]
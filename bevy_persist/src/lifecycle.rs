@@ -0,0 +1,58 @@
+//! Flushing saves when the app is backgrounded (`lifecycle` feature).
+
+use crate::PersistManager;
+use bevy::ecs::event::EventCursor;
+use bevy::prelude::*;
+use bevy::window::{AppLifecycle, PrimaryWindow, WindowFocused, WindowOccluded};
+use log::info;
+
+/// Writes every dirty type when the app is about to be suspended.
+///
+/// Android and iOS may kill a backgrounded app without warning, so this
+/// doesn't wait for the end of the frame or a manual flush.
+pub(crate) fn flush_on_suspend(world: &mut World, mut cursor: Local<EventCursor<AppLifecycle>>) {
+    let Some(events) = world.get_resource::<Events<AppLifecycle>>() else {
+        return;
+    };
+    let suspending = cursor
+        .read(events)
+        .any(|event| matches!(event, AppLifecycle::WillSuspend | AppLifecycle::Suspended));
+    if suspending {
+        info!("App is suspending, flushing persisted data");
+        // Failures are already logged and sent as events
+        PersistManager::flush(world).ok();
+    }
+}
+
+/// Writes every dirty type when the primary window loses focus or is minimized.
+///
+/// Desktop games get killed from the task manager too, and alt-tabbing
+/// away is a much cheaper moment to save than every frame.
+pub(crate) fn flush_on_focus_lost(
+    world: &mut World,
+    mut focused: Local<EventCursor<WindowFocused>>,
+    mut occluded: Local<EventCursor<WindowOccluded>>,
+) {
+    let is_primary = |world: &World, window: Entity| world.get::<PrimaryWindow>(window).is_some();
+    let mut windows = Vec::new();
+    if let Some(events) = world.get_resource::<Events<WindowFocused>>() {
+        windows.extend(
+            focused
+                .read(events)
+                .filter(|event| !event.focused)
+                .map(|event| event.window),
+        );
+    }
+    if let Some(events) = world.get_resource::<Events<WindowOccluded>>() {
+        windows.extend(
+            occluded
+                .read(events)
+                .filter(|event| event.occluded)
+                .map(|event| event.window),
+        );
+    }
+    if windows.into_iter().any(|window| is_primary(world, window)) {
+        info!("Primary window lost focus, flushing persisted data");
+        PersistManager::flush(world).ok();
+    }
+}
@@ -0,0 +1,90 @@
+//! Tracking which persisted types have had their data applied.
+
+use bevy::prelude::*;
+use std::any::TypeId;
+use std::collections::{BTreeSet, HashMap};
+
+/// Which registered types have finished loading.
+///
+/// Each type is pending from registration until its load has run, whether
+/// or not anything was persisted yet. Systems that build UI from settings
+/// can wait for it with the [`persist_loaded`] and [`all_persist_loaded`]
+/// run conditions:
+///
+/// ```ignore
+/// app.add_systems(Update, build_settings_menu.run_if(persist_loaded::<Settings>()));
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct PersistLoadState {
+    /// Storage key each registered Rust type is persisted under
+    keys: HashMap<TypeId, String>,
+    /// Type name of each storage key
+    type_names: HashMap<String, String>,
+    /// Types whose load hasn't run yet
+    pending: BTreeSet<String>,
+    /// Types whose load has run
+    loaded: BTreeSet<String>,
+}
+
+impl PersistLoadState {
+    /// Checks if `T` has finished loading.
+    pub fn is_loaded<T: 'static>(&self) -> bool {
+        self.keys
+            .get(&TypeId::of::<T>())
+            .is_some_and(|key| self.loaded.contains(key))
+    }
+
+    /// Checks if the type persisted under `type_name` has finished loading.
+    ///
+    /// Takes the type's name or its storage key.
+    pub fn is_loaded_named(&self, type_name: &str) -> bool {
+        self.loaded.contains(type_name)
+            || self
+                .loaded
+                .iter()
+                .any(|key| self.type_name(key) == type_name)
+    }
+
+    /// Checks if every registered type has finished loading.
+    pub fn is_all_loaded(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Names of the types still waiting to load.
+    pub fn pending(&self) -> impl Iterator<Item = &str> {
+        self.pending.iter().map(|key| self.type_name(key))
+    }
+
+    /// Records a newly registered type as pending.
+    pub(crate) fn expect<T: 'static>(&mut self, key: &str, type_name: &str) {
+        self.keys.insert(TypeId::of::<T>(), key.to_string());
+        self.type_names
+            .insert(key.to_string(), type_name.to_string());
+        if !self.loaded.contains(key) {
+            self.pending.insert(key.to_string());
+        }
+    }
+
+    /// Records that a type's load has run.
+    pub(crate) fn finish(&mut self, key: &str) {
+        self.pending.remove(key);
+        self.loaded.insert(key.to_string());
+    }
+
+    /// Type name registered for a storage key.
+    fn type_name<'a>(&'a self, key: &'a str) -> &'a str {
+        self.type_names.get(key).map_or(key, String::as_str)
+    }
+}
+
+/// Run condition that's true once `T` has finished loading.
+///
+/// Works for persisted resources and components alike.
+pub fn persist_loaded<T: 'static>() -> impl FnMut(Option<Res<PersistLoadState>>) -> bool + Clone {
+    |state: Option<Res<PersistLoadState>>| state.is_some_and(|state| state.is_loaded::<T>())
+}
+
+/// Run condition that's true once every registered type has finished loading.
+pub fn all_persist_loaded() -> impl FnMut(Option<Res<PersistLoadState>>) -> bool + Clone {
+    |state: Option<Res<PersistLoadState>>| state.is_some_and(|state| state.is_all_loaded())
+}
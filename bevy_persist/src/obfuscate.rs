@@ -0,0 +1,51 @@
+//! Scrambling obfuscated saves so they can't be edited in a text editor.
+//!
+//! This is not encryption: anyone with the crate can undo it. It only keeps
+//! players from opening a save in notepad and changing a number, and the
+//! checksum catches edits made to the scrambled bytes.
+
+use crate::{checksum, PersistError, PersistResult};
+
+/// Marks an obfuscated file, and the layout version it uses
+const MAGIC: &[u8; 4] = b"BPO1";
+
+/// Scrambles `plain` and prefixes it with a checksum.
+pub(crate) fn obfuscate(plain: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 4 + plain.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&checksum(plain).to_le_bytes());
+    bytes.extend(plain.iter().zip(keystream()).map(|(byte, key)| byte ^ key));
+    bytes
+}
+
+/// Unscrambles bytes from [`obfuscate`], checking they weren't edited.
+pub(crate) fn deobfuscate(bytes: &[u8]) -> PersistResult<Vec<u8>> {
+    let invalid =
+        |reason: &str| PersistError::serialization(format!("Invalid obfuscated data, {}", reason));
+    let Some(rest) = bytes.strip_prefix(MAGIC) else {
+        return Err(invalid("it isn't obfuscated"));
+    };
+    let [c0, c1, c2, c3, scrambled @ ..] = rest else {
+        return Err(invalid("it's too short"));
+    };
+    let plain: Vec<u8> = scrambled
+        .iter()
+        .zip(keystream())
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+    if checksum(&plain) != u32::from_le_bytes([*c0, *c1, *c2, *c3]) {
+        return Err(invalid("its checksum doesn't match"));
+    }
+    Ok(plain)
+}
+
+/// Bytes XORed with the data, from a fixed xorshift generator.
+fn keystream() -> impl Iterator<Item = u8> {
+    let mut state: u32 = 0x9e37_79b9;
+    std::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state >> 24) as u8
+    })
+}
@@ -0,0 +1,184 @@
+//! Writing several types' own files at once on the IO task pool.
+
+use crate::{PersistError, PersistFile, PersistFormat, PersistManager, PersistResult};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::tasks::{IoTaskPool, TaskPool};
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Sent when a flush has written several types' files in parallel.
+///
+/// Follows the [`PersistSaved`](crate::PersistSaved) and
+/// [`PersistSaveFailed`](crate::PersistSaveFailed) events of the types in
+/// the batch, e.g. to close the game once everything is on disk.
+#[derive(Event, Debug, Clone)]
+pub struct PersistBatchSaved {
+    /// Types whose files were written
+    pub saved: Vec<String>,
+    /// Types whose files failed to write
+    pub failed: Vec<String>,
+    /// How long the batch took, from the first file to the last
+    pub duration: Duration,
+}
+
+/// A type's own file, waiting to be written with the rest of the flush.
+pub(crate) struct PendingWrite {
+    pub(crate) type_name: String,
+    pub(crate) file: PersistFile,
+    pub(crate) path: PathBuf,
+    pub(crate) format: PersistFormat,
+    pub(crate) compress: bool,
+}
+
+impl PersistManager {
+    /// Sets whether a flush writes types' own files in parallel (defaults
+    /// to `true`).
+    ///
+    /// In production, each dynamic and secure type has its own file. When
+    /// several are saved by the same flush, e.g. on exit, they're
+    /// serialized and written on the [`IoTaskPool`] and the flush
+    /// waits for all of them, rather than writing one after another.
+    /// Group files and the dev file are written as usual.
+    pub fn with_parallel_saves(mut self, enabled: bool) -> Self {
+        self.parallel_saves = enabled;
+        self
+    }
+
+    /// Checks if a flush writes types' own files in parallel.
+    pub fn is_parallel_saves(&self) -> bool {
+        self.parallel_saves
+    }
+
+    /// Holds back writes of types' own files until
+    /// [`write_deferred_files`](Self::write_deferred_files).
+    pub(crate) fn defer_file_writes(&mut self) {
+        if self.parallel_saves {
+            self.deferred_file_writes.get_or_insert_with(Vec::new);
+        }
+    }
+
+    /// Whether `type_name`'s file is waiting to be written.
+    pub(crate) fn is_write_deferred(&self, type_name: &str) -> bool {
+        self.deferred_file_writes
+            .as_ref()
+            .is_some_and(|writes| writes.iter().any(|write| write.type_name == type_name))
+    }
+
+    /// Writes every file held back since [`defer_file_writes`](Self::defer_file_writes),
+    /// in parallel if there's more than one.
+    ///
+    /// Returns the batch, if any, and the error of each type whose file
+    /// failed to write.
+    pub(crate) fn write_deferred_files(
+        &mut self,
+    ) -> (Option<PersistBatchSaved>, HashMap<String, PersistError>) {
+        let Some(writes) = self.deferred_file_writes.take() else {
+            return (None, HashMap::new());
+        };
+        if writes.is_empty() {
+            return (None, HashMap::new());
+        }
+
+        let started = Instant::now();
+        let batches = self.batch_by_path(writes);
+        let parallel = batches.len() > 1;
+        let manager = &*self;
+        // Writes to the same path run one after another, in the order they
+        // were deferred, so they can't interleave
+        let write = move |batch: Vec<PendingWrite>| {
+            batch
+                .into_iter()
+                .map(|mut pending| {
+                    let started = Instant::now();
+                    let result = manager.save_standalone(
+                        &pending.type_name,
+                        &mut pending.file,
+                        &pending.path,
+                        pending.format,
+                        pending.compress,
+                    );
+                    (pending.type_name, pending.path, result, started.elapsed())
+                })
+                .collect::<Vec<_>>()
+        };
+        let results: Vec<(String, PathBuf, PersistResult<()>, Duration)> = if parallel {
+            IoTaskPool::get_or_init(TaskPool::default)
+                .scope(|scope| {
+                    for batch in batches {
+                        scope.spawn(async move { write(batch) });
+                    }
+                })
+                .into_iter()
+                .flatten()
+                .collect()
+        } else {
+            batches.into_iter().flat_map(write).collect()
+        };
+        let duration = started.elapsed();
+
+        let mut batch = PersistBatchSaved {
+            saved: Vec::new(),
+            failed: Vec::new(),
+            duration,
+        };
+        let mut errors = HashMap::new();
+        for (type_name, path, result, elapsed) in results {
+            match result {
+                Ok(()) => {
+                    debug!("Saved {} to {:?}", type_name, path);
+                    self.record_save(&[type_name.as_str()], &path, elapsed);
+                    let mode = self.get_type_mode(&type_name);
+                    self.remove_legacy_file(&type_name, mode);
+                    batch.saved.push(self.get_type_name(&type_name).to_string());
+                }
+                Err(e) => {
+                    self.last_saved.remove(&type_name);
+                    self.content_hashes.remove(&type_name);
+                    let e = e.for_type(self.get_type_name(&type_name));
+                    batch
+                        .failed
+                        .push(self.get_type_name(&type_name).to_string());
+                    errors.insert(type_name, e);
+                }
+            }
+        }
+        if parallel {
+            debug!(
+                "Wrote {} files in parallel in {:?}",
+                batch.saved.len() + batch.failed.len(),
+                duration
+            );
+        }
+        (parallel.then_some(batch), errors)
+    }
+
+    /// Splits pending writes into one batch per path, keeping their order.
+    ///
+    /// Types whose files resolve to the same path, e.g. through a
+    /// [`PathResolver`](crate::PathResolver) that ignores the file name,
+    /// overwrite each other, so this warns about them.
+    fn batch_by_path(&self, writes: Vec<PendingWrite>) -> Vec<Vec<PendingWrite>> {
+        let mut batches: Vec<Vec<PendingWrite>> = Vec::new();
+        for pending in writes {
+            match batches
+                .iter_mut()
+                .find(|batch| batch[0].path == pending.path)
+            {
+                Some(batch) => {
+                    warn!(
+                        "{} and {} are both saved to {:?} and overwrite each other",
+                        self.get_type_name(&batch[0].type_name),
+                        self.get_type_name(&pending.type_name),
+                        pending.path
+                    );
+                    batch.push(pending);
+                }
+                None => batches.push(vec![pending]),
+            }
+        }
+        batches
+    }
+}
@@ -0,0 +1,128 @@
+//! Checking a save can be written before touching its file (`preflight` feature).
+
+use crate::{PersistError, PersistManager, PersistResult};
+use bevy::prelude::*;
+use log::warn;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Sent when a save is stopped because its file couldn't have been written.
+///
+/// [`PersistSaveFailed`](crate::PersistSaveFailed) is sent as well; this one
+/// is for "disk full" or "can't write saves" dialogs that shouldn't fire for
+/// every other kind of failure.
+#[derive(Event, Debug, Clone)]
+pub struct PersistPreflightFailed {
+    /// Name of the type that wasn't saved
+    pub type_name: String,
+    /// File the data would have been written to
+    pub path: PathBuf,
+    /// [`PersistError::PermissionDenied`] or [`PersistError::InsufficientSpace`]
+    pub error: PersistError,
+}
+
+impl PersistManager {
+    /// Checks before each save that its directory is writable and has room.
+    ///
+    /// A save that would fail stops with [`PersistError::PermissionDenied`]
+    /// or [`PersistError::InsufficientSpace`] before the old file is
+    /// truncated, so the player keeps their last good save. `min_free_space`
+    /// is how many bytes must still be free once the data is written.
+    pub fn with_preflight_checks(mut self, min_free_space: u64) -> Self {
+        self.min_free_space = Some(min_free_space);
+        self
+    }
+
+    /// Checks that `bytes` of `type_name`'s data can be written to `path`,
+    /// if [preflight checks](Self::with_preflight_checks) are on.
+    pub(crate) fn preflight(
+        &mut self,
+        type_name: &str,
+        path: &Path,
+        bytes: u64,
+    ) -> PersistResult<()> {
+        let Some(min_free_space) = self.min_free_space else {
+            return Ok(());
+        };
+        let type_name = self.get_type_name(type_name).to_string();
+        let result =
+            check(path, bytes.saturating_add(min_free_space)).map_err(|e| e.for_type(&type_name));
+        if let Err(error) = &result {
+            warn!("Not saving {}: {}", type_name, error);
+            self.preflight_failures
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(PersistPreflightFailed {
+                    type_name,
+                    path: path.to_path_buf(),
+                    error: error.clone(),
+                });
+        }
+        result
+    }
+}
+
+/// Checks `path` can be written and its directory has `needed` bytes free.
+fn check(path: &Path, needed: u64) -> PersistResult<()> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+    if fs::metadata(path).is_ok_and(|meta| meta.permissions().readonly()) {
+        return Err(PersistError::PermissionDenied {
+            path: path.to_path_buf(),
+            type_name: None,
+        });
+    }
+
+    // Creating a file is the only reliable test of a directory's permissions
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let probe = dir.join(format!(".{}.preflight", file_name));
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&probe)
+        .map_err(|e| io_error(dir, e))?;
+    let _ = fs::remove_file(&probe);
+
+    let available = fs2::available_space(dir).map_err(|e| io_error(dir, e))?;
+    if available < needed {
+        return Err(PersistError::InsufficientSpace {
+            path: path.to_path_buf(),
+            needed,
+            available,
+            type_name: None,
+        });
+    }
+    Ok(())
+}
+
+fn io_error(dir: &Path, e: io::Error) -> PersistError {
+    match e.kind() {
+        io::ErrorKind::PermissionDenied => PersistError::PermissionDenied {
+            path: dir.to_path_buf(),
+            type_name: None,
+        },
+        _ => PersistError::io("Failed to check", e).at(dir),
+    }
+}
+
+/// Sends the saves stopped by preflight checks as events.
+pub(crate) fn send_preflight_events(
+    manager: Res<PersistManager>,
+    mut events: EventWriter<PersistPreflightFailed>,
+) {
+    let failed = std::mem::take(
+        &mut *manager
+            .preflight_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()),
+    );
+    events.write_batch(failed);
+}
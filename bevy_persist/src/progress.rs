@@ -0,0 +1,419 @@
+//! Saving and loading large files in the background, with progress events.
+
+use crate::{
+    create_parent_dir, display_name, read_error, storage_key_of, write_error, PersistError,
+    PersistManager, PersistMode, PersistResult, PersistSaveFailed, PersistSaved, Persistable,
+};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{IoTaskPool, Task, TaskPool};
+use log::{debug, error};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Bytes written or read between progress updates
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Whether a [`PersistProgress`] event is about a save or a load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistOperation {
+    /// Writing a type's file
+    Save,
+    /// Reading a type's file
+    Load,
+}
+
+/// Sent while a type's file is written or read in the background, e.g. to
+/// draw a progress bar for a multi-megabyte save.
+///
+/// Started by [`PersistManager::save_in_background`] and
+/// [`PersistManager::load_in_background`]. One event is sent when the
+/// operation starts, one each frame it makes progress, and a last one with
+/// `finished` set, followed by [`PersistSaved`] or [`PersistSaveFailed`] for
+/// saves and [`PersistLoaded`](crate::PersistLoaded) for loads.
+#[derive(Event, Debug, Clone)]
+pub struct PersistProgress {
+    /// Name of the type being saved or loaded
+    pub type_name: String,
+    /// Whether the type is being saved or loaded
+    pub operation: PersistOperation,
+    /// Bytes written or read so far
+    pub bytes_done: u64,
+    /// Size of the whole file
+    pub bytes_total: u64,
+    /// Whether the operation is over, successfully or not
+    pub finished: bool,
+}
+
+impl PersistProgress {
+    /// How far along the operation is, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total == 0 {
+            return if self.finished { 1.0 } else { 0.0 };
+        }
+        (self.bytes_done as f64 / self.bytes_total as f64).min(1.0) as f32
+    }
+}
+
+/// Saves and loads running on the [`IoTaskPool`].
+#[derive(Resource, Default)]
+pub(crate) struct BackgroundJobs(Vec<BackgroundJob>);
+
+struct BackgroundJob {
+    key: String,
+    operation: PersistOperation,
+    path: PathBuf,
+    bytes_total: u64,
+    bytes_done: Arc<AtomicU64>,
+    reported: u64,
+    started: Instant,
+    /// The bytes read for loads, nothing for saves
+    task: Task<PersistResult<Option<Vec<u8>>>>,
+}
+
+impl PersistManager {
+    /// Saves a resource without waiting for its file to be written.
+    ///
+    /// The data is taken and encoded now, and the file is written on the
+    /// [`IoTaskPool`] a chunk at a time, sending [`PersistProgress`] events
+    /// as it goes. Only production dynamic and secure types have a file of
+    /// their own to write this way; other types are saved at once, sending
+    /// just [`PersistSaved`] or [`PersistSaveFailed`].
+    ///
+    /// Auto-saves of the type wait until the write finishes. Don't save it
+    /// explicitly before then, or the older data may land last.
+    pub fn save_in_background<T: Persistable>(world: &mut World) -> PersistResult<()> {
+        let key = storage_key_of::<T>(world);
+        Self::save_in_background_named(world, &key)
+    }
+
+    /// Saves a registered resource by name without waiting for its file to
+    /// be written.
+    ///
+    /// See [`save_in_background`](Self::save_in_background).
+    pub fn save_in_background_named(world: &mut World, type_name: &str) -> PersistResult<()> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let key = manager.get_storage_key(type_name);
+        let hooks = manager
+            .get_type_hooks(&key)
+            .cloned()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        hooks.before_save_in_world(world);
+        let data = hooks
+            .extract_from_world(world)
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+
+        // Holds back the type's own file like a parallel flush does
+        let mut manager = world.resource_mut::<PersistManager>();
+        let outer = manager.deferred_file_writes.replace(Vec::new());
+        let result = manager.write_type_data(&key, data);
+        let writes =
+            std::mem::replace(&mut manager.deferred_file_writes, outer).unwrap_or_default();
+        let name = manager.get_type_name(&key).to_string();
+        let path = match result {
+            Ok(path) => path,
+            Err(e) => {
+                world.send_event(PersistSaveFailed {
+                    type_name: name,
+                    error: e.clone(),
+                });
+                return Err(e);
+            }
+        };
+        let Some(mut pending) = writes.into_iter().next() else {
+            // Written at once, having no file of its own
+            if let Some(path) = path {
+                let bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+                world.send_event(PersistSaved {
+                    type_name: name,
+                    path,
+                    bytes,
+                });
+            }
+            return Ok(());
+        };
+
+        let bytes = match manager.encode_standalone(
+            &pending.type_name,
+            &mut pending.file,
+            pending.format,
+            pending.compress,
+        ) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                manager.last_saved.remove(&key);
+                manager.content_hashes.remove(&key);
+                let e = e.for_type(&name);
+                world.send_event(PersistSaveFailed {
+                    type_name: name,
+                    error: e.clone(),
+                });
+                return Err(e);
+            }
+        };
+        let bytes_total = bytes.len() as u64;
+        let bytes_done = Arc::new(AtomicU64::new(0));
+        let task = IoTaskPool::get_or_init(TaskPool::default).spawn({
+            let path = pending.path.clone();
+            let bytes_done = bytes_done.clone();
+            async move { write_in_chunks(&path, &bytes, &bytes_done).map(|()| None) }
+        });
+        debug!("Saving {} to {:?} in the background", name, pending.path);
+        manager.background_saves.insert(key.clone());
+        start_job(
+            world,
+            BackgroundJob {
+                key,
+                operation: PersistOperation::Save,
+                path: pending.path,
+                bytes_total,
+                bytes_done,
+                reported: 0,
+                started: Instant::now(),
+                task,
+            },
+        );
+        Ok(())
+    }
+
+    /// Reloads a resource without waiting for its file to be read.
+    ///
+    /// The file is read on the [`IoTaskPool`] a chunk at a time, sending
+    /// [`PersistProgress`] events as it goes, and applied once it's all in,
+    /// like [`reload`](Self::reload). Types without a file of their own on
+    /// disk, e.g. those in the dev file, are reloaded at once.
+    pub fn load_in_background<T: Persistable>(world: &mut World) -> PersistResult<()> {
+        let key = storage_key_of::<T>(world);
+        Self::load_in_background_named(world, &key)
+    }
+
+    /// Reloads a registered resource by name without waiting for its file
+    /// to be read.
+    ///
+    /// See [`load_in_background`](Self::load_in_background).
+    pub fn load_in_background_named(world: &mut World, type_name: &str) -> PersistResult<()> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let key = manager.get_storage_key(type_name);
+        if manager.get_type_hooks(&key).is_none() {
+            return Err(PersistError::ResourceNotFound(type_name.to_string()));
+        }
+        let Some(path) = manager.own_file(&key) else {
+            return Self::reload_named(world, &key).map(|_| ());
+        };
+
+        let bytes_total = fs::metadata(&path).map_or(0, |meta| meta.len());
+        let bytes_done = Arc::new(AtomicU64::new(0));
+        let task = IoTaskPool::get_or_init(TaskPool::default).spawn({
+            let path = path.clone();
+            let bytes_done = bytes_done.clone();
+            async move { read_in_chunks(&path, &bytes_done).map(Some) }
+        });
+        debug!(
+            "Loading {} from {:?} in the background",
+            manager.get_type_name(&key),
+            path
+        );
+        start_job(
+            world,
+            BackgroundJob {
+                key,
+                operation: PersistOperation::Load,
+                path,
+                bytes_total,
+                bytes_done,
+                reported: 0,
+                started: Instant::now(),
+                task,
+            },
+        );
+        Ok(())
+    }
+
+    /// Whether a type's file is being written by
+    /// [`save_in_background`](Self::save_in_background).
+    pub fn is_saving_in_background(&self, type_name: &str) -> bool {
+        self.background_saves.contains(&*self.key(type_name))
+    }
+
+    /// The existing file holding just a type, or its group, that a load
+    /// would read.
+    fn own_file(&self, key: &str) -> Option<PathBuf> {
+        let mode = self.get_type_mode(key);
+        if self.memory_path(key).is_some()
+            || self.backend.is_some()
+            || self.session_store.contains_key(key)
+            || matches!(mode, PersistMode::Session | PersistMode::Embed)
+        {
+            return None;
+        }
+        let path = if let Some(path) = self.get_type_file_path(key, mode) {
+            path
+        } else if let Some(group) = self.resolve_type_group(key, mode) {
+            let format = self.get_group_format(group, key);
+            self.get_group_path(group, format, mode)
+        } else if self.is_production() && mode.saves_to_resource_path() {
+            self.get_resource_path(key, mode)
+        } else {
+            return None;
+        };
+        path.exists().then_some(path)
+    }
+}
+
+/// Queues a job and sends its first progress event.
+fn start_job(world: &mut World, job: BackgroundJob) {
+    world.send_event(PersistProgress {
+        type_name: display_name(world, &job.key),
+        operation: job.operation,
+        bytes_done: 0,
+        bytes_total: job.bytes_total,
+        finished: false,
+    });
+    world.get_resource_or_init::<BackgroundJobs>().0.push(job);
+}
+
+/// Sends progress of background saves and loads, and finishes those done.
+pub(crate) fn poll_background_jobs(world: &mut World) {
+    let Some(mut jobs) = world.get_resource_mut::<BackgroundJobs>() else {
+        return;
+    };
+    let jobs = std::mem::take(&mut jobs.0);
+    let mut running = Vec::new();
+    for mut job in jobs {
+        if let Some(result) = check_ready(&mut job.task) {
+            finish_job(world, job, result);
+            continue;
+        }
+        let bytes_done = job.bytes_done.load(Ordering::Relaxed);
+        if bytes_done != job.reported {
+            job.reported = bytes_done;
+            world.send_event(PersistProgress {
+                type_name: display_name(world, &job.key),
+                operation: job.operation,
+                bytes_done,
+                bytes_total: job.bytes_total,
+                finished: false,
+            });
+        }
+        running.push(job);
+    }
+    world.resource_mut::<BackgroundJobs>().0.extend(running);
+}
+
+/// Records the outcome of a finished job and sends its events.
+fn finish_job(world: &mut World, job: BackgroundJob, result: PersistResult<Option<Vec<u8>>>) {
+    let name = display_name(world, &job.key);
+    world.send_event(PersistProgress {
+        type_name: name.clone(),
+        operation: job.operation,
+        bytes_done: job.bytes_done.load(Ordering::Relaxed),
+        bytes_total: job.bytes_total,
+        finished: true,
+    });
+    let mut manager = world.resource_mut::<PersistManager>();
+    if job.operation == PersistOperation::Save {
+        manager.background_saves.remove(&job.key);
+    }
+    match (job.operation, result) {
+        (PersistOperation::Save, Ok(_)) => {
+            debug!("Saved {} to {:?} in the background", name, job.path);
+            manager.record_save(&[job.key.as_str()], &job.path, job.started.elapsed());
+            let mode = manager.get_type_mode(&job.key);
+            manager.remove_legacy_file(&job.key, mode);
+            world.send_event(PersistSaved {
+                type_name: name,
+                path: job.path,
+                bytes: job.bytes_total,
+            });
+        }
+        (PersistOperation::Save, Err(e)) => {
+            manager.last_saved.remove(&job.key);
+            manager.content_hashes.remove(&job.key);
+            let e = e.for_type(&name);
+            error!("Failed to save {} in the background: {}", name, e);
+            manager.report_error(&job.key, &e);
+            world.send_event(PersistSaveFailed {
+                type_name: name,
+                error: e,
+            });
+        }
+        (PersistOperation::Load, Ok(bytes)) => {
+            // Read from memory by the reload instead of from disk again
+            manager
+                .prefetched
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(job.path.clone(), bytes.unwrap_or_default());
+            if let Err(e) = PersistManager::reload_named(world, &job.key) {
+                error!("Failed to load {} in the background: {}", name, e);
+            }
+            world
+                .resource_mut::<PersistManager>()
+                .prefetched
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&job.path);
+        }
+        (PersistOperation::Load, Err(e)) => {
+            let e = e.for_type(&name);
+            error!("Failed to load {} in the background: {}", name, e);
+            manager.report_error(&job.key, &e);
+        }
+    }
+}
+
+/// Writes `bytes` over `path` a chunk at a time, counting them in `done`.
+///
+/// Like other saves, the file is written next to `path` and renamed over it,
+/// but under a name of its own so an explicit save of the same file in the
+/// meantime can't write into it.
+fn write_in_chunks(path: &Path, bytes: &[u8], done: &AtomicU64) -> PersistResult<()> {
+    static NEXT_WRITE: AtomicU64 = AtomicU64::new(0);
+
+    create_parent_dir(path)?;
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.tmp",
+        NEXT_WRITE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp = path.with_file_name(name);
+    let write = || -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp)?;
+        for chunk in bytes.chunks(CHUNK_SIZE) {
+            file.write_all(chunk)?;
+            done.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        }
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        write_error(e).at(path)
+    })
+}
+
+/// Reads `path` a chunk at a time, counting the bytes in `done`.
+fn read_in_chunks(path: &Path, done: &AtomicU64) -> PersistResult<Vec<u8>> {
+    let read = || -> std::io::Result<Vec<u8>> {
+        let mut file = fs::File::open(path)?;
+        let mut bytes = Vec::new();
+        let mut chunk = vec![0; CHUNK_SIZE];
+        loop {
+            let read = file.read(&mut chunk)?;
+            if read == 0 {
+                return Ok(bytes);
+            }
+            bytes.extend_from_slice(&chunk[..read]);
+            done.fetch_add(read as u64, Ordering::Relaxed);
+        }
+    };
+    read().map_err(|e| read_error(path, e))
+}
@@ -0,0 +1,160 @@
+//! Quick-save and quick-load events, for binding keys like F5 and F9.
+
+use crate::{PersistError, PersistFile, PersistFormat, PersistManager, PersistMode, PersistResult};
+use bevy::ecs::event::EventCursor;
+use bevy::prelude::*;
+use log::{error, info};
+use std::path::PathBuf;
+
+/// Name quick-save files are resolved under in production
+const QUICK_SAVE_NAME: &str = "quicksave";
+
+/// Send to write every registered resource, or one group's, to the
+/// quick-save file.
+///
+/// Handled at the end of `PostUpdate`, so changes made earlier in the
+/// frame are included. Each group has its own quick save, apart from the
+/// one for every type:
+///
+/// ```ignore
+/// fn quick_save_keys(
+///     keys: Res<ButtonInput<KeyCode>>,
+///     mut saves: EventWriter<QuickSave>,
+///     mut loads: EventWriter<QuickLoad>,
+/// ) {
+///     if keys.just_pressed(KeyCode::F5) { saves.write(QuickSave::all()); }
+///     if keys.just_pressed(KeyCode::F9) { loads.write(QuickLoad::all()); }
+/// }
+/// ```
+#[derive(Event, Debug, Clone, Default)]
+pub struct QuickSave {
+    /// Group whose types to save, or every registered type if `None`
+    pub group: Option<String>,
+}
+
+impl QuickSave {
+    /// Quick-saves every registered type.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Quick-saves just the types in `group`.
+    pub fn group(group: impl Into<String>) -> Self {
+        Self {
+            group: Some(group.into()),
+        }
+    }
+}
+
+/// Send to restore the resources in the quick-save file written by the
+/// matching [`QuickSave`].
+///
+/// Restored resources are marked as changed, so auto-save writes them to
+/// their usual files like any other change. Nothing happens if there's no
+/// quick save yet.
+#[derive(Event, Debug, Clone, Default)]
+pub struct QuickLoad {
+    /// Group whose quick save to load, or the one for every type if `None`
+    pub group: Option<String>,
+}
+
+impl QuickLoad {
+    /// Loads the quick save of every registered type.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Loads the quick save of just the types in `group`.
+    pub fn group(group: impl Into<String>) -> Self {
+        Self {
+            group: Some(group.into()),
+        }
+    }
+}
+
+impl PersistManager {
+    /// Writes every registered resource, or just those in `group`, to its
+    /// quick-save file, replacing the last quick save.
+    ///
+    /// Session data is left out. Returns the file written.
+    pub fn quick_save(world: &mut World, group: Option<&str>) -> PersistResult<PathBuf> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let mut file = manager.capture_file(world, |type_name| {
+            group.map_or(true, |group| {
+                manager.get_type_group(type_name) == Some(group)
+            })
+        })?;
+        let path = manager.quick_save_path(group);
+        file.save_to_file_at(&path, PersistFormat::Ron, &manager.style, manager.now())?;
+        info!(
+            "Quick-saved {} resources to {:?}",
+            file.type_data.len(),
+            path
+        );
+        Ok(path)
+    }
+
+    /// Restores the resources in the quick-save file for `group`.
+    ///
+    /// Returns how many were restored, or 0 if there's no quick save.
+    pub fn quick_load(world: &mut World, group: Option<&str>) -> PersistResult<usize> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let path = manager.quick_save_path(group);
+        if !path.exists() {
+            info!("No quick save at {:?} to load", path);
+            return Ok(0);
+        }
+        let file = PersistFile::load_from_file_as(&path, PersistFormat::Ron)?;
+        let restored = Self::restore_file(world, file, |_| true)?;
+        info!("Quick-loaded {} resources from {:?}", restored, path);
+        Ok(restored)
+    }
+
+    /// File the quick save for `group`, or for every type, is written to.
+    pub fn quick_save_path(&self, group: Option<&str>) -> PathBuf {
+        let name = match group {
+            Some(group) => format!("{}_{}", QUICK_SAVE_NAME, group),
+            None => QUICK_SAVE_NAME.to_string(),
+        };
+        if self.is_production() {
+            self.resolve_path(&name, PersistMode::Dynamic, "ron")
+        } else {
+            let stem = self
+                .dev_file
+                .file_stem()
+                .map_or_else(|| "persist".into(), |stem| stem.to_string_lossy());
+            self.dev_file
+                .with_file_name(format!("{}_{}.ron", stem, name))
+        }
+    }
+}
+
+/// Writes the quick saves asked for by [`QuickSave`] events.
+pub(crate) fn handle_quick_saves(world: &mut World, mut cursor: Local<EventCursor<QuickSave>>) {
+    let Some(events) = world.get_resource::<Events<QuickSave>>() else {
+        return;
+    };
+    let requests: Vec<QuickSave> = cursor.read(events).cloned().collect();
+    for request in requests {
+        if let Err(e) = PersistManager::quick_save(world, request.group.as_deref()) {
+            error!("Failed to quick-save: {}", e);
+        }
+    }
+}
+
+/// Restores the quick saves asked for by [`QuickLoad`] events.
+pub(crate) fn handle_quick_loads(world: &mut World, mut cursor: Local<EventCursor<QuickLoad>>) {
+    let Some(events) = world.get_resource::<Events<QuickLoad>>() else {
+        return;
+    };
+    let requests: Vec<QuickLoad> = cursor.read(events).cloned().collect();
+    for request in requests {
+        if let Err(e) = PersistManager::quick_load(world, request.group.as_deref()) {
+            error!("Failed to quick-load: {}", e);
+        }
+    }
+}
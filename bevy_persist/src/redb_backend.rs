@@ -0,0 +1,113 @@
+//! A [`PersistBackend`] keeping every type in one embedded redb database (`redb` feature).
+
+use crate::{create_parent_dir, PersistBackend, PersistError, PersistResult};
+use log::error;
+use redb::{Database, ReadableTable, TableDefinition, TableError};
+use std::path::{Path, PathBuf};
+
+/// Table every type's data is stored in, keyed by storage key
+const TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("bevy_persist");
+
+/// Stores each type under its key in a redb database file.
+///
+/// Games with many frequently-saved types otherwise write lots of small
+/// files. Here every save is one transaction on a single file, so a crash
+/// mid-save leaves the previous data intact:
+///
+/// ```ignore
+/// app.add_plugins(
+///     PersistPlugin::new("MyCompany", "MyGame").with_backend(RedbBackend::new("saves.redb")),
+/// );
+/// ```
+pub struct RedbBackend {
+    path: PathBuf,
+    /// The open database, or why it couldn't be opened
+    db: Result<Database, PersistError>,
+}
+
+impl RedbBackend {
+    /// Opens or creates the database at `path`.
+    ///
+    /// If it can't be opened, every read and write fails with the reason,
+    /// which surfaces like any other failed save. Use [`open`](Self::open)
+    /// to handle it up front instead.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let db = open_database(&path);
+        if let Err(e) = &db {
+            error!("{}", e);
+        }
+        Self { path, db }
+    }
+
+    /// Opens or creates the database at `path`, failing if it can't be opened.
+    pub fn open(path: impl Into<PathBuf>) -> PersistResult<Self> {
+        let path = path.into();
+        let db = open_database(&path)?;
+        Ok(Self { path, db: Ok(db) })
+    }
+
+    /// Path of the database file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The open database, or why it couldn't be opened.
+    fn db(&self) -> PersistResult<&Database> {
+        self.db.as_ref().map_err(Clone::clone)
+    }
+}
+
+/// Opens or creates the database at `path`, creating its directory if needed.
+fn open_database(path: &Path) -> PersistResult<Database> {
+    create_parent_dir(path)?;
+    Database::create(path)
+        .map_err(|e| PersistError::backend(format!("Failed to open {}: {}", path.display(), e)))
+}
+
+impl PersistBackend for RedbBackend {
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+        let txn = self.db()?.begin_read().map_err(PersistError::backend)?;
+        let table = match txn.open_table(TABLE) {
+            Ok(table) => table,
+            // Nothing has been written yet
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(PersistError::backend(e)),
+        };
+        let value = table.get(key).map_err(PersistError::backend)?;
+        Ok(value.map(|value| value.value().to_vec()))
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()> {
+        let txn = self.db()?.begin_write().map_err(PersistError::backend)?;
+        {
+            let mut table = txn.open_table(TABLE).map_err(PersistError::backend)?;
+            table.insert(key, bytes).map_err(PersistError::backend)?;
+        }
+        txn.commit().map_err(PersistError::backend)
+    }
+
+    fn write_if(&self, key: &str, expected: Option<&[u8]>, bytes: &[u8]) -> PersistResult<bool> {
+        let txn = self.db()?.begin_write().map_err(PersistError::backend)?;
+        {
+            let mut table = txn.open_table(TABLE).map_err(PersistError::backend)?;
+            let current = table.get(key).map_err(PersistError::backend)?;
+            if current.as_ref().map(|value| value.value()) != expected {
+                return Ok(false);
+            }
+            drop(current);
+            table.insert(key, bytes).map_err(PersistError::backend)?;
+        }
+        txn.commit().map_err(PersistError::backend)?;
+        Ok(true)
+    }
+
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        let txn = self.db()?.begin_write().map_err(PersistError::backend)?;
+        {
+            let mut table = txn.open_table(TABLE).map_err(PersistError::backend)?;
+            table.remove(key).map_err(PersistError::backend)?;
+        }
+        txn.commit().map_err(PersistError::backend)
+    }
+}
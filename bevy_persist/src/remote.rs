@@ -0,0 +1,124 @@
+//! [Bevy Remote Protocol](bevy::remote) methods for persisted resources (`remote` feature).
+//!
+//! ```ignore
+//! use bevy::remote::{http::RemoteHttpPlugin, RemotePlugin};
+//! use bevy_persist::remote::PersistRemoteExt;
+//!
+//! app.add_plugins(RemotePlugin::default().with_persist_methods())
+//!     .add_plugins(RemoteHttpPlugin::default());
+//! ```
+//!
+//! | Method | Params | Result |
+//! |---|---|---|
+//! | `persist/list` | none | every registered type, as [`PersistTypeInfo`](crate::PersistTypeInfo) |
+//! | `persist/get` | `{"type_name"}` | `{"type_name", "data"}` |
+//! | `persist/set` | `{"type_name", "data"}` | `{"type_name", "data"}` after patching |
+//! | `persist/save` | `{"type_name"}` | `null` |
+//!
+//! `persist/set` only replaces the fields in `data` and goes through
+//! auto-save like any other change. `persist/save` writes immediately,
+//! including types with auto-save turned off.
+
+use crate::{PersistData, PersistError, PersistManager};
+use bevy::prelude::*;
+use bevy::remote::{error_codes, BrpError, BrpResult, RemotePlugin};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// The remote method that lists registered types.
+pub const PERSIST_LIST_METHOD: &str = "persist/list";
+
+/// The remote method that reads a resource's persisted fields.
+pub const PERSIST_GET_METHOD: &str = "persist/get";
+
+/// The remote method that patches a resource's persisted fields.
+pub const PERSIST_SET_METHOD: &str = "persist/set";
+
+/// The remote method that saves a resource right away.
+pub const PERSIST_SAVE_METHOD: &str = "persist/save";
+
+/// Adds the `persist/*` methods to a [`RemotePlugin`].
+pub trait PersistRemoteExt {
+    /// Registers `persist/list`, `persist/get`, `persist/set` and `persist/save`.
+    fn with_persist_methods(self) -> Self;
+}
+
+impl PersistRemoteExt for RemotePlugin {
+    fn with_persist_methods(self) -> Self {
+        self.with_method(PERSIST_LIST_METHOD, process_persist_list_request)
+            .with_method(PERSIST_GET_METHOD, process_persist_get_request)
+            .with_method(PERSIST_SET_METHOD, process_persist_set_request)
+            .with_method(PERSIST_SAVE_METHOD, process_persist_save_request)
+    }
+}
+
+/// Params for `persist/get` and `persist/save`.
+#[derive(Deserialize)]
+struct TypeParams {
+    type_name: String,
+}
+
+/// Params for `persist/set`.
+#[derive(Deserialize)]
+struct SetParams {
+    type_name: String,
+    data: HashMap<String, Value>,
+}
+
+/// Handles a `persist/list` request.
+pub fn process_persist_list_request(In(_params): In<Option<Value>>, world: &World) -> BrpResult {
+    let manager = world.get_resource::<PersistManager>().ok_or_else(|| {
+        to_brp_error(PersistError::ResourceNotFound("PersistManager".to_string()))
+    })?;
+    let types: Vec<_> = manager.registered_types().collect();
+    serde_json::to_value(types).map_err(|e| to_brp_error(PersistError::serialization(e)))
+}
+
+/// Handles a `persist/get` request.
+pub fn process_persist_get_request(In(params): In<Option<Value>>, world: &World) -> BrpResult {
+    let TypeParams { type_name } = parse_params(params)?;
+    let data = PersistManager::live_data(world, &type_name).map_err(to_brp_error)?;
+    Ok(json!({ "type_name": type_name, "data": data.to_json() }))
+}
+
+/// Handles a `persist/set` request.
+pub fn process_persist_set_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let SetParams { type_name, data } = parse_params(params)?;
+    let data = PersistManager::patch_live_data(world, &type_name, PersistData::from_json(data))
+        .map_err(to_brp_error)?;
+    Ok(json!({ "type_name": type_name, "data": data.to_json() }))
+}
+
+/// Handles a `persist/save` request.
+pub fn process_persist_save_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let TypeParams { type_name } = parse_params(params)?;
+    PersistManager::save_named(world, &type_name).map_err(to_brp_error)?;
+    Ok(Value::Null)
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, BrpError> {
+    let params = params.ok_or_else(|| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: "Params not provided".to_string(),
+        data: None,
+    })?;
+    serde_json::from_value(params).map_err(|e| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+        data: None,
+    })
+}
+
+fn to_brp_error(error: PersistError) -> BrpError {
+    let code = match error {
+        PersistError::ResourceNotFound(_) => error_codes::INVALID_PARAMS,
+        _ => error_codes::INTERNAL_ERROR,
+    };
+    BrpError {
+        code,
+        message: error.to_string(),
+        data: None,
+    }
+}
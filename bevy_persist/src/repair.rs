@@ -0,0 +1,325 @@
+//! Salvaging what's readable from damaged save files.
+
+use crate::{read_error, PersistError, PersistFile, PersistFormat, PersistResult};
+use std::fs;
+use std::path::Path;
+
+/// What [`PersistFile::load_lenient`] could and couldn't read.
+#[derive(Debug, Default, Clone)]
+pub struct PersistRepairReport {
+    /// Types whose data was read intact, in file order
+    pub recovered: Vec<String>,
+    /// Entries that couldn't be read, by type name if that much was
+    /// readable, with the reason
+    pub skipped: Vec<(Option<String>, PersistError)>,
+}
+
+impl PersistRepairReport {
+    /// Whether the whole file was read.
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+impl PersistFile {
+    /// Loads as much of a damaged file as can be read, e.g. one cut short
+    /// by a crash mid-write.
+    ///
+    /// Each type's entry is read on its own, so a truncated or corrupted
+    /// entry costs just that type's data rather than the whole file:
+    ///
+    /// ```ignore
+    /// let (file, report) = PersistFile::load_lenient("savegame.ron")?;
+    /// for (type_name, error) in &report.skipped {
+    ///     warn!("Couldn't recover {:?}: {}", type_name, error);
+    /// }
+    /// ```
+    ///
+    /// The format comes from the file's extension. RON and JSON files are
+    /// salvaged entry by entry; other formats are read whole. Only fails if
+    /// the file can't be read, and a missing file loads as an empty one.
+    pub fn load_lenient(path: impl AsRef<Path>) -> PersistResult<(Self, PersistRepairReport)> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok((Self::new(), PersistRepairReport::default()));
+        }
+        let content = fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+        let (file, mut report) = Self::parse_lenient(&content, PersistFormat::from_path(path));
+        for (_, error) in &mut report.skipped {
+            *error = error.clone().at(path);
+        }
+        Ok((file, report))
+    }
+
+    /// Like [`load_lenient`](Self::load_lenient), from a string in the
+    /// given format.
+    pub fn parse_lenient(content: &str, format: PersistFormat) -> (Self, PersistRepairReport) {
+        let mut report = PersistRepairReport::default();
+        let error = match Self::parse_as(content, format) {
+            Ok(file) => {
+                report.recovered = file.type_data.keys().cloned().collect();
+                return (file, report);
+            }
+            Err(e) => e,
+        };
+        let mut file = Self::new();
+        if !matches!(format, PersistFormat::Ron | PersistFormat::Json) {
+            report.skipped.push((None, error));
+            return (file, report);
+        }
+
+        let entries = split_entries(content, format);
+        if entries.texts.is_empty() {
+            report.skipped.push((None, error));
+            return (file, report);
+        }
+        let mut last_failed = false;
+        for text in entries.texts {
+            if read_entry(&mut file, &mut report, text, format).is_ok() {
+                last_failed = false;
+                continue;
+            }
+            // A missing bracket runs the entry into the ones after it
+            let pieces = match entries.indent {
+                Some(indent) => split_at_keys(text, indent),
+                None => vec![text],
+            };
+            for piece in pieces {
+                match read_entry(&mut file, &mut report, piece, format) {
+                    Ok(()) => last_failed = false,
+                    Err(e) => {
+                        let key = entry_key(piece);
+                        // Leftovers of a broken entry have no key of their own
+                        if key.is_some() || !last_failed {
+                            report.skipped.push((key, e));
+                        }
+                        last_failed = true;
+                    }
+                }
+            }
+        }
+        (file, report)
+    }
+}
+
+/// Reads one `key: value` entry into `file`.
+fn read_entry(
+    file: &mut PersistFile,
+    report: &mut PersistRepairReport,
+    text: &str,
+    format: PersistFormat,
+) -> PersistResult<()> {
+    let part = PersistFile::parse_as(&format!("{{{}}}", text), format)?;
+    match entry_key(text).as_deref() {
+        Some("last_saved") => file.last_saved = part.last_saved,
+        Some("version") => file.version = part.version,
+        _ => {
+            for (type_name, data) in part.type_data {
+                report.recovered.push(type_name.clone());
+                file.type_data.insert(type_name, data);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Entries of a file's top-level map, as text.
+struct Entries<'a> {
+    /// Each `key: value` entry, in file order
+    texts: Vec<&'a str>,
+    /// Indentation of the top-level keys, if each starts its own line
+    indent: Option<&'a str>,
+}
+
+/// Splits the top-level map of a RON or JSON file into its entries.
+///
+/// Brackets are matched outside strings and comments; an entry ends at a
+/// comma or closing brace at the top level, or at the end of the text if
+/// the file was cut short.
+fn split_entries<'a>(content: &'a str, format: PersistFormat) -> Entries<'a> {
+    let bytes = content.as_bytes();
+    let mut entries = Entries {
+        texts: Vec::new(),
+        indent: None,
+    };
+    let open = skip_trivia(bytes, 0);
+    if bytes.get(open) != Some(&b'{') {
+        return entries;
+    }
+
+    let push = |entries: &mut Entries<'a>, start: usize, end: usize| {
+        let start = skip_trivia(bytes, start).min(end);
+        let text = content[start..end].trim();
+        if text.is_empty() {
+            return;
+        }
+        if entries.texts.is_empty() {
+            let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+            let indent = &content[line_start..start];
+            entries.indent = (!indent.is_empty() && indent.trim().is_empty()).then_some(indent);
+        }
+        entries.texts.push(text);
+    };
+
+    let mut depth = 0usize;
+    let mut start = open + 1;
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i = skip_string(bytes, i);
+                continue;
+            }
+            b'\'' if format == PersistFormat::Ron => {
+                i = skip_char(content, i);
+                continue;
+            }
+            b'r' if format == PersistFormat::Ron && starts_raw_string(bytes, i) => {
+                i = skip_raw_string(bytes, i);
+                continue;
+            }
+            b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                i = skip_trivia(bytes, i);
+                continue;
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' if depth == 0 => {}
+            b')' | b']' | b'}' if depth > 0 => depth -= 1,
+            b'}' | b',' if depth == 0 => {
+                push(&mut entries, start, i);
+                start = i + 1;
+                if bytes[i] == b'}' && skip_trivia(bytes, start) == bytes.len() {
+                    return entries;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    push(&mut entries, start, bytes.len());
+    entries
+}
+
+/// Splits an entry that ran into the ones after it at each line starting
+/// a top-level key, stopping at the line closing the map.
+fn split_at_keys<'a>(text: &'a str, indent: &str) -> Vec<&'a str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        if line_start == 0 || line.trim().is_empty() {
+            continue;
+        }
+        let starts_key = line
+            .strip_prefix(indent)
+            .is_some_and(|rest| rest.starts_with('"'));
+        let closes_map = line.len() - line.trim_start().len() < indent.len();
+        if starts_key || closes_map {
+            pieces.push(&text[start..line_start]);
+            start = line_start;
+        }
+        if closes_map {
+            return trim_pieces(pieces);
+        }
+    }
+    pieces.push(&text[start..]);
+    trim_pieces(pieces)
+}
+
+/// Pieces without surrounding whitespace or trailing commas.
+fn trim_pieces(pieces: Vec<&str>) -> Vec<&str> {
+    pieces
+        .into_iter()
+        .map(|piece| piece.trim().trim_end_matches(',').trim_end())
+        .filter(|piece| !piece.is_empty())
+        .collect()
+}
+
+/// Key of an entry, if it starts with a readable quoted one.
+fn entry_key(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let start = skip_trivia(bytes, 0);
+    if bytes.get(start) != Some(&b'"') {
+        return None;
+    }
+    let end = skip_string(bytes, start);
+    serde_json::from_str(text.get(start..end)?).ok()
+}
+
+/// Index after whitespace and comments starting at `i`.
+fn skip_trivia(bytes: &[u8], mut i: usize) -> usize {
+    loop {
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        match (bytes.get(i), bytes.get(i + 1)) {
+            (Some(b'/'), Some(b'/')) => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            (Some(b'/'), Some(b'*')) => {
+                i += 2;
+                while i < bytes.len() && !bytes[i..].starts_with(b"*/") {
+                    i += 1;
+                }
+                i = (i + 2).min(bytes.len());
+            }
+            _ => return i,
+        }
+    }
+}
+
+/// Index after the quoted string starting at `i`.
+fn skip_string(bytes: &[u8], i: usize) -> usize {
+    let mut j = i + 1;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' => j += 2,
+            b'"' => return j + 1,
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Index after the RON char literal starting at `i`.
+fn skip_char(content: &str, i: usize) -> usize {
+    let bytes = content.as_bytes();
+    let mut j = i + 1;
+    match content.get(j..).and_then(|rest| rest.chars().next()) {
+        Some('\\') => j += 2,
+        Some(c) => j += c.len_utf8(),
+        None => return bytes.len(),
+    }
+    // Escapes like `\u{1F600}` run on to the closing quote
+    while j < bytes.len() && bytes[j] != b'\'' {
+        j += 1;
+    }
+    (j + 1).min(bytes.len())
+}
+
+/// Whether a RON raw string like `r#"..."#` starts at `i`.
+fn starts_raw_string(bytes: &[u8], i: usize) -> bool {
+    let after_word = i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_');
+    let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+    !after_word && bytes.get(i + 1 + hashes) == Some(&b'"')
+}
+
+/// Index after the RON raw string starting at `i`.
+fn skip_raw_string(bytes: &[u8], i: usize) -> usize {
+    let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+    let mut j = i + hashes + 2;
+    while j < bytes.len() {
+        let closes = bytes
+            .get(j + 1..j + 1 + hashes)
+            .is_some_and(|end| end.iter().all(|&b| b == b'#'));
+        if bytes[j] == b'"' && closes {
+            return (j + 1 + hashes).min(bytes.len());
+        }
+        j += 1;
+    }
+    bytes.len()
+}
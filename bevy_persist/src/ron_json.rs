@@ -0,0 +1,339 @@
+//! Converting RON text to JSON without knowing its type.
+//!
+//! `ron` drops enum variant names when deserializing untyped values, so a
+//! unit variant like `Windowed` comes out as `null`. This reads the text
+//! itself and lays variants out the way `serde_json` serializes them:
+//! `Windowed` as `"Windowed"`, `Sized(2)` as `{"Sized": 2}` and
+//! `Custom(w: 3)` as `{"Custom": {"w": 3}}`. Unnamed structs and tuples
+//! become objects and arrays, options their value or `null`.
+
+use serde_json::{Map, Number, Value};
+
+/// Converts a RON value to JSON, or `None` if it isn't valid RON.
+pub(crate) fn to_json(ron: &str) -> Option<Value> {
+    let mut reader = Reader { text: ron, pos: 0 };
+    reader.skip_attributes();
+    let value = reader.value()?;
+    reader.skip_trivia();
+    reader.rest().is_empty().then_some(value)
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_trivia();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skips whitespace and comments, including nested block comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let mut depth = 0;
+                while !self.rest().is_empty() {
+                    if self.rest().starts_with("/*") {
+                        depth += 1;
+                        self.pos += 2;
+                    } else if self.rest().starts_with("*/") {
+                        depth -= 1;
+                        self.pos += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        self.bump();
+                    }
+                }
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Skips `#![enable(...)]` extension attributes.
+    fn skip_attributes(&mut self) {
+        self.skip_trivia();
+        while self.rest().starts_with("#!") {
+            self.pos += self
+                .rest()
+                .find(']')
+                .map_or(self.rest().len(), |end| end + 1);
+            self.skip_trivia();
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_trivia();
+        let rest = self.rest();
+        match self.peek()? {
+            '"' => self.string().map(Value::String),
+            '\'' => self.char().map(Value::String),
+            '[' => {
+                self.bump();
+                self.list(']').map(Value::Array)
+            }
+            '{' => {
+                self.bump();
+                self.map()
+            }
+            '(' => {
+                self.bump();
+                self.parenthesized()
+            }
+            'r' if rest.starts_with("r\"") || rest.starts_with("r#") => {
+                self.raw_string().map(Value::String)
+            }
+            'b' if rest.starts_with("b\"") || rest.starts_with("b'") => {
+                self.bump();
+                self.value()
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.number(),
+            c if c == '_' || c.is_alphabetic() => self.named(),
+            _ => None,
+        }
+    }
+
+    /// A value that starts with an identifier: keywords, options and variants.
+    fn named(&mut self) -> Option<Value> {
+        let ident = self.ident()?;
+        match ident {
+            "true" => return Some(Value::Bool(true)),
+            "false" => return Some(Value::Bool(false)),
+            "None" => return Some(Value::Null),
+            "inf" | "NaN" => return Some(Value::Null),
+            _ => {}
+        }
+        if !self.eat('(') {
+            return Some(Value::String(ident.to_string()));
+        }
+        let inner = self.parenthesized()?;
+        if ident == "Some" {
+            return Some(inner);
+        }
+        let mut variant = Map::new();
+        variant.insert(ident.to_string(), inner);
+        Some(Value::Object(variant))
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_trivia();
+        if self.rest().starts_with("r#") {
+            self.pos += 2;
+        }
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c == '_' || c.is_alphanumeric()))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        (len > 0).then(|| &rest[..len])
+    }
+
+    /// The inside of `(...)`: a struct's fields, or a tuple's elements.
+    fn parenthesized(&mut self) -> Option<Value> {
+        self.skip_trivia();
+        let start = self.pos;
+        let is_struct = self.ident().is_some() && {
+            self.skip_trivia();
+            self.rest().starts_with(':') && !self.rest().starts_with("::")
+        };
+        self.pos = start;
+        if is_struct {
+            return self.fields().map(Value::Object);
+        }
+
+        let mut elements = self.list(')')?;
+        Some(match elements.len() {
+            0 => Value::Null,
+            // Newtypes are transparent in JSON
+            1 => elements.remove(0),
+            _ => Value::Array(elements),
+        })
+    }
+
+    fn fields(&mut self) -> Option<Map<String, Value>> {
+        let mut fields = Map::new();
+        loop {
+            if self.eat(')') {
+                return Some(fields);
+            }
+            let name = self.ident()?;
+            if !self.eat(':') {
+                return None;
+            }
+            fields.insert(name.to_string(), self.value()?);
+            if !self.eat(',') {
+                return self.eat(')').then_some(fields);
+            }
+        }
+    }
+
+    fn list(&mut self, close: char) -> Option<Vec<Value>> {
+        let mut elements = Vec::new();
+        loop {
+            if self.eat(close) {
+                return Some(elements);
+            }
+            elements.push(self.value()?);
+            if !self.eat(',') {
+                return self.eat(close).then_some(elements);
+            }
+        }
+    }
+
+    fn map(&mut self) -> Option<Value> {
+        let mut entries = Map::new();
+        loop {
+            if self.eat('}') {
+                return Some(Value::Object(entries));
+            }
+            // JSON keys are strings, so other keys are written as JSON text
+            let key = match self.value()? {
+                Value::String(key) => key,
+                key => key.to_string(),
+            };
+            if !self.eat(':') {
+                return None;
+            }
+            entries.insert(key, self.value()?);
+            if !self.eat(',') {
+                return self.eat('}').then_some(Value::Object(entries));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.bump();
+        let mut string = String::new();
+        loop {
+            match self.bump()? {
+                '"' => return Some(string),
+                '\\' => string.push(self.escape()?),
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn char(&mut self) -> Option<String> {
+        self.bump();
+        let c = match self.bump()? {
+            '\\' => self.escape()?,
+            c => c,
+        };
+        (self.bump()? == '\'').then(|| c.to_string())
+    }
+
+    fn escape(&mut self) -> Option<char> {
+        Some(match self.bump()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'u' => {
+                let rest = self.rest().strip_prefix('{')?;
+                let end = rest.find('}')?;
+                let c = char::from_u32(u32::from_str_radix(&rest[..end], 16).ok()?)?;
+                self.pos += end + 2;
+                c
+            }
+            'x' => {
+                let hex = self.rest().get(..2)?;
+                self.pos += 2;
+                char::from(u8::from_str_radix(hex, 16).ok()?)
+            }
+            c => c,
+        })
+    }
+
+    fn raw_string(&mut self) -> Option<String> {
+        self.bump();
+        let hashes = self.rest().len() - self.rest().trim_start_matches('#').len();
+        self.pos += hashes;
+        if self.bump()? != '"' {
+            return None;
+        }
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        let end = self.rest().find(&terminator)?;
+        let string = self.rest()[..end].to_string();
+        self.pos += end + terminator.len();
+        Some(string)
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.' | '_')))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        let text: String = rest[..len].chars().filter(|&c| c != '_').collect();
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        if matches!(unsigned, "inf" | "NaN") {
+            return Some(Value::Null);
+        }
+
+        let radix = match unsigned.get(..2) {
+            Some("0x") => 16,
+            Some("0b") => 2,
+            Some("0o") => 8,
+            _ => 10,
+        };
+        let digits = if radix == 10 {
+            strip_suffix(unsigned, &["i", "u", "f"])
+        } else {
+            strip_suffix(&unsigned[2..], &["i", "u"])
+        };
+        if let Ok(n) = u64::from_str_radix(digits, radix) {
+            return Some(if negative {
+                i64::try_from(n)
+                    .ok()
+                    .map_or_else(|| Value::from(-(n as f64)), |n| Value::from(-n))
+            } else {
+                Value::from(n)
+            });
+        }
+        let n: f64 = digits.parse().ok()?;
+        Number::from_f64(if negative { -n } else { n }).map(Value::Number)
+    }
+}
+
+/// Strips a type suffix like `u8` or `f32` from a number literal.
+fn strip_suffix<'a>(digits: &'a str, prefixes: &[&str]) -> &'a str {
+    for prefix in prefixes {
+        for bits in ["8", "16", "32", "64", "128", "size"] {
+            if let Some(stripped) = digits.strip_suffix(&format!("{}{}", prefix, bits)) {
+                return stripped;
+            }
+        }
+    }
+    digits
+}
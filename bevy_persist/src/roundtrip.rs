@@ -0,0 +1,71 @@
+//! A one-line check that a persisted type survives being saved and loaded.
+
+use crate::{PersistFile, PersistFormat, Persistable};
+use std::fmt::Debug;
+
+/// Formats files can be written in with the enabled features
+const FORMATS: &[PersistFormat] = &[
+    PersistFormat::Ron,
+    PersistFormat::Json,
+    #[cfg(feature = "toml")]
+    PersistFormat::Toml,
+];
+
+/// Panics unless `value` loads back unchanged from a file in every enabled format.
+///
+/// Each format goes through the same steps as a real save and load: the
+/// value is turned into [`PersistData`](crate::PersistData), written into a
+/// [`PersistFile`], parsed again, and loaded into `T::default()`. Fields
+/// that are skipped, lose precision or can't be written in a format show
+/// up here instead of in a player's save.
+///
+/// ```ignore
+/// #[test]
+/// fn settings_roundtrip() {
+///     bevy_persist::check_roundtrip(&Settings { volume: 0.25, ..default() });
+/// }
+/// ```
+pub fn check_roundtrip<T: Persistable + Default + PartialEq + Debug>(value: &T) {
+    let type_name = T::type_name();
+    for &format in FORMATS {
+        let mut file = PersistFile::new();
+        file.set_type_data(type_name.to_string(), value.to_persist_data());
+        let content = file.to_string_as(format).unwrap_or_else(|e| {
+            panic!(
+                "{} can't be saved as {}: {}",
+                type_name,
+                format.extension(),
+                e
+            )
+        });
+
+        let parsed = PersistFile::parse_as(&content, format).unwrap_or_else(|e| {
+            panic!(
+                "{} saved as {} can't be parsed: {}\n{}",
+                type_name,
+                format.extension(),
+                e,
+                content
+            )
+        });
+        let Some(data) = parsed.get_type_data(type_name) else {
+            panic!(
+                "{} is missing from its {} file:\n{}",
+                type_name,
+                format.extension(),
+                content
+            );
+        };
+        let mut loaded = T::default();
+        loaded.load_from_persist_data(data);
+
+        assert_eq!(
+            &loaded,
+            value,
+            "{} changed on its way through {}:\n{}",
+            type_name,
+            format.extension(),
+            content
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Keyed stores that persist the same type separately per player or session.
+
+use crate::{
+    read_error, remove_file_if_exists, PersistBackend, PersistData, PersistFile, PersistFormat,
+    PersistManager, PersistMode, PersistResult, Persistable,
+};
+use log::{debug, error};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory every scope's files are kept in, beside the type's usual file
+const SCOPES_DIR: &str = "scopes";
+
+impl PersistManager {
+    /// A store keeping data under `key`, apart from the live resources and
+    /// every other scope.
+    ///
+    /// For servers that keep one copy of a type per player or session:
+    ///
+    /// ```ignore
+    /// let mut scope = manager.scoped("player:1234");
+    /// scope.save(&inventory)?;
+    /// let inventory: Inventory = scope.load().unwrap_or_default();
+    /// ```
+    ///
+    /// A scope's files go in a `scopes/<key>` directory next to where each
+    /// type is normally saved, so modes, formats, groups and pinned files
+    /// apply as usual. Characters other than ASCII letters, digits, `-` and
+    /// `_` become `_` in the directory name, so `player:1234` is stored in
+    /// `scopes/player_1234`. With a [`PersistBackend`], each type is stored
+    /// under `scopes/<key>/<storage key>` instead.
+    pub fn scoped(&mut self, key: impl Into<String>) -> PersistScope<'_> {
+        PersistScope {
+            manager: self,
+            key: key.into(),
+        }
+    }
+}
+
+/// Data persisted under one key, from [`PersistManager::scoped`].
+///
+/// Saves aren't applied to the type's resource and don't count as its
+/// saves; the live resource and the scopes never see each other's data.
+/// Session-mode types, and every type once saves have fallen back to
+/// memory, are kept in memory per scope.
+pub struct PersistScope<'a> {
+    manager: &'a mut PersistManager,
+    key: String,
+}
+
+impl PersistScope<'_> {
+    /// Key this scope was opened with.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// File a type's data is saved to in this scope, if it's saved to disk.
+    pub fn path(&self, type_name: &str) -> Option<PathBuf> {
+        let type_name = &*self.manager.key(type_name);
+        if self.backend(type_name).is_some() {
+            return None;
+        }
+        let base = self
+            .manager
+            .get_resource_path(type_name, self.manager.get_type_mode(type_name));
+        let file_name = base.file_name()?;
+        let dir = base.parent().unwrap_or(&base);
+        Some(
+            dir.join(SCOPES_DIR)
+                .join(scope_dir_name(&self.key))
+                .join(file_name),
+        )
+    }
+
+    /// Saves `value` in this scope, returning the file it was written to.
+    pub fn save<T: Persistable>(&mut self, value: &T) -> PersistResult<Option<PathBuf>> {
+        let key = self.manager.storage_key_of::<T>();
+        self.save_data(&key, value.to_persist_data())
+    }
+
+    /// Saves `data` as a type's data in this scope.
+    pub fn save_data(
+        &mut self,
+        type_name: &str,
+        data: PersistData,
+    ) -> PersistResult<Option<PathBuf>> {
+        let type_name = &*self.manager.key(type_name);
+        let manager = &mut *self.manager;
+        if manager.get_type_mode(type_name) == PersistMode::Session {
+            manager
+                .scoped_memory
+                .entry(self.key.clone())
+                .or_default()
+                .insert(type_name.to_string(), data);
+            return Ok(None);
+        }
+        if manager.read_only {
+            debug!("Read-only, not saving {} for {}", type_name, self.key);
+            return Ok(None);
+        }
+        if let Some((backend, key)) = self.backend(type_name) {
+            let manager = &*self.manager;
+            let data = manager
+                .seal_fields(type_name, data)
+                .map_err(|e| e.for_type(manager.get_type_name(type_name)))?;
+            let mut file = PersistFile::new();
+            file.set_type_data_at(type_name.to_string(), data, manager.now());
+            let bytes =
+                manager.encode_standalone(type_name, &mut file, self.format(type_name), false)?;
+            backend
+                .write(&key, &bytes)
+                .map_err(|e| e.for_type(manager.get_type_name(type_name)))?;
+            debug!(
+                "Saved {} for {} to the backend under {}",
+                type_name, self.key, key
+            );
+            return Ok(Some(PathBuf::from(key)));
+        }
+        let Some(path) = self.path(type_name) else {
+            debug!(
+                "{} isn't saved to disk, not saving it for {}",
+                type_name, self.key
+            );
+            return Ok(None);
+        };
+
+        let manager = &*self.manager;
+        let data = manager
+            .seal_fields(type_name, data)
+            .map_err(|e| e.for_type(manager.get_type_name(type_name)))?;
+        let format = self.format(type_name);
+        let mut file = self
+            .read_file(type_name, &path, format)
+            .map_err(|e| e.for_type(manager.get_type_name(type_name)).at(&path))?;
+        file.set_type_data_at(type_name.to_string(), data, manager.now());
+        manager
+            .save_standalone(type_name, &mut file, &path, format, false)
+            .map_err(|e| e.for_type(manager.get_type_name(type_name)))?;
+        debug!("Saved {} for {} to {:?}", type_name, self.key, path);
+        Ok(Some(path))
+    }
+
+    /// Loads `T` from this scope, or `None` if nothing is saved for it.
+    ///
+    /// Fields that weren't saved keep their values from `T::default()`.
+    pub fn load<T: Persistable + Default>(&self) -> Option<T> {
+        let data = self.load_data(&self.manager.storage_key_of::<T>())?;
+        let mut value = T::default();
+        value.load_from_persist_data(&data);
+        Some(value)
+    }
+
+    /// A type's data in this scope, layered over its shipped defaults.
+    pub fn load_data(&self, type_name: &str) -> Option<PersistData> {
+        let manager = &*self.manager;
+        let type_name = &*manager.key(type_name);
+        let saved = match manager
+            .scoped_memory
+            .get(&self.key)
+            .and_then(|types| types.get(type_name))
+        {
+            Some(data) => Some(data.clone()),
+            None => self.read_saved(type_name),
+        }?;
+        let saved = manager.unseal_fields(type_name, saved);
+        let mut data = manager
+            .type_defaults
+            .get(type_name)
+            .cloned()
+            .unwrap_or_default();
+        data.merge(saved);
+        Some(data)
+    }
+
+    /// Removes `T`'s data from this scope.
+    pub fn remove<T: Persistable>(&mut self) -> PersistResult<()> {
+        let key = self.manager.storage_key_of::<T>();
+        self.remove_data(&key)
+    }
+
+    /// Removes a type's data from this scope, deleting its file once it's empty.
+    pub fn remove_data(&mut self, type_name: &str) -> PersistResult<()> {
+        let type_name = &*self.manager.key(type_name);
+        let manager = &mut *self.manager;
+        if let Some(types) = manager.scoped_memory.get_mut(&self.key) {
+            types.remove(type_name);
+        }
+        if manager.read_only {
+            debug!("Not removing {} for {} from disk", type_name, self.key);
+            return Ok(());
+        }
+        if let Some((backend, key)) = self.backend(type_name) {
+            let manager = &*self.manager;
+            return backend
+                .remove(&key)
+                .map_err(|e| e.for_type(manager.get_type_name(type_name)));
+        }
+        let Some(path) = self.path(type_name) else {
+            return Ok(());
+        };
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let manager = &*self.manager;
+        let format = self.format(type_name);
+        let mut file = self
+            .read_file(type_name, &path, format)
+            .map_err(|e| e.for_type(manager.get_type_name(type_name)).at(&path))?;
+        let removed = file.type_data.remove(type_name).is_some()
+            | file
+                .type_data
+                .remove(manager.get_type_name(type_name))
+                .is_some();
+        if !removed {
+            return Ok(());
+        }
+        if file.type_data.is_empty() {
+            return remove_file_if_exists(&path);
+        }
+        manager.save_standalone(type_name, &mut file, &path, format, false)
+    }
+
+    /// Format of the file a type is saved to, matching its usual file.
+    fn format(&self, type_name: &str) -> PersistFormat {
+        let manager = &*self.manager;
+        let mode = manager.get_type_mode(type_name);
+        let base = manager.get_resource_path(type_name, mode);
+        if base == manager.dev_file {
+            return PersistFormat::from_path(&base);
+        }
+        match manager.resolve_type_group(type_name, mode) {
+            Some(group) => manager.get_group_format(group, type_name),
+            None => manager.get_type_format(type_name),
+        }
+    }
+
+    /// Reads the whole scope file at `path`, or an empty file if there's none yet.
+    fn read_file(
+        &self,
+        type_name: &str,
+        path: &Path,
+        format: PersistFormat,
+    ) -> PersistResult<PersistFile> {
+        if !path.exists() {
+            return Ok(PersistFile::new());
+        }
+        let bytes = fs::read(path).map_err(|e| read_error(path, e))?;
+        let content = self.manager.decode_standalone(type_name, bytes)?;
+        PersistFile::parse_as(&content, format)
+    }
+
+    /// The manager's backend and the key a type's data is stored under in
+    /// this scope, if the type is kept in one.
+    fn backend(&self, type_name: &str) -> Option<(&dyn PersistBackend, String)> {
+        let manager = &*self.manager;
+        let backend = manager.backend.as_deref()?;
+        if manager.get_type_mode(type_name) == PersistMode::Embed {
+            return None;
+        }
+        let key = format!("{}/{}/{}", SCOPES_DIR, self.key, type_name);
+        Some((backend, key))
+    }
+
+    /// Reads just the data saved for a type in this scope.
+    fn read_saved(&self, type_name: &str) -> Option<PersistData> {
+        let manager = &*self.manager;
+        if let Some((backend, key)) = self.backend(type_name) {
+            return manager.read_backend(backend, type_name, &key, self.format(type_name));
+        }
+        let path = self.path(type_name)?;
+        if !path.exists() {
+            return None;
+        }
+        let manager = &*self.manager;
+        match self.read_file(type_name, &path, self.format(type_name)) {
+            Ok(file) => file
+                .get_type_data_or_legacy(type_name, manager.get_type_name(type_name))
+                .cloned(),
+            Err(e) => {
+                let e = e.for_type(manager.get_type_name(type_name)).at(&path);
+                error!("Failed to load {} for {}: {}", type_name, self.key, e);
+                manager.report_error(type_name, &e);
+                None
+            }
+        }
+    }
+}
+
+/// `key` as a single directory name that's valid on every platform.
+fn scope_dir_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "_".to_string()
+    } else {
+        name
+    }
+}
@@ -0,0 +1,100 @@
+//! Sharing a resource as a short text code (`share-codes` feature).
+
+use crate::{
+    checksum, validate_loaded, PersistData, PersistError, PersistManager, PersistResult,
+    Persistable,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Layout version of share codes, the first byte of every code
+const SHARE_CODE_VERSION: u8 = 1;
+
+/// What a share code holds, before checksumming and encoding.
+#[derive(Serialize, Deserialize)]
+struct SharePayload {
+    /// Type the values belong to
+    #[serde(rename = "t")]
+    type_name: String,
+    #[serde(rename = "v")]
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+impl PersistManager {
+    /// Encodes `T`'s current values as a code players can paste into chat.
+    ///
+    /// The code is URL-safe base64 of the values, with the type's name and a
+    /// checksum so [`from_share_code`](Self::from_share_code) can reject codes
+    /// for other types or that got mangled on the way.
+    pub fn to_share_code<T: Persistable>(world: &World) -> PersistResult<String> {
+        let resource = world
+            .get_resource::<T>()
+            .ok_or_else(|| PersistError::ResourceNotFound(T::type_name().to_string()))?;
+        let payload = SharePayload {
+            type_name: T::type_name().to_string(),
+            values: resource.to_persist_data().to_json(),
+        };
+        let json = serde_json::to_vec(&payload).map_err(PersistError::serialization)?;
+
+        let mut bytes = Vec::with_capacity(json.len() + 5);
+        bytes.push(SHARE_CODE_VERSION);
+        bytes.extend_from_slice(&checksum(&json).to_le_bytes());
+        bytes.extend_from_slice(&json);
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Applies a code from [`to_share_code`](Self::to_share_code) to the live `T`.
+    ///
+    /// The values go through the same validation as loading, and the change
+    /// is saved like any other. Codes for another type, damaged codes and
+    /// codes from a newer version are rejected without touching the resource.
+    pub fn from_share_code<T: Persistable>(world: &mut World, code: &str) -> PersistResult<()> {
+        let type_name = T::type_name();
+        let data = decode_share_code(code, type_name)?;
+        let mut resource = world
+            .get_resource_mut::<T>()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        resource.load_from_persist_data(&data);
+
+        let Some(manager) = world.get_resource::<PersistManager>() else {
+            return Ok(());
+        };
+        let policy = manager.validation_policy();
+        let key = manager.storage_key_of::<T>();
+        if let Some(hooks) = manager.get_type_hooks(&key).cloned() {
+            validate_loaded(world, &key, &hooks, policy);
+        }
+        Ok(())
+    }
+}
+
+/// Checks and unpacks a share code meant for `type_name`.
+fn decode_share_code(code: &str, type_name: &str) -> PersistResult<PersistData> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(code.trim())
+        .map_err(|e| invalid_code(format!("it isn't base64 ({})", e)))?;
+    let [version, c0, c1, c2, c3, json @ ..] = bytes.as_slice() else {
+        return Err(invalid_code("it's too short"));
+    };
+    if *version > SHARE_CODE_VERSION {
+        return Err(invalid_code("it's from a newer version"));
+    }
+    if checksum(json) != u32::from_le_bytes([*c0, *c1, *c2, *c3]) {
+        return Err(invalid_code("its checksum doesn't match"));
+    }
+
+    let payload: SharePayload =
+        serde_json::from_slice(json).map_err(|e| invalid_code(e.to_string()))?;
+    if payload.type_name != type_name {
+        return Err(invalid_code(format!(
+            "it's for {}, not {}",
+            payload.type_name, type_name
+        )));
+    }
+    Ok(PersistData::from_json(payload.values))
+}
+
+fn invalid_code(reason: impl std::fmt::Display) -> PersistError {
+    PersistError::serialization(format!("Invalid share code, {}", reason))
+}
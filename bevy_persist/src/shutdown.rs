@@ -0,0 +1,61 @@
+//! A final flush when the process is asked to stop (`shutdown` feature).
+
+use crate::PersistManager;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Whether SIGINT (Ctrl-C) or SIGTERM has asked the app to stop.
+///
+/// Inserted by [`PersistPlugin`](crate::PersistPlugin) when
+/// [`with_flush_on_shutdown`](crate::PersistPlugin::with_flush_on_shutdown)
+/// is set. Instead of the process dying on the spot, the next frame
+/// finishes, every dirty type is flushed and [`AppExit`] is sent.
+#[derive(Resource, Clone, Default)]
+pub struct PersistShutdown {
+    requested: Arc<AtomicBool>,
+}
+
+impl PersistShutdown {
+    /// Asks for a final flush and exit, as a signal would.
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether a shutdown was asked for and hasn't been handled yet.
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
+
+/// Installs the signal handler and the system reacting to it.
+pub(crate) fn start(app: &mut App) {
+    let shutdown = PersistShutdown::default();
+    let handler = shutdown.clone();
+    // Only one handler can be set per process
+    if let Err(e) = ctrlc::set_handler(move || handler.request()) {
+        warn!("Failed to install shutdown signal handler: {}", e);
+    }
+    app.insert_resource(shutdown)
+        .add_systems(Last, flush_on_shutdown);
+}
+
+/// Flushes everything and exits once a shutdown was requested.
+///
+/// Runs in `Last`, so changes made during the frame were marked dirty in
+/// `PostUpdate` already.
+fn flush_on_shutdown(world: &mut World) {
+    let requested = world
+        .get_resource::<PersistShutdown>()
+        .is_some_and(|shutdown| shutdown.requested.swap(false, Ordering::SeqCst));
+    if !requested {
+        return;
+    }
+
+    info!("Shutdown requested, flushing persisted data");
+    // Failures are already logged and sent as events
+    PersistManager::flush(world).ok();
+    world.send_event(AppExit::Success);
+}
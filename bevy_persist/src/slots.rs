@@ -0,0 +1,168 @@
+//! Finding the most recent save, e.g. for a "Continue" button.
+
+use crate::{PersistData, PersistFile, PersistFormat, PersistManager, PersistResult, Persistable};
+use chrono::{DateTime, Utc};
+use log::warn;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A directory of save-slot files, one slot per file.
+///
+/// Slots are files holding persisted data, like those written for
+/// [groups](PersistManager::set_type_group) or pinned
+/// [files](PersistManager::set_type_file), autosaves, or `WorldSnapshot`
+/// files with the `scene` feature. Encrypted or compressed files can't be
+/// read without the manager and are skipped.
+///
+/// ```ignore
+/// let slots = SaveSlots::new("saves");
+/// if let Some(slot) = slots.most_recent() {
+///     // Show "Continue" and load `slot.path`
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaveSlots {
+    dir: PathBuf,
+}
+
+/// One readable slot, from [`SaveSlots`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveSlot {
+    /// The slot file
+    pub path: PathBuf,
+    /// File name without its extension, e.g. `slot1`
+    pub name: String,
+    /// When anything in the slot was last saved, or when the file was
+    /// modified if its entries carry no times
+    pub saved_at: DateTime<Utc>,
+    /// Keys of the entries saved in the slot, sorted
+    pub type_names: Vec<String>,
+}
+
+impl SaveSlots {
+    /// Slots in the files directly inside `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory the slots are read from.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Every readable slot, newest first.
+    ///
+    /// Files that can't be parsed, e.g. after a crash mid-write, are
+    /// skipped with a warning, as are empty ones.
+    pub fn list(&self) -> Vec<SaveSlot> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut slots: Vec<SaveSlot> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if !path.is_file() {
+                    return None;
+                }
+                match read_slot(&path) {
+                    Ok(slot) => slot,
+                    Err(e) => {
+                        warn!("Skipping unreadable save slot {:?}: {}", path, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        slots.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then(a.name.cmp(&b.name)));
+        slots
+    }
+
+    /// The slot saved most recently, which a "Continue" button should load.
+    ///
+    /// `None` if there's no readable slot.
+    pub fn most_recent(&self) -> Option<SaveSlot> {
+        self.list().into_iter().next()
+    }
+}
+
+/// Reads a slot file, or `None` if it holds nothing.
+fn read_slot(path: &Path) -> PersistResult<Option<SaveSlot>> {
+    let file = read_slot_file(path)?;
+    if file.type_data.is_empty() {
+        return Ok(None);
+    }
+    let saved_at = file
+        .type_data
+        .values()
+        .filter_map(PersistData::last_saved)
+        .max()
+        .or_else(|| {
+            let modified = fs::metadata(path).ok()?.modified().ok()?;
+            Some(DateTime::<Utc>::from(modified))
+        });
+    let Some(saved_at) = saved_at else {
+        return Ok(None);
+    };
+    Ok(Some(SaveSlot {
+        path: path.to_path_buf(),
+        name: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        saved_at,
+        type_names: file.type_data.into_keys().collect(),
+    }))
+}
+
+/// Parses a slot file, whether a plain persist file or a world snapshot.
+fn read_slot_file(path: &Path) -> PersistResult<PersistFile> {
+    let result = PersistFile::load_from_file_as(path, PersistFormat::from_path(path));
+    #[cfg(feature = "scene")]
+    if result.is_err() {
+        if let Ok(content) = fs::read_to_string(path) {
+            if let Ok(file) = crate::snapshot::snapshot_resources(&content) {
+                return Ok(file);
+            }
+        }
+    }
+    result
+}
+
+impl PersistManager {
+    /// The most recently saved state of `T`, across its usual file and
+    /// every [autosave](Self::list_autosaves).
+    ///
+    /// Candidates are compared by the time recorded with each entry; an
+    /// entry without one only wins if there's nothing else. Autosaves that
+    /// can't be read are skipped. Fields that weren't saved keep their
+    /// shipped defaults or `T::default()`. `None` if `T` was never saved.
+    pub fn most_recent_state<T: Persistable + Default>(&self) -> Option<T> {
+        let key = self.storage_key_of::<T>();
+        let mut newest = self.read_saved_type_data(&key, None);
+        for autosave in self.list_autosaves() {
+            let file = match PersistFile::load_from_file_as(&autosave.path, PersistFormat::Ron) {
+                Ok(file) => file,
+                Err(e) => {
+                    warn!("Skipping unreadable autosave {:?}: {}", autosave.path, e);
+                    continue;
+                }
+            };
+            let Some(data) = file.get_type_data_or_legacy(&key, T::type_name()) else {
+                continue;
+            };
+            let is_newer = newest
+                .as_ref()
+                .map_or(true, |current| current.last_saved() < data.last_saved());
+            if is_newer {
+                newest = Some(data.clone());
+            }
+        }
+
+        let saved = self.unseal_fields(&key, newest?);
+        let mut data = self.type_defaults.get(&key).cloned().unwrap_or_default();
+        data.merge(saved);
+        let mut value = T::default();
+        value.load_from_persist_data(&data);
+        Some(value)
+    }
+}
@@ -0,0 +1,218 @@
+//! Save-slot files holding entities and persisted resources (`scene` feature).
+
+use crate::{
+    display_name, validate_loaded, PersistData, PersistError, PersistFile, PersistFormat,
+    PersistLoaded, PersistManager, PersistResult,
+};
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::scene::serde::{SceneDeserializer, SceneSerializer};
+use bevy::scene::{DynamicScene, DynamicSceneBuilder, SceneFilter};
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Entities and every persisted resource, captured into one save slot.
+///
+/// Entities go into a [`DynamicScene`] holding only the components that
+/// pass a [`SceneFilter`], so save games can skip cameras, UI and other
+/// runtime state. Those components must be reflected and registered with
+/// the app's type registry. Resources are captured through the
+/// [`PersistManager`] like any save, whether or not they are reflected.
+///
+/// ```ignore
+/// let filter = SceneFilter::deny_all()
+///     .allow::<Transform>()
+///     .allow::<Health>();
+/// WorldSnapshot::capture(world, filter).save_to_file("slot1.ron", world)?;
+///
+/// let snapshot = WorldSnapshot::load_from_file("slot1.ron", world)?;
+/// snapshot.restore(world)?;
+/// ```
+pub struct WorldSnapshot {
+    resources: BTreeMap<String, PersistData>,
+    scene: DynamicScene,
+}
+
+/// Shape of a snapshot file as written.
+struct SnapshotRef<'a> {
+    resources: &'a PersistFile,
+    scene: SceneSerializer<'a>,
+}
+
+impl Serialize for SnapshotRef<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut snapshot = serializer.serialize_struct("WorldSnapshot", 2)?;
+        snapshot.serialize_field("resources", self.resources)?;
+        snapshot.serialize_field("scene", &self.scene)?;
+        snapshot.end()
+    }
+}
+
+/// Shape of a snapshot file as read, keeping each part's text.
+#[derive(Deserialize)]
+struct SnapshotText {
+    resources: Box<ron::value::RawValue>,
+    scene: Box<ron::value::RawValue>,
+}
+
+impl WorldSnapshot {
+    /// Captures the components passing `filter` on every entity, plus every
+    /// registered persisted resource.
+    ///
+    /// Runs each resource's [`Persistable::before_save`](crate::Persistable::before_save)
+    /// first, as a regular save would.
+    pub fn capture(world: &mut World, filter: SceneFilter) -> Self {
+        let hooks: Vec<_> = world
+            .get_resource::<PersistManager>()
+            .map(|manager| {
+                manager
+                    .registered_types()
+                    .filter_map(|info| {
+                        let hooks = manager.get_type_hooks(info.type_name)?.clone();
+                        Some((info.type_name.to_string(), hooks))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut resources = BTreeMap::new();
+        for (type_name, hooks) in hooks {
+            hooks.before_save_in_world(world);
+            if let Some(data) = hooks.extract_from_world(world) {
+                resources.insert(type_name, data);
+            }
+        }
+
+        let scene = DynamicSceneBuilder::from_world(world)
+            .with_component_filter(filter)
+            .deny_all_resources()
+            .extract_entities(world.iter_entities().map(|entity| entity.id()))
+            .build();
+
+        Self { resources, scene }
+    }
+
+    /// The persisted resources in the snapshot, by type name.
+    pub fn resources(&self) -> &BTreeMap<String, PersistData> {
+        &self.resources
+    }
+
+    /// The captured entities.
+    pub fn scene(&self) -> &DynamicScene {
+        &self.scene
+    }
+
+    /// Writes the snapshot into a world.
+    ///
+    /// Resources are applied and validated as if reloaded from disk, and
+    /// auto-save writes them to their usual files afterwards. Entities are
+    /// spawned as new entities, so despawn the ones from the running game
+    /// first. Returns which new entity each captured one became.
+    pub fn restore(&self, world: &mut World) -> PersistResult<EntityHashMap<Entity>> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let policy = manager.validation_policy();
+        let hooks: Vec<_> = self
+            .resources
+            .iter()
+            .filter_map(|(type_name, data)| {
+                let hooks = manager.get_type_hooks(type_name)?.clone();
+                Some((type_name, data, hooks))
+            })
+            .collect();
+
+        for (type_name, data, hooks) in hooks {
+            if hooks.apply_to_world(world, data) {
+                validate_loaded(world, type_name, &hooks, policy);
+                world.send_event(PersistLoaded {
+                    type_name: display_name(world, type_name),
+                });
+            }
+        }
+
+        let mut entities = EntityHashMap::default();
+        self.scene
+            .write_to_world(world, &mut entities)
+            .map_err(PersistError::serialization)?;
+        Ok(entities)
+    }
+
+    /// Serializes the snapshot to RON in the manager's
+    /// [`PersistStyle`](crate::PersistStyle).
+    pub fn to_ron(&self, world: &World) -> PersistResult<String> {
+        let style = world
+            .get_resource::<PersistManager>()
+            .map(|manager| manager.style().clone())
+            .unwrap_or_default();
+        let registry = type_registry(world)?.read();
+        let mut resources = PersistFile::new();
+        resources.type_data = self.resources.clone();
+        let snapshot = SnapshotRef {
+            resources: &resources,
+            scene: SceneSerializer::new(&self.scene, &registry),
+        };
+        PersistFormat::Ron.serialize_styled(&snapshot, &style)
+    }
+
+    /// Parses a snapshot written by [`to_ron`](Self::to_ron).
+    pub fn from_ron(content: &str, world: &World) -> PersistResult<Self> {
+        let parse_error = |e: ron::error::SpannedError| {
+            PersistError::serialization(format!("RON parse error: {}", e))
+        };
+        let text: SnapshotText = ron::from_str(content).map_err(parse_error)?;
+        let resources = PersistFile::parse_as(text.resources.get_ron(), PersistFormat::Ron)?;
+
+        let registry = type_registry(world)?.read();
+        let mut deserializer =
+            ron::Deserializer::from_str(text.scene.get_ron()).map_err(parse_error)?;
+        let scene = SceneDeserializer {
+            type_registry: &registry,
+        }
+        .deserialize(&mut deserializer)
+        .map_err(|e| PersistError::serialization(format!("Scene parse error: {}", e)))?;
+
+        Ok(Self {
+            resources: resources.type_data,
+            scene,
+        })
+    }
+
+    /// Writes the snapshot to a file, creating its directory if needed.
+    pub fn save_to_file(&self, path: impl AsRef<Path>, world: &World) -> PersistResult<()> {
+        let path = path.as_ref();
+        let content = self.to_ron(world)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| PersistError::io("Failed to create directory", e).at(parent))?;
+        }
+        fs::write(path, content).map_err(|e| PersistError::io("Failed to write file", e).at(path))
+    }
+
+    /// Reads a snapshot from a file.
+    pub fn load_from_file(path: impl AsRef<Path>, world: &World) -> PersistResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| PersistError::io("Failed to read file", e).at(path))?;
+        Self::from_ron(&content, world).map_err(|e| e.at(path))
+    }
+}
+
+/// Reads just the resources of a snapshot file, without needing a world.
+pub(crate) fn snapshot_resources(content: &str) -> PersistResult<PersistFile> {
+    let text: SnapshotText = ron::from_str(content)
+        .map_err(|e| PersistError::serialization(format!("RON parse error: {}", e)))?;
+    PersistFile::parse_as(text.resources.get_ron(), PersistFormat::Ron)
+}
+
+/// Components in scenes are (de)serialized through reflection.
+fn type_registry(world: &World) -> PersistResult<&AppTypeRegistry> {
+    world
+        .get_resource::<AppTypeRegistry>()
+        .ok_or_else(|| PersistError::ResourceNotFound("AppTypeRegistry".to_string()))
+}
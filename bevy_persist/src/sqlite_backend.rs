@@ -0,0 +1,314 @@
+//! A [`PersistBackend`] keeping every type in a SQLite database, with a
+//! history of earlier revisions (`sqlite` feature).
+
+use crate::{create_parent_dir, PersistBackend, PersistError, PersistResult};
+use chrono::{DateTime, Utc};
+use log::error;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Revisions kept per key unless configured otherwise
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Tables holding the latest data and the revisions before it
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS persist_data (
+        key TEXT PRIMARY KEY,
+        revision INTEGER NOT NULL,
+        saved_at TEXT NOT NULL,
+        data BLOB NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS persist_history (
+        key TEXT NOT NULL,
+        revision INTEGER NOT NULL,
+        saved_at TEXT NOT NULL,
+        data BLOB NOT NULL,
+        PRIMARY KEY (key, revision)
+    );
+";
+
+/// Stores each type as one row of a SQLite database, keeping earlier
+/// revisions for restoring.
+///
+/// Every save is a transaction, so SQLite's journal leaves the previous
+/// data intact if the game dies mid-write. The last
+/// [`history_limit`](Self::with_history_limit) revisions of each key are
+/// kept, and [`restore_to`](Self::restore_to) rolls every key back to a
+/// point in time:
+///
+/// ```ignore
+/// let backend = Arc::new(SqliteBackend::new("saves.db"));
+/// app.add_plugins(PersistPlugin::new("MyCompany", "MyGame").with_backend(backend.clone()));
+///
+/// // Later, undo the last ten minutes of saves
+/// backend.restore_to(Utc::now() - TimeDelta::minutes(10))?;
+/// PersistManager::load_all(world)?;
+/// ```
+pub struct SqliteBackend {
+    path: PathBuf,
+    /// The open database, or why it couldn't be opened
+    conn: Result<Mutex<Connection>, PersistError>,
+    history_limit: usize,
+}
+
+/// One saved revision of a key, from [`SqliteBackend::history`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistRevision {
+    /// Revision number, counting up from 1 for each key
+    pub revision: u64,
+    /// When the revision was saved
+    pub saved_at: DateTime<Utc>,
+}
+
+impl SqliteBackend {
+    /// Opens or creates the database at `path`.
+    ///
+    /// If it can't be opened, every read and write fails with the reason,
+    /// which surfaces like any other failed save. Use [`open`](Self::open)
+    /// to handle it up front instead.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let conn = open_database(&path).map(Mutex::new);
+        if let Err(e) = &conn {
+            error!("{}", e);
+        }
+        Self {
+            path,
+            conn,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Opens or creates the database at `path`, failing if it can't be opened.
+    pub fn open(path: impl Into<PathBuf>) -> PersistResult<Self> {
+        let path = path.into();
+        let conn = open_database(&path)?;
+        Ok(Self {
+            path,
+            conn: Ok(Mutex::new(conn)),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        })
+    }
+
+    /// Keeps at most `limit` revisions of each key, including the latest (50 by default).
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit.max(1);
+        self
+    }
+
+    /// Path of the database file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every kept revision of `key`, oldest first.
+    pub fn history(&self, key: &str) -> PersistResult<Vec<PersistRevision>> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT revision, saved_at FROM persist_history WHERE key = ?1 ORDER BY revision",
+            )
+            .map_err(PersistError::backend)?;
+        let rows = stmt
+            .query_map(params![key], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(PersistError::backend)?;
+        let mut revisions = Vec::new();
+        for row in rows {
+            let (revision, saved_at) = row.map_err(PersistError::backend)?;
+            revisions.push(PersistRevision {
+                revision: revision as u64,
+                saved_at: parse_time(&saved_at)?,
+            });
+        }
+        Ok(revisions)
+    }
+
+    /// Makes an earlier revision of `key` its latest data again.
+    ///
+    /// The restored data is saved as a new revision, so the restore can
+    /// itself be undone.
+    pub fn restore(&self, key: &str, revision: u64) -> PersistResult<()> {
+        let data: Option<Vec<u8>> = self
+            .conn()?
+            .query_row(
+                "SELECT data FROM persist_history WHERE key = ?1 AND revision = ?2",
+                params![key, revision as i64],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(PersistError::backend)?;
+        match data {
+            Some(data) => self.write(key, &data),
+            None => Err(PersistError::backend(format!(
+                "No revision {} of {}",
+                revision, key
+            ))),
+        }
+    }
+
+    /// Rolls every key back to the revision it had at `time`.
+    ///
+    /// Keys first saved after `time` are left alone. Returns how many keys
+    /// were restored. Live resources keep their values until they're
+    /// loaded again, e.g. with [`PersistManager::load_all`](crate::PersistManager::load_all).
+    pub fn restore_to(&self, time: DateTime<Utc>) -> PersistResult<usize> {
+        let targets: Vec<(String, i64)> = {
+            let conn = self.conn()?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT h.key, MAX(h.revision) FROM persist_history h
+                     WHERE h.saved_at <= ?1 GROUP BY h.key",
+                )
+                .map_err(PersistError::backend)?;
+            let rows = stmt
+                .query_map(params![format_time(time)], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .map_err(PersistError::backend)?;
+            rows.collect::<Result<_, _>>()
+                .map_err(PersistError::backend)?
+        };
+        let mut restored = 0;
+        for (key, revision) in targets {
+            let current: Option<i64> = self
+                .conn()?
+                .query_row(
+                    "SELECT revision FROM persist_data WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(PersistError::backend)?;
+            if current != Some(revision) {
+                self.restore(&key, revision as u64)?;
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+
+    /// The open connection, or why the database couldn't be opened.
+    fn conn(&self) -> PersistResult<MutexGuard<'_, Connection>> {
+        let conn = self.conn.as_ref().map_err(Clone::clone)?;
+        Ok(conn.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Opens or creates the database at `path` with its tables.
+fn open_database(path: &Path) -> PersistResult<Connection> {
+    create_parent_dir(path)?;
+    let conn = Connection::open(path)
+        .and_then(|conn| conn.execute_batch(SCHEMA).map(|()| conn))
+        .map_err(|e| PersistError::backend(format!("Failed to open {}: {}", path.display(), e)))?;
+    Ok(conn)
+}
+
+/// `time` as stored in the tables, sortable as text.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+/// Reads a time written by [`format_time`].
+fn parse_time(text: &str) -> PersistResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| PersistError::backend(format!("Invalid save time {:?}: {}", text, e)))
+}
+
+/// Saves `bytes` as the next revision of `key`, dropping revisions past
+/// `history_limit`.
+fn write_revision(
+    txn: &Transaction<'_>,
+    key: &str,
+    bytes: &[u8],
+    history_limit: usize,
+) -> PersistResult<()> {
+    let revision: i64 = txn
+        .query_row(
+            "SELECT COALESCE(MAX(revision), 0) + 1 FROM persist_history WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .map_err(PersistError::backend)?;
+    let saved_at = format_time(Utc::now());
+    txn.execute(
+        "INSERT INTO persist_history (key, revision, saved_at, data) VALUES (?1, ?2, ?3, ?4)",
+        params![key, revision, saved_at, bytes],
+    )
+    .map_err(PersistError::backend)?;
+    txn.execute(
+        "INSERT INTO persist_data (key, revision, saved_at, data) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(key) DO UPDATE SET
+             revision = excluded.revision,
+             saved_at = excluded.saved_at,
+             data = excluded.data",
+        params![key, revision, saved_at, bytes],
+    )
+    .map_err(PersistError::backend)?;
+    txn.execute(
+        "DELETE FROM persist_history WHERE key = ?1 AND revision <= ?2",
+        params![key, revision - history_limit as i64],
+    )
+    .map_err(PersistError::backend)?;
+    Ok(())
+}
+
+impl PersistBackend for SqliteBackend {
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+        self.conn()?
+            .query_row(
+                "SELECT data FROM persist_data WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(PersistError::backend)
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()> {
+        let mut conn = self.conn()?;
+        let txn = conn.transaction().map_err(PersistError::backend)?;
+        write_revision(&txn, key, bytes, self.history_limit)?;
+        txn.commit().map_err(PersistError::backend)
+    }
+
+    fn write_if(&self, key: &str, expected: Option<&[u8]>, bytes: &[u8]) -> PersistResult<bool> {
+        let mut conn = self.conn()?;
+        // Takes the write lock up front, so no other connection writes
+        // between the check and the write
+        let txn = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(PersistError::backend)?;
+        let current: Option<Vec<u8>> = txn
+            .query_row(
+                "SELECT data FROM persist_data WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(PersistError::backend)?;
+        if current.as_deref() != expected {
+            return Ok(false);
+        }
+        write_revision(&txn, key, bytes, self.history_limit)?;
+        txn.commit().map_err(PersistError::backend)?;
+        Ok(true)
+    }
+
+    /// Removes `key` along with its history, e.g. for "delete save data".
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        let mut conn = self.conn()?;
+        let txn = conn.transaction().map_err(PersistError::backend)?;
+        for table in ["persist_data", "persist_history"] {
+            txn.execute(
+                &format!("DELETE FROM {} WHERE key = ?1", table),
+                params![key],
+            )
+            .map_err(PersistError::backend)?;
+        }
+        txn.commit().map_err(PersistError::backend)
+    }
+}
@@ -0,0 +1,800 @@
+//! Syncing saved data with a remote store, e.g. a cloud save service.
+
+use crate::{
+    create_parent_dir, display_name, stable_hash, write_error, PersistBackend, PersistData,
+    PersistError, PersistManager, PersistMerge, PersistMode, PersistResult,
+};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{IoTaskPool, Task, TaskPool};
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Name the sync state file is resolved under in production
+const SYNC_STATE_NAME: &str = "sync";
+
+/// How a type changed both locally and remotely since the last sync is
+/// resolved.
+#[derive(Clone, Default)]
+pub enum ConflictStrategy {
+    /// Keeps whichever side was saved last
+    #[default]
+    NewestWins,
+    /// Keeps the local data and overwrites the remote copy
+    LocalWins,
+    /// Keeps the remote data and overwrites the local copy
+    RemoteWins,
+    /// Three-way merges both sides with [`PersistData::merge3`], over the
+    /// data they were last in sync with. Fields both sides changed go to a
+    /// callback, which returns `None` to leave the conflict to the game,
+    /// like [`Manual`](Self::Manual)
+    Merge(Arc<MergeFn>),
+    /// Sends [`PersistSyncConflict`] and leaves both sides alone until
+    /// [`PersistManager::resolve_conflict`] is called
+    Manual,
+}
+
+/// Callback of [`ConflictStrategy::Merge`].
+pub type MergeFn = dyn Fn(&PersistSyncConflict) -> Option<PersistData> + Send + Sync;
+
+impl ConflictStrategy {
+    /// Merges changes to different fields on their own, and conflicting
+    /// data with `merge`.
+    ///
+    /// `merge` is only called when both sides changed the same field, or
+    /// when there's no synced copy to merge over, e.g. on a device's first
+    /// sync.
+    ///
+    /// ```ignore
+    /// ConflictStrategy::merge(|conflict| {
+    ///     let mut merged = conflict.remote.clone();
+    ///     let best = |data: &PersistData| data.get::<u32>("best_score").unwrap_or(0);
+    ///     merged.insert("best_score", best(&conflict.local).max(best(&conflict.remote)));
+    ///     Some(merged)
+    /// })
+    /// ```
+    pub fn merge(
+        merge: impl Fn(&PersistSyncConflict) -> Option<PersistData> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Merge(Arc::new(merge))
+    }
+}
+
+impl fmt::Debug for ConflictStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NewestWins => f.write_str("NewestWins"),
+            Self::LocalWins => f.write_str("LocalWins"),
+            Self::RemoteWins => f.write_str("RemoteWins"),
+            Self::Merge(_) => f.write_str("Merge(..)"),
+            Self::Manual => f.write_str("Manual"),
+        }
+    }
+}
+
+/// Sent when a type changed both locally and remotely and the
+/// [`ConflictStrategy`] left it to the game.
+///
+/// Neither side changes until [`PersistManager::resolve_conflict`] is
+/// called, e.g. after asking the player which save to keep.
+#[derive(Event, Debug, Clone)]
+pub struct PersistSyncConflict {
+    /// Name of the type in conflict
+    pub type_name: String,
+    /// The local data
+    pub local: PersistData,
+    /// The remote data
+    pub remote: PersistData,
+    /// The data both sides were last in sync with, if this device has synced it
+    pub base: Option<PersistData>,
+    /// When the local data was last saved
+    pub local_modified: DateTime<Utc>,
+    /// When the remote data was pushed
+    pub remote_modified: DateTime<Utc>,
+}
+
+/// Sent after each sync, listing the types it pushed, pulled or failed to sync.
+#[derive(Event, Debug, Clone, Default)]
+pub struct PersistSynced {
+    /// Types whose local data was pushed to the remote store
+    pub pushed: Vec<String>,
+    /// Types whose remote data was applied to their resource
+    pub pulled: Vec<String>,
+    /// Types that couldn't be synced
+    pub failed: Vec<String>,
+}
+
+/// How to settle a [`PersistSyncConflict`].
+#[derive(Debug, Clone)]
+pub enum SyncResolution {
+    /// Keep the local data, pushing it on the next sync
+    KeepLocal,
+    /// Apply the remote data
+    KeepRemote,
+    /// Apply this data, pushing it on the next sync
+    Use(PersistData),
+}
+
+/// Cloud sync settings and state, kept by the manager.
+pub(crate) struct SyncConfig {
+    remote: Arc<dyn PersistBackend>,
+    strategy: ConflictStrategy,
+    interval: Duration,
+    last_started: Option<Instant>,
+    /// What each type looked like when it was last in sync, read on the first sync
+    records: Option<HashMap<String, SyncRecord>>,
+    /// Unresolved conflicts by storage key
+    conflicts: HashMap<String, RemoteEntry>,
+}
+
+impl SyncConfig {
+    pub(crate) fn new(
+        remote: Arc<dyn PersistBackend>,
+        strategy: ConflictStrategy,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            remote,
+            strategy,
+            interval,
+            last_started: None,
+            records: None,
+            conflicts: HashMap::new(),
+        }
+    }
+}
+
+/// A type's state as of its last sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncRecord {
+    /// Remote revision the local data matched
+    revision: u64,
+    /// [`stable_hash`] of the local data at the time
+    digest: u64,
+    /// The data of that revision, merged over by [`ConflictStrategy::Merge`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+impl SyncRecord {
+    fn new(revision: u64, digest: u64, data: &PersistData) -> Self {
+        Self {
+            revision,
+            digest,
+            data: Some(data.to_json()),
+        }
+    }
+
+    fn data(&self) -> Option<PersistData> {
+        self.data.clone().map(PersistData::from_json)
+    }
+}
+
+/// What the remote store holds for a type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RemoteEntry {
+    /// Bumped by every push
+    revision: u64,
+    /// When the pushed data was saved
+    modified: DateTime<Utc>,
+    data: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RemoteEntry {
+    fn data(&self) -> PersistData {
+        PersistData::from_json(self.data.clone())
+    }
+}
+
+/// A type's local side, taken when a sync starts.
+struct LocalEntry {
+    key: String,
+    data: PersistData,
+    digest: u64,
+    changed: bool,
+    modified: DateTime<Utc>,
+    record: Option<SyncRecord>,
+}
+
+/// What a sync did with one type.
+enum SyncOutcome {
+    Unchanged,
+    Pushed {
+        key: String,
+        revision: u64,
+        digest: u64,
+        data: PersistData,
+    },
+    /// Remote data to apply locally; `pushed` if it was merged and pushed too
+    Pulled {
+        key: String,
+        revision: u64,
+        data: PersistData,
+        local_digest: u64,
+        pushed: bool,
+    },
+    Conflict {
+        key: String,
+        local: PersistData,
+        local_modified: DateTime<Utc>,
+        remote: RemoteEntry,
+        base: Option<PersistData>,
+    },
+    Failed {
+        key: String,
+        error: PersistError,
+    },
+}
+
+/// The sync running on the [`IoTaskPool`], if any.
+#[derive(Resource, Default)]
+pub(crate) struct SyncTask(Option<Task<Vec<SyncOutcome>>>);
+
+impl PersistManager {
+    /// Syncs every registered type with `remote` each `interval`, resolving
+    /// types changed on both sides with `strategy`.
+    ///
+    /// The first sync runs on startup. Each one pushes types changed
+    /// locally since the last sync and pulls those changed remotely, on the
+    /// [`IoTaskPool`] so a slow connection doesn't hold up the game. Pulled
+    /// data is applied to the resource and auto-saved like any change.
+    /// `remote` is any [`PersistBackend`], e.g. one calling a cloud save
+    /// service; it keeps a revision and timestamp with each type's data.
+    /// Session and embedded types aren't synced.
+    pub fn with_cloud_sync(
+        mut self,
+        remote: impl PersistBackend,
+        strategy: ConflictStrategy,
+        interval: Duration,
+    ) -> Self {
+        self.set_cloud_sync(Arc::new(remote), strategy, interval);
+        self
+    }
+
+    /// Syncs every registered type with a shared remote store.
+    ///
+    /// See [`with_cloud_sync`](Self::with_cloud_sync).
+    pub fn set_cloud_sync(
+        &mut self,
+        remote: Arc<dyn PersistBackend>,
+        strategy: ConflictStrategy,
+        interval: Duration,
+    ) {
+        self.sync = Some(SyncConfig::new(remote, strategy, interval));
+    }
+
+    /// Checks if saved data is synced with a remote store.
+    pub fn has_cloud_sync(&self) -> bool {
+        self.sync.is_some()
+    }
+
+    /// Remote revision a type's local data was last in sync with, if it
+    /// has been synced.
+    pub fn sync_revision(&self, type_name: &str) -> Option<u64> {
+        let key = self.key(type_name);
+        let records = self.sync.as_ref()?.records.as_ref()?;
+        records.get(&*key).map(|record| record.revision)
+    }
+
+    /// Names of the types waiting on [`resolve_conflict`](Self::resolve_conflict).
+    pub fn sync_conflicts(&self) -> Vec<&str> {
+        self.sync.as_ref().map_or_else(Vec::new, |sync| {
+            sync.conflicts
+                .keys()
+                .map(|key| self.get_type_name(key))
+                .collect()
+        })
+    }
+
+    /// File the sync state is kept in.
+    pub fn sync_state_path(&self) -> PathBuf {
+        if self.is_production() {
+            self.resolve_path(SYNC_STATE_NAME, PersistMode::Dynamic, "json")
+        } else {
+            let stem = self
+                .dev_file
+                .file_stem()
+                .map_or_else(|| "persist".into(), |stem| stem.to_string_lossy());
+            self.dev_file
+                .with_file_name(format!("{}_{}.json", stem, SYNC_STATE_NAME))
+        }
+    }
+
+    /// Starts a sync now instead of waiting for the interval.
+    ///
+    /// Does nothing if a sync is already running.
+    pub fn sync_now(world: &mut World) -> PersistResult<()> {
+        let manager = world
+            .get_resource::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        if manager.sync.is_none() {
+            return Err(PersistError::ResourceNotFound("cloud sync".to_string()));
+        }
+        if world
+            .get_resource::<SyncTask>()
+            .is_some_and(|task| task.0.is_some())
+        {
+            debug!("A sync is already running");
+            return Ok(());
+        }
+        start_sync(world);
+        Ok(())
+    }
+
+    /// Settles a [`PersistSyncConflict`].
+    ///
+    /// The kept data is applied to the resource if it isn't the local data,
+    /// and pushed on the next sync if it isn't the remote data, which
+    /// starts right away.
+    pub fn resolve_conflict(
+        world: &mut World,
+        type_name: &str,
+        resolution: SyncResolution,
+    ) -> PersistResult<()> {
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        let key = manager.get_storage_key(type_name);
+        let hooks = manager
+            .get_type_hooks(&key)
+            .cloned()
+            .ok_or_else(|| PersistError::ResourceNotFound(type_name.to_string()))?;
+        let sync = manager
+            .sync
+            .as_mut()
+            .ok_or_else(|| PersistError::ResourceNotFound("cloud sync".to_string()))?;
+        let remote = sync
+            .conflicts
+            .remove(&key)
+            .ok_or_else(|| PersistError::ResourceNotFound(format!("conflict for {}", type_name)))?;
+
+        let keep_remote = matches!(resolution, SyncResolution::KeepRemote);
+        let data = match resolution {
+            SyncResolution::KeepLocal => None,
+            SyncResolution::KeepRemote => Some(remote.data()),
+            SyncResolution::Use(data) => Some(data),
+        };
+        if let Some(data) = &data {
+            if !hooks.apply_to_world(world, data) {
+                return Err(PersistError::ResourceNotFound(type_name.to_string()));
+            }
+        }
+        // In sync with the remote revision, so the next sync pushes the
+        // local data unless it's the remote data
+        let digest = if keep_remote {
+            hooks
+                .extract_from_world(world)
+                .map_or(0, |data| stable_hash(&data))
+        } else {
+            stable_hash(&remote.data())
+        };
+        let mut manager = world.resource_mut::<PersistManager>();
+        manager.set_sync_record(
+            &key,
+            SyncRecord::new(remote.revision, digest, &remote.data()),
+        );
+        manager.save_sync_state();
+        info!(
+            "Resolved the sync conflict of {}",
+            manager.get_type_name(&key)
+        );
+        if world
+            .get_resource::<SyncTask>()
+            .map_or(true, |task| task.0.is_none())
+        {
+            start_sync(world);
+        }
+        Ok(())
+    }
+
+    fn set_sync_record(&mut self, key: &str, record: SyncRecord) {
+        if let Some(sync) = &mut self.sync {
+            sync.records
+                .get_or_insert_with(HashMap::new)
+                .insert(key.to_string(), record);
+        }
+    }
+
+    /// Reads the sync state file the first time it's needed.
+    fn load_sync_state(&mut self) {
+        let path = self.sync_state_path();
+        let Some(sync) = &mut self.sync else {
+            return;
+        };
+        if sync.records.is_some() {
+            return;
+        }
+        let records = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable sync state {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        sync.records = Some(records);
+    }
+
+    fn save_sync_state(&self) {
+        let Some(records) = self.sync.as_ref().and_then(|sync| sync.records.as_ref()) else {
+            return;
+        };
+        let path = self.sync_state_path();
+        if self.read_only || self.memory_paths.contains_key(&path) {
+            return;
+        }
+        let result = serde_json::to_vec_pretty(records)
+            .map_err(|e| PersistError::serialization(e.to_string()))
+            .and_then(|bytes| {
+                create_parent_dir(&path)?;
+                fs::write(&path, bytes).map_err(|e| write_error(e).at(&path))
+            });
+        if let Err(e) = result {
+            error!("Failed to save the sync state: {}", e);
+        }
+    }
+}
+
+/// Starts a sync when one is due and applies the outcome of a finished one.
+pub(crate) fn run_sync(world: &mut World) {
+    let Some(manager) = world.get_resource::<PersistManager>() else {
+        return;
+    };
+    let Some(sync) = &manager.sync else {
+        return;
+    };
+    let due = sync
+        .last_started
+        .map_or(true, |started| started.elapsed() >= sync.interval);
+
+    let mut task = world.get_resource_or_init::<SyncTask>();
+    let Some(running) = &mut task.0 else {
+        if due {
+            start_sync(world);
+        }
+        return;
+    };
+    if let Some(outcomes) = check_ready(running) {
+        task.0 = None;
+        finish_sync(world, outcomes);
+    }
+}
+
+/// Takes each type's local data and syncs it on the [`IoTaskPool`].
+fn start_sync(world: &mut World) {
+    let mut manager = world.resource_mut::<PersistManager>();
+    manager.load_sync_state();
+    let now = manager.now();
+    let manager = world.resource::<PersistManager>();
+    let Some(sync) = &manager.sync else {
+        return;
+    };
+    let records = sync.records.clone().unwrap_or_default();
+    let locals: Vec<LocalEntry> = manager
+        .hooks
+        .iter()
+        .filter(|(key, _)| {
+            !matches!(
+                manager.get_type_mode(key),
+                PersistMode::Session | PersistMode::Embed
+            ) && !sync.conflicts.contains_key(*key)
+        })
+        .filter_map(|(key, hooks)| {
+            let data = hooks.extract_from_world(world)?;
+            let digest = stable_hash(&data);
+            let modified = local_modified(manager, key, &data, now);
+            let record = records.get(key).cloned();
+            let changed = match &record {
+                Some(record) => record.digest != digest,
+                // Never synced, so only data loaded from before syncing counts
+                None => manager.last_loaded.contains_key(key),
+            };
+            Some(LocalEntry {
+                key: key.clone(),
+                data,
+                digest,
+                changed,
+                modified,
+                record,
+            })
+        })
+        .collect();
+    let remote = sync.remote.clone();
+    let strategy = sync.strategy.clone();
+    let names: HashMap<String, String> = locals
+        .iter()
+        .map(|local| {
+            (
+                local.key.clone(),
+                manager.get_type_name(&local.key).to_string(),
+            )
+        })
+        .collect();
+
+    debug!("Syncing {} types", locals.len());
+    let task = IoTaskPool::get_or_init(TaskPool::default).spawn(async move {
+        locals
+            .into_iter()
+            .map(|local| {
+                let name = &names[&local.key];
+                sync_type(&*remote, &strategy, name, local)
+            })
+            .collect()
+    });
+    if let Some(sync) = &mut world.resource_mut::<PersistManager>().sync {
+        sync.last_started = Some(Instant::now());
+    }
+    world.get_resource_or_init::<SyncTask>().0 = Some(task);
+}
+
+/// When a type's local data was last changed, for [`ConflictStrategy::NewestWins`].
+///
+/// Data still as it was loaded keeps the save time recorded with it, so
+/// data saved in an earlier run isn't taken to be newer than a remote
+/// change just because the manager was rebuilt. Changes that haven't been
+/// saved yet are as new as it gets.
+fn local_modified(
+    manager: &PersistManager,
+    key: &str,
+    data: &PersistData,
+    now: DateTime<Utc>,
+) -> DateTime<Utc> {
+    if let Some(saved) = manager.loaded_save_time(key, data) {
+        return saved;
+    }
+    if manager.dirty.contains(key) {
+        return now;
+    }
+    manager
+        .last_saved
+        .get(key)
+        .copied()
+        .or_else(|| manager.saved_at(key))
+        .unwrap_or(now)
+}
+
+/// Pushes or pulls one type, or reports its conflict.
+fn sync_type(
+    remote: &dyn PersistBackend,
+    strategy: &ConflictStrategy,
+    type_name: &str,
+    local: LocalEntry,
+) -> SyncOutcome {
+    let key = local.key.clone();
+    let (bytes, entry) = match read_remote(remote, &key) {
+        Ok(Some((bytes, entry))) => (Some(bytes), Some(entry)),
+        Ok(None) => (None, None),
+        Err(error) => return SyncOutcome::Failed { key, error },
+    };
+    let bytes = bytes.as_deref();
+    let synced = local.record.as_ref().map_or(0, |record| record.revision);
+    let latest = entry.as_ref().map_or(0, |entry| entry.revision);
+    let Some(entry) = entry.filter(|_| latest > synced) else {
+        // Types the remote store has never seen are pushed as they are
+        if !local.changed && local.record.is_some() {
+            return SyncOutcome::Unchanged;
+        }
+        return push(remote, &local, &local.data, synced.max(latest), bytes);
+    };
+    // Unchanged here, or changed the same way on both sides
+    if !local.changed || stable_hash(&entry.data()) == local.digest {
+        return SyncOutcome::Pulled {
+            key,
+            revision: entry.revision,
+            data: entry.data(),
+            local_digest: local.digest,
+            pushed: false,
+        };
+    }
+
+    let keep_local = match strategy {
+        ConflictStrategy::NewestWins => Some(local.modified >= entry.modified),
+        ConflictStrategy::LocalWins => Some(true),
+        ConflictStrategy::RemoteWins => Some(false),
+        ConflictStrategy::Merge(_) | ConflictStrategy::Manual => None,
+    };
+    match keep_local {
+        Some(true) => push(remote, &local, &local.data, entry.revision, bytes),
+        Some(false) => SyncOutcome::Pulled {
+            key,
+            revision: entry.revision,
+            data: entry.data(),
+            local_digest: local.digest,
+            pushed: false,
+        },
+        None => {
+            let base = local.record.as_ref().and_then(SyncRecord::data);
+            let merged = match strategy {
+                ConflictStrategy::Merge(merge) => {
+                    let clean = base
+                        .as_ref()
+                        .map(|base| PersistData::merge3(base, &local.data, &entry.data()))
+                        .filter(PersistMerge::is_clean);
+                    match clean {
+                        Some(clean) => Some(clean.data),
+                        None => merge(&PersistSyncConflict {
+                            type_name: type_name.to_string(),
+                            local: local.data.clone(),
+                            remote: entry.data(),
+                            base: base.clone(),
+                            local_modified: local.modified,
+                            remote_modified: entry.modified,
+                        }),
+                    }
+                }
+                _ => None,
+            };
+            let Some(merged) = merged else {
+                return SyncOutcome::Conflict {
+                    key,
+                    local: local.data,
+                    local_modified: local.modified,
+                    remote: entry,
+                    base,
+                };
+            };
+            match push(remote, &local, &merged, entry.revision, bytes) {
+                SyncOutcome::Pushed { revision, .. } => SyncOutcome::Pulled {
+                    key,
+                    revision,
+                    data: merged,
+                    local_digest: local.digest,
+                    pushed: true,
+                },
+                failed => failed,
+            }
+        }
+    }
+}
+
+/// Writes `data` to the remote store as the revision after `after`.
+///
+/// `expected` is what the remote store held when the sync read it. If
+/// another device pushed since, nothing is written and the next sync sees
+/// its revision.
+fn push(
+    remote: &dyn PersistBackend,
+    local: &LocalEntry,
+    data: &PersistData,
+    after: u64,
+    expected: Option<&[u8]>,
+) -> SyncOutcome {
+    let entry = RemoteEntry {
+        revision: after + 1,
+        modified: local.modified,
+        data: data.to_json(),
+    };
+    let result = serde_json::to_vec(&entry)
+        .map_err(|e| PersistError::serialization(e.to_string()))
+        .and_then(|bytes| remote.write_if(&local.key, expected, &bytes));
+    match result {
+        Ok(true) => SyncOutcome::Pushed {
+            key: local.key.clone(),
+            revision: entry.revision,
+            digest: stable_hash(data),
+            data: data.clone(),
+        },
+        Ok(false) => SyncOutcome::Failed {
+            key: local.key.clone(),
+            error: PersistError::backend("the remote copy changed during the sync"),
+        },
+        Err(error) => SyncOutcome::Failed {
+            key: local.key.clone(),
+            error,
+        },
+    }
+}
+
+/// What the remote store holds under `key`, as stored and as an entry.
+fn read_remote(
+    remote: &dyn PersistBackend,
+    key: &str,
+) -> PersistResult<Option<(Vec<u8>, RemoteEntry)>> {
+    let Some(bytes) = remote.read(key)? else {
+        return Ok(None);
+    };
+    let entry = serde_json::from_slice(&bytes)
+        .map_err(|e| PersistError::serialization(format!("Invalid remote entry: {}", e)))?;
+    Ok(Some((bytes, entry)))
+}
+
+/// Applies pulled data, records what's now in sync and sends the events.
+fn finish_sync(world: &mut World, outcomes: Vec<SyncOutcome>) {
+    let mut synced = PersistSynced::default();
+    for outcome in outcomes {
+        match outcome {
+            SyncOutcome::Unchanged => {}
+            SyncOutcome::Pushed {
+                key,
+                revision,
+                digest,
+                data,
+            } => {
+                let mut manager = world.resource_mut::<PersistManager>();
+                manager.set_sync_record(&key, SyncRecord::new(revision, digest, &data));
+                synced.pushed.push(display_name(world, &key));
+            }
+            SyncOutcome::Pulled {
+                key,
+                revision,
+                data,
+                local_digest,
+                pushed,
+            } => {
+                let Some(hooks) = world
+                    .resource::<PersistManager>()
+                    .get_type_hooks(&key)
+                    .cloned()
+                else {
+                    continue;
+                };
+                // Changed while the sync ran, so the next one sees both sides changed
+                let current = hooks.extract_from_world(world);
+                if current.map(|data| stable_hash(&data)) != Some(local_digest) {
+                    debug!("{} changed during the sync, not applying it", key);
+                    continue;
+                }
+                if !hooks.apply_to_world(world, &data) {
+                    continue;
+                }
+                let digest = hooks
+                    .extract_from_world(world)
+                    .map_or(0, |data| stable_hash(&data));
+                let mut manager = world.resource_mut::<PersistManager>();
+                manager.set_sync_record(&key, SyncRecord::new(revision, digest, &data));
+                let name = display_name(world, &key);
+                if pushed {
+                    synced.pushed.push(name.clone());
+                }
+                synced.pulled.push(name);
+            }
+            SyncOutcome::Conflict {
+                key,
+                local,
+                local_modified,
+                remote,
+                base,
+            } => {
+                let type_name = display_name(world, &key);
+                warn!("{} changed both here and remotely", type_name);
+                let event = PersistSyncConflict {
+                    type_name,
+                    local,
+                    remote: remote.data(),
+                    base,
+                    local_modified,
+                    remote_modified: remote.modified,
+                };
+                if let Some(sync) = &mut world.resource_mut::<PersistManager>().sync {
+                    sync.conflicts.insert(key, remote);
+                }
+                world.send_event(event);
+            }
+            SyncOutcome::Failed { key, error } => {
+                let type_name = display_name(world, &key);
+                error!("Failed to sync {}: {}", type_name, error);
+                world
+                    .resource::<PersistManager>()
+                    .report_error(&key, &error);
+                synced.failed.push(type_name);
+            }
+        }
+    }
+    world.resource::<PersistManager>().save_sync_state();
+    if !synced.pushed.is_empty() || !synced.pulled.is_empty() || !synced.failed.is_empty() {
+        info!(
+            "Synced: {} pushed, {} pulled, {} failed",
+            synced.pushed.len(),
+            synced.pulled.len(),
+            synced.failed.len()
+        );
+    }
+    world.send_event(synced);
+}
@@ -0,0 +1,213 @@
+//! A persistence setup for tests that keeps every file in a temporary
+//! directory (`test-utils` feature).
+
+use crate::{
+    PathResolver, PersistData, PersistFile, PersistManager, PersistMode, PersistPlugin, PersistSet,
+    Persistable,
+};
+use bevy::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Adds a [`PersistPlugin`] whose files all live in a fresh temporary directory.
+///
+/// The dev file, pinned and group files, embed files and production files
+/// are all kept in the directory, so tests can't see each other's saves or
+/// leave files in the working directory. It's deleted along with the app.
+///
+/// ```ignore
+/// let mut app = App::new();
+/// app.add_plugins(MinimalPlugins).add_plugins(
+///     PersistTestPlugin::new()
+///         .seed(&Settings { volume: 0.25 })
+///         .seed_file("scores.ron", r#"{"Scores": (values: {"best": 10})}"#),
+/// );
+/// app.update();
+///
+/// app.world_mut().resource_mut::<Settings>().volume = 0.5;
+/// app.update();
+/// let dir = app.world().resource::<PersistTestDir>();
+/// dir.assert_saved(app.world(), &Settings { volume: 0.5 });
+/// ```
+pub struct PersistTestPlugin {
+    plugin: PersistPlugin,
+    dir: PersistTestDir,
+    seeds: Vec<(&'static str, PersistData)>,
+}
+
+impl PersistTestPlugin {
+    /// Creates the plugin with a default [`PersistPlugin`].
+    pub fn new() -> Self {
+        Self::with_plugin(PersistPlugin::new("TestOrg", "TestApp"))
+    }
+
+    /// Creates the plugin around a configured [`PersistPlugin`].
+    ///
+    /// Its root directory is replaced, and so is its path resolver if it
+    /// doesn't have one, so production files stay in the directory too.
+    pub fn with_plugin(mut plugin: PersistPlugin) -> Self {
+        let dir = PersistTestDir(Arc::new(
+            TempDir::new().expect("failed to create a temporary directory"),
+        ));
+        plugin.root_dir = Some(dir.path().to_path_buf());
+        plugin
+            .path_resolver
+            .get_or_insert_with(|| Arc::new(TestPathResolver(dir.path().join("platform"))));
+        Self {
+            plugin,
+            dir,
+            seeds: Vec::new(),
+        }
+    }
+
+    /// The directory the app's files are kept in.
+    pub fn dir(&self) -> &PersistTestDir {
+        &self.dir
+    }
+
+    /// Saves `value` before the app loads, as if an earlier run had saved it.
+    ///
+    /// It's written wherever `T`'s registration says it belongs, once every
+    /// type has been registered.
+    pub fn seed<T: Persistable>(mut self, value: &T) -> Self {
+        self.seeds.push((T::type_path(), value.to_persist_data()));
+        self
+    }
+
+    /// Writes `contents` to `path` in the directory before the app starts.
+    ///
+    /// For files hand-written in a particular layout, e.g. from an older
+    /// version. Relative paths are taken from the directory.
+    pub fn seed_file(self, path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Self {
+        let path = self.dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("failed to create a seed file's directory");
+        }
+        fs::write(&path, contents)
+            .unwrap_or_else(|e| panic!("failed to write seed file {:?}: {}", path, e));
+        self
+    }
+}
+
+impl Default for PersistTestPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugin for PersistTestPlugin {
+    fn build(&self, app: &mut App) {
+        self.plugin.build(app);
+        app.insert_resource(self.dir.clone());
+
+        let seeds = self.seeds.clone();
+        let write_seeds = move |mut manager: ResMut<PersistManager>| {
+            for (type_name, data) in &seeds {
+                if let Err(e) = manager.write_type_data(type_name, data.clone()) {
+                    panic!("failed to seed {}: {}", type_name, e);
+                }
+                let key = manager.get_storage_key(type_name);
+                manager.last_saved.remove(&key);
+            }
+            // Seeds stand in for an earlier run, not saves made by this one
+            manager.stats = Default::default();
+        };
+        app.add_systems(PreStartup, write_seeds.before(PersistSet::Load));
+    }
+}
+
+/// The temporary directory of a [`PersistTestPlugin`], inserted as a resource.
+///
+/// The directory is deleted once the app and every clone of this are dropped.
+#[derive(Resource, Clone)]
+pub struct PersistTestDir(Arc<TempDir>);
+
+impl PersistTestDir {
+    /// Path of the directory.
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    /// `path` inside the directory, or `path` itself if it's absolute.
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path().join(path)
+    }
+
+    /// Reads a file in the directory, panicking if it can't be read.
+    pub fn read_to_string(&self, path: impl AsRef<Path>) -> String {
+        let path = self.join(path);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {:?}: {}", path, e))
+    }
+
+    /// What's saved on disk for `T`, bypassing the manager's in-memory copies.
+    ///
+    /// Obfuscated, compressed and encrypted data is decoded like a load
+    /// would. Shipped defaults aren't layered in.
+    pub fn saved<T: Persistable>(&self, world: &World) -> Option<PersistData> {
+        let manager = world.resource::<PersistManager>();
+        let key = manager.storage_key_of::<T>();
+        let mode = manager.get_type_mode(&key);
+        if mode == PersistMode::Session {
+            return None;
+        }
+
+        let path = manager.get_resource_path(&key, mode);
+        let data = if path == manager.dev_file {
+            PersistFile::load_from_file(&path)
+                .ok()?
+                .get_type_data_or_legacy(&key, T::type_name())
+                .cloned()
+        } else {
+            let format = match manager.resolve_type_group(&key, mode) {
+                Some(group) => manager.get_group_format(group, &key),
+                None => manager.get_type_format(&key),
+            };
+            manager.read_standalone(&key, &path, format)
+        }?;
+        Some(manager.unseal_fields(&key, data))
+    }
+
+    /// Panics unless the data saved for `T` loads as `expected`.
+    pub fn assert_saved<T: Persistable + Default>(&self, world: &World, expected: &T) {
+        let Some(saved) = self.saved::<T>(world) else {
+            panic!("nothing is saved for {}", T::type_name());
+        };
+        let manager = world.resource::<PersistManager>();
+        let mut data = manager
+            .type_defaults
+            .get(&manager.storage_key_of::<T>())
+            .cloned()
+            .unwrap_or_default();
+        data.merge(saved);
+        let mut loaded = T::default();
+        loaded.load_from_persist_data(&data);
+
+        let saved = serde_json::Value::Object(loaded.to_persist_data().to_json());
+        let expected = serde_json::Value::Object(expected.to_persist_data().to_json());
+        assert_eq!(
+            saved,
+            expected,
+            "saved {} doesn't match the expected value",
+            T::type_name()
+        );
+    }
+}
+
+/// Places production files in one directory per mode under a root.
+struct TestPathResolver(PathBuf);
+
+impl PathResolver for TestPathResolver {
+    fn resolve(&self, type_name: &str, mode: PersistMode) -> PathBuf {
+        self.resolve_file(type_name, &type_name.to_lowercase(), mode)
+    }
+
+    fn resolve_file(&self, _type_name: &str, file_name: &str, mode: PersistMode) -> PathBuf {
+        let dir = match mode {
+            PersistMode::Secure | PersistMode::Obfuscated => "data",
+            _ => "config",
+        };
+        self.0.join(dir).join(file_name)
+    }
+}
@@ -0,0 +1,78 @@
+//! A [`PersistBackend`] over stores that only keep strings (`text-backend` feature).
+
+use crate::{PersistBackend, PersistResult};
+use base64::{engine::general_purpose, Engine as _};
+
+/// A key-value store that keeps strings, e.g. bevy_pkv's `PkvStore` or
+/// `localStorage` on the web.
+pub trait PersistTextStore: Send + Sync + 'static {
+    /// Returns the string stored under `key`, or `None` if nothing is.
+    fn get(&self, key: &str) -> PersistResult<Option<String>>;
+
+    /// Stores `value` under `key`, replacing anything already there.
+    fn set(&self, key: &str, value: &str) -> PersistResult<()>;
+
+    /// Removes what's stored under `key`, if anything.
+    fn remove(&self, key: &str) -> PersistResult<()>;
+}
+
+/// Keeps saves in a [`PersistTextStore`] as base64.
+///
+/// Obfuscated, compressed and encrypted saves aren't valid UTF-8, so they
+/// can't be stored as text directly. Here every save is base64 encoded on
+/// the way in and decoded on the way out:
+///
+/// ```ignore
+/// struct Pkv(Mutex<PkvStore>);
+///
+/// impl PersistTextStore for Pkv {
+///     fn get(&self, key: &str) -> PersistResult<Option<String>> {
+///         match self.0.lock().unwrap().get::<String>(key) {
+///             Ok(text) => Ok(Some(text)),
+///             Err(GetError::NotFound) => Ok(None),
+///             Err(e) => Err(PersistError::backend(e)),
+///         }
+///     }
+///     // ...
+/// }
+///
+/// let store = Pkv(Mutex::new(PkvStore::new("MyCompany", "MyGame")));
+/// app.add_plugins(PersistPlugin::new("MyCompany", "MyGame").with_backend(TextBackend::new(store)));
+/// ```
+///
+/// Values that aren't base64, e.g. saves a plain-text adapter wrote before,
+/// are read as they are.
+pub struct TextBackend<S> {
+    store: S,
+}
+
+impl<S: PersistTextStore> TextBackend<S> {
+    /// Keeps saves in `store`.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// The store saves are kept in.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S: PersistTextStore> PersistBackend for TextBackend<S> {
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+        Ok(self.store.get(key)?.map(|text| {
+            general_purpose::STANDARD
+                .decode(&text)
+                .unwrap_or_else(|_| text.into_bytes())
+        }))
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()> {
+        self.store
+            .set(key, &general_purpose::STANDARD.encode(bytes))
+    }
+
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        self.store.remove(key)
+    }
+}
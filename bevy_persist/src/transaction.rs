@@ -0,0 +1,225 @@
+//! Saving several resources so that either all of them or none are written.
+
+use crate::{
+    create_parent_dir, obfuscate, write_error, PersistData, PersistError, PersistFile,
+    PersistFormat, PersistManager, PersistMode, PersistResult, Persistable,
+};
+use bevy::prelude::*;
+use log::{error, info, warn};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Name the journal file is resolved under in production
+const JOURNAL_NAME: &str = "persist_transaction";
+
+/// Types to save together, built up in [`PersistManager::transaction`].
+#[derive(Debug, Default)]
+pub struct PersistTransaction {
+    type_names: Vec<String>,
+}
+
+impl PersistTransaction {
+    /// Adds `T` to the transaction.
+    pub fn save<T: Persistable>(&mut self) -> &mut Self {
+        self.save_named(T::type_path())
+    }
+
+    /// Adds a registered type to the transaction by name.
+    pub fn save_named(&mut self, type_name: impl Into<String>) -> &mut Self {
+        let type_name = type_name.into();
+        if !self.type_names.contains(&type_name) {
+            self.type_names.push(type_name);
+        }
+        self
+    }
+}
+
+impl PersistManager {
+    /// Saves several resources so a crash can't leave only some of them written.
+    ///
+    /// Saving `PlayerProgress` and `Inventory` separately can leave the
+    /// progress saved and the inventory not if the game dies between the
+    /// writes. Here both are first written together to a journal file in
+    /// one step, then to their usual files:
+    ///
+    /// ```ignore
+    /// PersistManager::transaction(world, |tx| {
+    ///     tx.save::<PlayerProgress>().save::<Inventory>();
+    /// })?;
+    /// ```
+    ///
+    /// If the game stops before every file is written, the journal is
+    /// replayed on the next start, before anything loads. A write that
+    /// fails while the game keeps running is reported like any failed save.
+    ///
+    /// Types are written regardless of manual flushing and
+    /// [`pause`](Self::pause), and are no longer dirty afterwards; those
+    /// whose file already holds their current data aren't rewritten. Fails
+    /// without writing anything if a type isn't registered.
+    pub fn transaction(
+        world: &mut World,
+        build: impl FnOnce(&mut PersistTransaction),
+    ) -> PersistResult<()> {
+        let mut tx = PersistTransaction::default();
+        build(&mut tx);
+        let mut manager = world
+            .get_resource_mut::<PersistManager>()
+            .ok_or_else(|| PersistError::ResourceNotFound("PersistManager".to_string()))?;
+        if let Some(missing) = tx
+            .type_names
+            .iter()
+            .find(|type_name| manager.get_type_hooks(type_name).is_none())
+        {
+            return Err(PersistError::ResourceNotFound(missing.clone()));
+        }
+        let keys: Vec<String> = tx
+            .type_names
+            .iter()
+            .map(|type_name| manager.get_storage_key(type_name))
+            .collect();
+        manager.dirty.retain(|key| !keys.contains(key));
+        Self::write_types(world, keys, true)
+    }
+
+    /// File transactions are written to before the files they change.
+    pub fn journal_path(&self) -> PathBuf {
+        if self.is_production() {
+            self.resolve_path(JOURNAL_NAME, PersistMode::Secure, "dat")
+        } else {
+            let stem = self
+                .dev_file
+                .file_stem()
+                .map_or_else(|| "persist".into(), |stem| stem.to_string_lossy());
+            self.dev_file
+                .with_file_name(format!("{}_transaction.dat", stem))
+        }
+    }
+
+    /// Writes the data of a transaction to the journal in one step.
+    ///
+    /// Types are journaled with their encrypted fields sealed, and the
+    /// journal is checksummed and scrambled, and encrypted when there's a
+    /// secret, so it gives away no more than the files it's replayed into.
+    /// It's built in a temporary file that's synced and renamed into place,
+    /// so it's either complete or missing.
+    ///
+    /// Nothing is journaled while read-only or with a backend, nor types
+    /// that are kept in memory, since none of those are written to files.
+    pub(crate) fn write_journal<'a>(
+        &self,
+        entries: impl Iterator<Item = (&'a String, &'a PersistData)>,
+    ) -> PersistResult<()> {
+        if self.read_only || self.backend.is_some() {
+            return Ok(());
+        }
+        let mut file = PersistFile::new();
+        for (type_name, data) in entries {
+            if self.get_type_mode(type_name) == PersistMode::Session
+                || self.memory_path(type_name).is_some()
+            {
+                continue;
+            }
+            let data = self
+                .seal_fields(type_name, self.data_to_write(type_name, data.clone()))
+                .map_err(|e| e.for_type(self.get_type_name(type_name)))?;
+            file.set_type_data_at(type_name.clone(), data, self.now());
+        }
+        if file.type_data.is_empty() {
+            return Ok(());
+        }
+        file.version = self.file_version();
+        let bytes = self.encode_journal(file.to_string_as(PersistFormat::Ron)?.as_bytes())?;
+
+        let path = self.journal_path();
+        let temp = path.with_extension("dat.tmp");
+        create_parent_dir(&temp)?;
+        let written = fs::File::create(&temp).and_then(|mut out| {
+            out.write_all(&bytes)?;
+            out.sync_all()
+        });
+        written
+            .and_then(|()| fs::rename(&temp, &path))
+            .map_err(|e| write_error(e).at(&path))
+    }
+
+    /// The bytes of a journal holding `content`.
+    fn encode_journal(&self, content: &[u8]) -> PersistResult<Vec<u8>> {
+        #[cfg(feature = "secure")]
+        if self.secret.is_some() {
+            return Ok(obfuscate::obfuscate(&self.encrypt_data(content)?));
+        }
+        Ok(obfuscate::obfuscate(content))
+    }
+
+    /// Reads a journal back, failing if it was edited or cut short.
+    fn decode_journal(&self, bytes: &[u8]) -> PersistResult<PersistFile> {
+        let bytes = obfuscate::deobfuscate(bytes)?;
+        #[cfg(feature = "secure")]
+        let bytes = if self.secret.is_some() {
+            self.decrypt_data(&bytes)?
+        } else {
+            bytes
+        };
+        let content = String::from_utf8(bytes)
+            .map_err(|e| PersistError::serialization(format!("Invalid UTF-8: {}", e)))?;
+        PersistFile::parse_as(&content, PersistFormat::Ron)
+    }
+
+    /// Removes the journal once its transaction has been written.
+    pub(crate) fn remove_journal(&self) {
+        let path = self.journal_path();
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove transaction journal {:?}: {}", path, e);
+            }
+        }
+    }
+
+    /// Finishes a transaction the last run didn't, by writing every type
+    /// in the journal again.
+    ///
+    /// A journal that fails its checksum or can't be decrypted isn't
+    /// replayed. The journal is removed afterwards even if a write fails, so
+    /// a transaction that can never be written doesn't block every start.
+    /// While read-only it's left for a run that can write.
+    pub(crate) fn replay_journal(&mut self) {
+        let path = self.journal_path();
+        if self.read_only || !path.exists() {
+            return;
+        }
+        let file = match fs::read(&path)
+            .map_err(|e| PersistError::io("Failed to read file", e).at(&path))
+            .and_then(|bytes| self.decode_journal(&bytes))
+        {
+            Ok(file) => file,
+            Err(e) => {
+                error!("Failed to read transaction journal {:?}: {}", path, e);
+                self.remove_journal();
+                return;
+            }
+        };
+        info!(
+            "Finishing an interrupted save of {} types from {:?}",
+            file.type_data.len(),
+            path
+        );
+        self.defer_dev_saves();
+        for (type_name, data) in file.type_data {
+            let data = self.unseal_fields(&type_name, data);
+            if let Err(e) = self.write_type_data(&type_name, data) {
+                error!("Failed to finish saving {}: {}", type_name, e);
+                self.report_error(&type_name, &e);
+            }
+        }
+        if let Err(e) = self.write_deferred_saves() {
+            error!("Failed to finish the interrupted save: {}", e);
+        }
+        self.remove_journal();
+    }
+}
+
+/// Replays an interrupted transaction before anything loads.
+pub(crate) fn replay_journal(mut manager: ResMut<PersistManager>) {
+    manager.replay_journal();
+}
@@ -0,0 +1,301 @@
+//! Serves registered types over WebSocket for remote tuning (`tweak-server` feature).
+
+use crate::{PersistData, PersistError, PersistManager, PersistMode, PersistResult};
+use bevy::prelude::*;
+use log::{debug, error, info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::Message;
+
+/// Clients served at once; further connections are closed right away
+const MAX_CONNECTIONS: usize = 8;
+
+/// How long a client has to send its handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The running tweak server.
+///
+/// Inserted by [`PersistPlugin`](crate::PersistPlugin) when
+/// [`with_tweak_server`](crate::PersistPlugin::with_tweak_server) is set.
+/// Each text message is a JSON request, answered with one JSON response:
+///
+/// ```text
+/// {"method": "list"}
+///     -> {"types": [{"type_name": "GameConfig", "mode": "Dev", ...}]}
+/// {"method": "get", "type_name": "GameConfig"}
+///     -> {"type_name": "GameConfig", "data": {"speed": 1.0, ...}}
+/// {"method": "set", "type_name": "GameConfig", "data": {"speed": 2.5}}
+///     -> {"type_name": "GameConfig", "data": {"speed": 2.5, ...}}
+/// ```
+///
+/// `set` only replaces the fields it names. The resource is marked as
+/// changed, so the patch is saved like any other change. Failures are
+/// answered with `{"error": "..."}`. Requests are answered once per frame
+/// during `Update`.
+///
+/// Clients connect to [`url`](Self::url), which carries a random token
+/// that's logged when the server starts; connections without it, or sent by
+/// a web page that isn't served from localhost, are refused. Secure types
+/// and types with encrypted fields aren't served, and at most 8 clients are
+/// served at once.
+#[derive(Resource)]
+pub struct PersistTweakServer {
+    local_addr: SocketAddr,
+    token: String,
+    requests: Mutex<Receiver<PendingRequest>>,
+}
+
+impl PersistTweakServer {
+    /// Binds the server and starts accepting connections on a background thread.
+    pub fn bind(address: &str) -> PersistResult<Self> {
+        let listener = TcpListener::bind(address).map_err(|e| {
+            PersistError::io(format!("Failed to bind tweak server to {}", address), e)
+        })?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| PersistError::io("Failed to read tweak server address", e))?;
+
+        let token = random_token();
+        let (sender, receiver) = channel();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = token.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(_) if connections.load(Ordering::SeqCst) >= MAX_CONNECTIONS => {
+                        warn!(
+                            "Tweak server is serving {} clients, refusing another",
+                            MAX_CONNECTIONS
+                        );
+                    }
+                    Ok(stream) => {
+                        connections.fetch_add(1, Ordering::SeqCst);
+                        let (sender, token, connections) =
+                            (sender.clone(), accepted.clone(), connections.clone());
+                        thread::spawn(move || {
+                            serve_connection(stream, &token, sender);
+                            connections.fetch_sub(1, Ordering::SeqCst);
+                        });
+                    }
+                    Err(e) => warn!("Tweak server failed to accept a connection: {}", e),
+                }
+            }
+        });
+
+        let server = Self {
+            local_addr,
+            token,
+            requests: Mutex::new(receiver),
+        };
+        info!("Tweak server listening on {}", server.url());
+        Ok(server)
+    }
+
+    /// Address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The token clients must send, generated when the server starts.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// The address to connect to, with the token, e.g. `ws://127.0.0.1:7878/?token=...`.
+    pub fn url(&self) -> String {
+        format!("ws://{}/?token={}", self.local_addr, self.token)
+    }
+}
+
+/// A request waiting for the game loop, with where to send the answer.
+struct PendingRequest {
+    message: String,
+    reply: Sender<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum TweakRequest {
+    List,
+    Get {
+        type_name: String,
+    },
+    Set {
+        type_name: String,
+        data: HashMap<String, Value>,
+    },
+}
+
+/// Forwards a client's messages to the game loop until it disconnects.
+#[allow(clippy::result_large_err)] // The handshake callback's error type is tungstenite's
+fn serve_connection(stream: TcpStream, token: &str, requests: Sender<PendingRequest>) {
+    // A client that never finishes the handshake doesn't hold its slot forever
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).ok();
+    let check = |request: &Request, response: Response| check_handshake(request, token, response);
+    let mut socket = match tungstenite::accept_hdr(stream, check) {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("Tweak server handshake failed: {}", e);
+            return;
+        }
+    };
+    socket.get_ref().set_read_timeout(None).ok();
+
+    loop {
+        let message = match socket.read() {
+            Ok(Message::Text(text)) => text.to_string(),
+            Ok(Message::Close(_)) => return,
+            Ok(_) => continue,
+            Err(e) => {
+                debug!("Tweak client disconnected: {}", e);
+                return;
+            }
+        };
+
+        let (reply, response) = channel();
+        if requests.send(PendingRequest { message, reply }).is_err() {
+            return;
+        }
+        // The game loop dropped the request, e.g. because the app exited
+        let Ok(response) = response.recv() else {
+            return;
+        };
+        if let Err(e) = socket.send(Message::text(response)) {
+            debug!("Tweak client disconnected: {}", e);
+            return;
+        }
+    }
+}
+
+/// Refuses handshakes without the token, or from a page on another site.
+///
+/// Browsers let any page open a WebSocket to localhost, so a page's origin
+/// is checked too.
+#[allow(clippy::result_large_err)] // The error type is tungstenite's
+fn check_handshake(
+    request: &Request,
+    token: &str,
+    response: Response,
+) -> Result<Response, ErrorResponse> {
+    let refuse = |status: StatusCode, reason: &str| {
+        warn!("Tweak server refused a connection: {}", reason);
+        let mut response = ErrorResponse::new(Some(reason.to_string()));
+        *response.status_mut() = status;
+        Err(response)
+    };
+    let has_token = request.uri().query().is_some_and(|query| {
+        query
+            .split('&')
+            .any(|pair| pair.strip_prefix("token=") == Some(token))
+    });
+    if !has_token {
+        return refuse(StatusCode::UNAUTHORIZED, "missing or wrong token");
+    }
+    let origin = request
+        .headers()
+        .get("origin")
+        .map(|origin| origin.to_str().unwrap_or_default());
+    if origin.is_some_and(|origin| !is_local_origin(origin)) {
+        return refuse(StatusCode::FORBIDDEN, "not a local origin");
+    }
+    Ok(response)
+}
+
+/// Whether a browser `Origin` header names a page served from this machine.
+fn is_local_origin(origin: &str) -> bool {
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next(),
+        None => host.split([':', '/']).next(),
+    };
+    matches!(host, Some("localhost" | "127.0.0.1" | "::1"))
+}
+
+/// 128 bits from the randomly seeded std hasher, as hex.
+fn random_token() -> String {
+    (0..2u64)
+        .map(|i| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(i);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// Answers the requests received since the last frame.
+pub(crate) fn answer_requests(world: &mut World) {
+    let pending: Vec<_> = match world
+        .get_resource::<PersistTweakServer>()
+        .map(|server| server.requests.lock())
+    {
+        Some(Ok(requests)) => requests.try_iter().collect(),
+        _ => return,
+    };
+
+    for request in pending {
+        let response = match serde_json::from_str(&request.message) {
+            Ok(parsed) => handle_request(world, parsed),
+            Err(e) => Err(PersistError::serialization(e)),
+        }
+        .unwrap_or_else(|e| json!({ "error": e.to_string() }));
+        request.reply.send(response.to_string()).ok();
+    }
+}
+
+fn handle_request(world: &mut World, request: TweakRequest) -> PersistResult<Value> {
+    let manager = world.resource::<PersistManager>();
+    if let TweakRequest::Get { type_name } | TweakRequest::Set { type_name, .. } = &request {
+        if is_hidden(manager, type_name) {
+            return Err(PersistError::ResourceNotFound(type_name.clone()));
+        }
+    }
+    match request {
+        TweakRequest::List => {
+            let types: Vec<_> = manager
+                .registered_types()
+                .filter(|info| !is_hidden(manager, info.storage_key))
+                .collect();
+            Ok(json!({ "types": types }))
+        }
+        TweakRequest::Get { type_name } => {
+            let data = PersistManager::live_data(world, &type_name)?;
+            Ok(json!({ "type_name": type_name, "data": data.to_json() }))
+        }
+        TweakRequest::Set { type_name, data } => {
+            let patch = PersistData::from_json(data);
+            let data = PersistManager::patch_live_data(world, &type_name, patch)?;
+            debug!("Tweaked {} remotely", type_name);
+            Ok(json!({ "type_name": type_name, "data": data.to_json() }))
+        }
+    }
+}
+
+/// Whether a type holds data that shouldn't leave the game, so isn't served.
+fn is_hidden(manager: &PersistManager, type_name: &str) -> bool {
+    manager.get_type_mode(type_name) == PersistMode::Secure
+        || manager
+            .encrypted_fields
+            .contains_key(&*manager.key(type_name))
+}
+
+/// Starts the server, logging instead of failing the app if the address is taken.
+pub(crate) fn start(app: &mut App, address: &str) {
+    match PersistTweakServer::bind(address) {
+        Ok(server) => {
+            app.insert_resource(server)
+                .add_systems(Update, answer_requests);
+        }
+        Err(e) => error!("{}", e),
+    }
+}
@@ -440,3 +440,4800 @@ fn test_persist_mode_enum() {
     assert_eq!(format!("{:?}", dynamic), "Dynamic");
     assert_eq!(format!("{:?}", secure), "Secure");
 }
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(name = "Scores", auto_save = false)]
+struct Scores<T: Send + Sync + 'static> {
+    best: u32,
+    #[serde(skip)]
+    _marker: std::marker::PhantomData<T>,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+struct Arcade;
+
+#[derive(Default, Debug, PartialEq, Clone)]
+struct Campaign;
+
+#[test]
+fn test_generic_resource_registration() {
+    // Each instantiation gets its own persistence key
+    assert_eq!(Scores::<Arcade>::type_name(), "Scores<Arcade>");
+    assert_eq!(Scores::<Campaign>::type_name(), "Scores<Campaign>");
+    assert!(!Scores::<Arcade>::default_auto_save());
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "GenericTest"));
+    app.register_persist::<Scores<Arcade>>()
+        .register_persist::<Scores<Campaign>>();
+
+    assert!(app.world().get_resource::<Scores<Arcade>>().is_some());
+    assert!(app.world().get_resource::<Scores<Campaign>>().is_some());
+
+    let manager = app.world().resource::<PersistManager>();
+    assert!(!manager.is_auto_save_enabled("Scores<Arcade>"));
+    assert!(manager.is_auto_save_enabled("TestSettings"));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic, format = "json")]
+struct JsonSettings {
+    scale: f32,
+}
+
+#[test]
+fn test_per_type_format() {
+    assert_eq!(TestSettings::persist_format(), PersistFormat::Ron);
+    assert_eq!(JsonSettings::persist_format(), PersistFormat::Json);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "FormatTest"));
+
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(manager.get_type_format("JsonSettings"), PersistFormat::Json);
+    assert_eq!(manager.get_type_format("TestSettings"), PersistFormat::Ron);
+
+    #[cfg(feature = "prod")]
+    {
+        let path = manager.get_resource_path("JsonSettings", PersistMode::Dynamic);
+        assert_eq!(path.extension().unwrap(), "json");
+    }
+}
+
+#[test]
+fn test_explicit_format_ignores_extension() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("settings.cfg");
+
+    let mut persist_file = PersistFile::new();
+    let mut data = PersistData::new();
+    data.insert("scale", 2.5f32);
+    persist_file.set_type_data("JsonSettings".to_string(), data);
+    persist_file
+        .save_to_file_as(&file_path, PersistFormat::Ron)
+        .unwrap();
+
+    // Extension guessing would pick JSON and fail on RON content
+    assert!(PersistFile::load_from_file(&file_path).is_err());
+
+    let loaded = PersistFile::load_from_file_as(&file_path, PersistFormat::Ron).unwrap();
+    let data = loaded.get_type_data("JsonSettings").unwrap();
+    assert_eq!(data.get::<f32>("scale"), Some(2.5));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(file = "pinned_settings.ron")]
+struct PinnedSettings {
+    level: u32,
+}
+
+#[test]
+fn test_file_attribute_pins_path() {
+    assert_eq!(PinnedSettings::persist_file(), Some("pinned_settings.ron"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let pinned_path = temp_dir.path().join("pinned.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "PinnedTest"));
+
+    {
+        let mut manager = app.world_mut().resource_mut::<PersistManager>();
+        assert_eq!(
+            manager.get_type_file("PinnedSettings"),
+            Some(std::path::Path::new("pinned_settings.ron"))
+        );
+
+        // Absolute overrides resolve as-is in every build
+        manager.set_type_file("PinnedSettings".to_string(), &pinned_path);
+        #[cfg(not(feature = "prod"))]
+        {
+            manager.dev_file = temp_dir.path().join("pinned_dev.ron");
+        }
+        assert_eq!(
+            manager.get_resource_path("PinnedSettings", PersistMode::Dev),
+            pinned_path
+        );
+    }
+
+    app.world_mut().resource_mut::<PinnedSettings>().level = 7;
+    app.update();
+
+    let loaded = PersistFile::load_from_file(&pinned_path).unwrap();
+    let data = loaded
+        .get_type_data("integration_test::PinnedSettings")
+        .unwrap();
+    assert_eq!(data.get::<u32>("level"), Some(7));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(group = "grouptest")]
+struct AudioGroupSettings {
+    volume: f32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(group = "grouptest")]
+struct VideoGroupSettings {
+    vsync: bool,
+}
+
+#[test]
+fn test_resource_groups() {
+    assert_eq!(AudioGroupSettings::persist_group(), Some("grouptest"));
+
+    let mut manager = PersistManager::new("TestOrg", "GroupTest");
+    manager.set_type_group("AudioGroupSettings".to_string(), "grouptest");
+    manager.set_type_group("VideoGroupSettings".to_string(), "grouptest");
+
+    let mut types = manager.get_group_types("grouptest");
+    types.sort();
+    assert_eq!(types, vec!["AudioGroupSettings", "VideoGroupSettings"]);
+
+    // Both members resolve to the same shared file
+    let audio_path = manager.get_resource_path("AudioGroupSettings", PersistMode::Dynamic);
+    let video_path = manager.get_resource_path("VideoGroupSettings", PersistMode::Dynamic);
+    assert_eq!(audio_path, video_path);
+    assert!(audio_path.ends_with("grouptest.ron"));
+
+    // Group-level auto-save toggles apply to every member
+    assert!(manager.is_auto_save_enabled("AudioGroupSettings"));
+    manager.set_group_auto_save("grouptest", false);
+    assert!(!manager.is_auto_save_enabled("AudioGroupSettings"));
+    assert!(!manager.is_auto_save_enabled("VideoGroupSettings"));
+    assert!(manager.is_auto_save_enabled("TestSettings"));
+}
+
+#[test]
+#[cfg(not(feature = "prod"))]
+fn test_group_file_keeps_all_members() {
+    let mut manager = PersistManager::new("TestOrg", "GroupFileTest");
+    manager.set_type_group("AudioGroupSettings".to_string(), "groupfiletest");
+    manager.set_type_group("VideoGroupSettings".to_string(), "groupfiletest");
+
+    let mut audio = PersistData::new();
+    audio.insert("volume", 0.25f32);
+    let mut video = PersistData::new();
+    video.insert("vsync", true);
+
+    let path = manager
+        .save_group_data(
+            "groupfiletest",
+            "AudioGroupSettings",
+            audio,
+            PersistMode::Dev,
+        )
+        .unwrap();
+    manager
+        .save_group_data(
+            "groupfiletest",
+            "VideoGroupSettings",
+            video,
+            PersistMode::Dev,
+        )
+        .unwrap();
+
+    let loaded = PersistFile::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        loaded
+            .get_type_data("AudioGroupSettings")
+            .unwrap()
+            .get::<f32>("volume"),
+        Some(0.25)
+    );
+    assert_eq!(
+        loaded
+            .get_type_data("VideoGroupSettings")
+            .unwrap()
+            .get::<bool>("vsync"),
+        Some(true)
+    );
+}
+
+#[test]
+fn test_mixed_format_group_keeps_files_apart() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut manager =
+        PersistManager::new("TestOrg", "MixedGroupTest").with_root_dir(temp_dir.path());
+    manager.set_type_group("AudioGroupSettings".to_string(), "mixed");
+    manager.set_type_group("VideoGroupSettings".to_string(), "mixed");
+    manager.set_type_format("VideoGroupSettings".to_string(), PersistFormat::Json);
+
+    let mut audio = PersistData::new();
+    audio.insert("volume", 0.25f32);
+    let mut video = PersistData::new();
+    video.insert("vsync", true);
+
+    let audio_path = manager
+        .save_group_data("mixed", "AudioGroupSettings", audio, PersistMode::Dynamic)
+        .unwrap();
+    let video_path = manager
+        .save_group_data("mixed", "VideoGroupSettings", video, PersistMode::Dynamic)
+        .unwrap();
+    assert_ne!(audio_path, video_path);
+
+    // Each file holds only the members saved in its format
+    let audio_file = PersistFile::load_from_file_as(&audio_path, PersistFormat::Ron).unwrap();
+    assert!(audio_file.get_type_data("AudioGroupSettings").is_some());
+    assert!(audio_file.get_type_data("VideoGroupSettings").is_none());
+    let video_file = PersistFile::load_from_file_as(&video_path, PersistFormat::Json).unwrap();
+    assert!(video_file.get_type_data("VideoGroupSettings").is_some());
+    assert!(video_file.get_type_data("AudioGroupSettings").is_none());
+
+    // Removing a member only rewrites its own file
+    manager.remove_type_data("VideoGroupSettings").unwrap();
+    let audio_file = PersistFile::load_from_file_as(&audio_path, PersistFormat::Ron).unwrap();
+    assert!(audio_file.get_type_data("AudioGroupSettings").is_some());
+    let video_file = PersistFile::load_from_file_as(&video_path, PersistFormat::Json).unwrap();
+    assert!(video_file.get_type_data("VideoGroupSettings").is_none());
+}
+
+#[test]
+fn test_storage_keys_are_namespaced() {
+    assert_eq!(TestSettings::type_path(), "integration_test::TestSettings");
+    assert_eq!(
+        Scores::<Arcade>::type_path(),
+        "integration_test::Scores<integration_test::Arcade>"
+    );
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "KeyTest"));
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(
+        manager.get_storage_key("TestSettings"),
+        "integration_test::TestSettings"
+    );
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "KeyTest").with_namespace("game"));
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(
+        manager.get_storage_key("TestSettings"),
+        "game::TestSettings"
+    );
+}
+
+#[test]
+fn test_legacy_short_keys_still_load() {
+    let mut file = PersistFile::new();
+    let mut data = PersistData::new();
+    data.insert("volume", 0.4f32);
+    file.set_type_data("TestSettings".to_string(), data);
+
+    let found = file
+        .get_type_data_or_legacy("integration_test::TestSettings", "TestSettings")
+        .unwrap();
+    assert_eq!(found.get::<f32>("volume"), Some(0.4));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+struct MigratedSettings {
+    volume: f32,
+}
+
+#[test]
+fn test_legacy_entries_are_migrated_once_read() {
+    let temp_dir = TempDir::new().unwrap();
+    let dev_file = PersistManager::new("TestOrg", "MigrateTest")
+        .with_root_dir(temp_dir.path())
+        .dev_file;
+    let mut legacy = PersistData::new();
+    legacy.insert("volume", 0.4f32);
+    let mut file = PersistFile::new();
+    file.set_type_data("MigratedSettings".to_string(), legacy);
+    file.save_to_file(&dev_file).unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "MigrateTest")
+            .with_environment(PersistEnvironment::Dev)
+            .with_root_dir(temp_dir.path()),
+    );
+    app.update();
+    assert_eq!(app.world().resource::<MigratedSettings>().volume, 0.4);
+
+    // Saving under the storage key drops the entry it was read from
+    app.world_mut().resource_mut::<MigratedSettings>().volume = 0.6;
+    app.update();
+    let saved = PersistFile::load_from_file(&dev_file).unwrap();
+    assert!(saved.get_type_data("MigratedSettings").is_none());
+    let data = saved
+        .get_type_data("integration_test::MigratedSettings")
+        .unwrap();
+    assert_eq!(data.get::<f32>("volume"), Some(0.6));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(dynamic)]
+struct MigratedProfile {
+    name: String,
+}
+
+/// Puts each file in one directory under the name it's given.
+struct FileNameResolver(std::path::PathBuf);
+
+impl PathResolver for FileNameResolver {
+    fn resolve(&self, type_name: &str, mode: PersistMode) -> std::path::PathBuf {
+        self.resolve_file(type_name, &type_name.to_lowercase(), mode)
+    }
+
+    fn resolve_file(
+        &self,
+        _type_name: &str,
+        file_name: &str,
+        _mode: PersistMode,
+    ) -> std::path::PathBuf {
+        self.0.join(file_name)
+    }
+}
+
+#[test]
+fn test_legacy_production_files_are_migrated_once_read() {
+    let temp_dir = TempDir::new().unwrap();
+    let legacy_path = temp_dir.path().join("migratedprofile.ron");
+    let mut legacy = PersistData::new();
+    legacy.insert("name", "Ada");
+    let mut file = PersistFile::new();
+    file.set_type_data("MigratedProfile".to_string(), legacy);
+    file.save_to_file(&legacy_path).unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "MigrateFileTest")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(FileNameResolver(temp_dir.path().to_path_buf())),
+    );
+    app.update();
+    assert_eq!(app.world().resource::<MigratedProfile>().name, "Ada");
+
+    // Saving writes the file named after the storage key in its place
+    app.world_mut().resource_mut::<MigratedProfile>().name = "Grace".to_string();
+    app.update();
+    assert!(!legacy_path.exists());
+    let path = temp_dir.path().join("integration_test_migratedprofile.ron");
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::MigratedProfile")
+        .unwrap();
+    assert_eq!(data.get::<String>("name"), Some("Grace".to_string()));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(name = "Duplicate", dynamic)]
+struct FirstDuplicate<T: Send + Sync + 'static> {
+    count: u32,
+    #[serde(skip)]
+    _marker: std::marker::PhantomData<T>,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(name = "Duplicate", dynamic)]
+struct SecondDuplicate<T: Send + Sync + 'static> {
+    count: u32,
+    #[serde(skip)]
+    _marker: std::marker::PhantomData<T>,
+}
+
+#[test]
+fn test_same_named_types_are_kept_apart() {
+    let temp_dir = TempDir::new().unwrap();
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "DuplicateTest")
+                .with_root_dir(temp_dir.path())
+                .with_path_resolver(FileNameResolver(temp_dir.path().to_path_buf())),
+        );
+        app.register_persist::<FirstDuplicate<Arcade>>()
+            .register_persist::<SecondDuplicate<Arcade>>();
+        app.update();
+        app
+    };
+
+    let mut app = build_app();
+    let manager = app.world().resource::<PersistManager>();
+    let first = manager.storage_key_of::<FirstDuplicate<Arcade>>();
+    let second = manager.storage_key_of::<SecondDuplicate<Arcade>>();
+    assert_ne!(first, second);
+    assert_eq!(manager.get_type_name(&first), "Duplicate<Arcade>");
+    assert_eq!(manager.get_type_name(&second), "Duplicate<Arcade>");
+
+    app.world_mut()
+        .resource_mut::<FirstDuplicate<Arcade>>()
+        .count = 1;
+    app.world_mut()
+        .resource_mut::<SecondDuplicate<Arcade>>()
+        .count = 2;
+    app.update();
+    drop(app);
+
+    let app = build_app();
+    assert_eq!(app.world().resource::<FirstDuplicate<Arcade>>().count, 1);
+    assert_eq!(app.world().resource::<SecondDuplicate<Arcade>>().count, 2);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Multiple Persist types are stored under `game::Duplicate<Arcade>`")]
+fn test_storage_key_collision_is_detected() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "DuplicateTest").with_namespace("game"));
+    app.register_persist::<FirstDuplicate<Arcade>>()
+        .register_persist::<SecondDuplicate<Arcade>>();
+}
+
+// Stands in for a resource from another crate that can't derive Persist
+#[derive(Resource, Default, Serialize, Deserialize, Debug, PartialEq)]
+struct ForeignSettings {
+    brightness: f32,
+}
+
+#[test]
+fn test_builder_registration_without_derive() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("foreign.json");
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(PersistPlugin::new("TestOrg", "BuilderTest"));
+        let builder = app
+            .persist_resource::<ForeignSettings>()
+            .mode(PersistMode::Dynamic)
+            .format(PersistFormat::Json)
+            .file(&path);
+        assert_eq!(builder.type_name(), "ForeignSettings");
+
+        let manager = app.world().resource::<PersistManager>();
+        assert_eq!(
+            manager.get_type_mode("ForeignSettings"),
+            PersistMode::Dynamic
+        );
+        assert_eq!(
+            manager.get_type_format("ForeignSettings"),
+            PersistFormat::Json
+        );
+
+        app.world_mut().resource_mut::<ForeignSettings>().brightness = 0.8;
+        app.update();
+    }
+
+    assert!(path.exists());
+
+    // A fresh app loads the saved value back
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "BuilderTest"));
+    app.persist_resource::<ForeignSettings>()
+        .auto_save(false)
+        .file(&path);
+    app.update();
+
+    assert_eq!(app.world().resource::<ForeignSettings>().brightness, 0.8);
+    assert!(!app
+        .world()
+        .resource::<PersistManager>()
+        .is_auto_save_enabled("ForeignSettings"));
+}
+
+// Stands in for a third-party resource with no serde impls at all
+#[derive(Resource)]
+struct ExternalDevice {
+    name: String,
+    volume: f32,
+}
+
+fn add_external_device(app: &mut App, path: &std::path::Path) {
+    app.persist_external::<ExternalDevice>(
+        |device| {
+            let mut data = PersistData::new();
+            data.insert("name", &device.name);
+            data.insert("volume", device.volume);
+            data
+        },
+        |device, data| {
+            if let Some(name) = data.get("name") {
+                device.name = name;
+            }
+            if let Some(volume) = data.get("volume") {
+                device.volume = volume;
+            }
+        },
+    )
+    .file(path);
+}
+
+#[test]
+fn test_external_resource_closures() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("device.ron");
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(PersistPlugin::new("TestOrg", "ExternalTest"));
+        app.insert_resource(ExternalDevice {
+            name: "Default".to_string(),
+            volume: 1.0,
+        });
+        add_external_device(&mut app, &path);
+        app.update();
+
+        let mut device = app.world_mut().resource_mut::<ExternalDevice>();
+        device.name = "Headphones".to_string();
+        device.volume = 0.4;
+        app.update();
+    }
+
+    assert!(path.exists());
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ExternalTest"));
+    app.insert_resource(ExternalDevice {
+        name: "Default".to_string(),
+        volume: 1.0,
+    });
+    add_external_device(&mut app, &path);
+    app.update();
+
+    let device = app.world().resource::<ExternalDevice>();
+    assert_eq!(device.name, "Headphones");
+    assert_eq!(device.volume, 0.4);
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .get_type_hooks("ExternalDevice")
+        .is_some());
+}
+
+#[test]
+fn test_explicit_registration_without_auto_register() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ManualRegisterTest").with_auto_register(false));
+
+    // Nothing derived is picked up on its own
+    assert!(app.world().get_resource::<TestSettings>().is_none());
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .get_type_path("TestSettings")
+        .is_none());
+
+    app.register_persist::<TestSettings>();
+    assert!(app.world().get_resource::<TestSettings>().is_some());
+    assert_eq!(
+        app.world()
+            .resource::<PersistManager>()
+            .get_type_path("TestSettings"),
+        Some("integration_test::TestSettings")
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct WindowScale {
+    factor: f32,
+}
+
+#[derive(Resource, Default)]
+struct AppliedScale(f32);
+
+#[test]
+fn test_systems_order_after_persist_set_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("scale.ron");
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(PersistPlugin::new("TestOrg", "PersistSetTest"));
+        app.persist_resource::<WindowScale>().file(&path);
+        app.world_mut().resource_mut::<WindowScale>().factor = 1.5;
+        app.update();
+    }
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "PersistSetTest"));
+    app.persist_resource::<WindowScale>().file(&path);
+    app.init_resource::<AppliedScale>();
+    app.add_systems(
+        PreStartup,
+        (|scale: Res<WindowScale>, mut applied: ResMut<AppliedScale>| {
+            applied.0 = scale.factor;
+        })
+        .after(PersistSet::Load),
+    );
+    app.update();
+
+    assert_eq!(app.world().resource::<AppliedScale>().0, 1.5);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct EventedSettings {
+    level: u32,
+}
+
+// Every auto-registered type saves on the first frame, so only look at ours
+fn evented_events<E: Event + Clone>(app: &App, type_name: impl Fn(&E) -> &str) -> Vec<E> {
+    let events = app.world().resource::<Events<E>>();
+    events
+        .get_cursor()
+        .read(events)
+        .filter(|event| type_name(event) == "EventedSettings")
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_lifecycle_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("evented.ron");
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(PersistPlugin::new("TestOrg", "EventTest"));
+        app.persist_resource::<EventedSettings>().file(&path);
+        app.world_mut().resource_mut::<EventedSettings>().level = 3;
+        app.update();
+
+        let saved = evented_events(&app, |e: &PersistSaved| &e.type_name);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].type_name, "EventedSettings");
+        assert_eq!(saved[0].path, path);
+        assert!(saved[0].bytes > 0);
+        assert!(evented_events(&app, |e: &PersistLoaded| &e.type_name).is_empty());
+    }
+
+    // Loading existing data reports it
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "EventTest"));
+    app.persist_resource::<EventedSettings>().file(&path);
+    app.update();
+    let loaded = evented_events(&app, |e: &PersistLoaded| &e.type_name);
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].type_name, "EventedSettings");
+
+    // A file nested under a regular file can't be written
+    let blocked = path.join("nested.ron");
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("EventedSettings".to_string(), &blocked);
+    app.world_mut().resource_mut::<EventedSettings>().level = 4;
+    app.update();
+    let failed = evented_events(&app, |e: &PersistSaveFailed| &e.type_name);
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].type_name, "EventedSettings");
+}
+
+#[test]
+fn test_failed_saves_are_retried() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("evented.ron");
+    let blocked = path.join("nested.ron");
+    std::fs::write(&path, "").unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "RetryTest").with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            initial_delay: std::time::Duration::ZERO,
+            ..default()
+        }),
+    );
+    app.persist_resource::<EventedSettings>().file(&blocked);
+    app.update();
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .is_retry_pending("EventedSettings"));
+
+    // The file becomes writable before the retries run out
+    std::fs::remove_file(&path).unwrap();
+    std::fs::create_dir(&path).unwrap();
+    app.update();
+    assert!(blocked.exists());
+    assert!(!app
+        .world()
+        .resource::<PersistManager>()
+        .is_retry_pending("EventedSettings"));
+
+    // A save that keeps failing is given up on after the last retry
+    let still_blocked = blocked.join("nested.ron");
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("EventedSettings".to_string(), &still_blocked);
+    app.world_mut().resource_mut::<EventedSettings>().level = 5;
+    for _ in 0..3 {
+        app.update();
+    }
+    let exhausted = evented_events(&app, |e: &PersistRetriesExhausted| &e.type_name);
+    assert_eq!(exhausted.len(), 1);
+    assert_eq!(exhausted[0].attempts, 3);
+    assert!(!app
+        .world()
+        .resource::<PersistManager>()
+        .is_retry_pending("EventedSettings"));
+}
+
+#[derive(Resource, Default)]
+struct AppliedCount(u32);
+
+#[test]
+fn test_typed_loaded_event_fires_once() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "TypedLoadedTest"));
+    app.init_resource::<AppliedCount>();
+    app.add_systems(
+        Update,
+        (|mut count: ResMut<AppliedCount>| count.0 += 1)
+            .run_if(on_event::<PersistResourceLoaded<TestSettings>>),
+    );
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(app.world().resource::<AppliedCount>().0, 1);
+}
+
+#[test]
+fn test_world_and_commands_manual_persistence() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ManualCommandsTest"));
+    app.update();
+
+    {
+        let mut settings = app.world_mut().resource_mut::<ManualSaveSettings>();
+        settings.value = 7;
+        settings.text = "saved".to_string();
+    }
+    app.world_mut()
+        .save_persistent::<ManualSaveSettings>()
+        .unwrap();
+
+    // Changing the value and loading restores what was saved
+    app.world_mut().resource_mut::<ManualSaveSettings>().value = 99;
+    assert!(app
+        .world_mut()
+        .load_persistent::<ManualSaveSettings>()
+        .unwrap());
+    assert_eq!(app.world().resource::<ManualSaveSettings>().value, 7);
+
+    // Commands queue the same operations
+    app.add_systems(Update, |mut commands: Commands| {
+        commands.reset_persistent::<ManualSaveSettings>();
+    });
+    app.update();
+    assert_eq!(
+        *app.world().resource::<ManualSaveSettings>(),
+        ManualSaveSettings::default()
+    );
+    // Resetting clears the stored data instead of saving defaults
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .get_persist_file()
+        .get_type_data("integration_test::ManualSaveSettings")
+        .is_none());
+}
+
+#[test]
+fn test_access_resources_by_name() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ByNameTest"));
+    app.update();
+
+    let world = app.world_mut();
+    let mut patch = PersistData::new();
+    patch.insert("value", 12);
+    let data = PersistManager::patch_live_data(world, "ManualSaveSettings", patch).unwrap();
+    assert_eq!(data.get::<i32>("value"), Some(12));
+    assert_eq!(data.get::<String>("text"), Some(String::new()));
+    assert_eq!(world.resource::<ManualSaveSettings>().value, 12);
+
+    // Saving by name works even though auto-save is off for the type
+    PersistManager::save_named(world, "ManualSaveSettings").unwrap();
+    let stored = world
+        .resource::<PersistManager>()
+        .get_persist_file()
+        .get_type_data("integration_test::ManualSaveSettings")
+        .unwrap()
+        .get::<i32>("value");
+    assert_eq!(stored, Some(12));
+
+    world.resource_mut::<ManualSaveSettings>().value = 20;
+    assert!(PersistManager::reload_named(world, "ManualSaveSettings").unwrap());
+    assert_eq!(world.resource::<ManualSaveSettings>().value, 12);
+
+    PersistManager::reset_named(world, "ManualSaveSettings").unwrap();
+    assert_eq!(world.resource::<ManualSaveSettings>().value, 0);
+    assert!(world
+        .resource::<PersistManager>()
+        .get_persist_file()
+        .get_type_data("integration_test::ManualSaveSettings")
+        .is_none());
+
+    assert!(PersistManager::live_data(world, "Unregistered").is_err());
+}
+
+#[test]
+fn test_reset_deletes_pinned_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("reset.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ResetTest"));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("ManualSaveSettings".to_string(), &path);
+    app.update();
+
+    app.world_mut().resource_mut::<ManualSaveSettings>().value = 5;
+    app.world_mut()
+        .save_persistent::<ManualSaveSettings>()
+        .unwrap();
+    assert!(path.exists());
+
+    PersistManager::reset::<ManualSaveSettings>(app.world_mut()).unwrap();
+    assert!(!path.exists());
+    assert_eq!(app.world().resource::<ManualSaveSettings>().value, 0);
+
+    // Nothing comes back on the next load
+    assert!(!PersistManager::reload::<ManualSaveSettings>(app.world_mut()).unwrap());
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct SyncedScale {
+    factor: f32,
+}
+
+#[test]
+fn test_reload_reverts_unsaved_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("synced.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ReloadTest"));
+    app.persist_resource::<SyncedScale>()
+        .auto_save(false)
+        .file(&path);
+    app.update();
+
+    app.world_mut().resource_mut::<ManualSaveSettings>().value = 3;
+    app.world_mut()
+        .save_persistent::<ManualSaveSettings>()
+        .unwrap();
+
+    let saved_at = app
+        .world()
+        .resource::<PersistManager>()
+        .last_saved(ManualSaveSettings::type_name())
+        .expect("manual save should be timestamped");
+
+    // Reverting a single type
+    app.world_mut().resource_mut::<ManualSaveSettings>().value = 10;
+    assert!(PersistManager::reload::<ManualSaveSettings>(app.world_mut()).unwrap());
+    assert_eq!(app.world().resource::<ManualSaveSettings>().value, 3);
+    let loaded_at = app
+        .world()
+        .resource::<PersistManager>()
+        .last_loaded(ManualSaveSettings::type_name())
+        .expect("reload should be timestamped");
+    assert!(loaded_at >= saved_at);
+
+    // Another process (e.g. cloud sync) rewrites a file behind our back
+    let mut data = PersistData::new();
+    data.insert("factor", 2.5f32);
+    let mut file = PersistFile::new();
+    file.set_type_data("integration_test::SyncedScale".to_string(), data);
+    file.save_to_file(&path).unwrap();
+
+    app.world_mut().resource_mut::<ManualSaveSettings>().value = 11;
+    let reloaded = PersistManager::reload_all(app.world_mut()).unwrap();
+    assert!(reloaded >= 2);
+    assert_eq!(app.world().resource::<ManualSaveSettings>().value, 3);
+    assert_eq!(app.world().resource::<SyncedScale>().factor, 2.5);
+}
+
+#[cfg(feature = "hot-reload")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct HotTuning {
+    speed: f32,
+}
+
+#[cfg(feature = "hot-reload")]
+#[test]
+fn test_hot_reload_applies_external_edits() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("tuning.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "HotReloadTest").with_hot_reload(true));
+    app.persist_resource::<HotTuning>().file(&path);
+    app.update();
+    assert!(path.exists());
+    assert!(app.world().resource::<PersistWatcher>().is_watching(&path));
+    let last_saved = |app: &App| {
+        app.world()
+            .resource::<PersistManager>()
+            .last_saved("HotTuning")
+    };
+    let saved_at = last_saved(&app);
+
+    // Edit the file like a text editor would
+    let mut data = PersistData::new();
+    data.insert("speed", 4.0f32);
+    let mut file = PersistFile::new();
+    file.set_type_data("integration_test::HotTuning".to_string(), data);
+    file.save_to_file(&path).unwrap();
+
+    for _ in 0..200 {
+        app.update();
+        if app.world().resource::<HotTuning>().speed == 4.0 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(app.world().resource::<HotTuning>().speed, 4.0);
+
+    // The reapplied value isn't saved back over the edited file
+    app.update();
+    assert_eq!(last_saved(&app), saved_at);
+}
+
+#[cfg(feature = "tweak-server")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct RemoteTuning {
+    speed: f32,
+    label: String,
+}
+
+#[cfg(feature = "tweak-server")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct RemoteAccount {
+    password: String,
+}
+
+#[cfg(feature = "tweak-server")]
+#[test]
+fn test_tweak_server_patches_resources() {
+    use serde_json::{json, Value};
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("remote.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "TweakTest").with_tweak_server("127.0.0.1:0"));
+    app.persist_resource::<RemoteTuning>().file(&path);
+    app.persist_resource::<RemoteAccount>()
+        .mode(PersistMode::Secure);
+    app.update();
+
+    let server = app.world().resource::<PersistTweakServer>();
+    let address = server.local_addr();
+    let url = server.url();
+
+    // Connections need the token, and pages served from elsewhere are refused
+    assert!(tungstenite::connect(format!("ws://{}", address)).is_err());
+    let mut from_site =
+        tungstenite::client::IntoClientRequest::into_client_request(url.as_str()).unwrap();
+    from_site
+        .headers_mut()
+        .insert("Origin", "https://example.com".parse().unwrap());
+    assert!(tungstenite::connect(from_site).is_err());
+
+    let client = std::thread::spawn(move || {
+        let (mut socket, _) = tungstenite::connect(url).unwrap();
+        let mut request = |message: Value| {
+            socket
+                .send(tungstenite::Message::text(message.to_string()))
+                .unwrap();
+            let response = socket.read().unwrap().into_text().unwrap();
+            serde_json::from_str::<Value>(&response).unwrap()
+        };
+        let listed = request(json!({ "method": "list" }));
+        let patched = request(json!({
+            "method": "set",
+            "type_name": "RemoteTuning",
+            "data": { "speed": 2.5 },
+        }));
+        let missing = request(json!({ "method": "get", "type_name": "Missing" }));
+        let secure = request(json!({ "method": "get", "type_name": "RemoteAccount" }));
+        (listed, patched, missing, secure)
+    });
+    while !client.is_finished() {
+        app.update();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let (listed, patched, missing, secure) = client.join().unwrap();
+
+    let listed = listed["types"].as_array().unwrap();
+    assert!(listed
+        .iter()
+        .any(|info| info["type_name"] == "RemoteTuning"));
+    // Secure types aren't served at all
+    assert!(!listed
+        .iter()
+        .any(|info| info["type_name"] == "RemoteAccount"));
+    assert!(secure["error"].is_string());
+    assert_eq!(patched["data"]["speed"], 2.5);
+    assert_eq!(patched["data"]["label"], "");
+    assert!(missing["error"].is_string());
+
+    // The patch goes through the normal save path
+    app.update();
+    assert_eq!(app.world().resource::<RemoteTuning>().speed, 2.5);
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::RemoteTuning")
+        .unwrap();
+    assert_eq!(data.get::<f32>("speed"), Some(2.5));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ChurnCounter {
+    ticks: u32,
+}
+
+#[test]
+fn test_save_diagnostics() {
+    use bevy::diagnostic::DiagnosticsStore;
+
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "DiagnosticsTest"));
+    app.add_plugins(PersistDiagnosticsPlugin);
+    app.persist_resource::<ChurnCounter>()
+        .file(temp_dir.path().join("churn.ron"));
+    // A misbehaving system that changes the resource every frame
+    app.add_systems(Update, |mut counter: ResMut<ChurnCounter>| {
+        counter.ticks += 1
+    });
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let stats = app.world().resource::<PersistManager>().stats().clone();
+    assert_eq!(stats.saves_per_type.get("ChurnCounter"), Some(&3));
+    assert!(stats.saves >= 3);
+    assert!(stats.bytes_written > 0);
+    assert!(stats.last_save_duration.is_some());
+
+    let store = app.world().resource::<DiagnosticsStore>();
+    let churn = PersistDiagnosticsPlugin::type_save_count("ChurnCounter");
+    assert_eq!(store.get(&churn).and_then(|d| d.value()), Some(3.0));
+    assert_eq!(
+        store
+            .get(&PersistDiagnosticsPlugin::SAVE_COUNT)
+            .and_then(|d| d.value()),
+        Some(stats.saves as f64)
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct TouchedSettings {
+    volume: f32,
+}
+
+#[test]
+fn test_unchanged_content_is_not_rewritten() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "SkipSaveTest"));
+    app.persist_resource::<TouchedSettings>()
+        .file(temp_dir.path().join("touched.ron"));
+    // Mutable access marks the resource as changed without modifying it
+    app.add_systems(Update, |mut settings: ResMut<TouchedSettings>| {
+        settings.set_changed();
+    });
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let stats = app.world().resource::<PersistManager>().stats().clone();
+    assert_eq!(stats.saves_per_type.get("TouchedSettings"), Some(&1));
+    assert_eq!(stats.skipped_saves, 2);
+
+    app.world_mut().resource_mut::<TouchedSettings>().volume = 0.5;
+    app.update();
+    let stats = app.world().resource::<PersistManager>().stats().clone();
+    assert_eq!(stats.saves_per_type.get("TouchedSettings"), Some(&2));
+}
+
+#[cfg(not(feature = "prod"))]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct BatchedAudio {
+    volume: f32,
+}
+
+#[cfg(not(feature = "prod"))]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct BatchedVideo {
+    fov: f32,
+}
+
+#[cfg(not(feature = "prod"))]
+#[test]
+fn test_changes_in_one_frame_write_dev_file_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let dev_file = temp_dir.path().join("batched_dev.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "BatchTest"));
+    app.world_mut().resource_mut::<PersistManager>().dev_file = dev_file.clone();
+    app.persist_resource::<BatchedAudio>();
+    app.persist_resource::<BatchedVideo>();
+    app.update();
+
+    let before = app.world().resource::<PersistManager>().stats().clone();
+    app.world_mut().resource_mut::<BatchedAudio>().volume = 0.3;
+    app.world_mut().resource_mut::<BatchedVideo>().fov = 90.0;
+    app.update();
+
+    let stats = app.world().resource::<PersistManager>().stats().clone();
+    assert_eq!(stats.saves - before.saves, 2);
+    // Both saves share a single write of the dev file
+    let file_size = std::fs::metadata(&dev_file).unwrap().len();
+    assert_eq!(stats.bytes_written - before.bytes_written, file_size);
+
+    let loaded = PersistFile::load_from_file(&dev_file).unwrap();
+    assert_eq!(
+        loaded
+            .get_type_data("integration_test::BatchedAudio")
+            .and_then(|data| data.get::<f32>("volume")),
+        Some(0.3)
+    );
+    assert_eq!(
+        loaded
+            .get_type_data("integration_test::BatchedVideo")
+            .and_then(|data| data.get::<f32>("fov")),
+        Some(90.0)
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(auto_save = false)]
+struct DeferredProgress {
+    level: u32,
+}
+
+#[test]
+fn test_manual_flush_defers_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("deferred.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ManualFlushTest"));
+    {
+        let mut manager = app.world_mut().resource_mut::<PersistManager>();
+        manager.set_type_file("DeferredProgress".to_string(), &path);
+        manager.set_manual_flush(true);
+    }
+    app.update();
+
+    app.world_mut().resource_mut::<DeferredProgress>().level = 4;
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .mark_dirty::<DeferredProgress>();
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .is_dirty::<DeferredProgress>());
+    assert!(!path.exists());
+
+    // e.g. on a loading screen
+    PersistManager::flush(app.world_mut()).unwrap();
+    let manager = app.world().resource::<PersistManager>();
+    assert!(!manager.is_dirty::<DeferredProgress>());
+    assert!(manager.last_saved("DeferredProgress").is_some());
+
+    let loaded = PersistFile::load_from_file(&path).unwrap();
+    let data = loaded
+        .get_type_data("integration_test::DeferredProgress")
+        .unwrap();
+    assert_eq!(data.get::<u32>("level"), Some(4));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+enum Difficulty {
+    #[default]
+    Normal,
+    Hard,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct NativeRonSettings {
+    difficulty: Difficulty,
+    seed: u64,
+    volume: f32,
+    spawn: (i32, i32),
+    save_slot: Option<u8>,
+}
+
+#[test]
+fn test_ron_files_hold_native_values() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("native.ron");
+    let settings = NativeRonSettings {
+        difficulty: Difficulty::Hard,
+        seed: u64::MAX,
+        volume: 0.3,
+        spawn: (4, -2),
+        save_slot: Some(2),
+    };
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(PersistPlugin::new("TestOrg", "NativeRonTest"));
+        app.persist_resource::<NativeRonSettings>().file(&path);
+        *app.world_mut().resource_mut::<NativeRonSettings>() = settings.clone();
+        app.update();
+    }
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("\"difficulty\": Hard"), "{}", content);
+    assert!(
+        content.contains("\"seed\": 18446744073709551615"),
+        "{}",
+        content
+    );
+    assert!(content.contains("\"volume\": 0.3,"), "{}", content);
+    assert!(content.contains("\"save_slot\": Some(2)"), "{}", content);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "NativeRonTest"));
+    app.persist_resource::<NativeRonSettings>().file(&path);
+    app.update();
+    assert_eq!(*app.world().resource::<NativeRonSettings>(), settings);
+}
+
+#[test]
+fn test_files_written_through_json_values_still_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("legacy.ron");
+    // How enums, tuples and options were written before fields were stored natively
+    std::fs::write(
+        &path,
+        r#"{
+    "integration_test::NativeRonSettings": (
+        values: {
+            "difficulty": "Hard",
+            "seed": 7,
+            "volume": 0.30000001192092896,
+            "spawn": [4, -2],
+            "save_slot": (),
+        },
+    ),
+    "last_saved": "2024-01-01T00:00:00+00:00",
+    "version": "0.1.0",
+}"#,
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "LegacyRonTest"));
+    app.persist_resource::<NativeRonSettings>().file(&path);
+    app.update();
+
+    let settings = app.world().resource::<NativeRonSettings>();
+    assert_eq!(settings.difficulty, Difficulty::Hard);
+    assert_eq!(settings.seed, 7);
+    assert_eq!(settings.spawn, (4, -2));
+    assert_eq!(settings.save_slot, None);
+}
+
+#[test]
+fn test_unknown_fields_survive_a_save() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("newer.ron");
+    // Written by a build that added `theme` and `hdr`
+    std::fs::write(
+        &path,
+        r#"{
+    "integration_test::NativeRonSettings": (
+        values: {
+            "difficulty": Hard,
+            "seed": 7,
+            "volume": 0.5,
+            "spawn": (0, 0),
+            "save_slot": None,
+            "theme": Dark,
+            "hdr": true,
+        },
+    ),
+}"#,
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "UnknownFieldsTest"));
+    let type_name = app
+        .persist_resource::<NativeRonSettings>()
+        .file(&path)
+        .type_name()
+        .to_string();
+    app.update();
+
+    app.world_mut().resource_mut::<NativeRonSettings>().seed = 8;
+    app.update();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("\"seed\": 8"), "{}", content);
+    assert!(content.contains("\"theme\": Dark"), "{}", content);
+    assert!(content.contains("\"hdr\": true"), "{}", content);
+
+    // Reset drops them along with the rest of the data
+    PersistManager::reset_named(app.world_mut(), &type_name).unwrap();
+    app.world_mut().resource_mut::<NativeRonSettings>().seed = 9;
+    app.update();
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("theme"), "{}", content);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct DocumentedBalance {
+    /// Damage dealt by a basic attack.
+    ///
+    /// Scaled by difficulty.
+    attack: u32,
+    /// Seconds between waves
+    wave_interval: f32,
+    undocumented: bool,
+}
+
+#[test]
+fn test_field_docs_are_written_as_comments() {
+    assert_eq!(
+        DocumentedBalance::field_docs(),
+        &[
+            (
+                "attack",
+                "Damage dealt by a basic attack.\n\nScaled by difficulty."
+            ),
+            ("wave_interval", "Seconds between waves"),
+        ]
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("balance.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "FieldDocsTest").with_environment(PersistEnvironment::Dev),
+    );
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("DocumentedBalance".to_string(), &path);
+    app.update();
+
+    app.world_mut().resource_mut::<DocumentedBalance>().attack = 12;
+    app.update();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        content.contains(
+            "            // Damage dealt by a basic attack.\n            //\n            // Scaled by difficulty.\n            \"attack\": 12,"
+        ),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("            // Seconds between waves\n            \"wave_interval\""),
+        "{}",
+        content
+    );
+    assert_eq!(content.matches("//").count(), 4, "{}", content);
+
+    // Comments don't get in the way of loading
+    let loaded = PersistFile::load_from_file(&path).unwrap();
+    let data = loaded
+        .get_type_data("integration_test::DocumentedBalance")
+        .unwrap();
+    assert_eq!(data.get::<u32>("attack"), Some(12));
+
+    // Players' files don't need them
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "FieldDocsTest")
+            .with_environment(PersistEnvironment::Production),
+    );
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("DocumentedBalance".to_string(), &path);
+    app.update();
+
+    app.world_mut().resource_mut::<DocumentedBalance>().attack = 13;
+    app.update();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("\"attack\": 13"), "{}", content);
+    assert!(!content.contains("//"), "{}", content);
+}
+
+#[test]
+fn test_plugin_style_applies_to_written_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("compact.json");
+    let settings = NativeRonSettings {
+        seed: 3,
+        ..default()
+    };
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "StyleTest").with_style(PersistStyle::compact()),
+        );
+        app.persist_resource::<NativeRonSettings>()
+            .file(&path)
+            .format(PersistFormat::Json);
+        *app.world_mut().resource_mut::<NativeRonSettings>() = settings.clone();
+        app.update();
+    }
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains('\n'), "{}", content);
+    assert!(content.contains("\"seed\":3"), "{}", content);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "StyleTest"));
+    app.persist_resource::<NativeRonSettings>()
+        .file(&path)
+        .format(PersistFormat::Json);
+    app.update();
+    assert_eq!(*app.world().resource::<NativeRonSettings>(), settings);
+}
+
+#[derive(Resource, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(validate = "check_audio")]
+struct ValidatedAudio {
+    volume: f32,
+    channels: u8,
+}
+
+impl Default for ValidatedAudio {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            channels: 2,
+        }
+    }
+}
+
+fn check_audio(audio: &mut ValidatedAudio) -> Result<(), String> {
+    if audio.channels == 0 {
+        return Err("no channels".to_string());
+    }
+    if !(0.0..=1.0).contains(&audio.volume) {
+        let volume = audio.volume;
+        audio.volume = volume.clamp(0.0, 1.0);
+        return Err(format!("volume {} out of range", volume));
+    }
+    Ok(())
+}
+
+fn load_validated_audio(path: &std::path::Path, content: &str, policy: ValidationPolicy) -> App {
+    std::fs::write(path, content).unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ValidateTest").with_validation_policy(policy));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("ValidatedAudio".to_string(), path);
+    app.update();
+    app
+}
+
+fn validation_failures(app: &App) -> Vec<PersistValidationFailed> {
+    let events = app.world().resource::<Events<PersistValidationFailed>>();
+    events.get_cursor().read(events).cloned().collect()
+}
+
+#[test]
+fn test_invalid_loaded_data_is_validated() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("audio.ron");
+    let loud =
+        r#"{"integration_test::ValidatedAudio": (values: {"volume": 900.0, "channels": 2})}"#;
+
+    let app = load_validated_audio(&path, loud, ValidationPolicy::Clamp);
+    assert_eq!(
+        *app.world().resource::<ValidatedAudio>(),
+        ValidatedAudio {
+            volume: 1.0,
+            channels: 2
+        }
+    );
+    let failures = validation_failures(&app);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].type_name, "ValidatedAudio");
+    assert_eq!(failures[0].error, "volume 900 out of range");
+    assert_eq!(failures[0].policy, ValidationPolicy::Clamp);
+
+    let silent =
+        r#"{"integration_test::ValidatedAudio": (values: {"volume": 0.8, "channels": 0})}"#;
+    let app = load_validated_audio(&path, silent, ValidationPolicy::Reset);
+    assert_eq!(
+        *app.world().resource::<ValidatedAudio>(),
+        ValidatedAudio::default()
+    );
+    assert_eq!(validation_failures(&app)[0].policy, ValidationPolicy::Reset);
+
+    let fine = r#"{"integration_test::ValidatedAudio": (values: {"volume": 0.8, "channels": 6})}"#;
+    let mut app = load_validated_audio(&path, fine, ValidationPolicy::Reset);
+    assert_eq!(app.world().resource::<ValidatedAudio>().channels, 6);
+    assert!(validation_failures(&app).is_empty());
+
+    // Reloads are validated too
+    std::fs::write(&path, silent).unwrap();
+    PersistManager::reload::<ValidatedAudio>(app.world_mut()).unwrap();
+    assert_eq!(
+        *app.world().resource::<ValidatedAudio>(),
+        ValidatedAudio::default()
+    );
+}
+
+#[derive(Resource, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct RangedAudio {
+    #[persist(range(min = 0.0, max = 1.0))]
+    volume: f32,
+    #[persist(range(min = -12, max = 12))]
+    pitch: i32,
+    #[persist(range(max = 8))]
+    channels: u8,
+}
+
+impl Default for RangedAudio {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            pitch: 0,
+            channels: 2,
+        }
+    }
+}
+
+#[test]
+fn test_range_attribute_clamps_loaded_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("ranged.ron");
+    std::fs::write(
+        &path,
+        r#"{"integration_test::RangedAudio": (values: {"volume": 900.0, "pitch": -40, "channels": 6})}"#,
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "RangeTest"));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("RangedAudio".to_string(), &path);
+    app.update();
+
+    assert_eq!(
+        *app.world().resource::<RangedAudio>(),
+        RangedAudio {
+            volume: 1.0,
+            pitch: -12,
+            channels: 6
+        }
+    );
+    let failures = validation_failures(&app);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        failures[0].error,
+        "volume 900 clamped to 1; pitch -40 clamped to -12"
+    );
+}
+
+#[derive(Resource, Serialize, Deserialize, Persist, Debug, PartialEq, Clone, Default)]
+#[persist(before_save = "tidy_recent")]
+struct RecentFiles {
+    paths: Vec<String>,
+}
+
+fn tidy_recent(recent: &mut RecentFiles) {
+    recent.paths.sort();
+    recent.paths.dedup();
+}
+
+#[test]
+fn test_before_save_tidies_resource() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("recent.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "BeforeSaveTest"));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("RecentFiles".to_string(), &path);
+    app.update();
+
+    app.world_mut().resource_mut::<RecentFiles>().paths = vec![
+        "b.map".to_string(),
+        "a.map".to_string(),
+        "b.map".to_string(),
+    ];
+    app.update();
+
+    let tidy = vec!["a.map".to_string(), "b.map".to_string()];
+    assert_eq!(app.world().resource::<RecentFiles>().paths, tidy);
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::RecentFiles")
+        .unwrap();
+    assert_eq!(data.get::<Vec<String>>("paths"), Some(tidy));
+
+    // Tidying doesn't count as a change, so nothing saves again
+    let saves = app.world().resource::<PersistManager>().stats().saves;
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world().resource::<PersistManager>().stats().saves,
+        saves
+    );
+}
+
+#[derive(Component, Serialize, Deserialize, PersistComponent, Debug, PartialEq, Clone)]
+struct SavedHealth(u32);
+
+fn component_app(path: &std::path::Path) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ComponentTest"));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("SavedHealth".to_string(), path);
+    app
+}
+
+fn health_by_id(app: &mut App) -> Vec<(String, u32)> {
+    let mut health: Vec<_> = app
+        .world_mut()
+        .query::<(&PersistId, &SavedHealth)>()
+        .iter(app.world())
+        .map(|(id, health)| (id.to_string(), health.0))
+        .collect();
+    health.sort();
+    health
+}
+
+#[test]
+fn test_components_are_saved_and_respawned_by_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("entities.ron");
+
+    let mut app = component_app(&path);
+    app.update();
+    app.world_mut()
+        .spawn((PersistId::new("player"), SavedHealth(80)));
+    app.world_mut()
+        .spawn((PersistId::new("goblin"), SavedHealth(5)));
+    // Not persisted without an id
+    app.world_mut().spawn(SavedHealth(1));
+    app.update();
+
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::SavedHealth")
+        .unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), vec!["goblin", "player"]);
+    assert_eq!(data.get::<SavedHealth>("player"), Some(SavedHealth(80)));
+
+    // Entities spawned at startup are overwritten, missing ones respawned
+    let mut app = component_app(&path);
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((PersistId::new("player"), SavedHealth(100)));
+    });
+    app.update();
+    assert_eq!(
+        health_by_id(&mut app),
+        vec![("goblin".to_string(), 5), ("player".to_string(), 80)]
+    );
+
+    // Despawning drops the entity from the file
+    let goblin = app
+        .world_mut()
+        .query::<(Entity, &PersistId)>()
+        .iter(app.world())
+        .find(|(_, id)| id.as_str() == "goblin")
+        .map(|(entity, _)| entity)
+        .unwrap();
+    app.world_mut().despawn(goblin);
+    app.update();
+
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::SavedHealth")
+        .unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), vec!["player"]);
+}
+
+#[test]
+fn test_checkpoint_and_rollback() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("checkpoints.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "CheckpointTest"));
+    app.persist_resource::<NativeRonSettings>().file(&path);
+    app.update();
+
+    let saves = app.world().resource::<PersistManager>().stats().saves;
+    PersistManager::checkpoint(app.world_mut()).unwrap();
+    app.world_mut().resource_mut::<NativeRonSettings>().seed = 1;
+    PersistManager::checkpoint(app.world_mut()).unwrap();
+    app.world_mut().resource_mut::<NativeRonSettings>().seed = 2;
+
+    // Checkpoints stay in memory
+    assert_eq!(
+        app.world().resource::<PersistManager>().stats().saves,
+        saves
+    );
+    assert_eq!(
+        app.world().resource::<PersistManager>().checkpoint_count(),
+        2
+    );
+
+    assert!(PersistManager::rollback(app.world_mut()).unwrap());
+    assert_eq!(app.world().resource::<NativeRonSettings>().seed, 1);
+    assert!(PersistManager::rollback(app.world_mut()).unwrap());
+    assert_eq!(
+        *app.world().resource::<NativeRonSettings>(),
+        NativeRonSettings::default()
+    );
+    assert!(!PersistManager::rollback(app.world_mut()).unwrap());
+
+    PersistManager::checkpoint(app.world_mut()).unwrap();
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .clear_checkpoints();
+    assert!(!PersistManager::rollback(app.world_mut()).unwrap());
+}
+
+#[cfg(feature = "assets")]
+#[derive(Asset, TypePath)]
+struct Portrait;
+
+#[cfg(feature = "assets")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct Profile {
+    portrait: PersistAsset<Portrait>,
+}
+
+#[cfg(feature = "assets")]
+impl PersistAssets for Profile {
+    fn resolve_assets(&mut self, assets: &AssetServer) {
+        self.portrait.resolve(assets);
+    }
+}
+
+#[cfg(feature = "assets")]
+#[test]
+fn test_asset_handles_persist_as_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("profile.ron");
+    std::fs::write(
+        &path,
+        r#"{"integration_test::Profile": (values: {"portrait": Some("portraits/hero.portrait")})}"#,
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.init_asset::<Portrait>();
+    app.add_plugins(PersistPlugin::new("TestOrg", "AssetTest"));
+    app.persist_resource::<Profile>().file(&path);
+    app.resolve_persist_assets::<Profile>();
+    app.update();
+
+    let portrait = &app.world().resource::<Profile>().portrait;
+    assert!(portrait.is_resolved());
+    assert_eq!(
+        portrait.handle().path().map(|path| path.to_string()),
+        Some("portraits/hero.portrait".to_string())
+    );
+
+    app.world_mut().resource_mut::<Profile>().portrait =
+        PersistAsset::from_path("portraits/villain.portrait");
+    app.update();
+    app.update();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        content.contains("\"portraits/villain.portrait\""),
+        "{}",
+        content
+    );
+    assert_eq!(
+        app.world()
+            .resource::<Profile>()
+            .portrait
+            .handle()
+            .path()
+            .map(|path| path.to_string()),
+        Some("portraits/villain.portrait".to_string())
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct QuestLog {
+    completed: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct MapFog {
+    revealed: u32,
+}
+
+#[test]
+fn test_request_save_event_filters_types() {
+    let temp_dir = TempDir::new().unwrap();
+    let quest_path = temp_dir.path().join("quests.ron");
+    let fog_path = temp_dir.path().join("fog.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "RequestSaveTest"));
+    let quest_log = app
+        .persist_resource::<QuestLog>()
+        .file(&quest_path)
+        .type_name()
+        .to_string();
+    app.persist_resource::<MapFog>().file(&fog_path);
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_manual_flush(true);
+    app.update();
+
+    app.world_mut().resource_mut::<QuestLog>().completed = 3;
+    app.world_mut().resource_mut::<MapFog>().revealed = 40;
+    app.world_mut().send_event(RequestSave::only([quest_log]));
+    app.update();
+    let saved = |path: &std::path::Path, key: &str, field: &str| {
+        PersistFile::load_from_file(path)
+            .unwrap()
+            .get_type_data(key)
+            .and_then(|data| data.get::<u32>(field))
+    };
+    assert_eq!(
+        saved(&quest_path, "integration_test::QuestLog", "completed"),
+        Some(3)
+    );
+    assert!(!fog_path.exists());
+
+    app.world_mut().send_event(RequestSave::all());
+    app.update();
+    assert_eq!(
+        saved(&fog_path, "integration_test::MapFog", "revealed"),
+        Some(40)
+    );
+}
+
+#[test]
+fn test_load_state_gates_systems() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "LoadStateTest"));
+    app.persist_resource::<QuestLog>()
+        .file(temp_dir.path().join("quests.ron"));
+    app.init_resource::<AppliedCount>();
+    app.add_systems(
+        Update,
+        (|mut count: ResMut<AppliedCount>| count.0 += 1)
+            .run_if(persist_loaded::<QuestLog>().and(all_persist_loaded())),
+    );
+
+    let state = app.world().resource::<PersistLoadState>();
+    assert!(!state.is_loaded::<QuestLog>());
+    assert!(state.pending().any(|type_name| type_name == "QuestLog"));
+    assert!(!state.is_all_loaded());
+
+    app.update();
+    let state = app.world().resource::<PersistLoadState>();
+    assert!(state.is_loaded::<QuestLog>());
+    assert!(state.is_loaded_named("QuestLog"));
+    assert!(state.is_all_loaded());
+    assert_eq!(app.world().resource::<AppliedCount>().0, 1);
+}
+
+#[test]
+fn test_save_all_and_load_all() {
+    let temp_dir = TempDir::new().unwrap();
+    let quest_path = temp_dir.path().join("quests.ron");
+    let fog_path = temp_dir.path().join("fog.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "SaveAllTest"));
+    app.persist_resource::<QuestLog>()
+        .auto_save(false)
+        .file(&quest_path);
+    app.persist_resource::<MapFog>()
+        .auto_save(false)
+        .file(&fog_path);
+    app.update();
+
+    app.world_mut().resource_mut::<QuestLog>().completed = 7;
+    app.world_mut().resource_mut::<MapFog>().revealed = 12;
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_manual_flush(true);
+    PersistManager::save_all(app.world_mut()).unwrap();
+    assert!(quest_path.exists());
+    assert!(fog_path.exists());
+
+    app.world_mut().resource_mut::<QuestLog>().completed = 0;
+    app.world_mut().resource_mut::<MapFog>().revealed = 0;
+    let loaded = PersistManager::load_all(app.world_mut()).unwrap();
+    assert!(loaded >= 2);
+    assert_eq!(app.world().resource::<QuestLog>().completed, 7);
+    assert_eq!(app.world().resource::<MapFog>().revealed, 12);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ImportedBindings {
+    count: u32,
+}
+
+#[test]
+fn test_pause_queues_saves_until_resumed() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("bindings.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "PauseTest"));
+    app.persist_resource::<ImportedBindings>().file(&path);
+    app.update();
+
+    let saved_count = || {
+        PersistFile::load_from_file(&path)
+            .unwrap()
+            .get_type_data("integration_test::ImportedBindings")
+            .and_then(|data| data.get::<u32>("count"))
+    };
+    assert_eq!(saved_count(), Some(0));
+
+    app.world_mut().resource_mut::<PersistManager>().pause();
+    for _ in 0..5 {
+        app.world_mut().resource_mut::<ImportedBindings>().count += 1;
+        app.update();
+    }
+    assert_eq!(saved_count(), Some(0));
+
+    app.world_mut().resource_mut::<PersistManager>().resume();
+    app.update();
+    assert_eq!(saved_count(), Some(5));
+}
+
+#[derive(Resource)]
+struct InCutscene(bool);
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct Journal {
+    entries: u32,
+}
+
+#[test]
+fn test_save_run_if_defers_auto_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("journal.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(InCutscene(true));
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "RunIfTest")
+            .save_run_if(|cutscene: Res<InCutscene>| !cutscene.0),
+    );
+    app.persist_resource::<Journal>().file(&path);
+    app.update();
+
+    for _ in 0..3 {
+        app.world_mut().resource_mut::<Journal>().entries += 1;
+        app.update();
+    }
+    assert!(!path.exists());
+
+    app.world_mut().resource_mut::<InCutscene>().0 = false;
+    app.update();
+    let loaded = PersistFile::load_from_file(&path).unwrap();
+    let data = loaded.get_type_data("integration_test::Journal").unwrap();
+    assert_eq!(data.get::<u32>("entries"), Some(3));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(session)]
+struct SessionTuning {
+    gravity: f32,
+}
+
+#[test]
+fn test_session_mode_stays_in_memory() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("session.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "SessionTest"));
+    // Even a pinned file is ignored
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("SessionTuning".to_string(), &path);
+    app.update();
+
+    app.world_mut().resource_mut::<SessionTuning>().gravity = 4.5;
+    app.update();
+    assert!(!path.exists());
+    let manager = app.world().resource::<PersistManager>();
+    assert!(manager
+        .read_type_data("SessionTuning", None)
+        .is_some_and(|data| data.get::<f32>("gravity") == Some(4.5)));
+
+    app.world_mut()
+        .resource_mut::<SessionTuning>()
+        .bypass_change_detection()
+        .gravity = 0.0;
+    assert!(PersistManager::reload_named(app.world_mut(), "SessionTuning").unwrap());
+    assert_eq!(app.world().resource::<SessionTuning>().gravity, 4.5);
+    assert!(!path.exists());
+}
+
+// Release builds would embed the file, which only exists once tuned
+#[cfg(not(feature = "prod"))]
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(embed, embed_root = "target/embed-test")]
+struct EmbedTuning {
+    drag: f32,
+}
+
+#[cfg(not(feature = "prod"))]
+#[test]
+fn test_embed_root_is_where_dev_saves_go() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("embed-test")
+        .join("embedtuning.ron");
+    assert_eq!(
+        EmbedTuning::embed_file().map(std::path::PathBuf::from),
+        Some(path.clone())
+    );
+    std::fs::remove_file(&path).ok();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "EmbedRootTest"));
+    app.update();
+    app.world_mut().resource_mut::<EmbedTuning>().drag = 0.25;
+    app.update();
+
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::EmbedTuning")
+        .unwrap();
+    assert_eq!(data.get::<f32>("drag"), Some(0.25));
+}
+
+#[cfg(feature = "prod")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(
+    embed_binary = "blobtuning.bin",
+    embed_root = "tests/embed",
+    store = "embed-binary"
+)]
+struct BlobTuning {
+    drag: f32,
+}
+
+#[cfg(feature = "prod")]
+#[test]
+fn test_embed_binary_decodes_at_startup() {
+    let blob = BlobTuning::embedded_bytes().unwrap();
+    assert!(!blob.windows(4).any(|w| w == b"drag"));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    // Its own store, so dev-mode tests don't save it into assets/
+    app.add_plugins(PersistPlugin::new("TestOrg", "EmbedBinaryTest").with_store("embed-binary"));
+    app.update();
+    assert_eq!(app.world().resource::<BlobTuning>().drag, 0.75);
+
+    let manager = app.world().resource::<PersistManager>();
+    let encoded = manager.encode_embedded(b"(drag: 0.75)").unwrap();
+    assert_eq!(manager.decode_embedded(&encoded).unwrap(), "(drag: 0.75)");
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct TuningCurve {
+    gain: f32,
+}
+
+#[test]
+fn test_export_for_embed_writes_one_type() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "ExportEmbedTest").with_environment(PersistEnvironment::Dev),
+    );
+    let type_name = app
+        .persist_resource::<TuningCurve>()
+        .mode(PersistMode::Embed)
+        .file(temp_dir.path().join("tuning.ron"))
+        .type_name()
+        .to_string();
+    app.update();
+    app.world_mut().resource_mut::<TuningCurve>().gain = 2.5;
+    app.update();
+
+    let manager = app.world().resource::<PersistManager>();
+    let path = temp_dir.path().join("embed").join("curve.ron");
+    manager.export_named_for_embed(&type_name, &path).unwrap();
+    let exported = PersistFile::load_from_file(&path).unwrap();
+    let data = exported
+        .get_type_data("integration_test::TuningCurve")
+        .unwrap();
+    assert_eq!(data.get::<f32>("gain"), Some(2.5));
+
+    let written = manager
+        .export_all_for_embed(temp_dir.path().join("all"))
+        .unwrap();
+    assert!(written.contains(&temp_dir.path().join("all").join("tuningcurve.ron")));
+    assert!(manager
+        .export_named_for_embed("integration_test::Missing", &path)
+        .is_err());
+}
+
+#[cfg(feature = "archive")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct CampaignSave {
+    chapter: u32,
+}
+
+#[cfg(feature = "archive")]
+#[test]
+fn test_archive_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let archive = temp_dir.path().join("backup").join("saves.tar");
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ArchiveTest"));
+    app.persist_resource::<CampaignSave>()
+        .file(temp_dir.path().join("campaign.ron"));
+    app.update();
+    app.world_mut().resource_mut::<CampaignSave>().chapter = 3;
+    app.update();
+
+    let archived = app
+        .world()
+        .resource::<PersistManager>()
+        .export_archive(&archive)
+        .unwrap();
+    assert!(archived.contains(&temp_dir.path().join("campaign.ron")));
+
+    app.world_mut().resource_mut::<CampaignSave>().chapter = 7;
+    app.update();
+    let restored = app
+        .world_mut()
+        .resource_mut::<PersistManager>()
+        .import_archive(&archive)
+        .unwrap();
+    assert!(restored.contains(&temp_dir.path().join("campaign.ron")));
+    PersistManager::load_all(app.world_mut()).unwrap();
+    assert_eq!(app.world().resource::<CampaignSave>().chapter, 3);
+
+    // Another game's manager refuses it
+    let mut other = PersistManager::new("TestOrg", "OtherGame");
+    assert!(other.import_archive(&archive).is_err());
+}
+
+#[cfg(feature = "archive")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct CampaignJournal {
+    entries: u32,
+}
+
+#[cfg(feature = "archive")]
+#[test]
+fn test_failed_archive_import_leaves_saves_untouched() {
+    let temp_dir = TempDir::new().unwrap();
+    let archive = temp_dir.path().join("saves.tar");
+    let journal_dir = temp_dir.path().join("journal");
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ArchiveFailTest"));
+    app.persist_resource::<CampaignSave>()
+        .file(temp_dir.path().join("campaign.ron"));
+    app.persist_resource::<CampaignJournal>()
+        .file(journal_dir.join("journal.ron"));
+    app.update();
+    app.world_mut().resource_mut::<CampaignSave>().chapter = 3;
+    app.world_mut().resource_mut::<CampaignJournal>().entries = 12;
+    app.update();
+    app.world()
+        .resource::<PersistManager>()
+        .export_archive(&archive)
+        .unwrap();
+
+    app.world_mut().resource_mut::<CampaignSave>().chapter = 7;
+    app.update();
+    // The journal can't be restored where a file now blocks its directory
+    std::fs::remove_dir_all(&journal_dir).unwrap();
+    std::fs::write(&journal_dir, "").unwrap();
+    assert!(app
+        .world_mut()
+        .resource_mut::<PersistManager>()
+        .import_archive(&archive)
+        .is_err());
+
+    let campaign = PersistFile::load_from_file(temp_dir.path().join("campaign.ron")).unwrap();
+    assert_eq!(
+        campaign
+            .get_type_data("integration_test::CampaignSave")
+            .unwrap()
+            .get::<u32>("chapter"),
+        Some(7)
+    );
+    assert!(!temp_dir.path().join("campaign.ron.tmp").exists());
+}
+
+#[cfg(feature = "share-codes")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(session)]
+struct Loadout {
+    weapon: String,
+    attachments: Vec<String>,
+}
+
+#[cfg(feature = "share-codes")]
+#[test]
+fn test_share_code_round_trip() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ShareCodeTest"));
+    app.update();
+    {
+        let mut loadout = app.world_mut().resource_mut::<Loadout>();
+        loadout.weapon = "crossbow".to_string();
+        loadout.attachments = vec!["scope".to_string()];
+    }
+
+    let code = PersistManager::to_share_code::<Loadout>(app.world()).unwrap();
+    assert!(!code.contains("crossbow"));
+    *app.world_mut().resource_mut::<Loadout>() = Loadout::default();
+    PersistManager::from_share_code::<Loadout>(app.world_mut(), &code).unwrap();
+    let loadout = app.world().resource::<Loadout>();
+    assert_eq!(loadout.weapon, "crossbow");
+    assert_eq!(loadout.attachments, ["scope"]);
+
+    // A typo, or a code for something else, leaves the resource alone
+    let mut damaged = code.clone().into_bytes();
+    let last = damaged.len() - 2;
+    damaged[last] = if damaged[last] == b'A' { b'B' } else { b'A' };
+    let damaged = String::from_utf8(damaged).unwrap();
+    assert!(PersistManager::from_share_code::<Loadout>(app.world_mut(), &damaged).is_err());
+    assert!(PersistManager::from_share_code::<SessionTuning>(app.world_mut(), &code).is_err());
+    assert_eq!(app.world().resource::<Loadout>().weapon, "crossbow");
+}
+
+#[cfg(feature = "prod")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(store = "field-encryption")]
+struct PlayerProgress {
+    name: String,
+    #[persist(encrypt)]
+    gold: u32,
+}
+
+#[cfg(feature = "prod")]
+#[test]
+fn test_encrypted_fields_are_sealed_in_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("progress.ron");
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    let plugin =
+        PersistPlugin::new("TestOrg", "FieldEncryptionTest").with_store("field-encryption");
+    #[cfg(feature = "secure")]
+    let plugin = plugin.with_secret("field-secret");
+    app.add_plugins(plugin);
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("PlayerProgress".to_string(), &path);
+    app.update();
+    {
+        let mut progress = app.world_mut().resource_mut::<PlayerProgress>();
+        progress.name = "Ayla".to_string();
+        progress.gold = 31337;
+    }
+    app.update();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("Ayla"), "{}", content);
+    assert!(!content.contains("31337"), "{}", content);
+    let sealed = if cfg!(feature = "secure") {
+        "enc:"
+    } else {
+        "b64:"
+    };
+    assert!(content.contains(sealed), "{}", content);
+
+    *app.world_mut().resource_mut::<PlayerProgress>() = PlayerProgress::default();
+    assert!(PersistManager::reload::<PlayerProgress>(app.world_mut()).unwrap());
+    let progress = app.world().resource::<PlayerProgress>();
+    assert_eq!(progress.name, "Ayla");
+    assert_eq!(progress.gold, 31337);
+}
+
+#[cfg(feature = "secure")]
+#[test]
+fn test_rotated_secret_reads_and_reencrypts() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("progress.ron");
+    let progress_app = |plugin: PersistPlugin| {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(plugin.with_store("field-encryption"));
+        app.world_mut()
+            .resource_mut::<PersistManager>()
+            .set_type_file("PlayerProgress".to_string(), &path);
+        app.update();
+        app
+    };
+
+    let mut app = progress_app(PersistPlugin::new("TestOrg", "RotationTest").with_secret("v1"));
+    app.world_mut().resource_mut::<PlayerProgress>().gold = 500;
+    app.update();
+    let written_with_v1 = std::fs::read_to_string(&path).unwrap();
+
+    // The old secret still opens the file, which is rewritten under the new one
+    let app =
+        progress_app(PersistPlugin::new("TestOrg", "RotationTest").with_secrets("v2", ["v1"]));
+    assert_eq!(app.world().resource::<PlayerProgress>().gold, 500);
+    let rewritten = std::fs::read_to_string(&path).unwrap();
+    assert_ne!(rewritten, written_with_v1);
+
+    let app = progress_app(PersistPlugin::new("TestOrg", "RotationTest").with_secret("v2"));
+    assert_eq!(app.world().resource::<PlayerProgress>().gold, 500);
+    let app = progress_app(PersistPlugin::new("TestOrg", "RotationTest").with_secret("v1"));
+    assert_eq!(app.world().resource::<PlayerProgress>().gold, 0);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+#[persist(obfuscated, store = "obfuscated")]
+struct ArcadeHighScore {
+    score: u32,
+}
+
+#[test]
+fn test_obfuscated_files_reject_edits() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("highscore.dat");
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "ObfuscationTest")
+            .with_store("obfuscated")
+            .with_environment(PersistEnvironment::Production),
+    );
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("ArcadeHighScore".to_string(), &path);
+    app.update();
+    app.world_mut().resource_mut::<ArcadeHighScore>().score = 987654;
+    app.update();
+
+    let bytes = std::fs::read(&path).unwrap();
+    let text = String::from_utf8_lossy(&bytes);
+    assert!(
+        !text.contains("987654") && !text.contains("score"),
+        "{}",
+        text
+    );
+
+    app.world_mut().resource_mut::<ArcadeHighScore>().score = 0;
+    assert!(PersistManager::reload::<ArcadeHighScore>(app.world_mut()).unwrap());
+    assert_eq!(app.world().resource::<ArcadeHighScore>().score, 987654);
+
+    // A flipped byte fails the checksum, so nothing is loaded
+    let mut edited = bytes;
+    let last = edited.len() - 3;
+    edited[last] ^= 0x01;
+    std::fs::write(&path, edited).unwrap();
+    app.world_mut().resource_mut::<ArcadeHighScore>().score = 0;
+    assert!(!PersistManager::reload::<ArcadeHighScore>(app.world_mut()).unwrap());
+    assert_eq!(app.world().resource::<ArcadeHighScore>().score, 0);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct CareerSave {
+    wins: u32,
+}
+
+fn career_app(path: &std::path::Path, policy: DowngradePolicy) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "DowngradeTest").with_app_version("1.5.0"));
+    app.persist_resource::<CareerSave>()
+        .file(path)
+        .downgrade_policy(policy);
+    app.update();
+    app
+}
+
+#[test]
+fn test_files_from_newer_versions_follow_downgrade_policy() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("career.ron");
+    let newer = r#"{"version": "2.0.0", "integration_test::CareerSave": (values: {"wins": 40})}"#;
+    std::fs::write(&path, newer).unwrap();
+
+    // Refused files are neither loaded nor overwritten
+    let mut app = career_app(&path, DowngradePolicy::Refuse);
+    assert_eq!(app.world().resource::<CareerSave>().wins, 0);
+    let events = app.world().resource::<Events<PersistVersionEvent>>();
+    let found: Vec<PersistVersionEvent> = events.get_cursor().read(events).cloned().collect();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].file_version, "2.0.0");
+    assert_eq!(found[0].app_version, "1.5.0");
+    app.world_mut().resource_mut::<CareerSave>().wins = 1;
+    app.update();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), newer);
+
+    let app = career_app(&path, DowngradePolicy::BestEffort);
+    assert_eq!(app.world().resource::<CareerSave>().wins, 40);
+
+    // Reset files are kept aside, then replaced by this version's saves
+    std::fs::write(&path, newer).unwrap();
+    let mut app = career_app(&path, DowngradePolicy::BackupAndReset);
+    assert_eq!(app.world().resource::<CareerSave>().wins, 0);
+    let backup = temp_dir.path().join("career.ron.2.0.0.bak");
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), newer);
+    app.world_mut().resource_mut::<CareerSave>().wins = 2;
+    app.update();
+    let loaded = PersistFile::load_from_file(&path).unwrap();
+    assert_eq!(loaded.version, "1.5.0");
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct LobbyFilters {
+    region: String,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ChatSettings {
+    profanity_filter: bool,
+}
+
+#[test]
+fn test_types_saved_since_lists_recent_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "TimestampTest"));
+    app.persist_resource::<LobbyFilters>()
+        .file(temp_dir.path().join("lobby.ron"));
+    app.persist_resource::<ChatSettings>()
+        .file(temp_dir.path().join("chat.ron"));
+    app.update();
+
+    let since = chrono::Utc::now();
+    app.world_mut().resource_mut::<LobbyFilters>().region = "eu".to_string();
+    app.update();
+
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(manager.types_saved_since(since), ["LobbyFilters"]);
+    assert!(manager.saved_at("LobbyFilters").unwrap() > since);
+    assert!(manager.saved_at("ChatSettings").unwrap() <= since);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct KioskProgress {
+    unlocked: Vec<String>,
+}
+
+#[test]
+fn test_read_only_never_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("kiosk.ron");
+    let saved = r#"{"integration_test::KioskProgress": (values: {"unlocked": ["forest"]})}"#;
+    std::fs::write(&path, saved).unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ReadOnlyTest").with_read_only(true));
+    let type_name = app
+        .persist_resource::<KioskProgress>()
+        .file(&path)
+        .type_name()
+        .to_string();
+    app.update();
+    assert_eq!(app.world().resource::<KioskProgress>().unlocked, ["forest"]);
+
+    app.world_mut()
+        .resource_mut::<KioskProgress>()
+        .unlocked
+        .push("castle".to_string());
+    app.update();
+    PersistManager::save_named(app.world_mut(), &type_name).unwrap();
+    let report = app
+        .world_mut()
+        .resource_mut::<PersistManager>()
+        .delete_all_data();
+    assert!(report.deleted.is_empty());
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct AudioMix {
+    music: f32,
+    effects: f32,
+    voice: f32,
+}
+
+#[test]
+fn test_user_file_layers_over_shipped_defaults() {
+    let temp_dir = TempDir::new().unwrap();
+    let defaults_path = temp_dir.path().join("audio_defaults.ron");
+    let user_path = temp_dir.path().join("audio.ron");
+    let ship = |music: f32| {
+        std::fs::write(
+            &defaults_path,
+            format!(
+                r#"{{"integration_test::AudioMix": (values: {{"music": {:?}, "effects": 0.8, "voice": 1.0}})}}"#,
+                music
+            ),
+        )
+        .unwrap();
+    };
+    ship(0.5);
+
+    {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(PersistPlugin::new("TestOrg", "DefaultsTest"));
+        app.persist_resource::<AudioMix>()
+            .file(&user_path)
+            .defaults(&defaults_path);
+        app.update();
+        assert_eq!(app.world().resource::<AudioMix>().music, 0.5);
+
+        app.world_mut().resource_mut::<AudioMix>().voice = 0.3;
+        app.update();
+    }
+    let saved = PersistFile::load_from_file(&user_path).unwrap();
+    let data = saved.get_type_data("integration_test::AudioMix").unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), ["voice"]);
+
+    // A patch changing a default still reaches players who never touched it
+    ship(0.7);
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "DefaultsTest"));
+    app.persist_resource::<AudioMix>()
+        .file(&user_path)
+        .defaults(&defaults_path);
+    app.update();
+    let mix = app.world().resource::<AudioMix>();
+    assert_eq!((mix.music, mix.effects, mix.voice), (0.7, 0.8, 0.3));
+}
+
+#[derive(Resource, Serialize, Deserialize, Persist)]
+#[persist(delta)]
+struct GraphicsOptions {
+    vsync: bool,
+    render_scale: f32,
+    shadows: u32,
+}
+
+impl Default for GraphicsOptions {
+    fn default() -> Self {
+        Self {
+            vsync: true,
+            render_scale: 1.0,
+            shadows: 2,
+        }
+    }
+}
+
+#[test]
+fn test_delta_saves_only_changed_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("graphics.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "DeltaTest"));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("GraphicsOptions".to_string(), &path);
+    app.update();
+
+    app.world_mut().resource_mut::<GraphicsOptions>().shadows = 0;
+    app.update();
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::GraphicsOptions")
+        .unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), ["shadows"]);
+
+    // Missing fields load as their defaults
+    *app.world_mut().resource_mut::<GraphicsOptions>() = GraphicsOptions {
+        vsync: false,
+        render_scale: 0.5,
+        shadows: 1,
+    };
+    assert!(PersistManager::reload_named(app.world_mut(), "GraphicsOptions").unwrap());
+    let options = app.world().resource::<GraphicsOptions>();
+    assert_eq!(
+        (options.vsync, options.render_scale, options.shadows),
+        (true, 1.0, 0)
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+struct QaSettings {
+    volume: f32,
+    name: String,
+}
+
+#[test]
+fn test_env_overrides_apply_without_saving() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("qa.ron");
+    std::fs::write(
+        &path,
+        r#"{"integration_test::QaSettings": (values: {"volume": 0.8, "name": "file"})}"#,
+    )
+    .unwrap();
+    std::env::set_var("ENVTEST__QASETTINGS__VOLUME", "0.25");
+    std::env::set_var("ENVTEST__QaSettings__name", "qa");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "EnvTest").with_env_overrides("ENVTEST"));
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("QaSettings".to_string(), &path);
+    app.update();
+    app.update();
+
+    let settings = app.world().resource::<QaSettings>();
+    assert_eq!(settings.volume, 0.25);
+    assert_eq!(settings.name, "qa");
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(
+        manager
+            .env_overridden_fields("QaSettings")
+            .collect::<Vec<_>>(),
+        ["name", "volume"]
+    );
+    let read = |path: &std::path::Path| {
+        PersistFile::load_from_file(path)
+            .unwrap()
+            .get_type_data("integration_test::QaSettings")
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(read(&path).get::<f32>("volume"), Some(0.8));
+
+    // Changing a field in-game saves it, other overrides stay out of the file
+    app.world_mut().resource_mut::<QaSettings>().name = "player".to_string();
+    app.update();
+    let data = read(&path);
+    assert_eq!(data.get::<String>("name"), Some("player".to_string()));
+    assert_eq!(data.get::<f32>("volume"), Some(0.8));
+}
+
+#[cfg(feature = "shutdown")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ServerConfig {
+    max_players: u32,
+}
+
+#[cfg(feature = "shutdown")]
+#[test]
+fn test_shutdown_flushes_and_exits() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("server.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ShutdownTest").with_flush_on_shutdown(true));
+    app.persist_resource::<ServerConfig>().file(&path);
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_manual_flush(true);
+    app.update();
+
+    app.world_mut().resource_mut::<ServerConfig>().max_players = 64;
+    app.update();
+    assert!(!path.exists());
+    assert!(app.should_exit().is_none());
+
+    // What the SIGINT/SIGTERM handler does
+    app.world().resource::<PersistShutdown>().request();
+    app.update();
+
+    assert_eq!(app.should_exit(), Some(AppExit::Success));
+    let loaded = PersistFile::load_from_file(&path).unwrap();
+    let data = loaded
+        .get_type_data("integration_test::ServerConfig")
+        .unwrap();
+    assert_eq!(data.get::<u32>("max_players"), Some(64));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(store = "editor")]
+struct EditorLayout {
+    panel_width: f32,
+}
+
+#[derive(bevy::app::AppLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct EditorApp;
+
+#[test]
+fn test_stores_register_their_own_types() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("editor.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "StoreTest"));
+
+    let mut editor = SubApp::new();
+    editor.update_schedule = Some(bevy::ecs::schedule::ScheduleLabel::intern(&Main));
+    editor.add_plugins(bevy::app::MainSchedulePlugin);
+    editor.add_plugins(PersistPlugin::new("TestOrg", "StoreTest").with_store("editor"));
+    editor
+        .world_mut()
+        .resource_mut::<PersistManager>()
+        .set_type_file("EditorLayout".to_string(), &path);
+    app.insert_sub_app(EditorApp, editor);
+
+    let game = app.world().resource::<PersistManager>();
+    assert_eq!(game.store(), None);
+    assert!(game.get_type_hooks("EditorLayout").is_none());
+    assert!(!app.world().contains_resource::<EditorLayout>());
+
+    let editor = app.sub_app(EditorApp).world();
+    let manager = editor.resource::<PersistManager>();
+    assert_eq!(manager.store(), Some("editor"));
+    assert_eq!(
+        manager.dev_file,
+        std::path::PathBuf::from("storetest_editor_dev.ron")
+    );
+    assert!(manager.get_type_hooks("SavedHealth").is_none());
+    assert!(editor.contains_resource::<EditorLayout>());
+
+    // The editor store saves on its own schedule
+    app.update();
+    app.sub_app_mut(EditorApp)
+        .world_mut()
+        .resource_mut::<EditorLayout>()
+        .panel_width = 320.0;
+    app.update();
+    let saved = PersistFile::load_from_file(&path).unwrap();
+    let data = saved
+        .get_type_data("integration_test::EditorLayout")
+        .unwrap();
+    assert_eq!(data.get::<f32>("panel_width"), Some(320.0));
+}
+
+struct DirResolver(std::path::PathBuf);
+
+impl PathResolver for DirResolver {
+    fn resolve(&self, type_name: &str, _mode: PersistMode) -> std::path::PathBuf {
+        self.0.join(type_name.to_lowercase())
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct LargeWorldSave {
+    tiles: Vec<u32>,
+}
+
+#[test]
+fn test_background_saves_and_loads_report_progress() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "ProgressTest")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(DirResolver(temp_dir.path().to_path_buf())),
+    );
+    app.update();
+    let mut progress = app
+        .world()
+        .resource::<Events<PersistProgress>>()
+        .get_cursor_current();
+    let mut saved = app
+        .world()
+        .resource::<Events<PersistSaved>>()
+        .get_cursor_current();
+    let mut loaded = app
+        .world()
+        .resource::<Events<PersistLoaded>>()
+        .get_cursor_current();
+
+    let tiles: Vec<u32> = (0..20_000).collect();
+    app.world_mut()
+        .resource_mut::<LargeWorldSave>()
+        .bypass_change_detection()
+        .tiles = tiles.clone();
+    PersistManager::save_in_background::<LargeWorldSave>(app.world_mut()).unwrap();
+    let mut events = Vec::new();
+    for _ in 0..1000 {
+        app.update();
+        let world = app.world();
+        events.extend(
+            progress
+                .read(world.resource::<Events<PersistProgress>>())
+                .cloned(),
+        );
+        if saved
+            .read(world.resource::<Events<PersistSaved>>())
+            .any(|event| event.type_name == "LargeWorldSave")
+        {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let first = events.first().unwrap();
+    let last = events.last().unwrap();
+    assert_eq!(first.operation, PersistOperation::Save);
+    assert_eq!((first.bytes_done, first.finished), (0, false));
+    assert!(last.finished);
+    assert!(last.bytes_total > 64 * 1024);
+    assert_eq!(last.bytes_done, last.bytes_total);
+    assert_eq!(last.fraction(), 1.0);
+
+    app.world_mut()
+        .resource_mut::<LargeWorldSave>()
+        .bypass_change_detection()
+        .tiles
+        .clear();
+    PersistManager::load_in_background::<LargeWorldSave>(app.world_mut()).unwrap();
+    let mut events = Vec::new();
+    for _ in 0..1000 {
+        app.update();
+        let world = app.world();
+        events.extend(
+            progress
+                .read(world.resource::<Events<PersistProgress>>())
+                .cloned(),
+        );
+        if loaded
+            .read(world.resource::<Events<PersistLoaded>>())
+            .any(|event| event.type_name == "LargeWorldSave")
+        {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let last = events.last().unwrap();
+    assert_eq!(last.operation, PersistOperation::Load);
+    assert!(last.finished);
+    assert_eq!(last.bytes_done, last.bytes_total);
+    assert_eq!(app.world().resource::<LargeWorldSave>().tiles, tiles);
+}
+
+#[test]
+fn test_background_save_holds_back_auto_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "BackgroundHoldTest")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(DirResolver(temp_dir.path().to_path_buf())),
+    );
+    app.update();
+
+    app.world_mut()
+        .resource_mut::<LargeWorldSave>()
+        .bypass_change_detection()
+        .tiles = vec![1; 20_000];
+    PersistManager::save_in_background::<LargeWorldSave>(app.world_mut()).unwrap();
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .is_saving_in_background("LargeWorldSave"));
+
+    // The change waits for the background write instead of racing it
+    app.world_mut().resource_mut::<LargeWorldSave>().tiles = vec![2; 20_000];
+    app.update();
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .is_dirty_named("LargeWorldSave"));
+
+    for _ in 0..1000 {
+        app.update();
+        let manager = app.world().resource::<PersistManager>();
+        if !manager.is_saving_in_background("LargeWorldSave")
+            && !manager.is_dirty_named("LargeWorldSave")
+        {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    app.world_mut()
+        .resource_mut::<LargeWorldSave>()
+        .bypass_change_detection()
+        .tiles
+        .clear();
+    PersistManager::reload::<LargeWorldSave>(app.world_mut()).unwrap();
+    assert_eq!(
+        app.world().resource::<LargeWorldSave>().tiles,
+        vec![2; 20_000]
+    );
+}
+
+#[cfg(feature = "preflight")]
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ExpeditionSave {
+    chapter: u32,
+}
+
+#[cfg(feature = "preflight")]
+fn expedition_app(path: &std::path::Path, min_free_space: u64) -> (App, String) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "PreflightTest").with_preflight_checks(min_free_space),
+    );
+    let type_name = app
+        .persist_resource::<ExpeditionSave>()
+        .file(path)
+        .type_name()
+        .to_string();
+    app.update();
+    (app, type_name)
+}
+
+#[cfg(feature = "preflight")]
+#[test]
+fn test_preflight_checks_stop_saves_that_would_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("expedition.ron");
+    let (mut app, _) = expedition_app(&path, 0);
+    app.world_mut().resource_mut::<ExpeditionSave>().chapter = 3;
+    app.update();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    let set_readonly = |readonly: bool| {
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(readonly);
+        std::fs::set_permissions(&path, permissions).unwrap();
+    };
+
+    set_readonly(true);
+    let (mut app, _) = expedition_app(&path, 0);
+    assert_eq!(app.world().resource::<ExpeditionSave>().chapter, 3);
+    app.world_mut().resource_mut::<ExpeditionSave>().chapter = 4;
+    app.update();
+    let events = app.world().resource::<Events<PersistPreflightFailed>>();
+    let failed: Vec<PersistPreflightFailed> = events.get_cursor().read(events).cloned().collect();
+    assert!(!failed.is_empty());
+    for failure in &failed {
+        assert_eq!(failure.path, path);
+        assert!(matches!(
+            failure.error,
+            PersistError::PermissionDenied { .. }
+        ));
+    }
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+
+    set_readonly(false);
+    let (mut app, type_name) = expedition_app(&path, u64::MAX / 2);
+    app.world_mut().resource_mut::<ExpeditionSave>().chapter = 5;
+    let error = PersistManager::save_named(app.world_mut(), &type_name).unwrap_err();
+    assert!(matches!(error, PersistError::InsufficientSpace { .. }));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ReplayLog {
+    frames: Vec<u32>,
+}
+
+fn replay_log_app(path: &std::path::Path, policy: QuotaPolicy) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "QuotaTest")
+            .with_size_quota(64 * 1024)
+            .with_quota_policy(policy),
+    );
+    app.persist_resource::<ReplayLog>()
+        .file(path)
+        .size_quota(1024);
+    app.update();
+    app
+}
+
+#[test]
+fn test_size_quota_refuses_oversized_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("replay.ron");
+    let mut app = replay_log_app(&path, QuotaPolicy::Refuse);
+    app.world_mut().resource_mut::<ReplayLog>().frames = vec![1, 2, 3];
+    app.update();
+    let saved = std::fs::read_to_string(&path).unwrap();
+
+    app.world_mut().resource_mut::<ReplayLog>().frames = vec![7; 1000];
+    app.update();
+    let events = app.world().resource::<Events<PersistQuotaExceeded>>();
+    let refused: Vec<PersistQuotaExceeded> = events.get_cursor().read(events).cloned().collect();
+    assert_eq!(refused.len(), 1);
+    assert_eq!(refused[0].type_name, "ReplayLog");
+    assert_eq!(refused[0].quota, 1024);
+    assert!(refused[0].bytes > 1024);
+    let events = app.world().resource::<Events<PersistSaveFailed>>();
+    let failed: Vec<PersistSaveFailed> = events.get_cursor().read(events).cloned().collect();
+    assert!(matches!(
+        failed[0].error,
+        PersistError::QuotaExceeded { .. }
+    ));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_size_quota_compresses_oversized_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("replay.ron");
+    let mut app = replay_log_app(&path, QuotaPolicy::Compress);
+    app.world_mut().resource_mut::<ReplayLog>().frames = vec![7; 1000];
+    app.update();
+    let events = app.world().resource::<Events<PersistQuotaExceeded>>();
+    assert!(events.is_empty());
+    let bytes = std::fs::read(&path).unwrap();
+    assert!(bytes.starts_with(&[0x1f, 0x8b]));
+    assert!(bytes.len() <= 1024);
+
+    let app = replay_log_app(&path, QuotaPolicy::Compress);
+    assert_eq!(app.world().resource::<ReplayLog>().frames, vec![7; 1000]);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct CameraOrbit {
+    yaw: f32,
+}
+
+#[test]
+fn test_min_write_interval_holds_back_rewrites() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("camera.ron");
+    let start = "2024-06-01T12:00:00Z"
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .unwrap();
+    let seconds = std::sync::Arc::new(std::sync::atomic::AtomicI64::new(0));
+    let clock = seconds.clone();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "WriteIntervalTest")
+            .with_clock(move || {
+                start + chrono::TimeDelta::seconds(clock.load(std::sync::atomic::Ordering::SeqCst))
+            })
+            .with_min_write_interval(std::time::Duration::from_secs(5)),
+    );
+    app.persist_resource::<CameraOrbit>().file(&path);
+    app.update();
+    let read_yaw = || {
+        PersistFile::load_from_file(&path)
+            .unwrap()
+            .get_type_data("integration_test::CameraOrbit")
+            .unwrap()
+            .get::<f32>("yaw")
+    };
+
+    assert_eq!(read_yaw(), Some(0.0));
+
+    // Written too recently, so the latest value waits
+    for yaw in [1.0, 2.0] {
+        app.world_mut().resource_mut::<CameraOrbit>().yaw = yaw;
+        app.update();
+    }
+    assert_eq!(read_yaw(), Some(0.0));
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .is_dirty_named("CameraOrbit"));
+
+    seconds.store(4, std::sync::atomic::Ordering::SeqCst);
+    app.update();
+    assert_eq!(read_yaw(), Some(0.0));
+
+    seconds.store(5, std::sync::atomic::Ordering::SeqCst);
+    app.update();
+    assert_eq!(read_yaw(), Some(2.0));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct MatchHistory {
+    wins: u32,
+}
+
+#[derive(Clone, Default)]
+struct RecordingTelemetry(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl PersistTelemetry for RecordingTelemetry {
+    fn on_save(&self, type_name: &str, _duration: std::time::Duration, bytes: u64) {
+        assert!(bytes > 0);
+        self.0.lock().unwrap().push(format!("save {}", type_name));
+    }
+
+    fn on_load(&self, type_name: &str, _duration: std::time::Duration, bytes: u64) {
+        assert!(bytes > 0);
+        self.0.lock().unwrap().push(format!("load {}", type_name));
+    }
+
+    fn on_error(&self, type_name: &str, _error: &PersistError) {
+        self.0.lock().unwrap().push(format!("error {}", type_name));
+    }
+}
+
+#[test]
+fn test_telemetry_reports_saves_loads_and_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("history.ron");
+    let telemetry = RecordingTelemetry::default();
+    let history_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "TelemetryTest").with_telemetry(telemetry.clone()),
+        );
+        app.persist_resource::<MatchHistory>().file(&path);
+        app.update();
+        app
+    };
+    let events = |kind: &str| {
+        telemetry
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| *event == &format!("{} MatchHistory", kind))
+            .count()
+    };
+
+    let mut app = history_app();
+    app.world_mut().resource_mut::<MatchHistory>().wins = 3;
+    app.update();
+    assert!(events("save") >= 1);
+    assert_eq!(events("load"), 0);
+
+    history_app();
+    assert_eq!(events("load"), 1);
+
+    std::fs::write(&path, "not ron").unwrap();
+    history_app();
+    assert_eq!(events("error"), 1);
+}
+
+#[cfg(feature = "test-utils")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+struct HarnessSettings {
+    volume: f32,
+    name: String,
+}
+
+#[cfg(feature = "test-utils")]
+#[test]
+fn test_test_plugin_keeps_files_in_a_temp_dir() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistTestPlugin::new()
+            .seed(&HarnessSettings {
+                volume: 0.25,
+                name: "seeded".to_string(),
+            })
+            .seed_file("notes/readme.txt", "hello"),
+    );
+    app.update();
+
+    let settings = app.world().resource::<HarnessSettings>();
+    assert_eq!(settings.volume, 0.25);
+    assert_eq!(settings.name, "seeded");
+    let dir = app.world().resource::<PersistTestDir>().clone();
+    assert_eq!(dir.read_to_string("notes/readme.txt"), "hello");
+    let manager = app.world().resource::<PersistManager>();
+    assert!(manager.dev_file.starts_with(dir.path()));
+    // The seed counts as an earlier run's save, and loading it changed nothing
+    assert!(!manager
+        .stats()
+        .saves_per_type
+        .contains_key("HarnessSettings"));
+
+    app.world_mut().resource_mut::<HarnessSettings>().volume = 0.5;
+    app.update();
+    dir.assert_saved(
+        app.world(),
+        &HarnessSettings {
+            volume: 0.5,
+            name: "seeded".to_string(),
+        },
+    );
+
+    let path = dir.path().to_path_buf();
+    drop(dir);
+    drop(app);
+    assert!(!path.exists());
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct ReplayFrame {
+    tick: u64,
+}
+
+#[test]
+fn test_clock_controls_saved_timestamps() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("frame.ron");
+    let start = "2024-01-01T00:00:00Z"
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "ClockTest").with_clock(move || start));
+    let type_name = app
+        .persist_resource::<ReplayFrame>()
+        .file(&path)
+        .type_name()
+        .to_string();
+    app.update();
+
+    app.world_mut().resource_mut::<ReplayFrame>().tick = 7;
+    app.update();
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(manager.last_saved(&type_name), Some(start));
+    assert_eq!(manager.saved_at(&type_name), Some(start));
+    let file = PersistFile::load_from_file(&path).unwrap();
+    assert_eq!(file.last_saved, start.to_rfc3339());
+}
+
+#[derive(Resource, Default, Debug, PartialEq, Serialize, Deserialize, Persist)]
+struct SpawnLoadout {
+    slots: Vec<String>,
+    primary: Option<u32>,
+    accuracy: f64,
+}
+
+#[derive(Resource, Default, Debug, PartialEq, Serialize, Deserialize, Persist)]
+struct CachedSpawnLoadout {
+    slots: Vec<String>,
+    #[serde(skip)]
+    weight: u32,
+}
+
+#[test]
+fn test_check_roundtrip_accepts_lossless_types() {
+    bevy_persist::check_roundtrip(&SpawnLoadout {
+        slots: vec!["sword".to_string(), "bow".to_string()],
+        primary: Some(1),
+        accuracy: 0.1 + 0.2,
+    });
+}
+
+#[test]
+#[should_panic(expected = "CachedSpawnLoadout changed on its way through ron")]
+fn test_check_roundtrip_catches_skipped_fields() {
+    bevy_persist::check_roundtrip(&CachedSpawnLoadout {
+        slots: vec!["shield".to_string()],
+        weight: 12,
+    });
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Crate {
+    items: Vec<CrateItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct CrateItem {
+    count: u32,
+}
+
+#[test]
+fn test_errors_carry_field_path_and_io_kind() {
+    let data = PersistData::parse_as(
+        r#"(values: {"items": [(count: 1), (count: "lots")]})"#,
+        PersistFormat::Ron,
+    )
+    .unwrap();
+    let error = data.deserialize::<Crate>().unwrap_err();
+    assert!(matches!(error, PersistError::SerializationError { .. }));
+    assert_eq!(error.field(), Some("items[1].count"));
+    assert!(
+        error.to_string().contains("at `items[1].count`"),
+        "{}",
+        error
+    );
+
+    let error = PersistFormat::Ron
+        .deserialize::<Crate>("(items: [(count: -1)])")
+        .unwrap_err();
+    assert_eq!(error.field(), Some("items[0].count"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let missing = temp_dir.path().join("missing.ron");
+    let error = PersistFile::convert(&missing, temp_dir.path().join("out.json")).unwrap_err();
+    assert_eq!(error.io_kind(), Some(std::io::ErrorKind::NotFound));
+    assert_eq!(error.path(), Some(missing.as_path()));
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist)]
+struct SandboxProgress {
+    level: u32,
+}
+
+fn count_events<E: Event>(app: &App) -> usize {
+    let events = app.world().resource::<Events<E>>();
+    events.get_cursor().read(events).count()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_saves_fall_back_to_memory_when_a_file_is_unwritable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let locked = temp_dir.path().join("locked");
+    std::fs::create_dir(&locked).unwrap();
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555)).unwrap();
+    if std::fs::write(locked.join("probe"), "").is_ok() {
+        // Permissions don't hold back this user, e.g. root
+        return;
+    }
+    let path = locked.join("progress.ron");
+    let start = "2024-06-01T12:00:00Z"
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .unwrap();
+    let seconds = std::sync::Arc::new(std::sync::atomic::AtomicI64::new(0));
+    let clock = seconds.clone();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "FallbackTest")
+            .with_retry_policy(RetryPolicy::none())
+            .with_memory_fallback(true)
+            .with_clock(move || {
+                start + chrono::TimeDelta::seconds(clock.load(std::sync::atomic::Ordering::SeqCst))
+            }),
+    );
+    app.persist_resource::<SandboxProgress>().file(&path);
+    app.update();
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .is_type_in_memory("SandboxProgress"));
+    assert_eq!(count_events::<PersistFellBackToMemory>(&app), 1);
+    let failures = count_events::<PersistSaveFailed>(&app);
+
+    // Later changes are kept in memory without any more errors
+    app.world_mut().resource_mut::<SandboxProgress>().level = 4;
+    app.update();
+    app.update();
+    assert_eq!(count_events::<PersistSaveFailed>(&app), failures);
+    assert_eq!(count_events::<PersistFellBackToMemory>(&app), 1);
+    assert!(!path.exists());
+
+    // Reloading sees the value saved in memory
+    app.world_mut().resource_mut::<SandboxProgress>().level = 0;
+    PersistManager::reload::<SandboxProgress>(app.world_mut()).unwrap();
+    assert_eq!(app.world().resource::<SandboxProgress>().level, 4);
+
+    // Once the file can be written again, the next save after a while goes to disk
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+    seconds.store(30, std::sync::atomic::Ordering::SeqCst);
+    app.world_mut().resource_mut::<SandboxProgress>().level = 5;
+    app.update();
+    assert!(!app.world().resource::<PersistManager>().is_in_memory());
+    assert!(path.exists());
+}
+
+#[test]
+fn test_memory_fallback_skips_files_that_are_only_broken() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("kiosk.ron");
+    std::fs::write(&path, "").unwrap();
+    // A file can't be a directory, which isn't a matter of permissions
+    let blocked = path.join("progress.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "NoFallbackTest")
+            .with_retry_policy(RetryPolicy::none())
+            .with_memory_fallback(true),
+    );
+    app.persist_resource::<SandboxProgress>().file(&blocked);
+    app.update();
+    assert!(!app.world().resource::<PersistManager>().is_in_memory());
+    assert_eq!(count_events::<PersistFellBackToMemory>(&app), 0);
+
+    app.world_mut().resource_mut::<SandboxProgress>().level = 4;
+    app.update();
+    assert!(count_events::<PersistSaveFailed>(&app) > 0);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct ScopedInventory {
+    gold: u32,
+    items: Vec<String>,
+}
+
+#[test]
+#[cfg(not(feature = "prod"))]
+fn test_scopes_keep_separate_data_per_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = PersistManager::new("TestOrg", "ScopeTest").with_root_dir(temp_dir.path());
+
+    let alice = ScopedInventory {
+        gold: 10,
+        items: vec!["sword".to_string()],
+    };
+    let bob = ScopedInventory {
+        gold: 25,
+        items: Vec::new(),
+    };
+    let path = manager.scoped("player:1").save(&alice).unwrap().unwrap();
+    manager.scoped("player:2").save(&bob).unwrap();
+    assert!(path.starts_with(temp_dir.path().join("scopes").join("player_1")));
+
+    assert_eq!(manager.scoped("player:1").load(), Some(alice));
+    assert_eq!(manager.scoped("player:2").load(), Some(bob.clone()));
+    assert_eq!(manager.scoped("player:3").load::<ScopedInventory>(), None);
+    // The live resource's own data is untouched
+    assert!(manager.read_type_data("ScopedInventory", None).is_none());
+
+    manager
+        .scoped("player:1")
+        .remove::<ScopedInventory>()
+        .unwrap();
+    assert_eq!(manager.scoped("player:1").load::<ScopedInventory>(), None);
+    assert!(!path.exists());
+    assert_eq!(manager.scoped("player:2").load(), Some(bob));
+}
+
+/// Key-value store shared between apps, standing in for something like bevy_pkv
+#[derive(Clone, Default)]
+struct SharedKv(std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, Vec<u8>>>>);
+
+impl PersistBackend for SharedKv {
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+        Ok(self.0.lock().unwrap().get(key).cloned())
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        self.0.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn write_if(&self, key: &str, expected: Option<&[u8]>, bytes: &[u8]) -> PersistResult<bool> {
+        let mut store = self.0.lock().unwrap();
+        if store.get(key).map(Vec::as_slice) != expected {
+            return Ok(false);
+        }
+        store.insert(key.to_string(), bytes.to_vec());
+        Ok(true)
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct SyncedProfile {
+    score: u32,
+    title: String,
+}
+
+/// Builds a device syncing with `remote`, keeping its files in `dir`.
+fn sync_device(
+    dir: &std::path::Path,
+    remote: &(impl PersistBackend + Clone),
+    strategy: ConflictStrategy,
+) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "SyncTest")
+            .with_root_dir(dir)
+            .with_path_resolver(FileNameResolver(dir.to_path_buf()))
+            .with_cloud_sync(
+                remote.clone(),
+                strategy,
+                std::time::Duration::from_secs(3600),
+            ),
+    );
+    wait_for_sync(&mut app);
+    app
+}
+
+/// Runs frames until the running sync finishes.
+fn wait_for_sync(app: &mut App) {
+    let mut cursor = app
+        .world()
+        .resource::<Events<PersistSynced>>()
+        .get_cursor_current();
+    for _ in 0..1000 {
+        app.update();
+        let events = app.world().resource::<Events<PersistSynced>>();
+        if cursor.read(events).next().is_some() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    panic!("sync didn't finish");
+}
+
+fn sync_now(app: &mut App) {
+    PersistManager::sync_now(app.world_mut()).unwrap();
+    wait_for_sync(app);
+}
+
+#[test]
+fn test_cloud_sync_pushes_and_pulls_between_devices() {
+    let remote = SharedKv::default();
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+
+    let mut first = sync_device(first_dir.path(), &remote, ConflictStrategy::NewestWins);
+    first.world_mut().resource_mut::<SyncedProfile>().score = 10;
+    first.update();
+    sync_now(&mut first);
+    let manager = first.world().resource::<PersistManager>();
+    let revision = manager.sync_revision("SyncedProfile").unwrap();
+    assert!(manager.sync_state_path().exists());
+
+    // A new device takes what was pushed
+    let mut second = sync_device(second_dir.path(), &remote, ConflictStrategy::NewestWins);
+    assert_eq!(second.world().resource::<SyncedProfile>().score, 10);
+    assert_eq!(
+        second
+            .world()
+            .resource::<PersistManager>()
+            .sync_revision("SyncedProfile"),
+        Some(revision)
+    );
+
+    second.world_mut().resource_mut::<SyncedProfile>().score = 25;
+    second.update();
+    sync_now(&mut second);
+    sync_now(&mut first);
+    assert_eq!(first.world().resource::<SyncedProfile>().score, 25);
+    first.update();
+    drop(first);
+
+    // Pulled data is saved locally like any change
+    let first = sync_device(first_dir.path(), &remote, ConflictStrategy::NewestWins);
+    assert_eq!(first.world().resource::<SyncedProfile>().score, 25);
+}
+
+#[test]
+fn test_cloud_sync_newest_wins_after_a_restart() {
+    let remote = SharedKv::default();
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+    let mut first = sync_device(first_dir.path(), &remote, ConflictStrategy::NewestWins);
+    let mut second = sync_device(second_dir.path(), &remote, ConflictStrategy::NewestWins);
+
+    // Saved on the first device, which quits before syncing it
+    first.world_mut().resource_mut::<SyncedProfile>().score = 50;
+    first.update();
+    drop(first);
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    second.world_mut().resource_mut::<SyncedProfile>().score = 60;
+    second.update();
+    sync_now(&mut second);
+
+    // The restarted device hasn't saved anything yet, but its file says
+    // when the local data was saved, which is before the remote data
+    let first = sync_device(first_dir.path(), &remote, ConflictStrategy::NewestWins);
+    assert_eq!(first.world().resource::<SyncedProfile>().score, 60);
+}
+
+#[test]
+fn test_cloud_sync_conflicts_wait_for_resolution() {
+    let remote = SharedKv::default();
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+    let mut first = sync_device(first_dir.path(), &remote, ConflictStrategy::Manual);
+    let mut second = sync_device(second_dir.path(), &remote, ConflictStrategy::Manual);
+
+    first.world_mut().resource_mut::<SyncedProfile>().score = 30;
+    first.update();
+    second.world_mut().resource_mut::<SyncedProfile>().score = 40;
+    second.update();
+    sync_now(&mut second);
+
+    let mut conflicts = first
+        .world()
+        .resource::<Events<PersistSyncConflict>>()
+        .get_cursor_current();
+    sync_now(&mut first);
+    let events = first.world().resource::<Events<PersistSyncConflict>>();
+    let conflict = conflicts.read(events).next().unwrap().clone();
+    assert_eq!(conflict.type_name, "SyncedProfile");
+    assert_eq!(conflict.local.get::<u32>("score"), Some(30));
+    assert_eq!(conflict.remote.get::<u32>("score"), Some(40));
+    assert_eq!(first.world().resource::<SyncedProfile>().score, 30);
+    assert_eq!(
+        first.world().resource::<PersistManager>().sync_conflicts(),
+        ["SyncedProfile"]
+    );
+
+    // Keeping the local data pushes it to the other device
+    PersistManager::resolve_conflict(
+        first.world_mut(),
+        "SyncedProfile",
+        SyncResolution::KeepLocal,
+    )
+    .unwrap();
+    wait_for_sync(&mut first);
+    assert!(first
+        .world()
+        .resource::<PersistManager>()
+        .sync_conflicts()
+        .is_empty());
+    sync_now(&mut second);
+    assert_eq!(second.world().resource::<SyncedProfile>().score, 30);
+}
+
+#[test]
+fn test_cloud_sync_strategies_settle_conflicts() {
+    // (strategy, whether the first device saves after the second, score both end up with)
+    let cases = [
+        (ConflictStrategy::NewestWins, false, 40),
+        (ConflictStrategy::NewestWins, true, 30),
+        (ConflictStrategy::LocalWins, false, 30),
+        (ConflictStrategy::RemoteWins, true, 40),
+    ];
+    for (strategy, first_is_newer, expected) in cases {
+        let remote = SharedKv::default();
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let mut first = sync_device(first_dir.path(), &remote, strategy.clone());
+        let mut second = sync_device(second_dir.path(), &remote, strategy.clone());
+
+        if !first_is_newer {
+            first.world_mut().resource_mut::<SyncedProfile>().score = 30;
+            first.update();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        second.world_mut().resource_mut::<SyncedProfile>().score = 40;
+        second.update();
+        sync_now(&mut second);
+        if first_is_newer {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            first.world_mut().resource_mut::<SyncedProfile>().score = 30;
+            first.update();
+        }
+
+        // The first device sees both sides changed
+        sync_now(&mut first);
+        sync_now(&mut second);
+        assert_eq!(
+            first.world().resource::<SyncedProfile>().score,
+            expected,
+            "{:?}, first device newer: {}",
+            strategy,
+            first_is_newer
+        );
+        assert_eq!(second.world().resource::<SyncedProfile>().score, expected);
+    }
+}
+
+#[test]
+fn test_cloud_sync_merges_over_the_synced_copy() {
+    let conflicts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let strategy = {
+        let conflicts = conflicts.clone();
+        ConflictStrategy::merge(move |conflict| {
+            conflicts.lock().unwrap().push(conflict.clone());
+            let score = |data: &PersistData| data.get::<u32>("score").unwrap_or(0);
+            let mut merged = conflict.local.clone();
+            merged.insert("score", score(&conflict.local).max(score(&conflict.remote)));
+            Some(merged)
+        })
+    };
+    let remote = SharedKv::default();
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+    let mut first = sync_device(first_dir.path(), &remote, strategy.clone());
+    let mut second = sync_device(second_dir.path(), &remote, strategy);
+
+    // Different fields changed on each device merge without the callback
+    first.world_mut().resource_mut::<SyncedProfile>().title = "Knight".to_string();
+    first.update();
+    second.world_mut().resource_mut::<SyncedProfile>().score = 40;
+    second.update();
+    sync_now(&mut second);
+    sync_now(&mut first);
+    let merged = SyncedProfile {
+        score: 40,
+        title: "Knight".to_string(),
+    };
+    assert_eq!(*first.world().resource::<SyncedProfile>(), merged);
+    assert!(conflicts.lock().unwrap().is_empty());
+    sync_now(&mut second);
+    assert_eq!(*second.world().resource::<SyncedProfile>(), merged);
+
+    // Both changing the same field goes to the callback, with the merged copy as the base
+    first.world_mut().resource_mut::<SyncedProfile>().score = 70;
+    first.update();
+    second.world_mut().resource_mut::<SyncedProfile>().score = 50;
+    second.update();
+    sync_now(&mut second);
+    sync_now(&mut first);
+    assert_eq!(first.world().resource::<SyncedProfile>().score, 70);
+    let conflicts = conflicts.lock().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    let base = conflicts[0].base.as_ref().unwrap();
+    assert_eq!(base.get::<u32>("score"), Some(40));
+    assert_eq!(base.get::<String>("title").as_deref(), Some("Knight"));
+}
+
+/// Key and bytes another device pushes once that key is read
+type ArmedPush = std::sync::Arc<std::sync::Mutex<Option<(String, Vec<u8>)>>>;
+
+/// Store where another device pushes right after the armed key is read
+#[derive(Clone, Default)]
+struct RacingKv {
+    store: SharedKv,
+    push: ArmedPush,
+}
+
+impl PersistBackend for RacingKv {
+    fn read(&self, key: &str) -> PersistResult<Option<Vec<u8>>> {
+        let bytes = self.store.read(key)?;
+        let mut push = self.push.lock().unwrap();
+        if push.as_ref().is_some_and(|(armed, _)| armed == key) {
+            let (_, pushed) = push.take().unwrap();
+            self.store.write(key, &pushed)?;
+        }
+        Ok(bytes)
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> PersistResult<()> {
+        self.store.write(key, bytes)
+    }
+
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        self.store.remove(key)
+    }
+
+    fn write_if(&self, key: &str, expected: Option<&[u8]>, bytes: &[u8]) -> PersistResult<bool> {
+        self.store.write_if(key, expected, bytes)
+    }
+}
+
+#[test]
+fn test_cloud_sync_push_does_not_overwrite_a_concurrent_push() {
+    let remote = RacingKv::default();
+    let other_remote = SharedKv::default();
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+    let mut first = sync_device(first_dir.path(), &remote, ConflictStrategy::NewestWins);
+    let mut second = sync_device(
+        second_dir.path(),
+        &other_remote,
+        ConflictStrategy::NewestWins,
+    );
+    second.world_mut().resource_mut::<SyncedProfile>().score = 60;
+    second.update();
+    sync_now(&mut second);
+    let key = first
+        .world()
+        .resource::<PersistManager>()
+        .get_storage_key("SyncedProfile");
+    let pushed = other_remote.0.lock().unwrap()[&key].clone();
+
+    // The other device pushes between this sync's read and its push
+    *remote.push.lock().unwrap() = Some((key.clone(), pushed.clone()));
+    first.world_mut().resource_mut::<SyncedProfile>().score = 30;
+    first.update();
+    let mut synced = first
+        .world()
+        .resource::<Events<PersistSynced>>()
+        .get_cursor_current();
+    sync_now(&mut first);
+    let events = first.world().resource::<Events<PersistSynced>>();
+    let event = synced.read(events).next().unwrap().clone();
+    assert_eq!(event.failed, ["SyncedProfile"]);
+    assert_eq!(remote.store.0.lock().unwrap()[&key], pushed);
+
+    // The next sync sees the other revision and settles it as a conflict
+    sync_now(&mut first);
+    assert_eq!(first.world().resource::<SyncedProfile>().score, 30);
+    assert_ne!(remote.store.0.lock().unwrap()[&key], pushed);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct BackendHighScores {
+    best: u32,
+}
+
+#[test]
+fn test_backend_replaces_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let kv = SharedKv::default();
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "BackendTest")
+                .with_root_dir(temp_dir.path())
+                .with_backend(kv.clone()),
+        );
+        app.persist_resource::<BackendHighScores>();
+        app.update();
+        app
+    };
+
+    let mut app = build_app();
+    let key = app
+        .world()
+        .resource::<PersistManager>()
+        .get_storage_key(BackendHighScores::type_name());
+    let key = key.as_str();
+    app.world_mut().resource_mut::<BackendHighScores>().best = 42;
+    app.update();
+    assert!(kv.0.lock().unwrap().contains_key(key));
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+    let mut app = build_app();
+    assert_eq!(app.world().resource::<BackendHighScores>().best, 42);
+
+    // Scopes keep their own keys in the same store
+    let mut manager = app.world_mut().resource_mut::<PersistManager>();
+    manager
+        .scoped("player:7")
+        .save(&BackendHighScores { best: 3 })
+        .unwrap();
+    assert!(kv
+        .0
+        .lock()
+        .unwrap()
+        .contains_key(&format!("scopes/player:7/{}", key)));
+    assert_eq!(
+        manager.scoped("player:7").load(),
+        Some(BackendHighScores { best: 3 })
+    );
+
+    manager
+        .remove_type_data(BackendHighScores::type_name())
+        .unwrap();
+    assert!(!kv.0.lock().unwrap().contains_key(key));
+}
+
+/// String-only store, standing in for something like bevy_pkv's `PkvStore`
+#[cfg(feature = "text-backend")]
+#[derive(Clone, Default)]
+struct SharedTextKv(std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>);
+
+#[cfg(feature = "text-backend")]
+impl PersistTextStore for SharedTextKv {
+    fn get(&self, key: &str) -> PersistResult<Option<String>> {
+        Ok(self.0.lock().unwrap().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &str) -> PersistResult<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> PersistResult<()> {
+        self.0.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "secure")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(secure, store = "text-backend")]
+struct TextVault {
+    owner: String,
+    #[persist(encrypt)]
+    pin: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(obfuscated, store = "text-backend")]
+struct TextArcadeScore {
+    score: u32,
+}
+
+#[test]
+#[cfg(all(feature = "text-backend", feature = "secure"))]
+fn test_text_backend_round_trips_binary_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let kv = SharedTextKv::default();
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "TextBackendTest")
+                .with_store("text-backend")
+                .with_root_dir(temp_dir.path())
+                .with_environment(PersistEnvironment::Production)
+                .with_secret("vault-secret")
+                .with_backend(TextBackend::new(kv.clone())),
+        );
+        app.update();
+        app
+    };
+
+    let mut app = build_app();
+    *app.world_mut().resource_mut::<TextVault>() = TextVault {
+        owner: "Ayla".to_string(),
+        pin: 4812,
+    };
+    app.world_mut().resource_mut::<TextArcadeScore>().score = 987654;
+    app.update();
+
+    let manager = app.world().resource::<PersistManager>();
+    let score_key = manager.get_storage_key(TextArcadeScore::type_name());
+    let stored = kv.0.lock().unwrap().get(&score_key).cloned().unwrap();
+    assert!(!stored.contains("987654"));
+    // Obfuscated saves aren't text, which is why they're stored as base64
+    let bytes = TextBackend::new(kv.clone())
+        .read(&score_key)
+        .unwrap()
+        .unwrap();
+    assert!(std::str::from_utf8(&bytes).is_err());
+
+    let app = build_app();
+    assert_eq!(
+        *app.world().resource::<TextVault>(),
+        TextVault {
+            owner: "Ayla".to_string(),
+            pin: 4812,
+        }
+    );
+    assert_eq!(app.world().resource::<TextArcadeScore>().score, 987654);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct RedbQuestLog {
+    completed: Vec<String>,
+}
+
+#[test]
+#[cfg(feature = "redb")]
+fn test_redb_backend_keeps_types_in_one_database() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("saves.redb");
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "RedbTest")
+                .with_root_dir(temp_dir.path())
+                .with_backend(RedbBackend::open(&db_path).unwrap()),
+        );
+        app.persist_resource::<RedbQuestLog>();
+        app.update();
+        app
+    };
+
+    let mut app = build_app();
+    app.world_mut()
+        .resource_mut::<RedbQuestLog>()
+        .completed
+        .push("tutorial".to_string());
+    app.update();
+    drop(app);
+
+    // Only the database file is written
+    let files: Vec<_> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files, vec![db_path.clone()]);
+
+    let app = build_app();
+    assert_eq!(
+        app.world().resource::<RedbQuestLog>().completed,
+        vec!["tutorial".to_string()]
+    );
+    drop(app);
+
+    // Conditional writes only replace what the caller last read
+    let backend = RedbBackend::open(&db_path).unwrap();
+    assert!(backend.write_if("cloud", None, b"one").unwrap());
+    assert!(!backend.write_if("cloud", None, b"two").unwrap());
+    assert!(backend.write_if("cloud", Some(b"one"), b"two").unwrap());
+    assert_eq!(backend.read("cloud").unwrap().as_deref(), Some(&b"two"[..]));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct SqliteCampaign {
+    chapter: u32,
+}
+
+#[test]
+#[cfg(feature = "sqlite")]
+fn test_sqlite_backend_restores_earlier_revisions() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend =
+        std::sync::Arc::new(SqliteBackend::open(temp_dir.path().join("saves.db")).unwrap());
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "SqliteTest")
+            .with_root_dir(temp_dir.path())
+            .with_backend(backend.clone()),
+    );
+    app.persist_resource::<SqliteCampaign>();
+    app.update();
+    let key = app
+        .world()
+        .resource::<PersistManager>()
+        .get_storage_key(SqliteCampaign::type_name());
+
+    app.world_mut().resource_mut::<SqliteCampaign>().chapter = 2;
+    app.update();
+    let before = chrono::Utc::now();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    app.world_mut().resource_mut::<SqliteCampaign>().chapter = 3;
+    app.update();
+
+    let history = backend.history(&key).unwrap();
+    assert!(history.len() >= 2);
+    assert!(history
+        .windows(2)
+        .all(|pair| pair[0].revision < pair[1].revision));
+
+    // Rolling back puts chapter 2 back as the latest revision
+    assert_eq!(backend.restore_to(before).unwrap(), 1);
+    assert_eq!(backend.history(&key).unwrap().len(), history.len() + 1);
+    PersistManager::reload::<SqliteCampaign>(app.world_mut()).unwrap();
+    assert_eq!(app.world().resource::<SqliteCampaign>().chapter, 2);
+
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .remove_type_data(SqliteCampaign::type_name())
+        .unwrap();
+    assert!(backend.history(&key).unwrap().is_empty());
+
+    // Conditional writes only replace what the caller last read
+    assert!(backend.write_if("cloud", None, b"one").unwrap());
+    assert!(!backend.write_if("cloud", Some(b"two"), b"three").unwrap());
+    assert!(backend.write_if("cloud", Some(b"one"), b"two").unwrap());
+    assert_eq!(backend.read("cloud").unwrap().as_deref(), Some(&b"two"[..]));
+    assert_eq!(backend.history("cloud").unwrap().len(), 2);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(auto_save = false)]
+struct TxProgress {
+    level: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(auto_save = false)]
+struct TxInventory {
+    potions: u32,
+}
+
+#[test]
+fn test_transaction_saves_together_and_skips_an_edited_journal() {
+    let temp_dir = TempDir::new().unwrap();
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "TransactionTest")
+                .with_root_dir(temp_dir.path())
+                .with_environment(PersistEnvironment::Dev),
+        );
+        app.persist_resource::<TxProgress>();
+        app.persist_resource::<TxInventory>();
+        app.update();
+        app
+    };
+
+    let mut app = build_app();
+    app.world_mut().resource_mut::<TxProgress>().level = 2;
+    app.world_mut().resource_mut::<TxInventory>().potions = 5;
+    PersistManager::transaction(app.world_mut(), |tx| {
+        tx.save::<TxProgress>().save::<TxInventory>();
+    })
+    .unwrap();
+    let manager = app.world().resource::<PersistManager>();
+    let saved = manager
+        .read_type_data(TxInventory::type_name(), None)
+        .unwrap();
+    assert_eq!(saved.get::<u32>("potions"), Some(5));
+    let journal = manager.journal_path();
+    assert!(!journal.exists());
+
+    // A journal that fails its checksum, e.g. one edited by hand, isn't replayed
+    let mut inventory = PersistData::new();
+    inventory.insert("potions", 99u32);
+    let mut file = PersistFile::new();
+    file.set_type_data(TxInventory::type_name().to_string(), inventory);
+    file.save_to_file(&journal).unwrap();
+    drop(app);
+
+    let app = build_app();
+    assert_eq!(app.world().resource::<TxProgress>().level, 2);
+    assert_eq!(app.world().resource::<TxInventory>().potions, 5);
+    assert!(!journal.exists());
+
+    let mut app = app;
+    let result = PersistManager::transaction(app.world_mut(), |tx| {
+        tx.save_named("NotRegistered");
+    });
+    assert!(matches!(result, Err(PersistError::ResourceNotFound(_))));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct AutosaveRun {
+    floor: u32,
+}
+
+#[test]
+fn test_autosaves_rotate_and_restore() {
+    let temp_dir = TempDir::new().unwrap();
+    let start = "2024-06-01T12:00:00Z"
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .unwrap();
+    let minutes = std::sync::Arc::new(std::sync::atomic::AtomicI64::new(0));
+    let clock = minutes.clone();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "AutosaveTest")
+            .with_root_dir(temp_dir.path())
+            .with_clock(move || {
+                start + chrono::TimeDelta::minutes(clock.load(std::sync::atomic::Ordering::SeqCst))
+            })
+            .with_autosaves(std::time::Duration::from_secs(3600), 2),
+    );
+    app.persist_resource::<AutosaveRun>();
+    app.update();
+
+    for floor in 1..=3 {
+        app.world_mut().resource_mut::<AutosaveRun>().floor = floor;
+        minutes.store(floor as i64, std::sync::atomic::Ordering::SeqCst);
+        let path = PersistManager::autosave(app.world_mut()).unwrap();
+        assert!(path.ends_with(format!("autosave_2024-06-01T12-0{}-00.ron", floor)));
+    }
+
+    // Only the newest two are kept, newest first
+    let autosaves = app.world().resource::<PersistManager>().list_autosaves();
+    assert_eq!(autosaves.len(), 2);
+    assert_eq!(autosaves[0].saved_at, start + chrono::TimeDelta::minutes(3));
+    assert_eq!(autosaves[1].saved_at, start + chrono::TimeDelta::minutes(2));
+
+    let restored = PersistManager::restore_autosave(app.world_mut(), &autosaves[1].path).unwrap();
+    assert!(restored > 0);
+    assert_eq!(app.world().resource::<AutosaveRun>().floor, 2);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(auto_save = false)]
+struct ContinueRun {
+    floor: u32,
+}
+
+#[test]
+fn test_continue_finds_the_most_recent_save() {
+    let temp_dir = TempDir::new().unwrap();
+    let start = "2024-06-01T12:00:00Z"
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .unwrap();
+    let slot = |floor: u32, minute: i64| {
+        let mut data = PersistData::new();
+        data.insert("floor", floor);
+        let mut file = PersistFile::new();
+        file.set_type_data_at(
+            "ContinueRun".to_string(),
+            data,
+            start + chrono::TimeDelta::minutes(minute),
+        );
+        file
+    };
+    let slots_dir = temp_dir.path().join("saves");
+    slot(1, 5)
+        .save_to_file(slots_dir.join("slot1.ron"))
+        .unwrap();
+    slot(2, 9)
+        .save_to_file(slots_dir.join("slot2.ron"))
+        .unwrap();
+    // A slot cut off mid-write doesn't hide the others
+    std::fs::write(slots_dir.join("slot3.ron"), "(version: \"1.0\", type_da").unwrap();
+
+    let slots = SaveSlots::new(&slots_dir);
+    let names: Vec<_> = slots.list().into_iter().map(|slot| slot.name).collect();
+    assert_eq!(names, ["slot2", "slot1"]);
+    let newest = slots.most_recent().unwrap();
+    assert_eq!(newest.saved_at, start + chrono::TimeDelta::minutes(9));
+    assert_eq!(newest.type_names, ["ContinueRun"]);
+    assert!(SaveSlots::new(temp_dir.path().join("missing"))
+        .most_recent()
+        .is_none());
+
+    let minutes = std::sync::Arc::new(std::sync::atomic::AtomicI64::new(0));
+    let clock = minutes.clone();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "ContinueTest")
+            .with_root_dir(temp_dir.path())
+            .with_path_resolver(DirResolver(temp_dir.path().to_path_buf()))
+            .with_clock(move || {
+                start + chrono::TimeDelta::minutes(clock.load(std::sync::atomic::Ordering::SeqCst))
+            }),
+    );
+    app.persist_resource::<ContinueRun>();
+    app.update();
+    let most_recent = |app: &App| {
+        app.world()
+            .resource::<PersistManager>()
+            .most_recent_state::<ContinueRun>()
+    };
+    assert_eq!(most_recent(&app), None);
+
+    app.world_mut().resource_mut::<ContinueRun>().floor = 5;
+    minutes.store(1, std::sync::atomic::Ordering::SeqCst);
+    PersistManager::save_named(app.world_mut(), ContinueRun::type_name()).unwrap();
+    assert_eq!(most_recent(&app).unwrap().floor, 5);
+
+    // An autosave newer than the usual file wins
+    app.world_mut().resource_mut::<ContinueRun>().floor = 7;
+    minutes.store(2, std::sync::atomic::Ordering::SeqCst);
+    PersistManager::autosave(app.world_mut()).unwrap();
+    assert_eq!(most_recent(&app).unwrap().floor, 7);
+
+    app.world_mut().resource_mut::<ContinueRun>().floor = 9;
+    minutes.store(3, std::sync::atomic::Ordering::SeqCst);
+    PersistManager::save_named(app.world_mut(), ContinueRun::type_name()).unwrap();
+    assert_eq!(most_recent(&app).unwrap().floor, 9);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct QuickHealth {
+    hp: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct QuickCamera {
+    zoom: u32,
+}
+
+#[test]
+fn test_quick_save_and_quick_load_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "QuickSaveTest")
+            .with_root_dir(temp_dir.path())
+            .with_path_resolver(DirResolver(temp_dir.path().to_path_buf())),
+    );
+    app.persist_resource::<QuickHealth>().group("combat");
+    app.persist_resource::<QuickCamera>();
+    app.update();
+
+    // Nothing to load yet
+    app.world_mut().send_event(QuickLoad::all());
+    app.update();
+    assert_eq!(app.world().resource::<QuickHealth>().hp, 0);
+
+    app.world_mut().resource_mut::<QuickHealth>().hp = 10;
+    app.world_mut().resource_mut::<QuickCamera>().zoom = 2;
+    app.world_mut().send_event(QuickSave::all());
+    app.update();
+    assert!(app
+        .world()
+        .resource::<PersistManager>()
+        .quick_save_path(None)
+        .exists());
+
+    app.world_mut().resource_mut::<QuickHealth>().hp = 3;
+    app.world_mut().resource_mut::<QuickCamera>().zoom = 5;
+    app.world_mut().send_event(QuickLoad::all());
+    app.update();
+    assert_eq!(app.world().resource::<QuickHealth>().hp, 10);
+    assert_eq!(app.world().resource::<QuickCamera>().zoom, 2);
+
+    // A group's quick save only holds and restores that group
+    app.world_mut().resource_mut::<QuickHealth>().hp = 7;
+    app.world_mut().send_event(QuickSave::group("combat"));
+    app.update();
+    app.world_mut().resource_mut::<QuickHealth>().hp = 1;
+    app.world_mut().resource_mut::<QuickCamera>().zoom = 9;
+    app.world_mut().send_event(QuickLoad::group("combat"));
+    app.update();
+    assert_eq!(app.world().resource::<QuickHealth>().hp, 7);
+    assert_eq!(app.world().resource::<QuickCamera>().zoom, 9);
+
+    // The full quick save is still there
+    app.world_mut().send_event(QuickLoad::all());
+    app.update();
+    assert_eq!(app.world().resource::<QuickHealth>().hp, 10);
+}
+
+#[cfg(feature = "ini")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct IniAudio {
+    volume: f32,
+    muted: bool,
+    device: String,
+}
+
+#[cfg(feature = "ini")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct IniVideo {
+    width: u32,
+    resolutions: Vec<u32>,
+}
+
+#[cfg(feature = "ini")]
+#[test]
+fn test_import_legacy_ini_on_first_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let ini_path = temp_dir.path().join("settings.ini");
+    std::fs::write(
+        &ini_path,
+        "; written by the old game\n\
+         [iniaudio]\nvolume = 0.25\nmuted = true\ndevice = Speakers\nunknown = 3\n\
+         [Graphics]\nwidth = 1280\nresolutions = [720, 1080]\n",
+    )
+    .unwrap();
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "IniTest")
+                .with_root_dir(temp_dir.path().join("saves"))
+                .with_path_resolver(DirResolver(temp_dir.path().join("saves")))
+                .with_ini_import(&ini_path),
+        );
+        let path = ini_path.clone();
+        app.add_systems(
+            PreStartup,
+            (move |world: &mut World| {
+                PersistManager::import_ini_section::<IniVideo>(world, &path, "Graphics").unwrap();
+            })
+            .after(PersistSet::Load),
+        );
+        app.update();
+        app
+    };
+
+    let mut app = build_app();
+    let audio = app.world().resource::<IniAudio>().clone();
+    assert_eq!(
+        audio,
+        IniAudio {
+            volume: 0.25,
+            muted: true,
+            device: "Speakers".to_string(),
+        }
+    );
+    let video = app.world().resource::<IniVideo>();
+    assert_eq!(video.width, 1280);
+    assert_eq!(video.resolutions, [720, 1080]);
+    app.update();
+    app.world_mut().resource_mut::<IniAudio>().volume = 0.5;
+    app.update();
+    drop(app);
+
+    // Once saved, the INI file no longer overrides anything
+    let mut app = build_app();
+    assert_eq!(app.world().resource::<IniAudio>().volume, 0.5);
+    assert!(PersistManager::import_ini(app.world_mut(), &ini_path)
+        .unwrap()
+        .is_empty());
+
+    let mut file = PersistFile::new();
+    let mut data = PersistData::new();
+    data.insert("resolutions", vec![720u32, 1080]);
+    data.insert("width", 1280u32);
+    file.set_type_data("Graphics".to_string(), data);
+    let round_trip = PersistFile::parse_ini(&file.to_ini()).unwrap();
+    let graphics = round_trip.get_type_data("Graphics").unwrap();
+    assert_eq!(
+        graphics.get::<Vec<u32>>("resolutions"),
+        Some(vec![720, 1080])
+    );
+    assert_eq!(graphics.get::<u32>("width"), Some(1280));
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic, format = "json")]
+struct LegacyProfile {
+    name: String,
+}
+
+#[test]
+fn test_legacy_locations_are_migrated_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let old_dir = temp_dir.path().join("OldStudio");
+    let new_dir = temp_dir.path().join("NewStudio");
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("NewStudio", "LegacyTest")
+                .with_environment(PersistEnvironment::Production)
+                .with_path_resolver(DirResolver(new_dir.clone()))
+                .with_legacy_locations(vec![std::sync::Arc::new(DirResolver(old_dir.clone()))]),
+        );
+        app.update();
+        app
+    };
+
+    // Saved by the old build, under the old studio name
+    let old_path = old_dir.join("legacyprofile.json");
+    let mut data = PersistData::new();
+    data.insert("name", "Ada");
+    let mut file = PersistFile::new();
+    file.set_type_data(
+        format!("integration_test::{}", LegacyProfile::type_name()),
+        data,
+    );
+    file.save_to_file(&old_path).unwrap();
+
+    let mut app = build_app();
+    assert_eq!(app.world().resource::<LegacyProfile>().name, "Ada");
+    let manager = app.world().resource::<PersistManager>();
+    let marker = manager.legacy_marker_path();
+    assert!(marker.exists());
+    assert!(!old_path.exists());
+    assert!(new_dir.join("legacyprofile.json").exists());
+
+    app.world_mut().resource_mut::<LegacyProfile>().name = "Grace".to_string();
+    app.update();
+    drop(app);
+
+    // Once migrated, files at the old location are left alone
+    file.save_to_file(&old_path).unwrap();
+    let app = build_app();
+    assert_eq!(app.world().resource::<LegacyProfile>().name, "Grace");
+    assert!(old_path.exists());
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic, dir = "controls")]
+struct NestedKeybinds {
+    jump: String,
+}
+
+#[test]
+fn test_dir_attribute_nests_production_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "NestedDirTest")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(DirResolver(temp_dir.path().to_path_buf())),
+    );
+    app.update();
+
+    app.world_mut().resource_mut::<NestedKeybinds>().jump = "Space".to_string();
+    app.update();
+
+    let manager = app.world().resource::<PersistManager>();
+    assert_eq!(
+        manager.get_type_dir(NestedKeybinds::type_name()),
+        Some(std::path::Path::new("controls"))
+    );
+    let path = temp_dir.path().join("controls").join("nestedkeybinds.ron");
+    assert!(path.exists());
+    assert_eq!(
+        manager.get_resource_path(NestedKeybinds::type_name(), PersistMode::Dynamic),
+        path
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct ParallelAudio {
+    volume: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic, format = "json")]
+struct ParallelVideo {
+    width: u32,
+}
+
+#[test]
+fn test_types_saved_together_are_written_in_parallel() {
+    let temp_dir = TempDir::new().unwrap();
+    let build_app = || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            PersistPlugin::new("TestOrg", "ParallelTest")
+                .with_environment(PersistEnvironment::Production)
+                .with_path_resolver(DirResolver(temp_dir.path().to_path_buf())),
+        );
+        app.update();
+        app
+    };
+    let batches = |app: &App, cursor: &mut bevy::ecs::event::EventCursor<PersistBatchSaved>| {
+        let events = app.world().resource::<Events<PersistBatchSaved>>();
+        cursor.read(events).cloned().collect::<Vec<_>>()
+    };
+
+    let mut app = build_app();
+    let mut cursor = app
+        .world()
+        .resource::<Events<PersistBatchSaved>>()
+        .get_cursor_current();
+    app.world_mut().resource_mut::<ParallelAudio>().volume = 7;
+    app.world_mut().resource_mut::<ParallelVideo>().width = 1920;
+    app.update();
+
+    let sent = batches(&app, &mut cursor);
+    assert_eq!(sent.len(), 1);
+    let mut saved = sent[0].saved.clone();
+    saved.sort();
+    assert_eq!(saved, ["ParallelAudio", "ParallelVideo"]);
+    assert!(sent[0].failed.is_empty());
+    assert!(temp_dir.path().join("parallelaudio.ron").exists());
+    assert!(temp_dir.path().join("parallelvideo.json").exists());
+
+    // A single change is written on its own
+    app.world_mut().resource_mut::<ParallelAudio>().volume = 8;
+    app.update();
+    assert!(batches(&app, &mut cursor).is_empty());
+    drop(app);
+
+    let app = build_app();
+    assert_eq!(app.world().resource::<ParallelAudio>().volume, 8);
+    assert_eq!(app.world().resource::<ParallelVideo>().width, 1920);
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct SharedPathAudio {
+    volume: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+#[persist(dynamic)]
+struct SharedPathVideo {
+    width: u32,
+}
+
+/// Resolves every type to the same file.
+struct SharedFileResolver(std::path::PathBuf);
+
+impl PathResolver for SharedFileResolver {
+    fn resolve(&self, _type_name: &str, _mode: PersistMode) -> std::path::PathBuf {
+        self.0.join("shared")
+    }
+}
+
+#[test]
+fn test_parallel_writes_to_the_same_path_run_in_turn() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(
+        PersistPlugin::new("TestOrg", "SharedPathTest")
+            .with_environment(PersistEnvironment::Production)
+            .with_path_resolver(SharedFileResolver(temp_dir.path().to_path_buf())),
+    );
+    app.update();
+
+    app.world_mut().resource_mut::<SharedPathAudio>().volume = 7;
+    app.world_mut().resource_mut::<SharedPathVideo>().width = 1920;
+    app.update();
+
+    // The writes don't interleave, so the file holds one whole entry
+    let file = PersistFile::load_from_file(temp_dir.path().join("shared.ron")).unwrap();
+    assert_eq!(file.type_data.len(), 1);
+    let audio = file.get_type_data("integration_test::SharedPathAudio");
+    let video = file.get_type_data("integration_test::SharedPathVideo");
+    assert!(
+        audio.is_some_and(|data| data.get::<u32>("volume") == Some(7))
+            || video.is_some_and(|data| data.get::<u32>("width") == Some(1920))
+    );
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct BudgetAudio {
+    volume: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct BudgetVideo {
+    width: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct BudgetControls {
+    invert_y: bool,
+}
+
+#[test]
+fn test_frame_budget_spreads_saves_across_frames() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "FrameBudgetTest"));
+    app.persist_resource::<BudgetAudio>()
+        .file(temp_dir.path().join("audio.ron"));
+    app.persist_resource::<BudgetVideo>()
+        .file(temp_dir.path().join("video.ron"));
+    app.persist_resource::<BudgetControls>()
+        .file(temp_dir.path().join("controls.ron"));
+    app.update();
+
+    // No time to spare, so one type is written per frame
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_frame_budget(Some(std::time::Duration::ZERO));
+    app.world_mut().resource_mut::<BudgetAudio>().volume = 3;
+    app.world_mut().resource_mut::<BudgetVideo>().width = 1280;
+    app.world_mut().resource_mut::<BudgetControls>().invert_y = true;
+    let dirty = |app: &App| {
+        let manager = app.world().resource::<PersistManager>();
+        ["BudgetAudio", "BudgetVideo", "BudgetControls"]
+            .into_iter()
+            .filter(|type_name| manager.is_dirty_named(type_name))
+            .count()
+    };
+    for remaining in [2, 1, 0] {
+        app.update();
+        assert_eq!(dirty(&app), remaining);
+    }
+    let saved = PersistFile::load_from_file(temp_dir.path().join("video.ron")).unwrap();
+    assert_eq!(
+        saved
+            .get_type_data("integration_test::BudgetVideo")
+            .unwrap()
+            .get::<u32>("width"),
+        Some(1280)
+    );
+
+    // Their last writes fit in a roomier budget, so they're written together
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_frame_budget(Some(std::time::Duration::from_secs(60)));
+    app.world_mut().resource_mut::<BudgetAudio>().volume = 5;
+    app.world_mut().resource_mut::<BudgetVideo>().width = 1600;
+    app.world_mut().resource_mut::<BudgetControls>().invert_y = false;
+    app.update();
+    assert_eq!(dirty(&app), 0);
+
+    // An explicit flush writes everything at once
+    app.world_mut().resource_mut::<BudgetAudio>().volume = 4;
+    app.world_mut().resource_mut::<BudgetVideo>().width = 1920;
+    app.world_mut()
+        .resource_mut::<PersistManager>()
+        .set_manual_flush(true);
+    app.update();
+    assert_eq!(dirty(&app), 2);
+    PersistManager::flush(app.world_mut()).unwrap();
+    assert_eq!(dirty(&app), 0);
+}
+
+#[cfg(feature = "inspector")]
+#[derive(Resource, Default, Serialize, Deserialize, Persist, Debug, PartialEq, Clone)]
+struct InspectedSettings {
+    brightness: f32,
+}
+
+#[cfg(feature = "inspector")]
+#[test]
+fn test_inspector_plugin_runs_without_a_window() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("inspected.ron");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(PersistPlugin::new("TestOrg", "InspectorTest"));
+    app.add_plugins(PersistInspectorPlugin);
+    app.persist_resource::<InspectedSettings>().file(&path);
+    app.update();
+
+    // With no window there's no egui context, so the panel draws nothing
+    app.world_mut().run_schedule(bevy_egui::EguiContextPass);
+
+    app.world_mut()
+        .resource_mut::<InspectedSettings>()
+        .brightness = 0.7;
+    app.update();
+    app.world_mut().run_schedule(bevy_egui::EguiContextPass);
+    assert_eq!(app.world().resource::<InspectedSettings>().brightness, 0.7);
+}
@@ -0,0 +1,149 @@
+//! Build-script helpers for [bevy_persist](https://docs.rs/bevy_persist).
+//!
+//! Embedded resources are tuned in development and compiled into release
+//! builds. Instead of copying the tuned values around by hand, a build
+//! script can pull them out of the development file into `OUT_DIR`, where
+//! `#[persist(embed, out_dir)]` includes them from:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     bevy_persist_build::embed_type("mygame_dev.ron", "GameBalance").unwrap();
+//! }
+//! ```
+
+use ron::value::RawValue;
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// Keys of the dev file that hold file metadata rather than a type
+const METADATA_KEYS: [&str; 2] = ["last_saved", "version"];
+
+/// Copies a type's data from the dev file into `OUT_DIR` for embedding.
+///
+/// `type_name` is the name the type is persisted under: its name, or the
+/// `name = "..."` it was given. Cargo is told to rerun the build script
+/// whenever the dev file changes. Returns the written file.
+pub fn embed_type(dev_file: impl AsRef<Path>, type_name: &str) -> Result<PathBuf> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            "OUT_DIR is not set, embed_type must run in a build script",
+        )
+    })?;
+    embed_type_into(dev_file, type_name, out_dir)
+}
+
+/// Like [`embed_type`], writing into `out_dir` instead of `OUT_DIR`.
+pub fn embed_type_into(
+    dev_file: impl AsRef<Path>,
+    type_name: &str,
+    out_dir: impl AsRef<Path>,
+) -> Result<PathBuf> {
+    let dev_file = dev_file.as_ref();
+    println!("cargo:rerun-if-changed={}", dev_file.display());
+
+    let content = fs::read_to_string(dev_file)?;
+    let entries: BTreeMap<String, Box<RawValue>> = ron::from_str(&content).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse {}: {}", dev_file.display(), e),
+        )
+    })?;
+
+    // Keys are namespaced, usually by module path
+    let mut matches = entries
+        .iter()
+        .filter(|(key, _)| !METADATA_KEYS.contains(&key.as_str()) && is_key_of(key, type_name));
+    let (key, data) = match (matches.next(), matches.next()) {
+        (Some(entry), None) => entry,
+        (None, _) => {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} has no data for {}", dev_file.display(), type_name),
+            ))
+        }
+        (Some(_), Some(_)) => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} has data for several types named {}",
+                    dev_file.display(),
+                    type_name
+                ),
+            ))
+        }
+    };
+
+    let key = ron::to_string(key).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let path = out_dir
+        .as_ref()
+        .join("persist")
+        .join(embed_file_name(type_name));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, format!("{{\n    {}: {},\n}}\n", key, data.get_ron()))?;
+    Ok(path)
+}
+
+/// Name of the file in `OUT_DIR/persist/` that a type is embedded from.
+pub fn embed_file_name(type_name: &str) -> String {
+    format!("{}.ron", type_name.to_lowercase().replace("::", "_"))
+}
+
+/// Whether a storage key belongs to the type persisted as `type_name`.
+fn is_key_of(key: &str, type_name: &str) -> bool {
+    key == type_name
+        || key
+            .strip_suffix(type_name)
+            .is_some_and(|prefix| prefix.ends_with("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_embed_type_extracts_one_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let dev_file = temp_dir.path().join("mygame_dev.ron");
+        fs::write(
+            &dev_file,
+            r#"{
+    "last_saved": "2024-01-01T00:00:00+00:00",
+    "version": "0.1.0",
+    "mygame::balance::GameBalance": (
+        values: {
+            "difficulty": Hard,
+            "spawn_rate": 1.25,
+        },
+    ),
+    "mygame::Settings": (
+        values: {
+            "volume": 0.5,
+        },
+    ),
+}"#,
+        )
+        .unwrap();
+
+        let path = embed_type_into(&dev_file, "GameBalance", temp_dir.path()).unwrap();
+        assert_eq!(
+            path,
+            temp_dir.path().join("persist").join("gamebalance.ron")
+        );
+        let embedded = fs::read_to_string(&path).unwrap();
+        assert!(embedded.contains("\"mygame::balance::GameBalance\""));
+        assert!(embedded.contains("Hard"));
+        assert!(!embedded.contains("volume"));
+        let entries: BTreeMap<String, ron::Value> = ron::from_str(&embedded).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        // Only whole path segments count
+        assert!(embed_type_into(&dev_file, "Balance", temp_dir.path()).is_err());
+    }
+}
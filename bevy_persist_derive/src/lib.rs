@@ -12,15 +12,36 @@ pub fn derive_persist(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(PersistComponent, attributes(persist))]
+pub fn derive_persist_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match impl_persist_component(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 fn impl_persist(input: &DeriveInput) -> SynResult<proc_macro2::TokenStream> {
     let name = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Parse persist attributes if any
     let mut auto_save = true;
     let mut persist_file = None;
     let mut persist_mode = "dev".to_string(); // default mode
     let mut embed_file = None;
+    let mut embed_out_dir = false;
+    let mut embed_binary = None;
+    let mut embed_root = None;
+    let mut custom_name = None;
+    let mut persist_format = "ron".to_string();
+    let mut persist_group = None;
+    let mut persist_dir = None;
+    let mut persist_store: Option<String> = None;
+    let mut validate_fn: Option<syn::Path> = None;
+    let mut before_save_fn: Option<syn::Path> = None;
+    let mut defaults_file = None;
+    let mut delta = false;
 
     for attr in &input.attrs {
         if attr.path().is_ident("persist") {
@@ -33,6 +54,33 @@ fn impl_persist(input: &DeriveInput) -> SynResult<proc_macro2::TokenStream> {
                     meta.input.parse::<syn::Token![=]>()?;
                     let lit: syn::LitStr = meta.input.parse()?;
                     persist_file = Some(lit.value());
+                } else if meta.path.is_ident("name") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    custom_name = Some(lit.value());
+                } else if meta.path.is_ident("group") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    persist_group = Some(lit.value());
+                } else if meta.path.is_ident("dir") {
+                    // Subdirectory the production file is nested in
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    persist_dir = Some(lit.value());
+                } else if meta.path.is_ident("store") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    persist_store = Some(lit.value());
+                } else if meta.path.is_ident("format") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    persist_format = lit.value().to_lowercase();
+                    if !matches!(persist_format.as_str(), "ron" | "json" | "toml") {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "unsupported format, expected \"ron\", \"json\" or \"toml\"",
+                        ));
+                    }
                 } else if meta.path.is_ident("mode") {
                     meta.input.parse::<syn::Token![=]>()?;
                     let lit: syn::LitStr = meta.input.parse()?;
@@ -47,43 +95,188 @@ fn impl_persist(input: &DeriveInput) -> SynResult<proc_macro2::TokenStream> {
                     } else {
                         persist_mode = "embed".to_string();
                     }
+                } else if meta.path.is_ident("embed_binary") {
+                    // Embedded as a blob from `PersistManager::encode_embedded`
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    embed_binary = Some(lit.value());
+                    persist_mode = "embed".to_string();
+                } else if meta.path.is_ident("embed_root") {
+                    // Directory embed files are looked up in, relative to the manifest
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    embed_root = Some(lit.value());
+                } else if meta.path.is_ident("out_dir") {
+                    // Embedded from OUT_DIR, where `bevy_persist_build` put it
+                    embed_out_dir = true;
+                    persist_mode = "embed".to_string();
+                } else if meta.path.is_ident("validate") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    validate_fn = Some(lit.parse()?);
+                } else if meta.path.is_ident("before_save") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    before_save_fn = Some(lit.parse()?);
+                } else if meta.path.is_ident("defaults") {
+                    // Shipped defaults are compiled in, relative to the source file
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    defaults_file = Some(lit.value());
+                } else if meta.path.is_ident("delta") {
+                    delta = true;
                 } else if meta.path.is_ident("dynamic") {
                     persist_mode = "dynamic".to_string();
                 } else if meta.path.is_ident("secure") {
                     persist_mode = "secure".to_string();
+                } else if meta.path.is_ident("obfuscated") {
+                    persist_mode = "obfuscated".to_string();
+                } else if meta.path.is_ident("session") {
+                    persist_mode = "session".to_string();
                 }
                 Ok(())
             })?;
         }
     }
 
-    let type_name_str = name.to_string();
+    let type_name_str = custom_name.unwrap_or_else(|| name.to_string());
     let persist_mode_str = persist_mode.clone();
-    
-    // Convert embed_file Option<String> to token stream for static context
-    let embed_file_tokens = match embed_file.as_ref() {
+
+    // Generic types can't be registered through inventory (the static has no
+    // concrete instantiation to name), so each instantiation gets its own key
+    // and must be registered explicitly with `register_persist::<T>()`.
+    let is_generic = input.generics.type_params().next().is_some()
+        || input.generics.const_params().next().is_some();
+
+    let type_name_body = if is_generic {
+        quote! { bevy_persist::generic_type_name::<Self>(#type_name_str) }
+    } else {
+        quote! { #type_name_str }
+    };
+    // The path always names the real type, even when `name` overrides the key
+    let ident_str = name.to_string();
+    let type_path_body = if is_generic {
+        quote! {
+            bevy_persist::generic_type_path::<Self>(concat!(module_path!(), "::", #ident_str))
+        }
+    } else {
+        quote! { concat!(module_path!(), "::", #ident_str) }
+    };
+
+    // Generic impls only hold for instantiations that are actually serializable
+    let mut generics = input.generics.clone();
+    if is_generic {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! {
+                Self: bevy_persist::serde::Serialize + bevy_persist::serde::de::DeserializeOwned
+            });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Embed files resolve against the manifest, wherever the type is declared
+    let embed_root = embed_root.or_else(|| std::env::var("BEVY_PERSIST_EMBED_ROOT").ok());
+    let embed_path = (persist_mode == "embed" && !embed_out_dir).then(|| {
+        let file = embed_binary
+            .clone()
+            .or_else(|| embed_file.clone())
+            .unwrap_or_else(|| {
+                format!(
+                    "{}.{}",
+                    type_name_str.to_lowercase().replace("::", "_"),
+                    persist_format
+                )
+            });
+        resolve_embed_path(embed_root.as_deref(), &file)
+    });
+    // A chosen location is where dev saves go too; otherwise the runtime's default applies.
+    // Dev saves are text, so they never overwrite a binary blob
+    let embed_file_tokens = match embed_path
+        .as_ref()
+        .filter(|_| embed_binary.is_none() && (embed_file.is_some() || embed_root.is_some()))
+    {
+        Some(path) => {
+            let path = path.to_string_lossy();
+            quote! { Some(#path) }
+        }
+        None => quote! { None },
+    };
+    let persist_file_tokens = match persist_file.as_ref() {
         Some(path) => quote! { Some(#path) },
         None => quote! { None },
     };
+    let persist_group_tokens = match persist_group.as_ref() {
+        Some(group) => quote! { Some(#group) },
+        None => quote! { None },
+    };
+    let persist_dir_tokens = match persist_dir.as_ref() {
+        Some(dir) => quote! { Some(#dir) },
+        None => quote! { None },
+    };
+    let persist_store_tokens = match persist_store.as_ref() {
+        Some(store) => quote! { Some(#store) },
+        None => quote! { None },
+    };
 
     // Generate embedded data if in embed mode
     // Only include the file in production builds, in dev we load dynamically
-    let embedded_data = if persist_mode == "embed" {
-        // Use specified file or auto-generate based on type name
-        // Auto-generated files are saved in assets/persist/ directory
-        // For include_str!, we need a path relative to the source file where the macro is used
-        // Most Bevy projects have src/ and assets/ as siblings, so we use ../assets/persist/
-        let file_path = embed_file.as_ref()
-            .map(|s| s.clone())
-            .unwrap_or_else(|| {
-                // Check if we need to use CARGO_MANIFEST_DIR (for workspace members)
-                // Otherwise use ../assets relative path (typical for single crate projects)
-                format!("../assets/persist/{}.ron", type_name_str.to_lowercase().replace("::", "_"))
-            });
+    if embed_binary.is_some() && (embed_out_dir || embed_file.is_some()) {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`embed_binary` names the file to embed, don't also give `embed` a path or `out_dir`",
+        ));
+    }
+    let embedded = if embed_out_dir {
+        if embed_file.is_some() {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`out_dir` embeds the file written by `bevy_persist_build`, don't also give `embed` a path",
+            ));
+        }
+        let file_name = format!(
+            "/persist/{}.ron",
+            type_name_str.to_lowercase().replace("::", "_")
+        );
+        quote! {
+            #[cfg(feature = "prod")]
+            {
+                Some(include_str!(concat!(env!("OUT_DIR"), #file_name)))
+            }
+            #[cfg(not(feature = "prod"))]
+            {
+                None
+            }
+        }
+    } else if let Some(path) = &embed_path {
+        // Checked here so a missing file names the type and how to fix it
+        let include = if path.exists() {
+            let path = path.to_string_lossy();
+            if embed_binary.is_some() {
+                quote! { Some(include_bytes!(#path)) }
+            } else {
+                quote! { Some(include_str!(#path)) }
+            }
+        } else if embed_binary.is_some() {
+            let message = format!(
+                "embed file for {} not found at {}; write it with `PersistManager::encode_embedded`",
+                type_name_str,
+                path.display()
+            );
+            quote! { compile_error!(#message) }
+        } else {
+            let message = format!(
+                "embed file for {} not found at {}; run the game in development to create it, \
+                 or point `embed = \"...\"` or `embed_root = \"...\"` at it",
+                type_name_str,
+                path.display()
+            );
+            quote! { compile_error!(#message) }
+        };
         quote! {
             #[cfg(feature = "prod")]
             {
-                Some(include_str!(#file_path))
+                #include
             }
             #[cfg(not(feature = "prod"))]
             {
@@ -93,11 +286,72 @@ fn impl_persist(input: &DeriveInput) -> SynResult<proc_macro2::TokenStream> {
     } else {
         quote! { None }
     };
+    let (embedded_data, embedded_bytes) = if embed_binary.is_some() {
+        (quote! { None }, embedded)
+    } else {
+        (embedded, quote! { None })
+    };
+
+    let defaults_data = match defaults_file.as_ref() {
+        Some(path) => quote! { Some(include_str!(#path)) },
+        None => quote! { None },
+    };
+
+    // Ranges clamp first, then `fn(&mut Self) -> Result<(), String>` sees the result
+    let FieldAttrs { ranges, encrypted } = field_attrs(input)?;
+    let validate = (!ranges.is_empty() || validate_fn.is_some()).then(|| {
+        let clamps = ranges.iter().map(|range| {
+            let FieldRange {
+                ident,
+                name,
+                min,
+                max,
+            } = range;
+            let min = option_tokens(min.as_ref());
+            let max = option_tokens(max.as_ref());
+            quote! {
+                errors.extend(bevy_persist::clamp_field(#name, &mut self.#ident, #min, #max));
+            }
+        });
+        let custom = validate_fn.map(|path| {
+            quote! {
+                errors.extend(#path(self).err());
+            }
+        });
+        quote! {
+            fn validate(&mut self) -> Result<(), String> {
+                let mut errors: Vec<String> = Vec::new();
+                #(#clamps)*
+                #custom
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors.join("; "))
+                }
+            }
+        }
+    });
+
+    let before_save = before_save_fn.map(|path| {
+        quote! {
+            fn before_save(&mut self) {
+                #path(self);
+            }
+        }
+    });
+
+    let field_docs = field_docs(input)
+        .into_iter()
+        .map(|(field, doc)| quote! { (#field, #doc) });
 
     let expanded = quote! {
         impl #impl_generics bevy_persist::Persistable for #name #ty_generics #where_clause {
             fn type_name() -> &'static str {
-                #type_name_str
+                #type_name_body
+            }
+
+            fn type_path() -> &'static str {
+                #type_path_body
             }
 
             fn persist_mode() -> bevy_persist::PersistMode {
@@ -105,48 +359,350 @@ fn impl_persist(input: &DeriveInput) -> SynResult<proc_macro2::TokenStream> {
                     "embed" => bevy_persist::PersistMode::Embed,
                     "dynamic" => bevy_persist::PersistMode::Dynamic,
                     "secure" => bevy_persist::PersistMode::Secure,
+                    "obfuscated" => bevy_persist::PersistMode::Obfuscated,
+                    "session" => bevy_persist::PersistMode::Session,
                     _ => bevy_persist::PersistMode::Dev,
                 }
             }
 
+            fn persist_file() -> Option<&'static str> {
+                #persist_file_tokens
+            }
+
+            fn persist_group() -> Option<&'static str> {
+                #persist_group_tokens
+            }
+
+            fn persist_dir() -> Option<&'static str> {
+                #persist_dir_tokens
+            }
+
+            fn persist_format() -> bevy_persist::PersistFormat {
+                match #persist_format {
+                    "json" => bevy_persist::PersistFormat::Json,
+                    "toml" => bevy_persist::PersistFormat::Toml,
+                    _ => bevy_persist::PersistFormat::Ron,
+                }
+            }
+
+            fn default_auto_save() -> bool {
+                #auto_save
+            }
+
+            fn embed_file() -> Option<&'static str> {
+                #embed_file_tokens
+            }
+
             fn embedded_data() -> Option<&'static str> {
                 #embedded_data
             }
 
+            fn embedded_bytes() -> Option<&'static [u8]> {
+                #embedded_bytes
+            }
+
+            fn defaults_data() -> Option<&'static str> {
+                #defaults_data
+            }
+
+            fn persist_delta() -> bool {
+                #delta
+            }
+
             fn to_persist_data(&self) -> bevy_persist::PersistData {
-                let mut data = bevy_persist::PersistData::new();
-                if let Ok(json_value) = serde_json::to_value(self) {
-                    if let serde_json::Value::Object(map) = json_value {
-                        for (key, value) in map {
-                            data.values.insert(key, value);
-                        }
-                    }
-                }
-                data
+                bevy_persist::PersistData::from_serialize(self)
             }
 
             fn load_from_persist_data(&mut self, data: &bevy_persist::PersistData) {
-                if let Ok(value) = serde_json::to_value(&data.values) {
-                    if let Ok(new_self) = serde_json::from_value(value) {
-                        *self = new_self;
-                    }
+                if let Ok(new_self) = data.deserialize() {
+                    *self = new_self;
+                }
+            }
+
+            fn field_docs() -> bevy_persist::FieldDocs {
+                &[#(#field_docs),*]
+            }
+
+            fn encrypted_fields() -> &'static [&'static str] {
+                &[#(#encrypted),*]
+            }
+
+            #validate
+
+            #before_save
+        }
+    };
+
+    // Auto-register this type when it's used
+    let registration = if is_generic {
+        quote! {}
+    } else {
+        quote! {
+            bevy_persist::inventory::submit! {
+                bevy_persist::PersistRegistration {
+                    type_name: #type_name_str,
+                    type_path: concat!(module_path!(), "::", #ident_str),
+                    persist_mode: #persist_mode_str,
+                    auto_save: #auto_save,
+                    embed_file: #embed_file_tokens,
+                    file: #persist_file_tokens,
+                    group: #persist_group_tokens,
+                    store: #persist_store_tokens,
+                    register_fn: |app: &mut bevy::prelude::App| {
+                        bevy_persist::register_persist_type::<#name>(app, #auto_save);
+                    },
                 }
             }
         }
+    };
+
+    let expanded = quote! {
+        #expanded
+        #registration
+    };
+
+    Ok(expanded)
+}
+
+fn impl_persist_component(input: &DeriveInput) -> SynResult<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    if input.generics.type_params().next().is_some()
+        || input.generics.const_params().next().is_some()
+    {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "PersistComponent can't be derived for generic types",
+        ));
+    }
+
+    let mut auto_save = true;
+    let mut persist_file = None;
+    let mut persist_mode = "dev".to_string();
+    let mut custom_name = None;
+    let mut persist_store = None;
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("persist") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("auto_save") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitBool = meta.input.parse()?;
+                    auto_save = lit.value();
+                } else if meta.path.is_ident("file") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    persist_file = Some(lit.value());
+                } else if meta.path.is_ident("name") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    custom_name = Some(lit.value());
+                } else if meta.path.is_ident("store") {
+                    meta.input.parse::<syn::Token![=]>()?;
+                    let lit: syn::LitStr = meta.input.parse()?;
+                    persist_store = Some(lit.value());
+                } else if meta.path.is_ident("dynamic") {
+                    persist_mode = "dynamic".to_string();
+                } else if meta.path.is_ident("secure") {
+                    persist_mode = "secure".to_string();
+                } else if meta.path.is_ident("obfuscated") {
+                    persist_mode = "obfuscated".to_string();
+                } else if meta.path.is_ident("session") {
+                    persist_mode = "session".to_string();
+                } else {
+                    return Err(meta.error(
+                        "unsupported attribute, expected `name`, `file`, `store`, `auto_save`, `dynamic`, `secure`, `obfuscated` or `session`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    let ident_str = name.to_string();
+    let type_name_str = custom_name.unwrap_or_else(|| ident_str.clone());
+    let persist_file_tokens = match persist_file.as_ref() {
+        Some(path) => quote! { Some(#path) },
+        None => quote! { None },
+    };
+    let persist_store_tokens = match persist_store.as_ref() {
+        Some(store) => quote! { Some(#store) },
+        None => quote! { None },
+    };
+
+    Ok(quote! {
+        impl bevy_persist::PersistComponent for #name {
+            fn type_name() -> &'static str {
+                #type_name_str
+            }
+
+            fn type_path() -> &'static str {
+                concat!(module_path!(), "::", #ident_str)
+            }
+
+            fn persist_mode() -> bevy_persist::PersistMode {
+                match #persist_mode {
+                    "dynamic" => bevy_persist::PersistMode::Dynamic,
+                    "secure" => bevy_persist::PersistMode::Secure,
+                    "obfuscated" => bevy_persist::PersistMode::Obfuscated,
+                    "session" => bevy_persist::PersistMode::Session,
+                    _ => bevy_persist::PersistMode::Dev,
+                }
+            }
+
+            fn persist_file() -> Option<&'static str> {
+                #persist_file_tokens
+            }
+
+            fn default_auto_save() -> bool {
+                #auto_save
+            }
+        }
 
-        // Auto-register this type when it's used
         bevy_persist::inventory::submit! {
             bevy_persist::PersistRegistration {
                 type_name: #type_name_str,
-                persist_mode: #persist_mode_str,
+                type_path: concat!(module_path!(), "::", #ident_str),
+                persist_mode: #persist_mode,
                 auto_save: #auto_save,
-                embed_file: #embed_file_tokens,
+                embed_file: None,
+                file: #persist_file_tokens,
+                group: None,
+                store: #persist_store_tokens,
                 register_fn: |app: &mut bevy::prelude::App| {
-                    bevy_persist::register_persist_type::<#name #ty_generics>(app, #auto_save);
+                    bevy_persist::register_persist_component::<#name>(app, #auto_save);
                 },
             }
         }
+    })
+}
+
+/// Collects the `///` comments of a struct's named fields.
+fn field_docs(input: &DeriveInput) -> Vec<(String, String)> {
+    let syn::Data::Struct(data) = &input.data else {
+        return Vec::new();
     };
+    data.fields
+        .iter()
+        .filter_map(|field| {
+            // Serde names `r#type` as `type`
+            let name = field.ident.as_ref()?.to_string();
+            let name = name.trim_start_matches("r#").to_string();
+            let lines: Vec<String> = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .filter_map(|attr| match &attr.meta {
+                    syn::Meta::NameValue(syn::MetaNameValue {
+                        value:
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(doc),
+                                ..
+                            }),
+                        ..
+                    }) => Some(doc.value()),
+                    _ => None,
+                })
+                .collect();
+            // `/// text` becomes `" text"`
+            let doc = lines
+                .iter()
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let doc = doc.trim_matches('\n');
+            (!doc.is_empty()).then(|| (name, doc.to_string()))
+        })
+        .collect()
+}
 
-    Ok(expanded)
+/// Resolves an embed file against the embed root, or `assets/persist` by
+/// default, both relative to the crate's manifest directory.
+///
+/// `BEVY_PERSIST_EMBED_ROOT` stands in for a missing `embed_root`. Absolute
+/// paths are used as they are.
+fn resolve_embed_path(embed_root: Option<&str>, file: &str) -> std::path::PathBuf {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    std::path::Path::new(&manifest_dir)
+        .join(embed_root.unwrap_or("assets/persist"))
+        .join(file)
+}
+
+/// A field with `#[persist(range(min = .., max = ..))]`.
+struct FieldRange {
+    ident: syn::Ident,
+    name: String,
+    min: Option<syn::Expr>,
+    max: Option<syn::Expr>,
+}
+
+/// What `#[persist(...)]` attributes on fields asked for.
+#[derive(Default)]
+struct FieldAttrs {
+    /// Ranges that loaded fields are clamped to
+    ranges: Vec<FieldRange>,
+    /// Names of fields marked `encrypt`
+    encrypted: Vec<String>,
+}
+
+/// Collects the `#[persist(...)]` attributes of each field.
+fn field_attrs(input: &DeriveInput) -> SynResult<FieldAttrs> {
+    let syn::Data::Struct(data) = &input.data else {
+        return Ok(FieldAttrs::default());
+    };
+    let mut attrs = FieldAttrs::default();
+    for field in &data.fields {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("persist"))
+        {
+            let Some(ident) = &field.ident else {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`persist` field attributes need a named field",
+                ));
+            };
+            let name = ident.to_string().trim_start_matches("r#").to_string();
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("encrypt") {
+                    attrs.encrypted.push(name.clone());
+                    return Ok(());
+                }
+                if !meta.path.is_ident("range") {
+                    return Err(
+                        meta.error("unsupported field attribute, expected `range` or `encrypt`")
+                    );
+                }
+                let (mut min, mut max) = (None, None);
+                meta.parse_nested_meta(|bound| {
+                    if bound.path.is_ident("min") {
+                        min = Some(bound.value()?.parse()?);
+                    } else if bound.path.is_ident("max") {
+                        max = Some(bound.value()?.parse()?);
+                    } else {
+                        return Err(bound.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                if min.is_none() && max.is_none() {
+                    return Err(meta.error("`range` needs a `min`, a `max` or both"));
+                }
+                attrs.ranges.push(FieldRange {
+                    ident: ident.clone(),
+                    name: name.clone(),
+                    min,
+                    max,
+                });
+                Ok(())
+            })?;
+        }
+    }
+    Ok(attrs)
+}
+
+fn option_tokens(value: Option<&syn::Expr>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
 }